@@ -1,25 +1,87 @@
 use actix_web::{
-    HttpResponse, post,
-    web::Json,
+    HttpMessage, HttpRequest, HttpResponse, post,
+    web::{self, Json},
 };
 use dto::{
-    ai::{AiSuggestionRequest, AiSuggestionResponse, PositionAnalysisRequest, PositionAnalysisResponse},
+    ai::{AgentPlayRequest, AiSuggestionRequest, AiSuggestionResponse, PositionAnalysisRequest, PositionAnalysisResponse},
     responses::ValidationErrorResponse,
 };
+use engine::{config::EngineConfig, EngineError};
 use error::error::ApiError;
+use sea_orm::DatabaseConnection;
 use serde_json::json;
+use uuid::Uuid;
 use validator::Validate;
 
-use service::engine_service::EngineService;
+use service::engine_service::{AgentDirectory, EngineService};
+use service::players::stellar_address_of;
 use std::env;
 
+fn engine_service() -> EngineService {
+    let engine_path = env::var("ENGINE_PATH").unwrap_or_else(|_| "stockfish".to_string());
+    let engine_service = EngineService::new(engine_path);
+    match env::var("ENGINE_BOOK_DIR") {
+        Ok(book_dir) if !book_dir.trim().is_empty() => engine_service.with_book_dir(book_dir.trim()),
+        _ => engine_service,
+    }
+}
+
+/// Check that the caller may play as `agent`: their linked Stellar account
+/// must be the NFT's owner or current renter.
+async fn authorize_agent(
+    req: &HttpRequest,
+    db: &DatabaseConnection,
+    agents: Option<&web::Data<dyn AgentDirectory>>,
+    agent: &AgentPlayRequest,
+) -> Result<EngineConfig, HttpResponse> {
+    let Some(agents) = agents else {
+        return Err(HttpResponse::ServiceUnavailable().json(json!({
+            "error": "AI agent play is not configured"
+        })));
+    };
+    let Some(player_id) = req.extensions().get::<Uuid>().copied() else {
+        return Err(HttpResponse::Unauthorized().json(json!({
+            "error": "Authentication required"
+        })));
+    };
+
+    let address = match stellar_address_of(db, player_id).await {
+        Ok(Some(address)) => address,
+        Ok(None) => {
+            return Err(HttpResponse::Forbidden().json(json!({
+                "error": "No Stellar account is linked to this player"
+            })));
+        }
+        Err(e) => return Err(e.error_response()),
+    };
+
+    EngineService::authorize_agent(agents.as_ref(), &address, agent.nft_id, &agent.engine_config)
+        .await
+        .map_err(|e| match e {
+            EngineError::AgentUnavailable(message) => HttpResponse::Forbidden().json(json!({
+                "error": message
+            })),
+            EngineError::ParseError(message) => HttpResponse::BadRequest().json(json!({
+                "error": message
+            })),
+            e => {
+                log::error!("Agent lookup failed for NFT {}: {}", agent.nft_id, e);
+                HttpResponse::BadGateway().json(json!({
+                    "error": "could not read the agent from the chain"
+                }))
+            }
+        })
+}
+
 #[utoipa::path(
     post,
     path = "/v1/ai/suggest",
     request_body = AiSuggestionRequest,
     responses(
         (status = 200, description = "AI suggestion generated", body = AiSuggestionResponse),
-        (status = 400, description = "Invalid FEN position", body = ValidationErrorResponse)
+        (status = 400, description = "Invalid FEN position", body = ValidationErrorResponse),
+        (status = 403, description = "Caller neither owns nor rents the agent NFT"),
+        (status = 503, description = "AI agent play is not configured")
     ),
     security(
         ("jwt_auth" = [])
@@ -27,18 +89,37 @@ use std::env;
     tag = "AI"
 )]
 #[post("/suggest")]
-pub async fn get_ai_suggestion(payload: Json<AiSuggestionRequest>) -> HttpResponse {
+pub async fn get_ai_suggestion(
+    req: HttpRequest,
+    payload: Json<AiSuggestionRequest>,
+    db: web::Data<DatabaseConnection>,
+    agents: Option<web::Data<dyn AgentDirectory>>,
+) -> HttpResponse {
     match payload.0.validate() {
         Ok(_) => {
-            let engine_path = env::var("ENGINE_PATH").unwrap_or_else(|_| "stockfish".to_string());
-            let engine_service = EngineService::new(engine_path);
+            let agent_config = match &payload.0.agent {
+                Some(agent) => match authorize_agent(&req, &db, agents.as_ref(), agent).await {
+                    Ok(config) => Some(config),
+                    Err(response) => return response,
+                },
+                None => None,
+            };
+            let engine_service = engine_service();
             
             let start_time = std::time::Instant::now();
-            let result = engine_service.get_suggestion(
-                &payload.0.fen,
-                payload.0.depth,
-                payload.0.time_limit_ms
-            ).await;
+            let result = match &agent_config {
+                Some(config) => engine_service.get_suggestion_as_agent(
+                    config,
+                    &payload.0.fen,
+                    payload.0.depth,
+                    payload.0.time_limit_ms
+                ).await,
+                None => engine_service.get_suggestion(
+                    &payload.0.fen,
+                    payload.0.depth,
+                    payload.0.time_limit_ms
+                ).await,
+            };
             let elapsed = u32::try_from(start_time.elapsed().as_millis()).unwrap_or(u32::MAX);
             
             match result {
@@ -92,8 +173,7 @@ pub async fn get_ai_suggestion(payload: Json<AiSuggestionRequest>) -> HttpRespon
 pub async fn analyze_position(payload: Json<PositionAnalysisRequest>) -> HttpResponse {
     match payload.0.validate() {
         Ok(_) => {
-            let engine_path = env::var("ENGINE_PATH").unwrap_or_else(|_| "stockfish".to_string());
-            let engine_service = EngineService::new(engine_path);
+            let engine_service = engine_service();
             
             match engine_service.analyze_position(&payload.0.fen, payload.0.depth).await {
                 Ok(result) => {
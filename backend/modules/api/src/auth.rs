@@ -1,10 +1,12 @@
-use actix_web::{web, HttpResponse, HttpRequest, post, cookie::{Cookie, time::Duration}};
+use actix_web::{web, HttpMessage, HttpResponse, HttpRequest, get, post, cookie::{Cookie, time::Duration}};
 use validator::Validate;
 use std::env;
 use uuid::Uuid;
 
-use dto::auth::{RegisterRequest, LoginRequest, AuthResponse, ErrorResponse, RefreshTokenRequest, RefreshResponse, LogoutResponse};
-use security::{JwtService, TokenService, TokenServiceError};
+use dto::auth::{RegisterRequest, LoginRequest, AuthResponse, ErrorResponse, RefreshTokenRequest, RefreshResponse, LogoutResponse, Sep10ChallengeQuery, Sep10ChallengeResponse, Sep10LinkResponse, Sep10TokenRequest};
+use error::error::ApiError;
+use security::{JwtService, Sep10Service, TokenService, TokenServiceError};
+use service::players::{find_or_create_wallet_player, link_stellar_address};
 use sea_orm::DatabaseConnection;
 
 /// Register a new user
@@ -73,8 +75,24 @@ pub async fn login(
     let user_id = 1;
     let username = payload.username.clone();
 
+    token_response(&db, &jwt_service, user_id, username, None).await
+}
+
+/// Issue an access token and a new refresh token family, with the refresh
+/// token also set as an HTTP-only cookie
+async fn token_response(
+    db: &DatabaseConnection,
+    jwt_service: &JwtService,
+    user_id: i32,
+    username: String,
+    player_id: Option<Uuid>,
+) -> HttpResponse {
     // Generate access token
-    let access_token = match jwt_service.generate_token(user_id, &username) {
+    let access_token = match player_id {
+        Some(player_id) => jwt_service.generate_player_token(user_id, &username, player_id),
+        None => jwt_service.generate_token(user_id, &username),
+    };
+    let access_token = match access_token {
         Ok(t) => t,
         Err(_) => {
             return HttpResponse::InternalServerError().json(ErrorResponse {
@@ -91,7 +109,7 @@ pub async fn login(
         .parse::<i64>()
         .unwrap_or(7);
 
-    let refresh_token = match TokenService::generate_refresh_token(&db, user_id, family_id, refresh_ttl).await {
+    let refresh_token = match TokenService::generate_refresh_token(db, user_id, family_id, refresh_ttl).await {
         Ok(t) => t,
         Err(e) => {
             log::error!("Failed to generate refresh token: {}", e);
@@ -126,6 +144,163 @@ pub async fn login(
     response
 }
 
+/// Start SEP-10 wallet sign-in: get a challenge transaction for the account
+/// to sign
+#[utoipa::path(
+    get,
+    path = "/v1/auth/sep10",
+    params(
+        ("account" = String, Query, description = "Stellar account (G...) to authenticate")
+    ),
+    responses(
+        (status = 200, description = "Challenge issued", body = Sep10ChallengeResponse),
+        (status = 400, description = "Invalid account", body = ErrorResponse),
+        (status = 503, description = "SEP-10 sign-in is not configured", body = ErrorResponse)
+    ),
+    tag = "Authentication"
+)]
+#[get("/sep10")]
+pub async fn sep10_challenge(
+    sep10: Option<web::Data<Sep10Service>>,
+    query: web::Query<Sep10ChallengeQuery>,
+) -> HttpResponse {
+    let Some(sep10) = sep10 else {
+        return sep10_disabled();
+    };
+    if let Err(errors) = query.validate() {
+        return HttpResponse::BadRequest().json(ErrorResponse {
+            message: format!("Validation failed: {:?}", errors),
+            code: "VALIDATION_ERROR".to_string(),
+        });
+    }
+
+    match sep10.challenge(&query.account) {
+        Ok(challenge) => HttpResponse::Ok().json(Sep10ChallengeResponse {
+            transaction: challenge.transaction,
+            network_passphrase: challenge.network_passphrase,
+        }),
+        Err(e) => HttpResponse::BadRequest().json(ErrorResponse {
+            message: e.to_string(),
+            code: "INVALID_ACCOUNT".to_string(),
+        }),
+    }
+}
+
+/// Finish SEP-10 wallet sign-in: exchange the signed challenge for tokens.
+/// The account is linked to a player, created on first sign-in.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/sep10",
+    request_body = Sep10TokenRequest,
+    responses(
+        (status = 200, description = "Signed in", body = AuthResponse),
+        (status = 401, description = "Invalid, expired or reused challenge", body = ErrorResponse),
+        (status = 403, description = "Player is disabled", body = ErrorResponse),
+        (status = 503, description = "SEP-10 sign-in is not configured", body = ErrorResponse)
+    ),
+    tag = "Authentication"
+)]
+#[post("/sep10")]
+pub async fn sep10_token(
+    db: web::Data<DatabaseConnection>,
+    sep10: Option<web::Data<Sep10Service>>,
+    jwt_service: web::Data<JwtService>,
+    payload: web::Json<Sep10TokenRequest>,
+) -> HttpResponse {
+    let Some(sep10) = sep10 else {
+        return sep10_disabled();
+    };
+
+    let account = match sep10.verify(&payload.transaction) {
+        Ok(account) => account,
+        Err(e) => {
+            return HttpResponse::Unauthorized().json(ErrorResponse {
+                message: e.to_string(),
+                code: "INVALID_CHALLENGE".to_string(),
+            });
+        }
+    };
+
+    let player = match find_or_create_wallet_player(&db, &account).await {
+        Ok(player) => player,
+        Err(ApiError::Forbidden(message)) => {
+            return HttpResponse::Forbidden().json(ErrorResponse {
+                message,
+                code: "PLAYER_DISABLED".to_string(),
+            });
+        }
+        Err(e) => {
+            log::error!("Failed to link Stellar account {}: {}", account, e);
+            return HttpResponse::InternalServerError().json(ErrorResponse {
+                message: "Failed to link Stellar account".to_string(),
+                code: "ACCOUNT_LINK_ERROR".to_string(),
+            });
+        }
+    };
+
+    token_response(&db, &jwt_service, player.user_id, player.username, Some(player.id)).await
+}
+
+/// Link a Stellar account to the signed-in player with a signed SEP-10
+/// challenge, for players who signed up with a password
+#[utoipa::path(
+    post,
+    path = "/v1/auth/sep10/link",
+    request_body = Sep10TokenRequest,
+    responses(
+        (status = 200, description = "Account linked", body = Sep10LinkResponse),
+        (status = 401, description = "Not signed in, or invalid, expired or reused challenge", body = ErrorResponse),
+        (status = 404, description = "Player not found"),
+        (status = 409, description = "Account linked to another player, or player linked to another account"),
+        (status = 503, description = "SEP-10 sign-in is not configured", body = ErrorResponse)
+    ),
+    security(("jwt_auth" = [])),
+    tag = "Authentication"
+)]
+#[post("")]
+pub async fn sep10_link(
+    req: HttpRequest,
+    db: web::Data<DatabaseConnection>,
+    sep10: Option<web::Data<Sep10Service>>,
+    payload: web::Json<Sep10TokenRequest>,
+) -> HttpResponse {
+    let Some(sep10) = sep10 else {
+        return sep10_disabled();
+    };
+    // Set by the JWT middleware for tokens issued to a player
+    let Some(player_id) = req.extensions().get::<Uuid>().copied() else {
+        return HttpResponse::Unauthorized().json(ErrorResponse {
+            message: "Authentication required".to_string(),
+            code: "UNAUTHORIZED".to_string(),
+        });
+    };
+
+    let account = match sep10.verify(&payload.transaction) {
+        Ok(account) => account,
+        Err(e) => {
+            return HttpResponse::Unauthorized().json(ErrorResponse {
+                message: e.to_string(),
+                code: "INVALID_CHALLENGE".to_string(),
+            });
+        }
+    };
+
+    match link_stellar_address(&db, player_id, &account).await {
+        Ok(player) => HttpResponse::Ok().json(Sep10LinkResponse {
+            player_id: player.id,
+            stellar_address: account,
+        }),
+        Err(e) => e.error_response(),
+    }
+}
+
+fn sep10_disabled() -> HttpResponse {
+    HttpResponse::ServiceUnavailable().json(ErrorResponse {
+        message: "SEP-10 sign-in is not configured".to_string(),
+        code: "SEP10_DISABLED".to_string(),
+    })
+}
+
 /// Refresh tokens - rotate refresh token and get new access token
 #[utoipa::path(
     post,
@@ -220,8 +395,12 @@ pub async fn refresh(
         }
     };
 
-    // Generate new access token
-    let new_access_token = match jwt_service.generate_token(claims.user_id, &claims.username) {
+    // Generate new access token, keeping the player of wallet sessions
+    let new_access_token = match claims.player_id {
+        Some(player_id) => jwt_service.generate_player_token(claims.user_id, &claims.username, player_id),
+        None => jwt_service.generate_token(claims.user_id, &claims.username),
+    };
+    let new_access_token = match new_access_token {
         Ok(t) => t,
         Err(_) => {
             return HttpResponse::InternalServerError().json(ErrorResponse {
@@ -6,7 +6,7 @@ use dto::{
     games::{
         CreateGameRequest, GameDisplayDTO, MakeMoveRequest, JoinGameRequest,
         GameStatus, ListGamesQuery, ImportGameRequest, ImportGameResponse,
-        CompleteGameRequest, CompleteGameResponse,
+        CompleteGameRequest, CompleteGameResponse, GameProofResponse, LinkChainGameRequest,
     },
     responses::{InvalidCredentialsResponse, NotFoundResponse},
 };
@@ -15,7 +15,10 @@ use serde_json::json;
 use validator::Validate;
 use uuid::Uuid;
 use sea_orm::DatabaseConnection;
+use service::disconnect::DisconnectTracker;
 use service::games::GameService;
+use service::proof_anchor::ProofRegistry;
+use std::sync::Arc;
 
 // ---------------------------------------------------------------------------
 // Helper: extract authenticated player UUID inserted by the JWT middleware.
@@ -31,6 +34,16 @@ fn authenticated_player(req: &HttpRequest) -> Result<Uuid, HttpResponse> {
         })
 }
 
+// ---------------------------------------------------------------------------
+// Helper: a finished game can no longer be abandoned, so drop its pending
+// disconnect slashes.
+// ---------------------------------------------------------------------------
+fn clear_disconnects(req: &HttpRequest, game_id: Uuid) {
+    if let Some(tracker) = req.app_data::<web::Data<Arc<DisconnectTracker>>>() {
+        tracker.clear_game(&game_id.to_string());
+    }
+}
+
 // ---------------------------------------------------------------------------
 // POST /v1/games
 // ---------------------------------------------------------------------------
@@ -310,6 +323,66 @@ pub async fn join_game(
     }
 }
 
+// ---------------------------------------------------------------------------
+// POST /v1/games/{id}/chain
+// ---------------------------------------------------------------------------
+#[utoipa::path(
+    post,
+    path = "/v1/games/{id}/chain",
+    params(
+        ("id" = String, Path, description = "Game ID in UUID format", format = "uuid")
+    ),
+    request_body = LinkChainGameRequest,
+    responses(
+        (status = 200, description = "Game linked to its game_contract game"),
+        (status = 400, description = "Chain game not indexed or not between this game's players", body = InvalidCredentialsResponse),
+        (status = 403, description = "Not a player in this game"),
+        (status = 404, description = "Game not found", body = NotFoundResponse)
+    ),
+    security(("jwt_auth" = [])),
+    tag = "Games"
+)]
+#[post("/{id}/chain")]
+pub async fn link_chain_game(
+    req: HttpRequest,
+    id: Path<Uuid>,
+    payload: Json<LinkChainGameRequest>,
+    db: web::Data<DatabaseConnection>,
+) -> HttpResponse {
+    let player_id = match authenticated_player(&req) {
+        Ok(id) => id,
+        Err(resp) => return resp,
+    };
+
+    let game_id = id.into_inner();
+
+    match GameService::link_chain_game(db.get_ref(), game_id, player_id, payload.chain_game_id).await {
+        Ok(game) => HttpResponse::Ok().json(json!({
+            "message": "Game linked successfully",
+            "data": {
+                "chain_game_id": game.chain_game_id,
+                "white_address": game.white_address,
+                "black_address": game.black_address
+            }
+        })),
+        Err(ApiError::NotFound(_)) => HttpResponse::NotFound().json(json!({
+            "message": "Game not found"
+        })),
+        Err(ApiError::Forbidden(_)) => HttpResponse::Forbidden().json(json!({
+            "message": "You are not a participant in this game"
+        })),
+        Err(ApiError::BadRequest(msg)) => HttpResponse::BadRequest().json(json!({
+            "message": msg
+        })),
+        Err(e) => {
+            eprintln!("link_chain_game error: {e}");
+            HttpResponse::InternalServerError().json(json!({
+                "message": "Failed to link game"
+            }))
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // DELETE /v1/games/{id}
 // ---------------------------------------------------------------------------
@@ -340,10 +413,13 @@ pub async fn abandon_game(
     let game_id = id.into_inner();
 
     match GameService::abandon_game(db.get_ref(), game_id, player_id).await {
-        Ok(_) => HttpResponse::Ok().json(json!({
-            "message": "Game abandoned successfully",
-            "data": {}
-        })),
+        Ok(_) => {
+            clear_disconnects(&req, game_id);
+            HttpResponse::Ok().json(json!({
+                "message": "Game abandoned successfully",
+                "data": {}
+            }))
+        }
         Err(ApiError::NotFound(_)) => HttpResponse::NotFound().json(json!({
             "message": "Game not found"
         })),
@@ -540,6 +616,7 @@ pub async fn complete_game(
     // Complete the game and update ratings
     match GameService::complete_game(db.get_ref(), game_id, result_enum.clone(), Some(rating_config)).await {
         Ok((white_new_rating, black_new_rating)) => {
+            clear_disconnects(&req, game_id);
             let white_change = white_new_rating - white_old_rating;
             let black_change = black_new_rating - black_old_rating;
 
@@ -588,4 +665,49 @@ pub async fn complete_game(
             })
         }
     }
-}
\ No newline at end of file
+}
+// ---------------------------------------------------------------------------
+// GET /v1/proofs/{id}  (public)
+// ---------------------------------------------------------------------------
+#[utoipa::path(
+    get,
+    path = "/v1/proofs/{id}",
+    params(
+        ("id" = String, Path, description = "Game ID in UUID format", format = "uuid")
+    ),
+    responses(
+        (status = 200, description = "Proof of game, re-verified against the stored game and game_registry", body = GameProofResponse),
+        (status = 404, description = "Game not found or not finished", body = NotFoundResponse)
+    ),
+    tag = "Games"
+)]
+#[get("/{id}")]
+pub async fn get_game_proof(
+    id: Path<Uuid>,
+    db: web::Data<DatabaseConnection>,
+    registry: Option<web::Data<dyn ProofRegistry>>,
+) -> HttpResponse {
+    let game_id = id.into_inner();
+    let registry = registry.as_ref().map(|registry| registry.get_ref());
+
+    match GameService::get_game_proof(db.get_ref(), game_id, registry).await {
+        Ok((proof, check)) => HttpResponse::Ok().json(GameProofResponse {
+            game_id,
+            move_hash: proof.move_hash,
+            meta_hash: proof.meta_hash,
+            proof_hash: proof.proof_hash,
+            valid: check.valid,
+            anchored: check.anchored,
+            epoch: check.epoch,
+        }),
+        Err(ApiError::NotFound(msg)) => HttpResponse::NotFound().json(json!({
+            "message": msg
+        })),
+        Err(e) => {
+            eprintln!("get_game_proof error: {e}");
+            HttpResponse::InternalServerError().json(json!({
+                "message": "Failed to fetch game proof"
+            }))
+        }
+    }
+}
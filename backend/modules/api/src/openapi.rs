@@ -40,11 +40,16 @@ impl Modify for SecurityAddon {
         games::make_move,
         games::list_games,
         games::join_game,
+        games::link_chain_game,
         games::abandon_game,
+        games::get_game_proof,
         
         // Authentication endpoints
         auth::login,
         auth::register,
+        auth::sep10_challenge,
+        auth::sep10_token,
+        auth::sep10_link,
         
         // AI suggestion endpoints
         ai::get_ai_suggestion,
@@ -68,9 +73,11 @@ impl Modify for SecurityAddon {
             dto::games::GameDisplayDTO,
             dto::games::MakeMoveRequest,
             dto::games::JoinGameRequest,
+            dto::games::LinkChainGameRequest,
             dto::games::GameStatus,
             dto::games::GameResult,
             dto::games::ListGamesQuery,
+            dto::games::GameProofResponse,
             
             // Auth schemas
             dto::auth::LoginRequest,
@@ -78,9 +85,13 @@ impl Modify for SecurityAddon {
             dto::auth::RegisterRequest,
             dto::auth::TokenResponse,
             dto::auth::UserInfo,
+            dto::auth::Sep10ChallengeResponse,
+            dto::auth::Sep10TokenRequest,
+            dto::auth::Sep10LinkResponse,
             
             // AI schemas
             dto::ai::AiSuggestionRequest,
+            dto::ai::AgentPlayRequest,
             dto::ai::AiSuggestionResponse,
             dto::ai::PositionAnalysisRequest,
             dto::ai::PositionAnalysisResponse,
@@ -8,22 +8,30 @@ use std::env;
 use std::sync::Arc;
 use security::JwtService;
 use security::JwtAuthMiddleware;
+use security::SettlementSigner;
+use security::Sep10Service;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 use utoipa_redoc::{Redoc, Servable};
 use actix::Actor;
 use crate::players::{add_player, delete_player, find_player_by_id, update_player};
-use crate::games::{create_game, get_game, make_move, list_games, join_game, abandon_game, import_game, complete_game};
-use crate::auth::{login, register, refresh, logout};
+use crate::games::{create_game, get_game, make_move, list_games, join_game, link_chain_game, abandon_game, import_game, complete_game, get_game_proof};
+use crate::auth::{login, register, refresh, logout, sep10_challenge, sep10_token, sep10_link};
 use crate::ai::{get_ai_suggestion, analyze_position};
 use crate::ws::{LobbyState, ws_route};
 use crate::config::AppConfig;
 use actix_governor::{Governor, GovernorConfigBuilder};
 use matchmaking::service::MatchmakingService;
 use matchmaking::redis::{create_redis_pool, test_redis_connection};
+use challenge::ledger::FileRewardLedger;
 use challenge::puzzle_validation::PuzzleValidationService;
+use challenge::voucher::VoucherSigner;
 use challenge::api::configure_puzzle_routes;
 use st_core::endpoint::configure as configure_nft_routes;
+use st_core::StellarRpcClient;
+use service::disconnect::{ChainSlasher, DisconnectSlasher, DisconnectTracker, LoggingSlasher};
+use service::engine_service::{AgentDirectory, ChainAgentDirectory};
+use service::proof_anchor::{ChainProofRegistry, ProofRegistry};
 
 use crate::openapi::ApiDoc;
 
@@ -98,8 +106,117 @@ pub async fn main() -> std::io::Result<()> {
     
     let matchmaking_service = MatchmakingService::new(redis_pool);
 
+    // Settlement signer for claim_win / claim_draw / claim_puzzle_reward payloads
+    let settlement_signer = match SettlementSigner::from_env() {
+        Ok(Some(signer)) => {
+            eprintln!("Settlement signing keys: {}", signer.key_ids().join(", "));
+            Some(Arc::new(signer))
+        }
+        Ok(None) => {
+            eprintln!("SETTLEMENT_SIGNING_KEYS not set; settlement signing disabled");
+            None
+        }
+        Err(e) => {
+            eprintln!("Settlement signing disabled: {}", e);
+            None
+        }
+    };
+
+    // SEP-10 wallet sign-in, from SEP10_SIGNING_KEY and SEP10_HOME_DOMAIN
+    let sep10 = match Sep10Service::from_env() {
+        Ok(Some(sep10)) => {
+            eprintln!("SEP-10 wallet sign-in enabled, server account {}", sep10.server_account());
+            Some(Arc::new(sep10))
+        }
+        Ok(None) => {
+            eprintln!("SEP10_SIGNING_KEY not set; SEP-10 wallet sign-in disabled");
+            None
+        }
+        Err(e) => {
+            eprintln!("SEP-10 wallet sign-in disabled: {}", e);
+            None
+        }
+    };
+
     // Initialize Puzzle Validation Service
-    let puzzle_service = Arc::new(PuzzleValidationService::new(jwt_secret.clone()));
+    let mut puzzle_service = PuzzleValidationService::new(jwt_secret.clone());
+    let ledger = FileRewardLedger::from_env();
+    match &ledger {
+        Ok(Some(_)) => {}
+        Ok(None) => eprintln!(
+            "PUZZLE_REWARD_LEDGER not set; puzzle rewards are only deduplicated until restart"
+        ),
+        Err(e) => eprintln!("Puzzle reward ledger unavailable: {}", e),
+    }
+    let voucher_signer = match (ledger, VoucherSigner::from_env()) {
+        // Without its ledger a restart would let every puzzle pay out again
+        (Err(e), _) => Err(e),
+        (Ok(ledger), signer) => {
+            if let Some(ledger) = ledger {
+                puzzle_service = puzzle_service.with_reward_ledger(Arc::new(ledger));
+            }
+            match signer {
+                Ok(None) => Ok(settlement_signer.clone().map(VoucherSigner::new)),
+                other => other,
+            }
+        }
+    };
+    match voucher_signer {
+        Ok(Some(signer)) => {
+            eprintln!("Puzzle reward vouchers enabled, admin key {}", signer.public_key_hex());
+            puzzle_service = puzzle_service.with_voucher_signer(signer);
+        }
+        Ok(None) => eprintln!("No signing key configured; puzzle reward vouchers disabled"),
+        Err(e) => eprintln!("Puzzle reward vouchers disabled: {}", e),
+    }
+    let puzzle_service = Arc::new(puzzle_service);
+
+    // Horizon / Soroban RPC, from HORIZON_URL and SOROBAN_RPC_URL
+    let stellar_rpc = StellarRpcClient::from_env();
+    eprintln!("Using Horizon at {}", stellar_rpc.config().horizon_url);
+
+    // Disconnect slashing, from GAME_CONTRACT_ID and SLASH_REPORTER_KEY
+    let slasher: Arc<dyn DisconnectSlasher> =
+        match ChainSlasher::from_env(db.clone(), stellar_rpc.clone()) {
+            Ok(Some(slasher)) => Arc::new(slasher),
+            Ok(None) => {
+                eprintln!("SLASH_REPORTER_KEY not set; disconnects are only logged");
+                Arc::new(LoggingSlasher)
+            }
+            Err(e) => {
+                eprintln!("Disconnect slashing disabled: {}", e);
+                Arc::new(LoggingSlasher)
+            }
+        };
+    let disconnect_tracker = Arc::new(DisconnectTracker::new(slasher));
+
+    // Proof checks against game_registry, from GAME_REGISTRY_CONTRACT_ID
+    let proof_registry: Option<Arc<dyn ProofRegistry>> =
+        match ChainProofRegistry::from_env(stellar_rpc.clone()) {
+            Ok(Some(registry)) => Some(Arc::new(registry)),
+            Ok(None) => {
+                eprintln!("GAME_REGISTRY_CONTRACT_ID not set; game proofs are not checked on chain");
+                None
+            }
+            Err(e) => {
+                eprintln!("On-chain proof checks disabled: {}", e);
+                None
+            }
+        };
+
+    // AI agent play, from AGENT_NFT_CONTRACT_ID and AGENT_SOURCE_ACCOUNT
+    let agent_directory: Option<Arc<dyn AgentDirectory>> =
+        match ChainAgentDirectory::from_env(stellar_rpc.clone()) {
+            Ok(Some(directory)) => Some(Arc::new(directory)),
+            Ok(None) => {
+                eprintln!("AGENT_NFT_CONTRACT_ID not set; AI agent play disabled");
+                None
+            }
+            Err(e) => {
+                eprintln!("AI agent play disabled: {}", e);
+                None
+            }
+        };
 
     eprintln!("Starting HTTP server on {}", server_addr);
 
@@ -110,6 +227,11 @@ pub async fn main() -> std::io::Result<()> {
         let jwt_secret = jwt_secret.clone();
         let matchmaking_service = matchmaking_service.clone();
         let puzzle_service = puzzle_service.clone();
+        let disconnect_tracker = disconnect_tracker.clone();
+        let stellar_rpc = stellar_rpc.clone();
+        let sep10 = sep10.clone();
+        let agent_directory = agent_directory.clone();
+        let proof_registry = proof_registry.clone();
         
         // Configure CORS middleware with environment variables for flexibility
         let cors = {
@@ -150,15 +272,31 @@ pub async fn main() -> std::io::Result<()> {
             .finish()
             .unwrap();
 
-        App::new()
+        let mut app = App::new()
             // Global middleware
-            .wrap(cors)
+            .wrap(cors);
+        // Handlers answer 503 when SEP-10 is not configured
+        if let Some(sep10) = sep10 {
+            app = app.app_data(web::Data::from(sep10));
+        }
+        // The AI endpoint answers 503 for agent play when this is missing
+        if let Some(agent_directory) = agent_directory {
+            app = app.app_data(web::Data::from(agent_directory));
+        }
+        // Without a registry every proof reports as not anchored
+        if let Some(proof_registry) = proof_registry {
+            app = app.app_data(web::Data::from(proof_registry));
+        }
+
+        app
             // App data
             .app_data(web::Data::from(db.clone()))
             .app_data(web::Data::new(jwt_service.clone()))
             .app_data(web::Data::new(lobby.clone()))
+            .app_data(web::Data::new(disconnect_tracker.clone()))
             .app_data(web::Data::new(matchmaking_service.clone()))
             .app_data(web::Data::new(puzzle_service.clone()))
+            .app_data(web::Data::new(stellar_rpc.clone()))
             // Register your routes
             .route("/health", web::get().to(health))
             .route("/", web::get().to(greet))
@@ -182,11 +320,18 @@ pub async fn main() -> std::io::Result<()> {
                     .service(get_game)
                     .service(list_games)
                     .service(join_game)
+                    .service(link_chain_game)
                     .service(make_move)
                     .service(abandon_game)
                     .service(import_game)
                     .service(complete_game),
             )
+            // Proof of game routes (public, so anyone can check an anchored proof)
+            .service(
+                web::scope("/v1/proofs")
+                    .wrap(Governor::new(&game_governor_conf))
+                    .service(get_game_proof),
+            )
             // Auth routes
             .service(
                 web::scope("/v1/auth")
@@ -195,6 +340,13 @@ pub async fn main() -> std::io::Result<()> {
                     .service(register)
                     .service(refresh)
                     .service(logout)
+                    .service(sep10_challenge)
+                    .service(sep10_token)
+                    .service(
+                        web::scope("/sep10/link")
+                            .wrap(JwtAuthMiddleware::new(jwt_secret.clone(), jwt_expiration))
+                            .service(sep10_link),
+                    )
             )
             // WebSocket routes
             .service(
@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use actix_web::{dev::Service, http::StatusCode, test, web, App, HttpMessage};
+use async_trait::async_trait;
+use db_entity::player;
+use engine::EngineError;
+use sea_orm::{DatabaseBackend, MockDatabase};
+use serde_json::json;
+use service::engine_service::AgentDirectory;
+use uuid::Uuid;
+
+use crate::ai::get_ai_suggestion;
+
+const CALLER: &str = "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H";
+const RENTER: &str = "GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA";
+
+/// Agent 7 is rented out to `RENTER`.
+struct RentedAgent;
+
+#[async_trait]
+impl AgentDirectory for RentedAgent {
+    async fn user_of(&self, _nft_id: u64) -> Result<String, EngineError> {
+        Ok(RENTER.to_string())
+    }
+
+    async fn engine_config(&self, _nft_id: u64) -> Result<Option<[u8; 32]>, EngineError> {
+        Ok(Some([0; 32]))
+    }
+}
+
+fn caller(player_id: Uuid) -> player::Model {
+    player::Model {
+        id: player_id,
+        username: CALLER.to_string(),
+        email: format!("{}@stellar.invalid", CALLER.to_lowercase()),
+        password_hash: Vec::new(),
+        biography: String::new(),
+        country: String::new(),
+        flair: String::new(),
+        real_name: String::new(),
+        location: None,
+        fide_rating: None,
+        elo_rating: 1200,
+        social_links: None,
+        is_enabled: true,
+        stellar_address: Some(CALLER.to_string()),
+        user_id: 1,
+    }
+}
+
+fn agent_request() -> serde_json::Value {
+    json!({
+        "fen": "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "depth": 5,
+        "agent": {
+            "nft_id": 7,
+            "engine_config": r#"{"engine":"stockfish","skill_level":12,"opening_book":null,"style":{}}"#
+        }
+    })
+}
+
+#[actix_web::test]
+async fn test_agent_suggestion_rejected_for_non_owner() {
+    let player_id = Uuid::new_v4();
+    let db = MockDatabase::new(DatabaseBackend::Postgres)
+        .append_query_results([vec![caller(player_id)]])
+        .into_connection();
+    let agents: Arc<dyn AgentDirectory> = Arc::new(RentedAgent);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .app_data(web::Data::from(agents))
+            .wrap_fn(move |req, srv| {
+                // Stands in for the JWT middleware
+                req.extensions_mut().insert(player_id);
+                srv.call(req)
+            })
+            .service(web::scope("/v1/ai").service(get_ai_suggestion)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/ai/suggest")
+        .set_json(agent_request())
+        .to_request();
+    let res = app.call(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = test::read_body_json(res).await;
+    assert!(body["error"].as_str().unwrap().contains("not the current user of agent 7"));
+}
+
+#[actix_web::test]
+async fn test_agent_suggestion_requires_player_session() {
+    let db = MockDatabase::new(DatabaseBackend::Postgres).into_connection();
+    let agents: Arc<dyn AgentDirectory> = Arc::new(RentedAgent);
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(db))
+            .app_data(web::Data::from(agents))
+            .service(web::scope("/v1/ai").service(get_ai_suggestion)),
+    )
+    .await;
+
+    let req = test::TestRequest::post()
+        .uri("/v1/ai/suggest")
+        .set_json(agent_request())
+        .to_request();
+    let res = app.call(req).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+}
@@ -1,5 +1,7 @@
 #[cfg(test)]
 mod rate_limit;
+#[cfg(test)]
+mod ai;
 
 #[cfg(test)]
 mod tests {
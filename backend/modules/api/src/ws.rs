@@ -9,6 +9,8 @@ use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
 use actix_web::error::ErrorUnauthorized;
 use serde_json::{Value, json};
 use uuid::Uuid;
+use std::sync::Arc;
+use service::disconnect::DisconnectTracker;
 
 // For Redis Pub/Sub
 // Redis pub/sub integration removed for test stability in CI environment
@@ -107,6 +109,7 @@ pub struct WsSession {
     pub username: String,
     pub session_id: String,
     pub redis_sub_task: Option<JoinHandle<()>>,     // Placeholder for compatibility
+    pub disconnects: Option<Arc<DisconnectTracker>>,
 }
 
 impl WsSession {
@@ -147,6 +150,13 @@ impl Actor for WsSession {
         let addr = ctx.address().recipient();
         self.lobby.do_send(Connect { game_id: self.game_id.clone(), addr });
 
+        // A player coming back within the grace period is not slashed
+        if let Some(tracker) = &self.disconnects {
+            if tracker.record_connect(&self.game_id, self.user_id) {
+                log::info!("User {} reconnected to game {}", self.username, self.game_id);
+            }
+        }
+
         // Redis pub/sub subscription intentionally disabled here; leave placeholder
         self.redis_sub_task = None;
     }
@@ -170,6 +180,10 @@ impl Actor for WsSession {
         
         let addr = ctx.address().recipient();
         self.lobby.do_send(Disconnect { game_id: self.game_id.clone(), addr });
+        // Start the reconnect grace period; see service::disconnect
+        if let Some(tracker) = &self.disconnects {
+            tracker.record_disconnect(&self.game_id, self.user_id);
+        }
         // Cancel Redis subscription task if running
         if let Some(handle) = self.redis_sub_task.take() {
             handle.abort();
@@ -267,6 +281,9 @@ pub async fn ws_route(
             username: claims.username,
             session_id,
             redis_sub_task: None,
+            disconnects: req
+                .app_data::<web::Data<Arc<DisconnectTracker>>>()
+                .map(|tracker| tracker.get_ref().clone()),
         },
         &req,
         stream,
@@ -3,8 +3,9 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use crate::puzzle_validation::{
     PuzzleValidationService, PuzzleSubmission, PuzzleValidationResult, 
-    PuzzleRewardToken, Puzzle, ChessMove
+    PuzzleRewardToken, PuzzleValidationError, Puzzle, ChessMove
 };
+use crate::voucher::PuzzleVoucher;
 use security::jwt::Claims;
 use std::sync::Arc;
 
@@ -38,6 +39,20 @@ pub struct VerifyTokenResponse {
     pub error: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct VoucherRequest {
+    pub token: String,
+    /// Stellar address (`G...` or `C...`) the reward is paid to
+    pub recipient: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VoucherResponse {
+    pub success: bool,
+    pub voucher: Option<PuzzleVoucher>,
+    pub error: Option<String>,
+}
+
 /// Submit puzzle solution
 pub async fn submit_solution(
     req: actix_web::HttpRequest,
@@ -69,7 +84,7 @@ pub async fn submit_solution(
             }))
         }
         Err(e) => {
-            Ok(HttpResponse::BadRequest().json(SubmitSolutionResponse {
+            let response = SubmitSolutionResponse {
                 success: false,
                 result: PuzzleValidationResult {
                     success: false,
@@ -78,7 +93,12 @@ pub async fn submit_solution(
                     reward_token: None,
                     reward_amount: None,
                 },
-            }))
+            };
+            Ok(match e {
+                PuzzleValidationError::AlreadyRewarded => HttpResponse::Conflict().json(response),
+                PuzzleValidationError::LedgerFailed(_) => HttpResponse::InternalServerError().json(response),
+                _ => HttpResponse::BadRequest().json(response),
+            })
         }
     }
 }
@@ -136,6 +156,44 @@ pub async fn verify_reward_token(
     }
 }
 
+/// Exchange a reward token for a signed voucher to submit with
+/// `game_contract::claim_puzzle_reward`
+pub async fn issue_voucher(
+    req: actix_web::HttpRequest,
+    puzzle_service: web::Data<Arc<PuzzleValidationService>>,
+    voucher_request: web::Json<VoucherRequest>,
+) -> Result<HttpResponse, Error> {
+    let claims = req.extensions().get::<Claims>()
+        .ok_or_else(|| actix_web::error::ErrorUnauthorized("User not authenticated"))?
+        .clone();
+
+    match puzzle_service.issue_voucher(&voucher_request.token, claims.user_id, &voucher_request.recipient) {
+        Ok(voucher) => {
+            Ok(HttpResponse::Ok().json(VoucherResponse {
+                success: true,
+                voucher: Some(voucher),
+                error: None,
+            }))
+        }
+        Err(e) => {
+            let response = VoucherResponse {
+                success: false,
+                voucher: None,
+                error: Some(format!("Voucher issuance failed: {}", e)),
+            };
+            Ok(match e {
+                PuzzleValidationError::VouchersDisabled => HttpResponse::ServiceUnavailable().json(response),
+                PuzzleValidationError::RewardNotOwned => HttpResponse::Forbidden().json(response),
+                PuzzleValidationError::AlreadyRewarded => HttpResponse::Conflict().json(response),
+                PuzzleValidationError::SigningFailed(_) | PuzzleValidationError::LedgerFailed(_) => {
+                    HttpResponse::InternalServerError().json(response)
+                }
+                _ => HttpResponse::BadRequest().json(response),
+            })
+        }
+    }
+}
+
 /// Configure puzzle routes
 pub fn configure_puzzle_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
@@ -144,5 +202,6 @@ pub fn configure_puzzle_routes(cfg: &mut web::ServiceConfig) {
             .route("/{puzzle_id}", web::get().to(get_puzzle_by_id))
             .route("/submit", web::post().to(submit_solution))
             .route("/verify-token", web::post().to(verify_reward_token))
+            .route("/voucher", web::post().to(issue_voucher))
     );
 }
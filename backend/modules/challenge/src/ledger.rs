@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::puzzle_validation::PuzzleValidationError;
+
+/// Environment variable naming the file puzzle rewards are recorded in
+pub const LEDGER_PATH_ENV: &str = "PUZZLE_REWARD_LEDGER";
+
+/// What happened to a (user, puzzle) pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerEvent {
+    /// A reward token was minted for a correct solution
+    Solved,
+    /// A reward token was exchanged for a voucher
+    Claimed,
+}
+
+/// One line of the ledger file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    pub event: LedgerEvent,
+    pub user_id: i32,
+    pub puzzle_id: Uuid,
+    pub token_id: String,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Records which (user, puzzle) pairs were rewarded, so each pays out once.
+///
+/// Vouchers take their nonce from the token id, so a second token for the
+/// same pair would be a second, separately claimable voucher.
+pub trait RewardLedger: Send + Sync {
+    /// Record that `user_id` solved `puzzle_id` and is given `token_id`.
+    /// `false` if the pair was rewarded before.
+    fn record_solved(
+        &self,
+        user_id: i32,
+        puzzle_id: Uuid,
+        token_id: &str,
+    ) -> Result<bool, PuzzleValidationError>;
+
+    /// Record a voucher for `token_id`. `false` if the pair already had a
+    /// voucher for another token; the same token may be exchanged again, as
+    /// its vouchers share one nonce.
+    fn record_claim(
+        &self,
+        user_id: i32,
+        puzzle_id: Uuid,
+        token_id: &str,
+    ) -> Result<bool, PuzzleValidationError>;
+}
+
+/// Token ids per (user, puzzle) pair, for each event
+#[derive(Debug, Default)]
+struct Pairs {
+    solved: HashMap<(i32, Uuid), String>,
+    claimed: HashMap<(i32, Uuid), String>,
+}
+
+impl Pairs {
+    /// Whether `entry` may be recorded
+    fn allows(&self, entry: &LedgerEntry) -> bool {
+        let key = (entry.user_id, entry.puzzle_id);
+        match entry.event {
+            LedgerEvent::Solved => !self.solved.contains_key(&key),
+            LedgerEvent::Claimed => self
+                .claimed
+                .get(&key)
+                .is_none_or(|token_id| *token_id == entry.token_id),
+        }
+    }
+
+    fn insert(&mut self, entry: &LedgerEntry) {
+        let key = (entry.user_id, entry.puzzle_id);
+        let pairs = match entry.event {
+            LedgerEvent::Solved => &mut self.solved,
+            LedgerEvent::Claimed => &mut self.claimed,
+        };
+        pairs.entry(key).or_insert_with(|| entry.token_id.clone());
+    }
+}
+
+fn entry(event: LedgerEvent, user_id: i32, puzzle_id: Uuid, token_id: &str) -> LedgerEntry {
+    LedgerEntry {
+        event,
+        user_id,
+        puzzle_id,
+        token_id: token_id.to_string(),
+        recorded_at: Utc::now(),
+    }
+}
+
+/// Appends entries to a JSON-lines file, reloaded on open
+#[derive(Debug)]
+pub struct FileRewardLedger {
+    inner: Mutex<(Pairs, File)>,
+}
+
+impl FileRewardLedger {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, PuzzleValidationError> {
+        let path = path.as_ref();
+        let mut pairs = Pairs::default();
+        if path.exists() {
+            let file = File::open(path).map_err(ledger_error)?;
+            for line in BufReader::new(file).lines() {
+                let line = line.map_err(ledger_error)?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let entry: LedgerEntry = serde_json::from_str(&line).map_err(ledger_error)?;
+                pairs.insert(&entry);
+            }
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(ledger_error)?;
+        Ok(Self {
+            inner: Mutex::new((pairs, file)),
+        })
+    }
+
+    /// Open the ledger named by `PUZZLE_REWARD_LEDGER`; `None` if unset
+    pub fn from_env() -> Result<Option<Self>, PuzzleValidationError> {
+        match std::env::var(LEDGER_PATH_ENV) {
+            Ok(path) => Self::open(path).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn record(&self, entry: LedgerEntry) -> Result<bool, PuzzleValidationError> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let (pairs, file) = &mut *inner;
+        if !pairs.allows(&entry) {
+            return Ok(false);
+        }
+        let line = serde_json::to_string(&entry).map_err(ledger_error)?;
+        writeln!(file, "{}", line)
+            .and_then(|_| file.sync_data())
+            .map_err(ledger_error)?;
+        pairs.insert(&entry);
+        Ok(true)
+    }
+}
+
+impl RewardLedger for FileRewardLedger {
+    fn record_solved(
+        &self,
+        user_id: i32,
+        puzzle_id: Uuid,
+        token_id: &str,
+    ) -> Result<bool, PuzzleValidationError> {
+        self.record(entry(LedgerEvent::Solved, user_id, puzzle_id, token_id))
+    }
+
+    fn record_claim(
+        &self,
+        user_id: i32,
+        puzzle_id: Uuid,
+        token_id: &str,
+    ) -> Result<bool, PuzzleValidationError> {
+        self.record(entry(LedgerEvent::Claimed, user_id, puzzle_id, token_id))
+    }
+}
+
+/// Keeps entries in memory only; rewards are refused again after a restart
+#[derive(Debug, Default)]
+pub struct MemoryRewardLedger {
+    pairs: Mutex<Pairs>,
+}
+
+impl MemoryRewardLedger {
+    fn record(&self, entry: LedgerEntry) -> Result<bool, PuzzleValidationError> {
+        let mut pairs = self.pairs.lock().unwrap_or_else(|e| e.into_inner());
+        if !pairs.allows(&entry) {
+            return Ok(false);
+        }
+        pairs.insert(&entry);
+        Ok(true)
+    }
+}
+
+impl RewardLedger for MemoryRewardLedger {
+    fn record_solved(
+        &self,
+        user_id: i32,
+        puzzle_id: Uuid,
+        token_id: &str,
+    ) -> Result<bool, PuzzleValidationError> {
+        self.record(entry(LedgerEvent::Solved, user_id, puzzle_id, token_id))
+    }
+
+    fn record_claim(
+        &self,
+        user_id: i32,
+        puzzle_id: Uuid,
+        token_id: &str,
+    ) -> Result<bool, PuzzleValidationError> {
+        self.record(entry(LedgerEvent::Claimed, user_id, puzzle_id, token_id))
+    }
+}
+
+fn ledger_error(e: impl std::fmt::Display) -> PuzzleValidationError {
+    PuzzleValidationError::LedgerFailed(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_ledger_keeps_pairs_across_reopen() {
+        let path = std::env::temp_dir().join(format!("puzzle_ledger_{}.jsonl", Uuid::new_v4()));
+        let puzzle = Uuid::new_v4();
+
+        let ledger = FileRewardLedger::open(&path).unwrap();
+        assert!(ledger.record_solved(1, puzzle, "token-a").unwrap());
+        assert!(ledger.record_claim(1, puzzle, "token-a").unwrap());
+        drop(ledger);
+
+        let ledger = FileRewardLedger::open(&path).unwrap();
+        assert!(!ledger.record_solved(1, puzzle, "token-b").unwrap());
+        assert!(ledger.record_claim(1, puzzle, "token-a").unwrap());
+        assert!(!ledger.record_claim(1, puzzle, "token-b").unwrap());
+        assert!(ledger.record_solved(2, puzzle, "token-c").unwrap());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
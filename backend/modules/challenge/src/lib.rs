@@ -1,2 +1,4 @@
 pub mod api;
+pub mod ledger;
 pub mod puzzle_validation;
+pub mod voucher;
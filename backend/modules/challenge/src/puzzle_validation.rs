@@ -4,6 +4,12 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use jsonwebtoken::{encode, Header, EncodingKey};
 use security::jwt::JwtService;
+use std::sync::Arc;
+use crate::ledger::{MemoryRewardLedger, RewardLedger};
+use crate::voucher::{PuzzleVoucher, VoucherSigner, STROOPS_PER_XLM};
+
+/// Reward for a solved puzzle: 0.01 XLM
+pub const PUZZLE_REWARD_STROOPS: i64 = 100_000;
 
 /// Error types for puzzle validation
 #[derive(Error, Debug)]
@@ -22,6 +28,20 @@ pub enum PuzzleValidationError {
     MoveValidationFailed(String),
     #[error("Token generation failed")]
     TokenGenerationFailed,
+    #[error("Invalid recipient address: {0}")]
+    InvalidRecipient(String),
+    #[error("Invalid voucher signing key")]
+    InvalidSigningKey,
+    #[error("Voucher signing failed: {0}")]
+    SigningFailed(String),
+    #[error("Reward vouchers are not enabled")]
+    VouchersDisabled,
+    #[error("Reward token belongs to another user")]
+    RewardNotOwned,
+    #[error("Puzzle reward already issued")]
+    AlreadyRewarded,
+    #[error("Reward ledger failed: {0}")]
+    LedgerFailed(String),
 }
 
 /// Puzzle data structure
@@ -80,7 +100,8 @@ pub struct PuzzleRewardToken {
     pub user_id: i32,
     pub username: String,
     pub completed_at: DateTime<Utc>,
-    pub reward_amount: f64,
+    /// Reward in stroops
+    pub reward_amount: i64,
     pub token_id: String,
 }
 
@@ -88,6 +109,8 @@ pub struct PuzzleRewardToken {
 pub struct PuzzleValidationService {
     jwt_service: JwtService,
     puzzles: Vec<Puzzle>,
+    voucher_signer: Option<VoucherSigner>,
+    ledger: Arc<dyn RewardLedger>,
 }
 
 impl PuzzleValidationService {
@@ -99,6 +122,8 @@ impl PuzzleValidationService {
         Self {
             jwt_service,
             puzzles,
+            voucher_signer: None,
+            ledger: Arc::new(MemoryRewardLedger::default()),
         }
     }
 
@@ -218,15 +243,21 @@ impl PuzzleValidationService {
         let is_correct = self.validate_solution_moves(&puzzle, &submission.moves)?;
 
         if is_correct {
+            // Each (user, puzzle) pair is rewarded once
+            let token_id = Uuid::new_v4().to_string();
+            if !self.ledger.record_solved(submission.user_id, puzzle.id, &token_id)? {
+                return Err(PuzzleValidationError::AlreadyRewarded);
+            }
+
             // Generate reward token
-            let reward_token = self.generate_reward_token(&puzzle, &submission)?;
+            let reward_token = self.generate_reward_token(puzzle, &submission, token_id)?;
             
             Ok(PuzzleValidationResult {
                 success: true,
                 correct: true,
                 message: "Puzzle solved correctly! Reward token generated.".to_string(),
                 reward_token: Some(reward_token),
-                reward_amount: Some(0.01), // 0.01 XLM reward
+                reward_amount: Some(PUZZLE_REWARD_STROOPS as f64 / STROOPS_PER_XLM as f64),
             })
         } else {
             Ok(PuzzleValidationResult {
@@ -273,14 +304,15 @@ impl PuzzleValidationService {
         &self,
         puzzle: &Puzzle,
         submission: &PuzzleSubmission,
+        token_id: String,
     ) -> Result<String, PuzzleValidationError> {
         let reward_data = PuzzleRewardToken {
             puzzle_id: puzzle.id,
             user_id: submission.user_id,
             username: submission.username.clone(),
             completed_at: Utc::now(),
-            reward_amount: 0.01,
-            token_id: Uuid::new_v4().to_string(),
+            reward_amount: PUZZLE_REWARD_STROOPS,
+            token_id,
         };
 
         let token_claims = serde_json::to_value(reward_data)
@@ -302,10 +334,14 @@ impl PuzzleValidationService {
 
     /// Verify reward token
     pub fn verify_reward_token(&self, token: &str) -> Result<PuzzleRewardToken, PuzzleValidationError> {
+        // Reward tokens don't expire; the ledger limits what they pay out
+        let mut validation = jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256);
+        validation.required_spec_claims.clear();
+        validation.validate_exp = false;
         let token_data = jsonwebtoken::decode::<serde_json::Value>(
             token,
             &jsonwebtoken::DecodingKey::from_secret(self.get_jwt_secret().as_ref()),
-            &jsonwebtoken::Validation::new(jsonwebtoken::Algorithm::HS256),
+            &validation,
         ).map_err(|_| PuzzleValidationError::InvalidFormat("Invalid token".to_string()))?;
 
         let reward_token: PuzzleRewardToken = serde_json::from_value(token_data.claims)
@@ -313,6 +349,40 @@ impl PuzzleValidationService {
 
         Ok(reward_token)
     }
+
+    /// Enable on-chain reward vouchers signed by `signer`
+    pub fn with_voucher_signer(mut self, signer: VoucherSigner) -> Self {
+        self.voucher_signer = Some(signer);
+        self
+    }
+
+    /// Record rewarded puzzles in `ledger` instead of in memory
+    pub fn with_reward_ledger(mut self, ledger: Arc<dyn RewardLedger>) -> Self {
+        self.ledger = ledger;
+        self
+    }
+
+    /// Exchange a reward token for a voucher `recipient` can redeem with
+    /// `game_contract::claim_puzzle_reward`
+    pub fn issue_voucher(
+        &self,
+        token: &str,
+        user_id: i32,
+        recipient: &str,
+    ) -> Result<PuzzleVoucher, PuzzleValidationError> {
+        let signer = self
+            .voucher_signer
+            .as_ref()
+            .ok_or(PuzzleValidationError::VouchersDisabled)?;
+        let reward = self.verify_reward_token(token)?;
+        if reward.user_id != user_id {
+            return Err(PuzzleValidationError::RewardNotOwned);
+        }
+        if !self.ledger.record_claim(reward.user_id, reward.puzzle_id, &reward.token_id)? {
+            return Err(PuzzleValidationError::AlreadyRewarded);
+        }
+        signer.issue(&reward, recipient)
+    }
 }
 
 #[cfg(test)]
@@ -383,4 +453,60 @@ mod tests {
         assert!(!result.correct);
         assert!(result.reward_token.is_none());
     }
+
+    fn fork_attack_solution(user_id: i32) -> PuzzleSubmission {
+        PuzzleSubmission {
+            puzzle_id: Uuid::parse_str("550e8400-e29b-41d4-a716-446655440001").unwrap(),
+            moves: vec![ChessMove {
+                from: "f3".to_string(),
+                to: "g5".to_string(),
+                promotion: None,
+            }],
+            user_id,
+            username: "testuser".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_puzzle_pays_out_once_per_user() {
+        const RECIPIENT: &str = "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H";
+        let signer = || {
+            VoucherSigner::from_secret(
+                "0101010101010101010101010101010101010101010101010101010101010101",
+            )
+            .unwrap()
+        };
+        let service = create_test_service().with_voucher_signer(signer());
+
+        let token = service
+            .validate_puzzle_solution(fork_attack_solution(1))
+            .unwrap()
+            .reward_token
+            .unwrap();
+        assert!(matches!(
+            service.validate_puzzle_solution(fork_attack_solution(1)),
+            Err(PuzzleValidationError::AlreadyRewarded)
+        ));
+        // Other users are still rewarded for the same puzzle
+        assert!(service
+            .validate_puzzle_solution(fork_attack_solution(2))
+            .unwrap()
+            .reward_token
+            .is_some());
+
+        // Re-exchanging the same token gives the same nonce, which the contract pays once
+        let voucher = service.issue_voucher(&token, 1, RECIPIENT).unwrap();
+        assert_eq!(service.issue_voucher(&token, 1, RECIPIENT).unwrap(), voucher);
+
+        // A second token for the pair, minted before it was recorded, is refused
+        let other_token = create_test_service()
+            .validate_puzzle_solution(fork_attack_solution(1))
+            .unwrap()
+            .reward_token
+            .unwrap();
+        assert!(matches!(
+            service.issue_voucher(&other_token, 1, RECIPIENT),
+            Err(PuzzleValidationError::AlreadyRewarded)
+        ));
+    }
 }
@@ -0,0 +1,187 @@
+use std::sync::Arc;
+
+use security::signer::{canonical_address, LogAuditLog, SettlementPayload, SettlementSigner};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::puzzle_validation::{PuzzleRewardToken, PuzzleValidationError};
+
+/// Environment variable holding a dedicated voucher signing key: a Stellar
+/// secret seed (`S...`) or 32 bytes of hex. Its public key is the `admin_key`
+/// passed to `game_contract::initialize_puzzle_rewards`. When unset, vouchers
+/// are signed by the shared `SettlementSigner`.
+pub const SIGNING_KEY_ENV: &str = "PUZZLE_REWARD_SIGNING_KEY";
+
+/// 1 XLM in stroops, the unit `claim_puzzle_reward` is paid in
+pub const STROOPS_PER_XLM: i64 = 10_000_000;
+
+/// A signed reward, ready to submit as the arguments of
+/// `game_contract::claim_puzzle_reward(recipient, reward_amount, nonce, signature)`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PuzzleVoucher {
+    pub puzzle_id: Uuid,
+    pub recipient: String,
+    /// Reward in stroops
+    pub reward_amount: i64,
+    pub nonce: u64,
+    /// Hex-encoded ED25519 signature over `voucher_digest`
+    pub signature: String,
+}
+
+/// Signs puzzle reward vouchers with the contract's admin key
+#[derive(Debug, Clone)]
+pub struct VoucherSigner {
+    signer: Arc<SettlementSigner>,
+}
+
+impl VoucherSigner {
+    /// Sign vouchers with the backend's settlement keys
+    pub fn new(signer: Arc<SettlementSigner>) -> Self {
+        Self { signer }
+    }
+
+    /// Build a signer from a Stellar secret seed or 32 hex-encoded bytes
+    pub fn from_secret(secret: &str) -> Result<Self, PuzzleValidationError> {
+        let signer = SettlementSigner::from_secrets(&[secret], Arc::new(LogAuditLog))
+            .map_err(|_| PuzzleValidationError::InvalidSigningKey)?;
+        Ok(Self::new(Arc::new(signer)))
+    }
+
+    /// Read the signer from `PUZZLE_REWARD_SIGNING_KEY`; `None` if unset
+    pub fn from_env() -> Result<Option<Self>, PuzzleValidationError> {
+        match std::env::var(SIGNING_KEY_ENV) {
+            Ok(secret) => Self::from_secret(&secret).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Hex of the 32-byte public key, as stored in the contract's `ADMIN_KEY`
+    pub fn public_key_hex(&self) -> String {
+        self.signer.primary_key_id()
+    }
+
+    /// Turn a verified reward token into a voucher for `recipient`
+    pub fn issue(
+        &self,
+        reward: &PuzzleRewardToken,
+        recipient: &str,
+    ) -> Result<PuzzleVoucher, PuzzleValidationError> {
+        let recipient = canonical_address(recipient)
+            .map_err(|_| PuzzleValidationError::InvalidRecipient(recipient.to_string()))?;
+        let nonce = voucher_nonce(&reward.token_id)?;
+        let payload =
+            SettlementPayload::claim_puzzle_reward(&recipient, reward.reward_amount, nonce)
+                .map_err(|e| PuzzleValidationError::InvalidFormat(e.to_string()))?;
+        let signed = self
+            .signer
+            .sign(&payload)
+            .map_err(|e| PuzzleValidationError::SigningFailed(e.to_string()))?;
+
+        Ok(PuzzleVoucher {
+            puzzle_id: reward.puzzle_id,
+            recipient,
+            reward_amount: reward.reward_amount,
+            nonce,
+            signature: signed.signature_hex(),
+        })
+    }
+}
+
+/// The message `claim_puzzle_reward` verifies:
+/// `SHA256(recipient_strkey || amount_le_i64 || nonce_le_u64)`
+pub fn voucher_digest(recipient: &str, reward_amount: i64, nonce: u64) -> [u8; 32] {
+    SettlementPayload::ClaimPuzzleReward {
+        recipient: recipient.to_string(),
+        reward_amount,
+        nonce,
+    }
+    .digest()
+}
+
+/// The contract keeps one set of used nonces for all recipients, so the
+/// nonce comes from the reward token's random id. A token can be exchanged
+/// again (e.g. after a failed submission), but all its vouchers share one
+/// nonce and only the first claim succeeds.
+pub fn voucher_nonce(token_id: &str) -> Result<u64, PuzzleValidationError> {
+    let id = Uuid::parse_str(token_id)
+        .map_err(|_| PuzzleValidationError::InvalidFormat("Invalid token id".to_string()))?;
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&id.as_bytes()[..8]);
+    Ok(u64::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use ed25519_dalek::{Signature, SigningKey, Verifier};
+
+    const SEED_HEX: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+
+    fn reward() -> PuzzleRewardToken {
+        PuzzleRewardToken {
+            puzzle_id: Uuid::new_v4(),
+            user_id: 1,
+            username: "testuser".to_string(),
+            completed_at: Utc::now(),
+            reward_amount: 100_000,
+            token_id: Uuid::new_v4().to_string(),
+        }
+    }
+
+    fn recipient() -> String {
+        stellar_strkey::ed25519::PublicKey([9; 32]).to_string()
+    }
+
+    #[test]
+    fn test_voucher_signature_verifies_against_admin_key() {
+        let signer = VoucherSigner::from_secret(SEED_HEX).unwrap();
+        let reward = reward();
+        let voucher = signer.issue(&reward, &recipient()).unwrap();
+
+        assert_eq!(voucher.reward_amount, 100_000);
+        assert_eq!(voucher.nonce, voucher_nonce(&reward.token_id).unwrap());
+
+        let verifying_key = SigningKey::from_bytes(&[1; 32]).verifying_key();
+        assert_eq!(
+            signer.public_key_hex(),
+            security::signer::encode_hex(verifying_key.as_bytes())
+        );
+        let signature_bytes: [u8; 64] = (0..64)
+            .map(|i| u8::from_str_radix(&voucher.signature[2 * i..2 * i + 2], 16).unwrap())
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        let digest = voucher_digest(&voucher.recipient, voucher.reward_amount, voucher.nonce);
+        assert!(verifying_key
+            .verify(&digest, &Signature::from_bytes(&signature_bytes))
+            .is_ok());
+
+        // The same seed as a Stellar secret gives the same key.
+        let strkey = stellar_strkey::ed25519::PrivateKey([1; 32]).to_string();
+        let from_strkey = VoucherSigner::from_secret(&strkey).unwrap();
+        assert_eq!(from_strkey.public_key_hex(), signer.public_key_hex());
+    }
+
+    #[test]
+    fn test_same_token_gets_same_nonce_and_bad_input_is_rejected() {
+        let signer = VoucherSigner::from_secret(SEED_HEX).unwrap();
+        let reward = reward();
+        let first = signer.issue(&reward, &recipient()).unwrap();
+        let second = signer.issue(&reward, &recipient()).unwrap();
+        assert_eq!(first.nonce, second.nonce);
+        assert_ne!(
+            first.nonce,
+            signer.issue(&self::reward(), &recipient()).unwrap().nonce
+        );
+
+        assert!(matches!(
+            signer.issue(&reward, "not-an-address"),
+            Err(PuzzleValidationError::InvalidRecipient(_))
+        ));
+        assert!(matches!(
+            VoucherSigner::from_secret("abcd"),
+            Err(PuzzleValidationError::InvalidSigningKey)
+        ));
+    }
+}
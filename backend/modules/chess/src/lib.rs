@@ -2,7 +2,9 @@ pub mod bitboard;
 pub mod time_control;
 pub mod pgn;
 pub mod rating;
+pub mod proof;
 
 pub use time_control::{TimeControl, PlayerClock};
 pub use pgn::{parse_pgn, validate_game, ParsedGame, ValidatedGame, PgnError, PgnHeaders, GameResult as PgnGameResult};
 pub use rating::{RatingService, RatingConfig, GameOutcome};
+pub use proof::{prove_game, verify_game, GameProof, ProofError};
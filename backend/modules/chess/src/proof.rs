@@ -0,0 +1,214 @@
+//! Proof of game: tamper-evident hashes of a finished game.
+//!
+//! Port of `pers-store/proofOfGame.ts`, so proofs made by either side agree:
+//!
+//! * move hash  = `sha256(moves.join(" "))` over SAN moves, without move numbers
+//! * meta hash  = `sha256(id|white|black|result|started_ms|ended_ms)`
+//! * proof hash = `sha256(hex(move hash) + hex(meta hash))`
+//!
+//! All three are lowercase hex. The proof hash is what is anchored on chain
+//! with `game_registry::record_game` and batched into Merkle roots by
+//! `st_core::merkle`.
+
+use db_entity::game::{self, ResultSide};
+use sea_orm::JsonValue;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    #[error("Game {0} has not finished")]
+    NotFinished(Uuid),
+
+    #[error("Game {0} has no move list")]
+    MissingMoves(Uuid),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameProof {
+    pub game_id: Uuid,
+    pub move_hash: String,
+    pub meta_hash: String,
+    pub proof_hash: String,
+}
+
+impl GameProof {
+    /// The proof hash as the 32 bytes passed to the contracts.
+    pub fn proof_hash_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&self.proof_hash[2 * i..2 * i + 2], 16)
+                .expect("proof hash is hex");
+        }
+        bytes
+    }
+}
+
+/// Compute the proof of a finished game row.
+pub fn prove_game(game: &game::Model) -> Result<GameProof, ProofError> {
+    let result = match &game.result {
+        Some(ResultSide::Ongoing) | None => return Err(ProofError::NotFinished(game.id)),
+        Some(result) => result_string(result),
+    };
+    let moves = moves_of(&game.pgn).ok_or(ProofError::MissingMoves(game.id))?;
+
+    let started_ms = game.started_at.timestamp_millis();
+    let ended_ms = started_ms + i64::from(game.duration_sec) * 1000;
+    let meta = [
+        game.id.to_string(),
+        game.white_player.to_string(),
+        game.black_player.to_string(),
+        result.to_string(),
+        started_ms.to_string(),
+        ended_ms.to_string(),
+    ]
+    .join("|");
+
+    let move_hash = sha256_hex(moves.join(" ").as_bytes());
+    let meta_hash = sha256_hex(meta.as_bytes());
+    let proof_hash = sha256_hex(format!("{}{}", move_hash, meta_hash).as_bytes());
+
+    Ok(GameProof {
+        game_id: game.id,
+        move_hash,
+        meta_hash,
+        proof_hash,
+    })
+}
+
+/// Recompute the proof of `game` and compare it with `proof_hash`.
+pub fn verify_game(game: &game::Model, proof_hash: &str) -> Result<bool, ProofError> {
+    Ok(prove_game(game)?
+        .proof_hash
+        .eq_ignore_ascii_case(proof_hash))
+}
+
+/// PGN result tokens, as used by the TypeScript prototype. Abandoned games
+/// have no PGN result and use the "unknown" token.
+fn result_string(result: &ResultSide) -> &'static str {
+    match result {
+        ResultSide::WhiteWins => "1-0",
+        ResultSide::BlackWins => "0-1",
+        ResultSide::Draw => "1/2-1/2",
+        ResultSide::Abandoned | ResultSide::Ongoing => "*",
+    }
+}
+
+/// SAN moves from the `pgn` column: `{"moves": "1. e4 e5 ..."}` or
+/// `{"moves": ["e4", "e5", ...]}`. Move numbers are dropped.
+fn moves_of(pgn: &JsonValue) -> Option<Vec<String>> {
+    let tokens: Vec<String> = match pgn.get("moves")? {
+        JsonValue::String(moves) => moves.split_whitespace().map(str::to_string).collect(),
+        JsonValue::Array(moves) => moves
+            .iter()
+            .map(|m| m.as_str().map(str::to_string))
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    Some(
+        tokens
+            .into_iter()
+            .filter(|token| !is_move_number(token))
+            .collect(),
+    )
+}
+
+fn is_move_number(token: &str) -> bool {
+    let digits = token.trim_end_matches('.');
+    digits.len() < token.len() && !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use db_entity::game::GameVariant;
+    use sea_orm::prelude::DateTimeWithTimeZone;
+    use serde_json::json;
+
+    fn finished_game(pgn: JsonValue) -> game::Model {
+        let started_at =
+            DateTimeWithTimeZone::parse_from_rfc3339("2025-01-01T12:00:00+00:00").unwrap();
+        game::Model {
+            id: Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            white_player: Uuid::parse_str("00000000-0000-0000-0000-0000000000aa").unwrap(),
+            black_player: Uuid::parse_str("00000000-0000-0000-0000-0000000000bb").unwrap(),
+            fen: String::new(),
+            pgn,
+            result: Some(ResultSide::WhiteWins),
+            variant: GameVariant::Standard,
+            started_at,
+            duration_sec: 600,
+            created_at: started_at,
+            updated_at: started_at,
+            is_imported: false,
+            original_pgn: None,
+            move_hash: None,
+            meta_hash: None,
+            proof_hash: None,
+            chain_game_id: None,
+            white_address: None,
+            black_address: None,
+            proof_epoch: None,
+            proof_path: None,
+            proof_root: None,
+            proof_claimed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_proof_matches_typescript_prototype() {
+        // generateGameProof in pers-store/proofOfGame.ts, for the same game:
+        // moves ["e4", "e5", "Nf3"], result "1-0", startedAt 1735732800000,
+        // endedAt 1735733400000.
+        let game = finished_game(json!({ "moves": "1. e4 e5 2. Nf3" }));
+        let proof = prove_game(&game).unwrap();
+
+        assert_eq!(
+            proof.move_hash,
+            "6ced3bc19f636ffdc3423406b9692c6b18dd0b7c52afcf61e68c7ad86832d6c7"
+        );
+        assert_eq!(
+            proof.meta_hash,
+            "8cc1973daf163e668677808bc1ed215bdde9937efbfa70d5bcad033295320210"
+        );
+        assert_eq!(
+            proof.proof_hash,
+            "1ca653e340b6f9617bec5d79b8e8eea4a031cc98e45180682a44752c9ad31ad4"
+        );
+        assert!(verify_game(&game, &proof.proof_hash.to_uppercase()).unwrap());
+
+        // Array and string forms hash the same.
+        let same = finished_game(json!({ "moves": ["e4", "e5", "Nf3"] }));
+        assert_eq!(prove_game(&same).unwrap(), proof);
+        assert_eq!(proof.proof_hash_bytes()[..2], [0x1c, 0xa6]);
+    }
+
+    #[test]
+    fn test_tampering_or_unfinished_game_fails() {
+        let game = finished_game(json!({ "moves": "e4 e5 Nf3" }));
+        let proof = prove_game(&game).unwrap();
+
+        let mut changed = finished_game(json!({ "moves": "e4 e5 Nc3" }));
+        assert!(!verify_game(&changed, &proof.proof_hash).unwrap());
+        changed = finished_game(json!({ "moves": "e4 e5 Nf3" }));
+        changed.result = Some(ResultSide::Draw);
+        assert!(!verify_game(&changed, &proof.proof_hash).unwrap());
+
+        changed.result = None;
+        assert_eq!(prove_game(&changed), Err(ProofError::NotFinished(game.id)));
+        let no_moves = finished_game(json!({}));
+        assert_eq!(
+            prove_game(&no_moves),
+            Err(ProofError::MissingMoves(game.id))
+        );
+    }
+}
@@ -0,0 +1,31 @@
+//! A corrective `game_contract` call queued by reconciliation
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "chain_corrections", schema_name = "smdb")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i64,
+    /// One pending correction per problem, e.g. `claim_win:7`
+    #[sea_orm(column_type = "Text", unique)]
+    pub dedupe_key: String,
+    pub game_id: Option<Uuid>,
+    pub chain_game_id: i64,
+    /// Contract function to invoke
+    #[sea_orm(column_type = "Text")]
+    pub method: String,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub args: Json,
+    #[sea_orm(column_type = "Text")]
+    pub reason: String,
+    /// `pending`, `submitted` or `failed`
+    #[sea_orm(column_type = "Text")]
+    pub status: String,
+    pub created_at: DateTimeWithTimeZone,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,21 @@
+//! Indexer progress: the last fully indexed ledger and its hash
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "chain_cursors", schema_name = "smdb")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub name: String,
+    pub ledger: i64,
+    #[sea_orm(column_type = "Text")]
+    pub ledger_hash: String,
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
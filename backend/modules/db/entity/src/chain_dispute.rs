@@ -0,0 +1,27 @@
+//! On-chain state of a game dispute
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "chain_disputes", schema_name = "smdb")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub dispute_id: i64,
+    pub game_id: i64,
+    #[sea_orm(column_type = "Text")]
+    pub filer: String,
+    #[sea_orm(column_type = "Text")]
+    pub against: String,
+    #[sea_orm(column_type = "Text")]
+    pub status: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub winner: Option<String>,
+    pub updated_ledger: i64,
+    #[sea_orm(column_type = "Text")]
+    pub last_event_id: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
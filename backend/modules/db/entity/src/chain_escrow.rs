@@ -0,0 +1,21 @@
+//! On-chain state of a tournament escrow
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "chain_escrows", schema_name = "smdb")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub escrow_id: i64,
+    pub game_id: i64,
+    pub locked_until: i64,
+    pub released: bool,
+    pub updated_ledger: i64,
+    #[sea_orm(column_type = "Text")]
+    pub last_event_id: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
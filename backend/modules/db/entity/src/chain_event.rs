@@ -0,0 +1,32 @@
+//! A decoded contract event, as indexed from Soroban RPC
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "chain_events", schema_name = "smdb")]
+pub struct Model {
+    /// Soroban RPC event id; sorts in chain order
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub id: String,
+    pub ledger: i64,
+    #[sea_orm(column_type = "TimestampWithTimeZone")]
+    pub ledger_closed_at: DateTime<Utc>,
+    #[sea_orm(column_type = "Text")]
+    pub contract_id: String,
+    #[sea_orm(column_type = "Text")]
+    pub tx_hash: String,
+    /// Event variant, e.g. `GameSettled` or `NftTransfer`
+    #[sea_orm(column_type = "Text")]
+    pub kind: String,
+    /// Projection row the event updates, e.g. `game:7`
+    #[sea_orm(column_type = "Text", nullable)]
+    pub subject: Option<String>,
+    #[sea_orm(column_type = "JsonBinary")]
+    pub payload: Json,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
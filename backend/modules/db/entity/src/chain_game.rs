@@ -0,0 +1,30 @@
+//! On-chain state of a `game_contract` game
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "chain_games", schema_name = "smdb")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub game_id: i64,
+    #[sea_orm(column_type = "Text")]
+    pub state: String,
+    #[sea_orm(column_type = "Text")]
+    pub player1: String,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub player2: Option<String>,
+    #[sea_orm(column_type = "Text")]
+    pub token: String,
+    #[sea_orm(column_type = "Decimal(Some((39, 0)))")]
+    pub wager_amount: Decimal,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub winner: Option<String>,
+    pub updated_ledger: i64,
+    #[sea_orm(column_type = "Text")]
+    pub last_event_id: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
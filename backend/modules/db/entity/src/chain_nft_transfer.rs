@@ -0,0 +1,25 @@
+//! An `ai_nft` ownership change; mints have no sender, burns no recipient
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "chain_nft_transfers", schema_name = "smdb")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub event_id: String,
+    #[sea_orm(column_type = "Text")]
+    pub contract_id: String,
+    pub nft_id: i64,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub from_address: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub to_address: Option<String>,
+    pub ledger: i64,
+    #[sea_orm(column_type = "Text")]
+    pub tx_hash: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
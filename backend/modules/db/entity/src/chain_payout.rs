@@ -0,0 +1,30 @@
+//! A winner payout or refund made by `game_contract`
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, DeriveEntityModel)]
+#[sea_orm(table_name = "chain_payouts", schema_name = "smdb")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false, column_type = "Text")]
+    pub event_id: String,
+    pub game_id: i64,
+    /// `winner` or `refund`
+    #[sea_orm(column_type = "Text")]
+    pub kind: String,
+    #[sea_orm(column_type = "Text")]
+    pub token: String,
+    #[sea_orm(column_type = "Text")]
+    pub recipient: String,
+    #[sea_orm(column_type = "Decimal(Some((39, 0)))")]
+    pub amount: Decimal,
+    #[sea_orm(column_type = "Decimal(Some((39, 0)))")]
+    pub fee: Decimal,
+    pub ledger: i64,
+    #[sea_orm(column_type = "Text")]
+    pub tx_hash: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
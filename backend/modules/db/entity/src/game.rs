@@ -58,6 +58,31 @@ pub struct Model {
     /// Original PGN string if game was imported
     #[sea_orm(column_type = "Text", nullable)]
     pub original_pgn: Option<String>,
+    /// Proof of game hashes (see `chess::proof`), set when the game finishes
+    #[sea_orm(column_type = "Text", nullable)]
+    pub move_hash: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub meta_hash: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub proof_hash: Option<String>,
+    /// `game_contract` game id, for wagered games
+    #[sea_orm(unique)]
+    pub chain_game_id: Option<i64>,
+    /// Stellar addresses playing white and black in the `game_contract` game
+    #[sea_orm(column_type = "Text", nullable)]
+    pub white_address: Option<String>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub black_address: Option<String>,
+    /// `game_registry` epoch the proof hash was anchored in, and the Merkle
+    /// path (hex sibling hashes, see `st_core::merkle`) to that epoch's root
+    pub proof_epoch: Option<i64>,
+    #[sea_orm(column_type = "JsonBinary", nullable)]
+    pub proof_path: Option<Json>,
+    /// Root (hex) of the batch the proof was claimed for by an anchoring
+    /// run, and when; set before the batch is submitted
+    #[sea_orm(column_type = "Text", nullable)]
+    pub proof_root: Option<String>,
+    pub proof_claimed_at: Option<DateTimeWithTimeZone>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,4 +1,12 @@
 pub mod prelude;
+pub mod chain_correction;
+pub mod chain_cursor;
+pub mod chain_dispute;
+pub mod chain_escrow;
+pub mod chain_event;
+pub mod chain_game;
+pub mod chain_nft_transfer;
+pub mod chain_payout;
 pub mod game;
 pub mod player;
 pub mod refresh_token;
@@ -22,7 +22,13 @@ pub struct Model {
     pub fide_rating: Option<i32>,
     pub elo_rating: i32,
     pub social_links: Option<Vec<String>>,
-    pub is_enabled: bool
+    pub is_enabled: bool,
+    /// Stellar account linked by SEP-10 sign-in
+    #[sea_orm(column_type = "Text", nullable, unique)]
+    pub stellar_address: Option<String>,
+    /// Numeric id carried in JWT claims and refresh tokens
+    #[sea_orm(unique)]
+    pub user_id: i32,
 }
 
 
@@ -1,5 +1,13 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.10
 
+pub use super::chain_correction::Entity as ChainCorrection;
+pub use super::chain_cursor::Entity as ChainCursor;
+pub use super::chain_dispute::Entity as ChainDispute;
+pub use super::chain_escrow::Entity as ChainEscrow;
+pub use super::chain_event::Entity as ChainEvent;
+pub use super::chain_game::Entity as ChainGame;
+pub use super::chain_nft_transfer::Entity as ChainNftTransfer;
+pub use super::chain_payout::Entity as ChainPayout;
 pub use super::game::Entity as Game;
 pub use super::player::Entity as Player;
 pub use super::refresh_token::Entity as RefreshToken;
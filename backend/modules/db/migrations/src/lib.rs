@@ -9,6 +9,12 @@ mod m20250605_090000_add_game_search_indexes;
 mod m20260127_create_refresh_tokens_table;
 mod m20260127_180000_add_game_imported_flag;
 mod m20250324_add_elo_rating_to_player;
+mod m20261018_000000_add_game_proof_hashes;
+mod m20261019_000000_create_chain_index_tables;
+mod m20261020_000000_add_game_chain_link;
+mod m20261021_000000_add_player_stellar_account;
+mod m20261022_000000_add_game_proof_anchor;
+mod m20261023_000000_add_game_proof_claim;
 
 
 pub struct Migrator;
@@ -26,6 +32,12 @@ impl MigratorTrait for Migrator {
             Box::new(m20260127_create_refresh_tokens_table::Migration),
             Box::new(m20260127_180000_add_game_imported_flag::Migration),
             Box::new(m20250324_add_elo_rating_to_player::Migration),
+            Box::new(m20261018_000000_add_game_proof_hashes::Migration),
+            Box::new(m20261019_000000_create_chain_index_tables::Migration),
+            Box::new(m20261020_000000_add_game_chain_link::Migration),
+            Box::new(m20261021_000000_add_player_stellar_account::Migration),
+            Box::new(m20261022_000000_add_game_proof_anchor::Migration),
+            Box::new(m20261023_000000_add_game_proof_claim::Migration),
         ]
     }
 }
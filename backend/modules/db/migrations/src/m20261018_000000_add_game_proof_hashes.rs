@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Proof of game hashes, filled in when a game finishes
+        manager
+            .alter_table(
+                Table::alter()
+                    .table((Smdb, Game::Table))
+                    .add_column(ColumnDef::new(Game::MoveHash).text().null())
+                    .add_column(ColumnDef::new(Game::MetaHash).text().null())
+                    .add_column(ColumnDef::new(Game::ProofHash).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Lets an anchored proof hash be traced back to its game
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_games_proof_hash")
+                    .table((Smdb, Game::Table))
+                    .col(Game::ProofHash)
+                    .to_owned(),
+            )
+            .await?;
+
+        println!("Added proof hash columns to game table.");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_games_proof_hash")
+                    .table((Smdb, Game::Table))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table((Smdb, Game::Table))
+                    .drop_column(Game::MoveHash)
+                    .drop_column(Game::MetaHash)
+                    .drop_column(Game::ProofHash)
+                    .to_owned(),
+            )
+            .await?;
+
+        println!("Removed proof hash columns from game table.");
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Game {
+    Table,
+    MoveHash,
+    MetaHash,
+    ProofHash,
+}
+
+#[derive(DeriveIden)]
+struct Smdb;
@@ -0,0 +1,413 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Last fully indexed ledger per indexer, with its hash to detect reorgs
+        manager
+            .create_table(
+                Table::create()
+                    .table((Smdb, ChainCursors::Table))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainCursors::Name)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainCursors::Ledger)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainCursors::LedgerHash).text().not_null())
+                    .col(
+                        ColumnDef::new(ChainCursors::UpdatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Every decoded event; projections are rebuilt from these on rollback
+        manager
+            .create_table(
+                Table::create()
+                    .table((Smdb, ChainEvents::Table))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainEvents::Id)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ChainEvents::Ledger).big_integer().not_null())
+                    .col(
+                        ColumnDef::new(ChainEvents::LedgerClosedAt)
+                            .timestamp_with_time_zone()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainEvents::ContractId).text().not_null())
+                    .col(ColumnDef::new(ChainEvents::TxHash).text().not_null())
+                    .col(ColumnDef::new(ChainEvents::Kind).text().not_null())
+                    .col(ColumnDef::new(ChainEvents::Subject).text().null())
+                    .col(
+                        ColumnDef::new(ChainEvents::Payload)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table((Smdb, ChainGames::Table))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainGames::GameId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ChainGames::State).text().not_null())
+                    .col(ColumnDef::new(ChainGames::Player1).text().not_null())
+                    .col(ColumnDef::new(ChainGames::Player2).text().null())
+                    .col(ColumnDef::new(ChainGames::Token).text().not_null())
+                    .col(
+                        ColumnDef::new(ChainGames::WagerAmount)
+                            .decimal_len(39, 0)
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainGames::Winner).text().null())
+                    .col(
+                        ColumnDef::new(ChainGames::UpdatedLedger)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainGames::LastEventId).text().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table((Smdb, ChainEscrows::Table))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainEscrows::EscrowId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainEscrows::GameId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainEscrows::LockedUntil)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainEscrows::Released)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(ChainEscrows::UpdatedLedger)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainEscrows::LastEventId).text().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table((Smdb, ChainDisputes::Table))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainDisputes::DisputeId)
+                            .big_integer()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainDisputes::GameId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainDisputes::Filer).text().not_null())
+                    .col(ColumnDef::new(ChainDisputes::Against).text().not_null())
+                    .col(ColumnDef::new(ChainDisputes::Status).text().not_null())
+                    .col(ColumnDef::new(ChainDisputes::Winner).text().null())
+                    .col(
+                        ColumnDef::new(ChainDisputes::UpdatedLedger)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainDisputes::LastEventId).text().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table((Smdb, ChainPayouts::Table))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainPayouts::EventId)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainPayouts::GameId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainPayouts::Kind).text().not_null())
+                    .col(ColumnDef::new(ChainPayouts::Token).text().not_null())
+                    .col(ColumnDef::new(ChainPayouts::Recipient).text().not_null())
+                    .col(
+                        ColumnDef::new(ChainPayouts::Amount)
+                            .decimal_len(39, 0)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainPayouts::Fee)
+                            .decimal_len(39, 0)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainPayouts::Ledger)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainPayouts::TxHash).text().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table((Smdb, ChainNftTransfers::Table))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainNftTransfers::EventId)
+                            .text()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainNftTransfers::ContractId)
+                            .text()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainNftTransfers::NftId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainNftTransfers::FromAddress).text().null())
+                    .col(ColumnDef::new(ChainNftTransfers::ToAddress).text().null())
+                    .col(
+                        ColumnDef::new(ChainNftTransfers::Ledger)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainNftTransfers::TxHash).text().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Rollbacks delete by ledger and replay by subject
+        for (name, table, col) in [
+            (
+                "idx_chain_events_ledger",
+                ChainEvents::Table.into_iden(),
+                ChainEvents::Ledger.into_iden(),
+            ),
+            (
+                "idx_chain_events_subject",
+                ChainEvents::Table.into_iden(),
+                ChainEvents::Subject.into_iden(),
+            ),
+            (
+                "idx_chain_games_player1",
+                ChainGames::Table.into_iden(),
+                ChainGames::Player1.into_iden(),
+            ),
+            (
+                "idx_chain_games_player2",
+                ChainGames::Table.into_iden(),
+                ChainGames::Player2.into_iden(),
+            ),
+            (
+                "idx_chain_escrows_game_id",
+                ChainEscrows::Table.into_iden(),
+                ChainEscrows::GameId.into_iden(),
+            ),
+            (
+                "idx_chain_disputes_game_id",
+                ChainDisputes::Table.into_iden(),
+                ChainDisputes::GameId.into_iden(),
+            ),
+            (
+                "idx_chain_payouts_game_id",
+                ChainPayouts::Table.into_iden(),
+                ChainPayouts::GameId.into_iden(),
+            ),
+            (
+                "idx_chain_payouts_ledger",
+                ChainPayouts::Table.into_iden(),
+                ChainPayouts::Ledger.into_iden(),
+            ),
+            (
+                "idx_chain_nft_transfers_nft_id",
+                ChainNftTransfers::Table.into_iden(),
+                ChainNftTransfers::NftId.into_iden(),
+            ),
+            (
+                "idx_chain_nft_transfers_ledger",
+                ChainNftTransfers::Table.into_iden(),
+                ChainNftTransfers::Ledger.into_iden(),
+            ),
+        ] {
+            manager
+                .create_index(
+                    Index::create()
+                        .name(name)
+                        .table((Smdb.into_iden(), table))
+                        .col(col)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        println!("Created chain index tables.");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for table in [
+            ChainNftTransfers::Table.into_iden(),
+            ChainPayouts::Table.into_iden(),
+            ChainDisputes::Table.into_iden(),
+            ChainEscrows::Table.into_iden(),
+            ChainGames::Table.into_iden(),
+            ChainEvents::Table.into_iden(),
+            ChainCursors::Table.into_iden(),
+        ] {
+            manager
+                .drop_table(Table::drop().table((Smdb.into_iden(), table)).to_owned())
+                .await?;
+        }
+
+        println!("Dropped chain index tables.");
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChainCursors {
+    Table,
+    Name,
+    Ledger,
+    LedgerHash,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ChainEvents {
+    Table,
+    Id,
+    Ledger,
+    LedgerClosedAt,
+    ContractId,
+    TxHash,
+    Kind,
+    Subject,
+    Payload,
+}
+
+#[derive(DeriveIden)]
+enum ChainGames {
+    Table,
+    GameId,
+    State,
+    Player1,
+    Player2,
+    Token,
+    WagerAmount,
+    Winner,
+    UpdatedLedger,
+    LastEventId,
+}
+
+#[derive(DeriveIden)]
+enum ChainEscrows {
+    Table,
+    EscrowId,
+    GameId,
+    LockedUntil,
+    Released,
+    UpdatedLedger,
+    LastEventId,
+}
+
+#[derive(DeriveIden)]
+enum ChainDisputes {
+    Table,
+    DisputeId,
+    GameId,
+    Filer,
+    Against,
+    Status,
+    Winner,
+    UpdatedLedger,
+    LastEventId,
+}
+
+#[derive(DeriveIden)]
+enum ChainPayouts {
+    Table,
+    EventId,
+    GameId,
+    Kind,
+    Token,
+    Recipient,
+    Amount,
+    Fee,
+    Ledger,
+    TxHash,
+}
+
+#[derive(DeriveIden)]
+enum ChainNftTransfers {
+    Table,
+    EventId,
+    ContractId,
+    NftId,
+    FromAddress,
+    ToAddress,
+    Ledger,
+    TxHash,
+}
+
+#[derive(DeriveIden)]
+struct Smdb;
@@ -0,0 +1,153 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The game_contract game a wagered game is played under, and the
+        // Stellar address playing each side
+        manager
+            .alter_table(
+                Table::alter()
+                    .table((Smdb, Game::Table))
+                    .add_column(ColumnDef::new(Game::ChainGameId).big_integer().null())
+                    .add_column(ColumnDef::new(Game::WhiteAddress).text().null())
+                    .add_column(ColumnDef::new(Game::BlackAddress).text().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_games_chain_game_id")
+                    .table((Smdb, Game::Table))
+                    .col(Game::ChainGameId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Corrective contract calls found by reconciliation, waiting to be
+        // submitted
+        manager
+            .create_table(
+                Table::create()
+                    .table((Smdb, ChainCorrections::Table))
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ChainCorrections::Id)
+                            .big_integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(ChainCorrections::DedupeKey)
+                            .text()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(ChainCorrections::GameId).uuid().null())
+                    .col(
+                        ColumnDef::new(ChainCorrections::ChainGameId)
+                            .big_integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainCorrections::Method).text().not_null())
+                    .col(
+                        ColumnDef::new(ChainCorrections::Args)
+                            .json_binary()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(ChainCorrections::Reason).text().not_null())
+                    .col(
+                        ColumnDef::new(ChainCorrections::Status)
+                            .text()
+                            .not_null()
+                            .default("pending"),
+                    )
+                    .col(
+                        ColumnDef::new(ChainCorrections::CreatedAt)
+                            .timestamp_with_time_zone()
+                            .not_null()
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_chain_corrections_status")
+                    .table((Smdb, ChainCorrections::Table))
+                    .col(ChainCorrections::Status)
+                    .to_owned(),
+            )
+            .await?;
+
+        println!("Linked games to game_contract and created chain_corrections.");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table((Smdb, ChainCorrections::Table))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_games_chain_game_id")
+                    .table((Smdb, Game::Table))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table((Smdb, Game::Table))
+                    .drop_column(Game::ChainGameId)
+                    .drop_column(Game::WhiteAddress)
+                    .drop_column(Game::BlackAddress)
+                    .to_owned(),
+            )
+            .await?;
+
+        println!("Removed game_contract link from game table.");
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Game {
+    Table,
+    ChainGameId,
+    WhiteAddress,
+    BlackAddress,
+}
+
+#[derive(DeriveIden)]
+enum ChainCorrections {
+    Table,
+    Id,
+    DedupeKey,
+    GameId,
+    ChainGameId,
+    Method,
+    Args,
+    Reason,
+    Status,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+struct Smdb;
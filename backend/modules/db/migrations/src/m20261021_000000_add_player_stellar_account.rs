@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Wallet linked through SEP-10 sign-in, and the numeric id that JWT
+        // claims and refresh tokens use. Existing rows get ids from the
+        // sequence.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Player::Table)
+                    .add_column(
+                        ColumnDef::new(Player::StellarAddress)
+                            .text()
+                            .null()
+                            .unique_key(),
+                    )
+                    .add_column(
+                        ColumnDef::new(Player::UserId)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .unique_key(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        println!("Added stellar_address and user_id columns to player table.");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Player::Table)
+                    .drop_column(Player::StellarAddress)
+                    .drop_column(Player::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        println!("Removed stellar_address and user_id columns from player table.");
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Player {
+    Table,
+    StellarAddress,
+    UserId,
+}
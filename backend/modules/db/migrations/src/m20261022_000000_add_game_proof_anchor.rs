@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The game_registry epoch a game's proof hash was anchored in, and the
+        // Merkle path from the proof hash to that epoch's root
+        manager
+            .alter_table(
+                Table::alter()
+                    .table((Smdb, Game::Table))
+                    .add_column(ColumnDef::new(Game::ProofEpoch).big_integer().null())
+                    .add_column(ColumnDef::new(Game::ProofPath).json_binary().null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // The anchoring job looks for proofs without an epoch
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_games_proof_epoch")
+                    .table((Smdb, Game::Table))
+                    .col(Game::ProofEpoch)
+                    .to_owned(),
+            )
+            .await?;
+
+        println!("Added proof anchor columns to game table.");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_games_proof_epoch")
+                    .table((Smdb, Game::Table))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table((Smdb, Game::Table))
+                    .drop_column(Game::ProofEpoch)
+                    .drop_column(Game::ProofPath)
+                    .to_owned(),
+            )
+            .await?;
+
+        println!("Removed proof anchor columns from game table.");
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Game {
+    Table,
+    ProofEpoch,
+    ProofPath,
+}
+
+#[derive(DeriveIden)]
+struct Smdb;
@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The batch root a proof was claimed for and when, so an anchoring
+        // run that stops between claiming and recording can be resumed
+        manager
+            .alter_table(
+                Table::alter()
+                    .table((Smdb, Game::Table))
+                    .add_column(ColumnDef::new(Game::ProofRoot).text().null())
+                    .add_column(
+                        ColumnDef::new(Game::ProofClaimedAt)
+                            .timestamp_with_time_zone()
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_games_proof_root")
+                    .table((Smdb, Game::Table))
+                    .col(Game::ProofRoot)
+                    .to_owned(),
+            )
+            .await?;
+
+        println!("Added proof claim columns to game table.");
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_games_proof_root")
+                    .table((Smdb, Game::Table))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table((Smdb, Game::Table))
+                    .drop_column(Game::ProofRoot)
+                    .drop_column(Game::ProofClaimedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        println!("Removed proof claim columns from game table.");
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Game {
+    Table,
+    ProofRoot,
+    ProofClaimedAt,
+}
+
+#[derive(DeriveIden)]
+struct Smdb;
@@ -0,0 +1,83 @@
+//! Index `game_contract` and `ai_nft` events into PostgreSQL.
+//!
+//! Usage: chain_indexer [--once]
+//!
+//! Needs `DATABASE_URL`, `SOROBAN_RPC_URL` and at least one of
+//! `GAME_CONTRACT_ID` / `AI_NFT_CONTRACT_ID` (see `IndexerConfig::from_env`).
+//! Without `--once` it keeps following the chain, polling every
+//! `INDEXER_POLL_SECONDS` once caught up.
+
+use std::process::ExitCode;
+
+use db::chain_indexer::{ChainIndexer, IndexerConfig, IndexerError, PgIndexStore, Step};
+use db::db::db::get_db;
+use st_core::StellarRpcClient;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let once = match std::env::args().nth(1).as_deref() {
+        None => false,
+        Some("--once") => true,
+        Some(other) => {
+            eprintln!("unknown argument {}", other);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let db = get_db().await;
+    let rpc = StellarRpcClient::from_env();
+    let store = PgIndexStore::new(&db);
+    let indexer = match IndexerConfig::from_env()
+        .and_then(|config| ChainIndexer::new(&rpc, &store, config))
+    {
+        Ok(indexer) => indexer,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let poll_interval = indexer.config().poll_interval;
+
+    loop {
+        let caught_up = match indexer.step().await {
+            Ok(Step::Idle { .. }) => true,
+            Ok(Step::Indexed {
+                from,
+                to,
+                events,
+                caught_up,
+            }) => {
+                println!("Indexed ledgers {}..={}: {} events", from, to, events);
+                caught_up
+            }
+            Ok(Step::RolledBack { from, to }) => {
+                println!("Ledger {} changed on chain; rolled back to {}", from, to);
+                false
+            }
+            // Retrying will not help with these
+            Err(
+                e @ (IndexerError::Decode { .. }
+                | IndexerError::LedgerUnavailable(_)
+                | IndexerError::Config(_)),
+            ) => {
+                eprintln!("Indexer stopped: {}", e);
+                return ExitCode::FAILURE;
+            }
+            Err(e) if once => {
+                eprintln!("Indexer step failed: {}", e);
+                return ExitCode::FAILURE;
+            }
+            Err(e) => {
+                eprintln!("Indexer step failed, retrying: {}", e);
+                true
+            }
+        };
+
+        if once && caught_up {
+            return ExitCode::SUCCESS;
+        }
+        if caught_up {
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
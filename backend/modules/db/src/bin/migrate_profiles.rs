@@ -0,0 +1,135 @@
+//! Copy player profiles into the `player_profile` contract.
+//!
+//! Usage: migrate_profiles [--dry-run] [--reconcile] [--batch-size N]
+//!                         [--checkpoint PATH]
+//!
+//! Needs `DATABASE_URL`, and unless `--dry-run` is given, `PROFILE_CONTRACT_ID`
+//! and `PROFILE_SOURCE_ACCOUNT` (see `CliProfileStore::from_env`).
+
+use std::process::ExitCode;
+
+use db::db::db::get_db;
+use db::profile_migration::{
+    CliProfileStore, MemoryProfileStore, ProfileMigration, MAX_BATCH_SIZE,
+};
+
+const DEFAULT_CHECKPOINT: &str = "profile_migration.checkpoint.json";
+
+struct Args {
+    dry_run: bool,
+    reconcile: bool,
+    batch_size: usize,
+    checkpoint: String,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        dry_run: false,
+        reconcile: false,
+        batch_size: MAX_BATCH_SIZE,
+        checkpoint: DEFAULT_CHECKPOINT.to_string(),
+    };
+    let mut argv = std::env::args().skip(1);
+    while let Some(arg) = argv.next() {
+        match arg.as_str() {
+            "--dry-run" => args.dry_run = true,
+            "--reconcile" => args.reconcile = true,
+            "--batch-size" => {
+                args.batch_size = argv
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .ok_or("--batch-size needs a number")?;
+            }
+            "--checkpoint" => args.checkpoint = argv.next().ok_or("--checkpoint needs a path")?,
+            other => return Err(format!("unknown argument {}", other)),
+        }
+    }
+    Ok(args)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let db = get_db().await;
+
+    let result = if args.dry_run && !args.reconcile {
+        // Nothing is written, so no contract is needed.
+        let store = MemoryProfileStore::default();
+        migrate(&db, &store, &args).await
+    } else {
+        match CliProfileStore::from_env() {
+            Ok(store) => migrate(&db, &store, &args).await,
+            Err(e) => Err(e.to_string()),
+        }
+    };
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(2),
+        Err(e) => {
+            eprintln!("Migration failed: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run the migration or the reconciliation. Returns false when the
+/// reconciliation found differences.
+async fn migrate<S: db::profile_migration::ProfileStore>(
+    db: &sea_orm::DatabaseConnection,
+    store: &S,
+    args: &Args,
+) -> Result<bool, String> {
+    let migration = ProfileMigration::new(db, store, &args.checkpoint)
+        .batch_size(args.batch_size)
+        .map_err(|e| e.to_string())?
+        .dry_run(args.dry_run);
+
+    if args.reconcile {
+        let report = migration.reconcile().await.map_err(|e| e.to_string())?;
+        println!(
+            "Checked {} profiles: {} match, {} missing on chain, {} differ, {} invalid",
+            report.checked,
+            report.matching,
+            report.missing_on_chain.len(),
+            report.mismatched.len(),
+            report.invalid.len()
+        );
+        for id in &report.missing_on_chain {
+            println!("  missing   {}", id);
+        }
+        for mismatch in &report.mismatched {
+            println!(
+                "  differs   {} ({}): {}",
+                mismatch.player_id,
+                mismatch.key,
+                mismatch.fields.join(", ")
+            );
+        }
+        for (id, reason) in &report.invalid {
+            println!("  invalid   {}: {}", id, reason);
+        }
+        return Ok(report.is_clean());
+    }
+
+    let summary = migration.run().await.map_err(|e| e.to_string())?;
+    let verb = if args.dry_run {
+        "Would migrate"
+    } else {
+        "Migrated"
+    };
+    println!(
+        "{} {} profiles in {} batches ({} new on chain)",
+        verb, summary.migrated, summary.batches, summary.created
+    );
+    for (id, reason) in &summary.skipped {
+        println!("  skipped   {}: {}", id, reason);
+    }
+    Ok(true)
+}
@@ -104,6 +104,16 @@ async fn main() -> Result<(), DbErr> {
             updated_at: Set(Utc::now().into()),
             is_imported: Set(false),
             original_pgn: Set(None),
+            move_hash: Set(None),
+            meta_hash: Set(None),
+            proof_hash: Set(None),
+            chain_game_id: Set(None),
+            white_address: Set(None),
+            black_address: Set(None),
+            proof_epoch: Set(None),
+            proof_path: Set(None),
+            proof_root: Set(None),
+            proof_claimed_at: Set(None),
         };
 
         Game::insert(game).exec(&db).await?;
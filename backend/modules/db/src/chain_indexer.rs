@@ -0,0 +1,1130 @@
+//! Chain event indexer.
+//!
+//! Follows the Soroban events of `game_contract` and `ai_nft` and writes them
+//! to `chain_events`, plus one row per game, escrow and dispute and one per
+//! payout and NFT transfer, so the API can serve on-chain state without
+//! calling RPC.
+//!
+//! Ledgers are indexed in windows. A window's events, projections and the new
+//! cursor are written in one transaction. The cursor holds the last indexed
+//! ledger and its hash. Before each window that hash is checked against the
+//! chain. On a mismatch the index is rolled back `rewind_depth` ledgers: later
+//! events are deleted and the rows they touched are rebuilt from the events
+//! that remain.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db_entity::{
+    chain_cursor, chain_dispute, chain_escrow, chain_event, chain_game, chain_nft_transfer,
+    chain_payout,
+};
+use sea_orm::prelude::{Decimal, Expr};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, TransactionTrait,
+};
+use serde_json::Value;
+use st_core::contract_events::{
+    decode_event_xdr, decode_nft_event_xdr, ContractEvent, DisputeEvent, EscrowEvent, GameEvent,
+    NftTransferEvent, PayoutEvent,
+};
+use st_core::rpc::{EventPage, EventsFrom, LedgerInfo, RpcEvent, StellarRpcClient};
+use thiserror::Error;
+
+/// Cursor name used when `INDEXER_NAME` is unset
+pub const DEFAULT_NAME: &str = "xlmate";
+/// Ledgers per window
+pub const DEFAULT_WINDOW: u32 = 500;
+/// Events per `getEvents` call
+pub const DEFAULT_PAGE_LIMIT: u32 = 200;
+/// Ledgers to roll back when the cursor's ledger hash changes
+pub const DEFAULT_REWIND_DEPTH: u32 = 16;
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+const NFT_TRANSFER: &str = "NftTransfer";
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("Database error: {0}")]
+    Database(#[from] DbErr),
+
+    #[error("RPC error: {0}")]
+    Rpc(String),
+
+    #[error("Cannot decode event {id}: {message}")]
+    Decode { id: String, message: String },
+
+    #[error("Ledger {0} is outside the RPC server's retention window")]
+    LedgerUnavailable(u32),
+
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexerConfig {
+    /// Name of the cursor row, so several indexers can share a database
+    pub name: String,
+    pub game_contract: Option<String>,
+    pub nft_contracts: Vec<String>,
+    /// First ledger to index when there is no cursor yet; the latest ledger
+    /// if `None`
+    pub start_ledger: Option<u32>,
+    pub window: u32,
+    pub page_limit: u32,
+    pub rewind_depth: u32,
+    pub poll_interval: Duration,
+}
+
+impl IndexerConfig {
+    pub fn new(game_contract: Option<String>, nft_contracts: Vec<String>) -> Self {
+        Self {
+            name: DEFAULT_NAME.to_string(),
+            game_contract,
+            nft_contracts,
+            start_ledger: None,
+            window: DEFAULT_WINDOW,
+            page_limit: DEFAULT_PAGE_LIMIT,
+            rewind_depth: DEFAULT_REWIND_DEPTH,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Read `GAME_CONTRACT_ID`, `AI_NFT_CONTRACT_ID` (comma-separated),
+    /// `INDEXER_NAME`, `INDEXER_START_LEDGER` and `INDEXER_POLL_SECONDS`.
+    pub fn from_env() -> Result<Self, IndexerError> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let number = |name: &str| {
+            var(name)
+                .map(|v| {
+                    v.trim()
+                        .parse::<u32>()
+                        .map_err(|_| IndexerError::Config(format!("{} must be a number", name)))
+                })
+                .transpose()
+        };
+
+        let mut config = Self::new(
+            var("GAME_CONTRACT_ID").map(|id| id.trim().to_string()),
+            var("AI_NFT_CONTRACT_ID")
+                .map(|ids| ids.split(',').map(|id| id.trim().to_string()).collect())
+                .unwrap_or_default(),
+        );
+        if let Some(name) = var("INDEXER_NAME") {
+            config.name = name;
+        }
+        config.start_ledger = number("INDEXER_START_LEDGER")?;
+        if let Some(seconds) = number("INDEXER_POLL_SECONDS")? {
+            config.poll_interval = Duration::from_secs(seconds.into());
+        }
+        Ok(config)
+    }
+
+    fn contract_ids(&self) -> Vec<String> {
+        self.game_contract
+            .iter()
+            .chain(&self.nft_contracts)
+            .cloned()
+            .collect()
+    }
+}
+
+/// All events up to and including `ledger` are indexed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    pub ledger: u32,
+    pub ledger_hash: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventBody {
+    Game(ContractEvent),
+    Nft(NftTransferEvent),
+}
+
+/// A decoded event with its position on chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedEvent {
+    pub id: String,
+    pub ledger: u32,
+    pub ledger_closed_at: DateTime<Utc>,
+    pub contract_id: String,
+    pub tx_hash: String,
+    pub body: EventBody,
+}
+
+impl IndexedEvent {
+    /// Event variant, e.g. `GameSettled`
+    pub fn kind(&self) -> String {
+        match &self.body {
+            EventBody::Game(_) => self.payload()["type"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            EventBody::Nft(_) => NFT_TRANSFER.to_string(),
+        }
+    }
+
+    /// The game, escrow or dispute row this event updates. Payouts and NFT
+    /// transfers get a row of their own instead.
+    pub fn subject(&self) -> Option<String> {
+        let EventBody::Game(event) = &self.body else {
+            return None;
+        };
+        match event {
+            ContractEvent::GameCreated(e)
+            | ContractEvent::GameJoined(e)
+            | ContractEvent::GameDrawn(e)
+            | ContractEvent::GameSettled(e)
+            | ContractEvent::GameCancelled(e)
+            | ContractEvent::GameForfeited(e)
+            | ContractEvent::GameTimedOut(e)
+            | ContractEvent::GameAbandoned(e) => Some(format!("game:{}", e.game_id)),
+            ContractEvent::DisputeFiled(e)
+            | ContractEvent::DisputeResolved(e)
+            | ContractEvent::DisputeRejected(e) => Some(format!("dispute:{}", e.dispute_id)),
+            ContractEvent::EscrowCreated(e) | ContractEvent::EscrowReleased(e) => {
+                Some(format!("escrow:{}", e.escrow_id))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn payload(&self) -> Value {
+        match &self.body {
+            EventBody::Game(event) => serde_json::to_value(event),
+            EventBody::Nft(event) => serde_json::to_value(event),
+        }
+        .unwrap_or(Value::Null)
+    }
+
+    fn to_row(&self) -> chain_event::ActiveModel {
+        chain_event::ActiveModel {
+            id: Set(self.id.clone()),
+            ledger: Set(self.ledger.into()),
+            ledger_closed_at: Set(self.ledger_closed_at),
+            contract_id: Set(self.contract_id.clone()),
+            tx_hash: Set(self.tx_hash.clone()),
+            kind: Set(self.kind()),
+            subject: Set(self.subject()),
+            payload: Set(self.payload()),
+        }
+    }
+
+    fn from_row(row: chain_event::Model) -> Result<Self, IndexerError> {
+        let decode_error = |e: serde_json::Error| IndexerError::Decode {
+            id: row.id.clone(),
+            message: e.to_string(),
+        };
+        let body = if row.kind == NFT_TRANSFER {
+            EventBody::Nft(serde_json::from_value(row.payload.clone()).map_err(decode_error)?)
+        } else {
+            EventBody::Game(serde_json::from_value(row.payload.clone()).map_err(decode_error)?)
+        };
+        Ok(Self {
+            ledger: u32::try_from(row.ledger).map_err(|_| IndexerError::Decode {
+                id: row.id.clone(),
+                message: format!("ledger {} out of range", row.ledger),
+            })?,
+            id: row.id,
+            ledger_closed_at: row.ledger_closed_at,
+            contract_id: row.contract_id,
+            tx_hash: row.tx_hash,
+            body,
+        })
+    }
+}
+
+/// Where events come from. Soroban RPC in production.
+#[async_trait]
+pub trait ChainSource: Send + Sync {
+    async fn latest_ledger(&self) -> Result<LedgerInfo, IndexerError>;
+
+    /// `None` if the ledger is not available (pruned or not closed yet)
+    async fn ledger(&self, sequence: u32) -> Result<Option<LedgerInfo>, IndexerError>;
+
+    /// Events from `from` up to, but excluding, `end_ledger`
+    async fn events(
+        &self,
+        contract_ids: &[String],
+        from: EventsFrom,
+        end_ledger: u32,
+        limit: u32,
+    ) -> Result<EventPage, IndexerError>;
+}
+
+#[async_trait]
+impl ChainSource for StellarRpcClient {
+    async fn latest_ledger(&self) -> Result<LedgerInfo, IndexerError> {
+        self.get_latest_ledger()
+            .await
+            .map_err(|e| IndexerError::Rpc(e.to_string()))
+    }
+
+    async fn ledger(&self, sequence: u32) -> Result<Option<LedgerInfo>, IndexerError> {
+        self.get_ledger(sequence)
+            .await
+            .map_err(|e| IndexerError::Rpc(e.to_string()))
+    }
+
+    async fn events(
+        &self,
+        contract_ids: &[String],
+        from: EventsFrom,
+        end_ledger: u32,
+        limit: u32,
+    ) -> Result<EventPage, IndexerError> {
+        self.get_events(contract_ids, from, Some(end_ledger), limit)
+            .await
+            .map_err(|e| IndexerError::Rpc(e.to_string()))
+    }
+}
+
+/// Where the index is kept. PostgreSQL in production.
+#[async_trait]
+pub trait IndexStore: Send + Sync {
+    async fn cursor(&self, name: &str) -> Result<Option<Cursor>, IndexerError>;
+
+    /// Store `events`, in chain order, and move the cursor, atomically.
+    async fn apply(
+        &self,
+        name: &str,
+        events: &[IndexedEvent],
+        cursor: &Cursor,
+    ) -> Result<(), IndexerError>;
+
+    /// Drop everything after `cursor.ledger` and move the cursor back to it.
+    async fn rollback(&self, name: &str, cursor: &Cursor) -> Result<(), IndexerError>;
+}
+
+/// What one `ChainIndexer::step` did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Step {
+    /// Nothing new since the last step
+    Idle { latest: u32 },
+    Indexed {
+        from: u32,
+        to: u32,
+        events: usize,
+        /// `to` is the latest ledger
+        caught_up: bool,
+    },
+    /// The cursor's ledger hash changed; the index now ends at `to`
+    RolledBack { from: u32, to: u32 },
+}
+
+pub struct ChainIndexer<'a, C, S> {
+    source: &'a C,
+    store: &'a S,
+    config: IndexerConfig,
+}
+
+impl<'a, C: ChainSource, S: IndexStore> ChainIndexer<'a, C, S> {
+    pub fn new(source: &'a C, store: &'a S, config: IndexerConfig) -> Result<Self, IndexerError> {
+        if config.game_contract.is_none() && config.nft_contracts.is_empty() {
+            return Err(IndexerError::Config(
+                "set GAME_CONTRACT_ID and/or AI_NFT_CONTRACT_ID".to_string(),
+            ));
+        }
+        if config.window == 0 || config.page_limit == 0 {
+            return Err(IndexerError::Config(
+                "window and page limit must be positive".to_string(),
+            ));
+        }
+        Ok(Self {
+            source,
+            store,
+            config,
+        })
+    }
+
+    pub fn config(&self) -> &IndexerConfig {
+        &self.config
+    }
+
+    /// Check the cursor against the chain and index the next window.
+    pub async fn step(&self) -> Result<Step, IndexerError> {
+        let latest = self.source.latest_ledger().await?;
+        let next = match self.store.cursor(&self.config.name).await? {
+            Some(cursor) if cursor.ledger > latest.sequence => {
+                return Ok(Step::Idle {
+                    latest: latest.sequence,
+                })
+            }
+            Some(cursor) => {
+                let on_chain = self.ledger(cursor.ledger, &latest).await?;
+                if on_chain.hash != cursor.ledger_hash {
+                    return self.rewind(&cursor, &latest).await;
+                }
+                cursor.ledger + 1
+            }
+            None => self.config.start_ledger.unwrap_or(latest.sequence),
+        };
+        if next > latest.sequence {
+            return Ok(Step::Idle {
+                latest: latest.sequence,
+            });
+        }
+
+        let end = latest
+            .sequence
+            .min(next.saturating_add(self.config.window - 1));
+        let events = self.fetch(next, end).await?;
+        let end_ledger = self.ledger(end, &latest).await?;
+        self.store
+            .apply(
+                &self.config.name,
+                &events,
+                &Cursor {
+                    ledger: end,
+                    ledger_hash: end_ledger.hash,
+                },
+            )
+            .await?;
+
+        Ok(Step::Indexed {
+            from: next,
+            to: end,
+            events: events.len(),
+            caught_up: end == latest.sequence,
+        })
+    }
+
+    async fn rewind(&self, cursor: &Cursor, latest: &LedgerInfo) -> Result<Step, IndexerError> {
+        let target = cursor.ledger.saturating_sub(self.config.rewind_depth);
+        let target_ledger = self.ledger(target, latest).await?;
+        self.store
+            .rollback(
+                &self.config.name,
+                &Cursor {
+                    ledger: target,
+                    ledger_hash: target_ledger.hash,
+                },
+            )
+            .await?;
+        Ok(Step::RolledBack {
+            from: cursor.ledger,
+            to: target,
+        })
+    }
+
+    async fn ledger(&self, sequence: u32, latest: &LedgerInfo) -> Result<LedgerInfo, IndexerError> {
+        if sequence == latest.sequence {
+            return Ok(latest.clone());
+        }
+        self.source
+            .ledger(sequence)
+            .await?
+            .ok_or(IndexerError::LedgerUnavailable(sequence))
+    }
+
+    /// Decoded events of ledgers `start..=end`, in chain order.
+    async fn fetch(&self, start: u32, end: u32) -> Result<Vec<IndexedEvent>, IndexerError> {
+        let contract_ids = self.config.contract_ids();
+        let mut from = EventsFrom::Ledger(start);
+        let mut events = Vec::new();
+        loop {
+            let page = self
+                .source
+                .events(&contract_ids, from, end + 1, self.config.page_limit)
+                .await?;
+            let full = page.events.len() >= self.config.page_limit as usize;
+            let past_end = page.events.iter().any(|event| event.ledger > end);
+            for raw in page.events {
+                if raw.ledger < start || raw.ledger > end || !raw.in_successful_contract_call {
+                    continue;
+                }
+                if let Some(event) = self.decode(raw)? {
+                    events.push(event);
+                }
+            }
+            match page.cursor {
+                Some(cursor) if full && !past_end => from = EventsFrom::Cursor(cursor),
+                _ => break,
+            }
+        }
+        events.sort_by(|a, b| a.id.cmp(&b.id));
+        events.dedup_by(|a, b| a.id == b.id);
+        Ok(events)
+    }
+
+    fn decode(&self, raw: RpcEvent) -> Result<Option<IndexedEvent>, IndexerError> {
+        let decode_error = |message: String| IndexerError::Decode {
+            id: raw.id.clone(),
+            message,
+        };
+        let body = if self.config.game_contract.as_deref() == Some(raw.contract_id.as_str()) {
+            decode_event_xdr(&raw.topic, &raw.value)
+                .map_err(|e| decode_error(e.to_string()))?
+                .map(EventBody::Game)
+        } else if self.config.nft_contracts.contains(&raw.contract_id) {
+            decode_nft_event_xdr(&raw.topic, &raw.value)
+                .map_err(|e| decode_error(e.to_string()))?
+                .map(EventBody::Nft)
+        } else {
+            None
+        };
+        let Some(body) = body else {
+            return Ok(None);
+        };
+        let ledger_closed_at = DateTime::parse_from_rfc3339(&raw.ledger_closed_at)
+            .map_err(|e| decode_error(format!("invalid close time: {}", e)))?
+            .with_timezone(&Utc);
+
+        Ok(Some(IndexedEvent {
+            id: raw.id,
+            ledger: raw.ledger,
+            ledger_closed_at,
+            contract_id: raw.contract_id,
+            tx_hash: raw.tx_hash,
+            body,
+        }))
+    }
+}
+
+/// The index in PostgreSQL, in the `chain_*` tables
+pub struct PgIndexStore<'a> {
+    db: &'a DatabaseConnection,
+}
+
+impl<'a> PgIndexStore<'a> {
+    pub fn new(db: &'a DatabaseConnection) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl IndexStore for PgIndexStore<'_> {
+    async fn cursor(&self, name: &str) -> Result<Option<Cursor>, IndexerError> {
+        Ok(chain_cursor::Entity::find_by_id(name.to_string())
+            .one(self.db)
+            .await?
+            .map(|row| Cursor {
+                ledger: u32::try_from(row.ledger).unwrap_or_default(),
+                ledger_hash: row.ledger_hash,
+            }))
+    }
+
+    async fn apply(
+        &self,
+        name: &str,
+        events: &[IndexedEvent],
+        cursor: &Cursor,
+    ) -> Result<(), IndexerError> {
+        let txn = self.db.begin().await?;
+        for event in events {
+            chain_event::Entity::insert(event.to_row())
+                .on_conflict(
+                    OnConflict::column(chain_event::Column::Id)
+                        .do_nothing()
+                        .to_owned(),
+                )
+                .exec_without_returning(&txn)
+                .await?;
+            project(&txn, event).await?;
+        }
+        save_cursor(&txn, name, cursor).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    async fn rollback(&self, name: &str, cursor: &Cursor) -> Result<(), IndexerError> {
+        let after = i64::from(cursor.ledger);
+        let txn = self.db.begin().await?;
+
+        let subjects: Vec<String> = chain_event::Entity::find()
+            .select_only()
+            .column(chain_event::Column::Subject)
+            .distinct()
+            .filter(chain_event::Column::Ledger.gt(after))
+            .filter(chain_event::Column::Subject.is_not_null())
+            .into_tuple()
+            .all(&txn)
+            .await?;
+
+        chain_event::Entity::delete_many()
+            .filter(chain_event::Column::Ledger.gt(after))
+            .exec(&txn)
+            .await?;
+        chain_payout::Entity::delete_many()
+            .filter(chain_payout::Column::Ledger.gt(after))
+            .exec(&txn)
+            .await?;
+        chain_nft_transfer::Entity::delete_many()
+            .filter(chain_nft_transfer::Column::Ledger.gt(after))
+            .exec(&txn)
+            .await?;
+
+        // Rebuild every row a dropped event touched from the events left.
+        for subject in &subjects {
+            delete_subject(&txn, subject).await?;
+        }
+        let remaining = chain_event::Entity::find()
+            .filter(chain_event::Column::Subject.is_in(subjects))
+            .order_by_asc(chain_event::Column::Id)
+            .all(&txn)
+            .await?;
+        for row in remaining {
+            project(&txn, &IndexedEvent::from_row(row)?).await?;
+        }
+
+        save_cursor(&txn, name, cursor).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+}
+
+async fn save_cursor<C: ConnectionTrait>(db: &C, name: &str, cursor: &Cursor) -> Result<(), DbErr> {
+    chain_cursor::Entity::insert(chain_cursor::ActiveModel {
+        name: Set(name.to_string()),
+        ledger: Set(cursor.ledger.into()),
+        ledger_hash: Set(cursor.ledger_hash.clone()),
+        updated_at: Set(Utc::now()),
+    })
+    .on_conflict(
+        OnConflict::column(chain_cursor::Column::Name)
+            .update_columns([
+                chain_cursor::Column::Ledger,
+                chain_cursor::Column::LedgerHash,
+                chain_cursor::Column::UpdatedAt,
+            ])
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+    Ok(())
+}
+
+/// Update the projection tables for one event.
+async fn project<C: ConnectionTrait>(db: &C, event: &IndexedEvent) -> Result<(), DbErr> {
+    match &event.body {
+        EventBody::Game(
+            ContractEvent::GameCreated(game)
+            | ContractEvent::GameJoined(game)
+            | ContractEvent::GameDrawn(game)
+            | ContractEvent::GameSettled(game)
+            | ContractEvent::GameCancelled(game)
+            | ContractEvent::GameForfeited(game)
+            | ContractEvent::GameTimedOut(game)
+            | ContractEvent::GameAbandoned(game),
+        ) => upsert_game(db, event, game).await,
+        EventBody::Game(ContractEvent::PayoutToWinner(payout)) => {
+            insert_payout(db, event, "winner", payout).await
+        }
+        EventBody::Game(ContractEvent::Refund(payout)) => {
+            insert_payout(db, event, "refund", payout).await
+        }
+        EventBody::Game(
+            ContractEvent::DisputeFiled(dispute)
+            | ContractEvent::DisputeResolved(dispute)
+            | ContractEvent::DisputeRejected(dispute),
+        ) => upsert_dispute(db, event, dispute).await,
+        EventBody::Game(ContractEvent::EscrowCreated(escrow)) => {
+            upsert_escrow(db, event, escrow).await
+        }
+        EventBody::Game(ContractEvent::EscrowReleased(escrow)) => {
+            chain_escrow::Entity::update_many()
+                .col_expr(chain_escrow::Column::Released, Expr::value(true))
+                .col_expr(
+                    chain_escrow::Column::UpdatedLedger,
+                    Expr::value(i64::from(event.ledger)),
+                )
+                .col_expr(
+                    chain_escrow::Column::LastEventId,
+                    Expr::value(event.id.clone()),
+                )
+                .filter(chain_escrow::Column::EscrowId.eq(to_i64(escrow.escrow_id)?))
+                .exec(db)
+                .await?;
+            Ok(())
+        }
+        EventBody::Nft(transfer) => {
+            chain_nft_transfer::Entity::insert(chain_nft_transfer::ActiveModel {
+                event_id: Set(event.id.clone()),
+                contract_id: Set(event.contract_id.clone()),
+                nft_id: Set(to_i64(transfer.nft_id)?),
+                from_address: Set(transfer.from.clone()),
+                to_address: Set(transfer.to.clone()),
+                ledger: Set(event.ledger.into()),
+                tx_hash: Set(event.tx_hash.clone()),
+            })
+            .on_conflict(
+                OnConflict::column(chain_nft_transfer::Column::EventId)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(db)
+            .await?;
+            Ok(())
+        }
+        // Moves, fee and governance proposals and side bets are only kept in
+        // `chain_events`.
+        EventBody::Game(_) => Ok(()),
+    }
+}
+
+async fn upsert_game<C: ConnectionTrait>(
+    db: &C,
+    event: &IndexedEvent,
+    game: &GameEvent,
+) -> Result<(), DbErr> {
+    use chain_game::Column;
+
+    chain_game::Entity::insert(chain_game::ActiveModel {
+        game_id: Set(to_i64(game.game_id)?),
+        state: Set(game.state.clone()),
+        player1: Set(game.player1.clone()),
+        player2: Set(game.player2.clone()),
+        token: Set(game.token.clone()),
+        wager_amount: Set(to_decimal(game.wager_amount)?),
+        winner: Set(game.winner.clone()),
+        updated_ledger: Set(event.ledger.into()),
+        last_event_id: Set(event.id.clone()),
+    })
+    .on_conflict(
+        OnConflict::column(Column::GameId)
+            .update_columns([
+                Column::State,
+                Column::Player1,
+                Column::Player2,
+                Column::Token,
+                Column::WagerAmount,
+                Column::Winner,
+                Column::UpdatedLedger,
+                Column::LastEventId,
+            ])
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+    Ok(())
+}
+
+async fn upsert_dispute<C: ConnectionTrait>(
+    db: &C,
+    event: &IndexedEvent,
+    dispute: &DisputeEvent,
+) -> Result<(), DbErr> {
+    use chain_dispute::Column;
+
+    chain_dispute::Entity::insert(chain_dispute::ActiveModel {
+        dispute_id: Set(to_i64(dispute.dispute_id)?),
+        game_id: Set(to_i64(dispute.game_id)?),
+        filer: Set(dispute.filer.clone()),
+        against: Set(dispute.against.clone()),
+        status: Set(dispute.status.clone()),
+        winner: Set(dispute.winner.clone()),
+        updated_ledger: Set(event.ledger.into()),
+        last_event_id: Set(event.id.clone()),
+    })
+    .on_conflict(
+        OnConflict::column(Column::DisputeId)
+            .update_columns([
+                Column::Status,
+                Column::Winner,
+                Column::UpdatedLedger,
+                Column::LastEventId,
+            ])
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+    Ok(())
+}
+
+async fn upsert_escrow<C: ConnectionTrait>(
+    db: &C,
+    event: &IndexedEvent,
+    escrow: &EscrowEvent,
+) -> Result<(), DbErr> {
+    use chain_escrow::Column;
+
+    chain_escrow::Entity::insert(chain_escrow::ActiveModel {
+        escrow_id: Set(to_i64(escrow.escrow_id)?),
+        game_id: Set(to_i64(escrow.game_id.unwrap_or_default())?),
+        locked_until: Set(to_i64(escrow.locked_until.unwrap_or_default())?),
+        released: Set(false),
+        updated_ledger: Set(event.ledger.into()),
+        last_event_id: Set(event.id.clone()),
+    })
+    .on_conflict(
+        OnConflict::column(Column::EscrowId)
+            .update_columns([
+                Column::GameId,
+                Column::LockedUntil,
+                Column::Released,
+                Column::UpdatedLedger,
+                Column::LastEventId,
+            ])
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+    Ok(())
+}
+
+async fn insert_payout<C: ConnectionTrait>(
+    db: &C,
+    event: &IndexedEvent,
+    kind: &str,
+    payout: &PayoutEvent,
+) -> Result<(), DbErr> {
+    chain_payout::Entity::insert(chain_payout::ActiveModel {
+        event_id: Set(event.id.clone()),
+        game_id: Set(to_i64(payout.game_id)?),
+        kind: Set(kind.to_string()),
+        token: Set(payout.token.clone()),
+        recipient: Set(payout.recipient.clone()),
+        amount: Set(to_decimal(payout.amount)?),
+        fee: Set(to_decimal(payout.fee)?),
+        ledger: Set(event.ledger.into()),
+        tx_hash: Set(event.tx_hash.clone()),
+    })
+    .on_conflict(
+        OnConflict::column(chain_payout::Column::EventId)
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+    Ok(())
+}
+
+/// Delete the projection row named by an event's `subject`.
+async fn delete_subject<C: ConnectionTrait>(db: &C, subject: &str) -> Result<(), DbErr> {
+    let invalid = || DbErr::Custom(format!("invalid event subject {}", subject));
+    let (table, id) = subject.split_once(':').ok_or_else(invalid)?;
+    let id: i64 = id.parse().map_err(|_| invalid())?;
+    match table {
+        "game" => chain_game::Entity::delete_by_id(id).exec(db).await?,
+        "dispute" => chain_dispute::Entity::delete_by_id(id).exec(db).await?,
+        "escrow" => chain_escrow::Entity::delete_by_id(id).exec(db).await?,
+        _ => return Err(invalid()),
+    };
+    Ok(())
+}
+
+fn to_i64(value: u64) -> Result<i64, DbErr> {
+    i64::try_from(value).map_err(|_| DbErr::Custom(format!("{} does not fit in BIGINT", value)))
+}
+
+fn to_decimal(amount: i128) -> Result<Decimal, DbErr> {
+    Decimal::try_from_i128_with_scale(amount, 0)
+        .map_err(|_| DbErr::Custom(format!("amount {} does not fit in NUMERIC", amount)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use stellar_xdr::curr::{Hash, Limits, ScAddress, ScSymbol, ScVal, ScVec, WriteXdr};
+
+    const GAME: &str = "CADQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQP5KR";
+    const NFT: &str = "NFT";
+
+    fn xdr(value: ScVal) -> String {
+        value.to_xdr_base64(Limits::none()).unwrap()
+    }
+
+    fn sym(s: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+    }
+
+    fn escrow_created(ledger: u32, index: u32, escrow_id: u64) -> RpcEvent {
+        let data = ScVal::Vec(Some(ScVec(
+            vec![ScVal::U64(escrow_id), ScVal::U64(7), ScVal::U64(900)]
+                .try_into()
+                .unwrap(),
+        )));
+        raw_event(
+            ledger,
+            index,
+            GAME,
+            vec![sym("tl_escrow"), sym("created")],
+            data,
+        )
+    }
+
+    fn nft_mint(ledger: u32, index: u32, nft_id: u64) -> RpcEvent {
+        let minter = ScVal::Address(ScAddress::Contract(Hash([1; 32])));
+        raw_event(
+            ledger,
+            index,
+            NFT,
+            vec![sym("mint"), minter],
+            ScVal::U64(nft_id),
+        )
+    }
+
+    fn raw_event(
+        ledger: u32,
+        index: u32,
+        contract: &str,
+        topic: Vec<ScVal>,
+        data: ScVal,
+    ) -> RpcEvent {
+        RpcEvent {
+            id: format!("{:019}-{:010}", u64::from(ledger) << 32, index),
+            ledger,
+            ledger_closed_at: "2026-10-19T12:00:00Z".to_string(),
+            contract_id: contract.to_string(),
+            tx_hash: format!("tx{}", ledger),
+            topic: topic.into_iter().map(xdr).collect(),
+            value: xdr(data),
+            in_successful_contract_call: true,
+        }
+    }
+
+    /// A chain whose ledger hashes and events the test controls
+    struct FakeChain {
+        latest: u32,
+        hashes: Mutex<BTreeMap<u32, String>>,
+        events: Mutex<Vec<RpcEvent>>,
+    }
+
+    impl FakeChain {
+        fn new(latest: u32, events: Vec<RpcEvent>) -> Self {
+            Self {
+                latest,
+                hashes: Mutex::new((1..=latest).map(|l| (l, format!("h{}", l))).collect()),
+                events: Mutex::new(events),
+            }
+        }
+
+        /// Replace the events and hashes of ledgers from `ledger` on.
+        fn fork(&self, ledger: u32, events: Vec<RpcEvent>) {
+            for (sequence, hash) in self.hashes.lock().unwrap().range_mut(ledger..) {
+                *hash = format!("fork{}", sequence);
+            }
+            let mut current = self.events.lock().unwrap();
+            current.retain(|event| event.ledger < ledger);
+            current.extend(events);
+        }
+    }
+
+    #[async_trait]
+    impl ChainSource for FakeChain {
+        async fn latest_ledger(&self) -> Result<LedgerInfo, IndexerError> {
+            Ok(self.ledger(self.latest).await?.unwrap())
+        }
+
+        async fn ledger(&self, sequence: u32) -> Result<Option<LedgerInfo>, IndexerError> {
+            Ok(self
+                .hashes
+                .lock()
+                .unwrap()
+                .get(&sequence)
+                .map(|hash| LedgerInfo {
+                    sequence,
+                    hash: hash.clone(),
+                }))
+        }
+
+        async fn events(
+            &self,
+            contract_ids: &[String],
+            from: EventsFrom,
+            end_ledger: u32,
+            limit: u32,
+        ) -> Result<EventPage, IndexerError> {
+            let mut events = self.events.lock().unwrap().clone();
+            events.sort_by(|a, b| a.id.cmp(&b.id));
+            let events: Vec<RpcEvent> = events
+                .into_iter()
+                .filter(|event| contract_ids.contains(&event.contract_id))
+                .filter(|event| match &from {
+                    EventsFrom::Ledger(start) => event.ledger >= *start,
+                    EventsFrom::Cursor(cursor) => event.id > *cursor,
+                })
+                .filter(|event| event.ledger < end_ledger)
+                .take(limit as usize)
+                .collect();
+            Ok(EventPage {
+                cursor: events.last().map(|event| event.id.clone()),
+                events,
+                latest_ledger: self.latest,
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryStore {
+        cursor: Mutex<Option<Cursor>>,
+        events: Mutex<Vec<IndexedEvent>>,
+    }
+
+    impl MemoryStore {
+        fn event_ids(&self) -> Vec<(u32, String)> {
+            self.events
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|event| (event.ledger, event.kind()))
+                .collect()
+        }
+    }
+
+    #[async_trait]
+    impl IndexStore for MemoryStore {
+        async fn cursor(&self, _name: &str) -> Result<Option<Cursor>, IndexerError> {
+            Ok(self.cursor.lock().unwrap().clone())
+        }
+
+        async fn apply(
+            &self,
+            _name: &str,
+            events: &[IndexedEvent],
+            cursor: &Cursor,
+        ) -> Result<(), IndexerError> {
+            self.events.lock().unwrap().extend_from_slice(events);
+            *self.cursor.lock().unwrap() = Some(cursor.clone());
+            Ok(())
+        }
+
+        async fn rollback(&self, _name: &str, cursor: &Cursor) -> Result<(), IndexerError> {
+            self.events
+                .lock()
+                .unwrap()
+                .retain(|event| event.ledger <= cursor.ledger);
+            *self.cursor.lock().unwrap() = Some(cursor.clone());
+            Ok(())
+        }
+    }
+
+    fn config() -> IndexerConfig {
+        let mut config = IndexerConfig::new(Some(GAME.to_string()), vec![NFT.to_string()]);
+        config.start_ledger = Some(1);
+        config.window = 5;
+        config.page_limit = 2;
+        config.rewind_depth = 4;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_indexes_windows_across_pages() {
+        let mut failed = nft_mint(4, 0, 9);
+        failed.in_successful_contract_call = false;
+        let chain = FakeChain::new(
+            10,
+            vec![
+                escrow_created(2, 0, 1),
+                nft_mint(3, 0, 1),
+                nft_mint(3, 1, 2),
+                failed,
+                escrow_created(7, 0, 2),
+            ],
+        );
+        let store = MemoryStore::default();
+        let indexer = ChainIndexer::new(&chain, &store, config()).unwrap();
+
+        assert_eq!(
+            indexer.step().await.unwrap(),
+            Step::Indexed {
+                from: 1,
+                to: 5,
+                events: 3,
+                caught_up: false
+            }
+        );
+        assert_eq!(
+            store.cursor("").await.unwrap(),
+            Some(Cursor {
+                ledger: 5,
+                ledger_hash: "h5".to_string()
+            })
+        );
+        assert_eq!(
+            indexer.step().await.unwrap(),
+            Step::Indexed {
+                from: 6,
+                to: 10,
+                events: 1,
+                caught_up: true
+            }
+        );
+        assert_eq!(indexer.step().await.unwrap(), Step::Idle { latest: 10 });
+
+        assert_eq!(
+            store.event_ids(),
+            vec![
+                (2, "EscrowCreated".to_string()),
+                (3, NFT_TRANSFER.to_string()),
+                (3, NFT_TRANSFER.to_string()),
+                (7, "EscrowCreated".to_string()),
+            ]
+        );
+        let events = store.events.lock().unwrap().clone();
+        assert_eq!(events[0].subject().as_deref(), Some("escrow:1"));
+        assert_eq!(events[1].subject(), None);
+    }
+
+    #[tokio::test]
+    async fn test_changed_ledger_hash_rolls_back_and_reindexes() {
+        let chain = FakeChain::new(10, vec![escrow_created(2, 0, 1), escrow_created(8, 0, 2)]);
+        let store = MemoryStore::default();
+        let indexer = ChainIndexer::new(&chain, &store, config()).unwrap();
+        indexer.step().await.unwrap();
+        indexer.step().await.unwrap();
+        assert_eq!(store.event_ids().len(), 2);
+
+        // Ledgers 8 to 10 are replaced; escrow 2 moves to ledger 9.
+        chain.fork(8, vec![escrow_created(9, 0, 2)]);
+        assert_eq!(
+            indexer.step().await.unwrap(),
+            Step::RolledBack { from: 10, to: 6 }
+        );
+        assert_eq!(store.event_ids(), vec![(2, "EscrowCreated".to_string())]);
+
+        assert_eq!(
+            indexer.step().await.unwrap(),
+            Step::Indexed {
+                from: 7,
+                to: 10,
+                events: 1,
+                caught_up: true
+            }
+        );
+        assert_eq!(
+            store.event_ids(),
+            vec![
+                (2, "EscrowCreated".to_string()),
+                (9, "EscrowCreated".to_string())
+            ]
+        );
+        assert_eq!(
+            store.cursor("").await.unwrap().unwrap().ledger_hash,
+            "fork10"
+        );
+    }
+
+    #[test]
+    fn test_event_rows_round_trip() {
+        let chain = FakeChain::new(1, vec![]);
+        let store = MemoryStore::default();
+        let indexer = ChainIndexer::new(&chain, &store, config()).unwrap();
+        for raw in [escrow_created(2, 0, 1), nft_mint(3, 0, 1)] {
+            let event = indexer.decode(raw).unwrap().unwrap();
+            let row = event.to_row();
+            let model = chain_event::Model {
+                id: row.id.unwrap(),
+                ledger: row.ledger.unwrap(),
+                ledger_closed_at: row.ledger_closed_at.unwrap(),
+                contract_id: row.contract_id.unwrap(),
+                tx_hash: row.tx_hash.unwrap(),
+                kind: row.kind.unwrap(),
+                subject: row.subject.unwrap(),
+                payload: row.payload.unwrap(),
+            };
+            assert_eq!(IndexedEvent::from_row(model).unwrap(), event);
+        }
+
+        assert!(matches!(
+            ChainIndexer::new(&chain, &store, IndexerConfig::new(None, vec![])),
+            Err(IndexerError::Config(_))
+        ));
+    }
+}
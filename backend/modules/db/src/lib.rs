@@ -1,4 +1,6 @@
+pub mod chain_indexer;
 pub mod db;
+pub mod profile_migration;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,637 @@
+//! Player profile migration to the `player_profile` contract.
+//!
+//! Port of `pers-store/playerProfileMigration.ts`. Each `player` row becomes
+//! a `(username, elo, games_played)` profile stored on chain under
+//! `storage_key(player_id)`: the first 16 bytes of
+//! `sha256("profile:" + player_id)`, the 32 hex characters the prototype used.
+//!
+//! Players are migrated in id order, one `put_profiles` call per batch. After
+//! each batch the last migrated id is written to a checkpoint file, so an
+//! interrupted run picks up where it stopped. A dry run builds the same
+//! batches but writes neither to chain nor to the checkpoint.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use db_entity::{game, player};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use uuid::Uuid;
+
+/// Most profiles the contract accepts in one `put_profiles` call
+pub const MAX_BATCH_SIZE: usize = 50;
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("Database error: {0}")]
+    Database(#[from] DbErr),
+
+    #[error("Checkpoint error: {0}")]
+    Checkpoint(String),
+
+    #[error("Contract call failed: {0}")]
+    Contract(String),
+
+    #[error("Batch size must be between 1 and {MAX_BATCH_SIZE}, got {0}")]
+    InvalidBatchSize(usize),
+}
+
+/// A profile as stored by the contract, minus its `updated_at`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainProfile {
+    pub username: String,
+    pub elo: u32,
+    pub games_played: u32,
+}
+
+/// One entry of a `put_profiles` batch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileEntry {
+    pub player_id: Uuid,
+    pub key: [u8; 16],
+    pub profile: ChainProfile,
+}
+
+/// Where profiles are written. `CliProfileStore` talks to the contract;
+/// tests use an in-memory map.
+#[async_trait]
+pub trait ProfileStore: Send + Sync {
+    /// Write a batch, returning how many profiles were new.
+    async fn put_profiles(&self, entries: &[ProfileEntry]) -> Result<u32, MigrationError>;
+
+    /// Read profiles by key, in order; `None` where there is none.
+    async fn get_profiles(
+        &self,
+        keys: &[[u8; 16]],
+    ) -> Result<Vec<Option<ChainProfile>>, MigrationError>;
+}
+
+/// Storage key of a player's profile, as in the TypeScript prototype.
+pub fn storage_key(player_id: Uuid) -> [u8; 16] {
+    let digest = Sha256::digest(format!("profile:{}", player_id).as_bytes());
+    let mut key = [0u8; 16];
+    key.copy_from_slice(&digest[..16]);
+    key
+}
+
+/// Hex form of a storage key, as the prototype printed it.
+pub fn storage_key_hex(key: &[u8; 16]) -> String {
+    key.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Build the chain profile of a player, or say why it cannot be migrated.
+pub fn profile_entry(player: &player::Model, games_played: u64) -> Result<ProfileEntry, String> {
+    if player.username.trim().is_empty() {
+        return Err("empty username".to_string());
+    }
+    let elo = u32::try_from(player.elo_rating)
+        .map_err(|_| format!("negative elo {}", player.elo_rating))?;
+    let games_played =
+        u32::try_from(games_played).map_err(|_| "games played overflows u32".to_string())?;
+
+    Ok(ProfileEntry {
+        player_id: player.id,
+        key: storage_key(player.id),
+        profile: ChainProfile {
+            username: player.username.clone(),
+            elo,
+            games_played,
+        },
+    })
+}
+
+/// Progress of a migration, saved after every batch
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Last player id written; the next run starts after it
+    pub last_player_id: Option<Uuid>,
+    pub migrated: u64,
+    pub skipped: u64,
+}
+
+impl Checkpoint {
+    /// Load a checkpoint; a missing file means a fresh start.
+    pub fn load(path: &Path) -> Result<Self, MigrationError> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| MigrationError::Checkpoint(format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(MigrationError::Checkpoint(format!(
+                "{}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    /// Write the checkpoint through a temporary file, so a crash never
+    /// leaves it half written.
+    pub fn save(&self, path: &Path) -> Result<(), MigrationError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| MigrationError::Checkpoint(e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .and_then(|_| std::fs::rename(&tmp, path))
+            .map_err(|e| MigrationError::Checkpoint(format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// What one migration run did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationSummary {
+    pub batches: u64,
+    /// Profiles written (or, in a dry run, that would have been)
+    pub migrated: u64,
+    /// Profiles that were new on chain
+    pub created: u64,
+    /// Players left out, with the reason
+    pub skipped: Vec<(Uuid, String)>,
+}
+
+/// A profile whose chain copy differs from the database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileMismatch {
+    pub player_id: Uuid,
+    pub key: String,
+    pub database: ChainProfile,
+    pub chain: ChainProfile,
+    /// Names of the fields that differ
+    pub fields: Vec<&'static str>,
+}
+
+/// Differences between the `player` table and the contract
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReconciliationReport {
+    pub checked: u64,
+    pub matching: u64,
+    pub missing_on_chain: Vec<Uuid>,
+    pub mismatched: Vec<ProfileMismatch>,
+    /// Players that cannot be migrated at all, with the reason
+    pub invalid: Vec<(Uuid, String)>,
+}
+
+impl ReconciliationReport {
+    pub fn is_clean(&self) -> bool {
+        self.missing_on_chain.is_empty() && self.mismatched.is_empty() && self.invalid.is_empty()
+    }
+
+    /// Compare a batch of database profiles with what the chain returned
+    /// for the same keys.
+    pub fn record(&mut self, entries: &[ProfileEntry], chain: &[Option<ChainProfile>]) {
+        for (entry, on_chain) in entries.iter().zip(chain) {
+            self.checked += 1;
+            match on_chain {
+                None => self.missing_on_chain.push(entry.player_id),
+                Some(on_chain) if *on_chain == entry.profile => self.matching += 1,
+                Some(on_chain) => {
+                    let mut fields = Vec::new();
+                    if on_chain.username != entry.profile.username {
+                        fields.push("username");
+                    }
+                    if on_chain.elo != entry.profile.elo {
+                        fields.push("elo");
+                    }
+                    if on_chain.games_played != entry.profile.games_played {
+                        fields.push("games_played");
+                    }
+                    self.mismatched.push(ProfileMismatch {
+                        player_id: entry.player_id,
+                        key: storage_key_hex(&entry.key),
+                        database: entry.profile.clone(),
+                        chain: on_chain.clone(),
+                        fields,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Copies `player` rows into a `ProfileStore`
+pub struct ProfileMigration<'a, S: ProfileStore> {
+    db: &'a DatabaseConnection,
+    store: &'a S,
+    batch_size: usize,
+    checkpoint_path: PathBuf,
+    dry_run: bool,
+}
+
+impl<'a, S: ProfileStore> ProfileMigration<'a, S> {
+    pub fn new(
+        db: &'a DatabaseConnection,
+        store: &'a S,
+        checkpoint_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            db,
+            store,
+            batch_size: MAX_BATCH_SIZE,
+            checkpoint_path: checkpoint_path.into(),
+            dry_run: false,
+        }
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> Result<Self, MigrationError> {
+        if batch_size == 0 || batch_size > MAX_BATCH_SIZE {
+            return Err(MigrationError::InvalidBatchSize(batch_size));
+        }
+        self.batch_size = batch_size;
+        Ok(self)
+    }
+
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Migrate every player after the checkpoint.
+    pub async fn run(&self) -> Result<MigrationSummary, MigrationError> {
+        let mut checkpoint = Checkpoint::load(&self.checkpoint_path)?;
+        let mut cursor = checkpoint.last_player_id;
+        let mut summary = MigrationSummary::default();
+
+        loop {
+            let players = self.next_players(cursor).await?;
+            let Some(last) = players.last() else {
+                break;
+            };
+            cursor = Some(last.id);
+
+            let (entries, skipped) = self.entries_for(&players).await?;
+            if !entries.is_empty() && !self.dry_run {
+                summary.created += u64::from(self.store.put_profiles(&entries).await?);
+            }
+            summary.batches += 1;
+            summary.migrated += entries.len() as u64;
+
+            if !self.dry_run {
+                checkpoint.last_player_id = cursor;
+                checkpoint.migrated += entries.len() as u64;
+                checkpoint.skipped += skipped.len() as u64;
+                checkpoint.save(&self.checkpoint_path)?;
+            }
+            summary.skipped.extend(skipped);
+        }
+
+        Ok(summary)
+    }
+
+    /// Compare every player with its profile on chain. Ignores the
+    /// checkpoint and writes nothing.
+    pub async fn reconcile(&self) -> Result<ReconciliationReport, MigrationError> {
+        let mut report = ReconciliationReport::default();
+        let mut cursor = None;
+
+        loop {
+            let players = self.next_players(cursor).await?;
+            let Some(last) = players.last() else {
+                break;
+            };
+            cursor = Some(last.id);
+
+            let (entries, invalid) = self.entries_for(&players).await?;
+            let keys: Vec<[u8; 16]> = entries.iter().map(|entry| entry.key).collect();
+            let chain = self.store.get_profiles(&keys).await?;
+            if chain.len() != entries.len() {
+                return Err(MigrationError::Contract(format!(
+                    "asked for {} profiles, got {}",
+                    entries.len(),
+                    chain.len()
+                )));
+            }
+            report.record(&entries, &chain);
+            report.invalid.extend(invalid);
+        }
+
+        Ok(report)
+    }
+
+    async fn next_players(&self, after: Option<Uuid>) -> Result<Vec<player::Model>, DbErr> {
+        let mut query = player::Entity::find().order_by_asc(player::Column::Id);
+        if let Some(after) = after {
+            query = query.filter(player::Column::Id.gt(after));
+        }
+        query.limit(self.batch_size as u64).all(self.db).await
+    }
+
+    async fn entries_for(
+        &self,
+        players: &[player::Model],
+    ) -> Result<(Vec<ProfileEntry>, Vec<(Uuid, String)>), DbErr> {
+        let mut entries = Vec::with_capacity(players.len());
+        let mut invalid = Vec::new();
+        for player in players {
+            let games_played = game::Entity::find()
+                .filter(
+                    Condition::any()
+                        .add(game::Column::WhitePlayer.eq(player.id))
+                        .add(game::Column::BlackPlayer.eq(player.id)),
+                )
+                .count(self.db)
+                .await?;
+            match profile_entry(player, games_played) {
+                Ok(entry) => entries.push(entry),
+                Err(reason) => invalid.push((player.id, reason)),
+            }
+        }
+        Ok((entries, invalid))
+    }
+}
+
+/// Invokes the contract through the `stellar` CLI, which handles signing
+/// and submission for `source_account`.
+pub struct CliProfileStore {
+    pub contract_id: String,
+    pub source_account: String,
+    pub network: String,
+}
+
+impl CliProfileStore {
+    /// Read `PROFILE_CONTRACT_ID`, `PROFILE_SOURCE_ACCOUNT` and
+    /// `STELLAR_NETWORK` (default `testnet`).
+    pub fn from_env() -> Result<Self, MigrationError> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .map_err(|_| MigrationError::Contract(format!("{} is not set", name)))
+        };
+        Ok(Self {
+            contract_id: var("PROFILE_CONTRACT_ID")?,
+            source_account: var("PROFILE_SOURCE_ACCOUNT")?,
+            network: std::env::var("STELLAR_NETWORK").unwrap_or_else(|_| "testnet".to_string()),
+        })
+    }
+
+    async fn invoke(
+        &self,
+        function: &str,
+        arg: &str,
+        value: serde_json::Value,
+        send: bool,
+    ) -> Result<String, MigrationError> {
+        let output = tokio::process::Command::new("stellar")
+            .args(["contract", "invoke", "--id", &self.contract_id])
+            .args(["--source-account", &self.source_account])
+            .args(["--network", &self.network])
+            .args(["--send", if send { "yes" } else { "no" }])
+            .args(["--", function, &format!("--{}", arg), &value.to_string()])
+            .output()
+            .await
+            .map_err(|e| MigrationError::Contract(format!("cannot run stellar CLI: {}", e)))?;
+
+        if !output.status.success() {
+            return Err(MigrationError::Contract(format!(
+                "{} failed: {}",
+                function,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+#[async_trait]
+impl ProfileStore for CliProfileStore {
+    async fn put_profiles(&self, entries: &[ProfileEntry]) -> Result<u32, MigrationError> {
+        let entries: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|entry| {
+                serde_json::json!({
+                    "key": storage_key_hex(&entry.key),
+                    "username": entry.profile.username,
+                    "elo": entry.profile.elo,
+                    "games_played": entry.profile.games_played,
+                })
+            })
+            .collect();
+        let created = self
+            .invoke("put_profiles", "entries", entries.into(), true)
+            .await?;
+        created
+            .parse()
+            .map_err(|_| MigrationError::Contract(format!("unexpected result {:?}", created)))
+    }
+
+    async fn get_profiles(
+        &self,
+        keys: &[[u8; 16]],
+    ) -> Result<Vec<Option<ChainProfile>>, MigrationError> {
+        let keys: Vec<String> = keys.iter().map(storage_key_hex).collect();
+        let profiles = self
+            .invoke("get_profiles", "keys", keys.into(), false)
+            .await?;
+        serde_json::from_str(&profiles)
+            .map_err(|e| MigrationError::Contract(format!("unexpected result: {}", e)))
+    }
+}
+
+/// Profiles kept in memory, keyed by storage key. Useful for tests and for
+/// rehearsing a migration.
+#[derive(Default)]
+pub struct MemoryProfileStore {
+    pub profiles: std::sync::Mutex<BTreeMap<[u8; 16], ChainProfile>>,
+}
+
+#[async_trait]
+impl ProfileStore for MemoryProfileStore {
+    async fn put_profiles(&self, entries: &[ProfileEntry]) -> Result<u32, MigrationError> {
+        let mut profiles = self.profiles.lock().unwrap();
+        let mut created = 0;
+        for entry in entries {
+            if profiles.insert(entry.key, entry.profile.clone()).is_none() {
+                created += 1;
+            }
+        }
+        Ok(created)
+    }
+
+    async fn get_profiles(
+        &self,
+        keys: &[[u8; 16]],
+    ) -> Result<Vec<Option<ChainProfile>>, MigrationError> {
+        let profiles = self.profiles.lock().unwrap();
+        Ok(keys.iter().map(|key| profiles.get(key).cloned()).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DatabaseBackend, MockDatabase, Value};
+
+    /// Result of one `count` query
+    fn count(n: i64) -> Vec<BTreeMap<&'static str, Value>> {
+        vec![BTreeMap::from([("num_items", Value::BigInt(Some(n)))])]
+    }
+
+    fn checkpoint_path() -> PathBuf {
+        std::env::temp_dir().join(format!("profile-checkpoint-{}.json", Uuid::new_v4()))
+    }
+
+    fn player(username: &str, elo_rating: i32) -> player::Model {
+        player::Model {
+            id: Uuid::new_v4(),
+            username: username.to_string(),
+            email: format!("{}@example.com", username),
+            password_hash: Vec::new(),
+            biography: String::new(),
+            country: String::new(),
+            flair: String::new(),
+            real_name: String::new(),
+            location: None,
+            fide_rating: None,
+            elo_rating,
+            social_links: None,
+            is_enabled: true,
+            stellar_address: None,
+            user_id: 0,
+        }
+    }
+
+    #[test]
+    fn test_storage_key_matches_typescript_prototype() {
+        let id = Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap();
+        let digest = Sha256::digest(b"profile:00000000-0000-0000-0000-000000000001");
+        let full_hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+
+        let key = storage_key(id);
+        assert_eq!(storage_key_hex(&key), full_hex[..32]);
+
+        assert!(profile_entry(&player("alice", 1500), 12).is_ok());
+        assert_eq!(
+            profile_entry(&player("bob", -1), 0).unwrap_err(),
+            "negative elo -1"
+        );
+        assert!(profile_entry(&player(" ", 1200), 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reconciliation_reports_missing_and_changed_profiles() {
+        let store = MemoryProfileStore::default();
+        let alice = profile_entry(&player("alice", 1500), 10).unwrap();
+        let bob = profile_entry(&player("bob", 1320), 4).unwrap();
+        let carol = profile_entry(&player("carol", 1710), 7).unwrap();
+        assert_eq!(
+            store
+                .put_profiles(&[alice.clone(), bob.clone()])
+                .await
+                .unwrap(),
+            2
+        );
+
+        // Bob played and gained rating since the migration.
+        let mut bob_now = bob.clone();
+        bob_now.profile.elo = 1345;
+        bob_now.profile.games_played = 5;
+
+        let entries = [alice.clone(), bob_now, carol.clone()];
+        let keys: Vec<_> = entries.iter().map(|entry| entry.key).collect();
+        let chain = store.get_profiles(&keys).await.unwrap();
+
+        let mut report = ReconciliationReport::default();
+        report.record(&entries, &chain);
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.matching, 1);
+        assert_eq!(report.missing_on_chain, vec![carol.player_id]);
+        assert_eq!(report.mismatched.len(), 1);
+        assert_eq!(report.mismatched[0].player_id, bob.player_id);
+        assert_eq!(report.mismatched[0].fields, vec!["elo", "games_played"]);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_checkpoint_round_trips_and_defaults_when_missing() {
+        let path = std::env::temp_dir().join(format!("profile-checkpoint-{}.json", Uuid::new_v4()));
+        assert_eq!(Checkpoint::load(&path).unwrap(), Checkpoint::default());
+
+        let checkpoint = Checkpoint {
+            last_player_id: Some(Uuid::new_v4()),
+            migrated: 50,
+            skipped: 1,
+        };
+        checkpoint.save(&path).unwrap();
+        assert_eq!(Checkpoint::load(&path).unwrap(), checkpoint);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_run_resumes_after_checkpoint() {
+        let done = player("alice", 1500);
+        let bob = player("bob", 1320);
+        let carol = player("carol", 1710);
+        let path = checkpoint_path();
+        Checkpoint {
+            last_player_id: Some(done.id),
+            migrated: 1,
+            skipped: 0,
+        }
+        .save(&path)
+        .unwrap();
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![bob.clone(), carol.clone()]])
+            .append_query_results([count(4)])
+            .append_query_results([count(7)])
+            .append_query_results([Vec::<player::Model>::new()])
+            .into_connection();
+        let store = MemoryProfileStore::default();
+
+        let summary = ProfileMigration::new(&db, &store, &path)
+            .batch_size(2)
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(summary.batches, 1);
+        assert_eq!(summary.migrated, 2);
+        assert_eq!(summary.created, 2);
+        assert!(summary.skipped.is_empty());
+
+        let profiles = store.profiles.lock().unwrap().clone();
+        assert!(!profiles.contains_key(&storage_key(done.id)));
+        assert_eq!(profiles[&storage_key(bob.id)].games_played, 4);
+        assert_eq!(profiles[&storage_key(carol.id)].elo, 1710);
+
+        let checkpoint = Checkpoint::load(&path).unwrap();
+        assert_eq!(checkpoint.last_player_id, Some(carol.id));
+        assert_eq!(checkpoint.migrated, 3);
+        std::fs::remove_file(&path).unwrap();
+
+        // The first page starts after the checkpointed player.
+        let log = format!("{:?}", db.into_transaction_log());
+        assert!(log.contains(&done.id.to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_writes_nothing() {
+        let alice = player("alice", 1500);
+        let broken = player("broken", -1);
+        let path = checkpoint_path();
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![alice.clone(), broken.clone()]])
+            .append_query_results([count(10)])
+            .append_query_results([count(0)])
+            .append_query_results([Vec::<player::Model>::new()])
+            .into_connection();
+        let store = MemoryProfileStore::default();
+
+        let summary = ProfileMigration::new(&db, &store, &path)
+            .dry_run(true)
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(summary.batches, 1);
+        assert_eq!(summary.migrated, 1);
+        assert_eq!(summary.created, 0);
+        assert_eq!(summary.skipped, vec![(broken.id, "negative elo -1".to_string())]);
+
+        assert!(store.profiles.lock().unwrap().is_empty());
+        assert!(!path.exists());
+    }
+}
@@ -7,7 +7,7 @@ use regex::Regex;
 // Define a regex for validating FEN chess position notation
 static FEN_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new(
-      r"^(?=\S*K)(?=\S*k)([rnbqkpRNBQKP1-8]+/){7}[rnbqkpRNBQKP1-8]+\s[bw]\s(-|[KQkq]+)\s(-|[a-h][36])\s\d+\s\d+$"
+      r"^([rnbqkpRNBQKP1-8]+/){7}[rnbqkpRNBQKP1-8]+\s[bw]\s(-|[KQkq]+)\s(-|[a-h][36])\s\d+\s\d+$"
     ).unwrap()
 });
 
@@ -27,6 +27,20 @@ pub struct AiSuggestionRequest {
     #[validate(range(min = 1000, max = 60000, message = "Time limit must be between 1 and 60 seconds"))]
     #[schema(example = 5000)]
     pub time_limit_ms: Option<u32>,
+
+    /// Play as an AI agent NFT the caller owns or rents
+    #[serde(default)]
+    pub agent: Option<AgentPlayRequest>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct AgentPlayRequest {
+    #[schema(example = 7)]
+    pub nft_id: u64,
+
+    /// Engine config JSON; must hash to the config the NFT committed to
+    #[schema(example = r#"{"engine":"stockfish","skill_level":12,"opening_book":null,"style":{}}"#)]
+    pub engine_config: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -116,4 +116,39 @@ pub struct LogoutResponse {
     pub message: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct Sep10ChallengeQuery {
+    /// Stellar account (`G...`) to authenticate
+    #[validate(length(equal = 56, message = "Account must be a 56 character G... address"))]
+    #[schema(example = "GAEQSCIJBEEQSCIJBEEQSCIJBEEQSCIJBEEQSCIJBEEQSCIJBEEQSH7S")]
+    pub account: String,
+}
+
+/// SEP-10 challenge, in the shape wallets expect
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Sep10ChallengeResponse {
+    /// Base64 transaction envelope XDR, signed by the server
+    #[schema(example = "AAAAAgAAAAA...")]
+    pub transaction: String,
+
+    #[schema(example = "Test SDF Network ; September 2015")]
+    pub network_passphrase: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Sep10TokenRequest {
+    /// The challenge transaction, signed by the account
+    #[schema(example = "AAAAAgAAAAA...")]
+    pub transaction: String,
+}
+
+/// A Stellar account linked to the signed-in player
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct Sep10LinkResponse {
+    pub player_id: Uuid,
+
+    #[schema(example = "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H")]
+    pub stellar_address: String,
+}
+
 pub type LoginResponse = AuthResponse;
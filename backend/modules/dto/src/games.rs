@@ -108,6 +108,13 @@ pub struct JoinGameRequest {
     pub player_id: Uuid,
 }
 
+/// The `game_contract` game a wagered game was created or joined under
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct LinkChainGameRequest {
+    #[schema(example = 42)]
+    pub chain_game_id: u64,
+}
+
 // UUID validation function
 pub fn validate_uuid(uuid: &Uuid) -> Result<(), ValidationError> {
     if uuid.is_nil() {
@@ -205,3 +212,31 @@ pub struct CompleteGameResponse {
     
     pub error: Option<String>,
 }
+
+/// Proof of game hashes of a finished game (see `chess::proof`)
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct GameProofResponse {
+    #[schema(value_type = String, format = "uuid")]
+    pub game_id: Uuid,
+    
+    /// SHA-256 of the SAN move list, space separated
+    pub move_hash: String,
+    
+    /// SHA-256 of `id|white|black|result|started_ms|ended_ms`
+    pub meta_hash: String,
+    
+    /// SHA-256 of `move_hash + meta_hash`; the value anchored on chain
+    pub proof_hash: String,
+    
+    /// Whether recomputing the proof from the stored game gives the same hash
+    /// and `game_registry` confirms that hash is anchored
+    pub valid: bool,
+
+    /// Whether `game_registry` confirms the proof hash is in its batch
+    pub anchored: bool,
+
+    /// `game_registry` epoch the proof was anchored in; none until the next
+    /// anchoring run
+    #[schema(example = 12)]
+    pub epoch: Option<u64>,
+}
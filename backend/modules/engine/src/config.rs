@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::EngineError;
+
+/// The engine setup an AI agent NFT plays with. The NFT stores
+/// `config_hash()` on chain, so the JSON form of this struct is the
+/// canonical one: field order is fixed and `style` is a sorted map.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineConfig {
+    /// Engine name; must match the engine binary the service runs.
+    pub engine: String,
+    /// UCI "Skill Level", 0..=20.
+    pub skill_level: u8,
+    /// Opening book name, if the agent uses one. Resolved inside the
+    /// service's book directory; never a path.
+    pub opening_book: Option<String>,
+    /// Further UCI options (e.g. "Contempt" => "24").
+    #[serde(default)]
+    pub style: BTreeMap<String, String>,
+}
+
+pub const MAX_SKILL_LEVEL: u8 = 20;
+
+impl EngineConfig {
+    /// SHA-256 of the canonical JSON encoding.
+    pub fn config_hash(&self) -> [u8; 32] {
+        let json = serde_json::to_vec(self).expect("EngineConfig serializes");
+        Sha256::digest(&json).into()
+    }
+
+    /// Reject values that could break out of a `setoption` line or point the
+    /// engine at files outside the book directory.
+    pub fn validate(&self) -> Result<(), EngineError> {
+        if self.skill_level > MAX_SKILL_LEVEL {
+            return Err(EngineError::ParseError(format!(
+                "skill level {} above {}",
+                self.skill_level, MAX_SKILL_LEVEL
+            )));
+        }
+        if let Some(book) = &self.opening_book
+            && (!is_safe_token(book) || book.contains(['/', '\\']) || book.contains(".."))
+        {
+            return Err(EngineError::ParseError(format!(
+                "invalid opening book {:?}",
+                book
+            )));
+        }
+        for (name, value) in &self.style {
+            let reserved = name.split_whitespace().any(|w| w == "name" || w == "value");
+            if !is_safe_token(name) || reserved || !is_safe_token(value) || names_file(name) {
+                return Err(EngineError::ParseError(format!(
+                    "invalid engine option {:?}",
+                    name
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// `setoption` name/value pairs for this config, in a stable order. A
+    /// book is looked up in `book_dir`; without one, configs that use a book
+    /// are refused.
+    pub fn uci_options(&self, book_dir: Option<&Path>) -> Result<Vec<(String, String)>, EngineError> {
+        let mut options = vec![("Skill Level".to_string(), self.skill_level.to_string())];
+        if let Some(book) = self.book_path(book_dir)? {
+            options.push(("OwnBook".to_string(), "true".to_string()));
+            options.push(("Book File".to_string(), book.to_string_lossy().into_owned()));
+        }
+        options.extend(self.style.iter().map(|(k, v)| (k.clone(), v.clone())));
+        Ok(options)
+    }
+
+    /// Path of the opening book inside `book_dir`, if the config uses one.
+    pub fn book_path(&self, book_dir: Option<&Path>) -> Result<Option<PathBuf>, EngineError> {
+        let Some(book) = &self.opening_book else {
+            return Ok(None);
+        };
+        self.validate()?;
+        let book_dir = book_dir.ok_or_else(|| {
+            EngineError::AgentUnavailable("no opening book directory configured".to_string())
+        })?;
+        Ok(Some(book_dir.join(book)))
+    }
+}
+
+fn is_safe_token(s: &str) -> bool {
+    !s.trim().is_empty() && !s.chars().any(|c| c.is_control())
+}
+
+/// Options such as "Book File", "Debug Log File" or "SyzygyPath" make the
+/// engine read or write files; agents only get the book, through
+/// `opening_book`.
+fn names_file(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.contains("file") || name.contains("path") || name.contains("book")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> EngineConfig {
+        EngineConfig {
+            engine: "stockfish".to_string(),
+            skill_level: 12,
+            opening_book: None,
+            style: BTreeMap::from([("Contempt".to_string(), "24".to_string())]),
+        }
+    }
+
+    #[test]
+    fn test_hash_is_stable_across_key_order() {
+        let a: EngineConfig = serde_json::from_str(
+            r#"{"engine":"stockfish","skill_level":12,"opening_book":null,"style":{"Contempt":"24"}}"#,
+        )
+        .unwrap();
+        let b: EngineConfig = serde_json::from_str(
+            r#"{"style":{"Contempt":"24"},"opening_book":null,"skill_level":12,"engine":"stockfish"}"#,
+        )
+        .unwrap();
+        assert_eq!(a.config_hash(), b.config_hash());
+        assert_eq!(a.config_hash(), config().config_hash());
+
+        let mut c = config();
+        c.skill_level = 13;
+        assert_ne!(c.config_hash(), config().config_hash());
+    }
+
+    #[test]
+    fn test_validate_rejects_injection() {
+        assert!(config().validate().is_ok());
+
+        let mut c = config();
+        c.style.insert("Threads".to_string(), "1\nquit".to_string());
+        assert!(c.validate().is_err());
+
+        let mut c = config();
+        c.style.insert("Hash value".to_string(), "16".to_string());
+        assert!(c.validate().is_err());
+
+        let mut c = config();
+        c.skill_level = 21;
+        assert!(c.validate().is_err());
+    }
+
+    #[test]
+    fn test_opening_book_resolves_inside_book_dir() {
+        let mut c = config();
+        c.opening_book = Some("performance.bin".to_string());
+        assert!(c.uci_options(None).is_err());
+
+        let options = c.uci_options(Some(Path::new("/srv/books"))).unwrap();
+        assert!(options.contains(&(
+            "Book File".to_string(),
+            Path::new("/srv/books").join("performance.bin").to_string_lossy().into_owned()
+        )));
+
+        for book in ["../etc/passwd", "/etc/passwd", "books/a.bin", "a\\b.bin", "..", ""] {
+            c.opening_book = Some(book.to_string());
+            assert!(c.validate().is_err(), "{:?}", book);
+            assert!(c.uci_options(Some(Path::new("/srv/books"))).is_err());
+        }
+
+        let mut c = config();
+        c.style.insert("Debug Log File".to_string(), "/tmp/x".to_string());
+        assert!(c.validate().is_err());
+        let mut c = config();
+        c.style.insert("book file".to_string(), "/etc/passwd".to_string());
+        assert!(c.validate().is_err());
+    }
+}
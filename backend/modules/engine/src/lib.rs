@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+pub mod config;
 pub mod parser;
 pub mod process;
 pub mod uci;
@@ -16,6 +17,8 @@ pub enum EngineError {
     Timeout,
     #[error("Parse error: {0}")]
     ParseError(String),
+    #[error("Agent not available: {0}")]
+    AgentUnavailable(String),
     #[error("Unknown error: {0}")]
     Unknown(String),
 }
@@ -40,6 +43,7 @@ pub trait Engine: Send + Sync {
     async fn go(&mut self, params: GoParams) -> Result<EngineResult, EngineError>;
     async fn stop(&mut self) -> Result<(), EngineError>;
     async fn set_position(&mut self, fen: &str) -> Result<(), EngineError>;
+    async fn set_option(&mut self, name: &str, value: &str) -> Result<(), EngineError>;
     async fn is_ready(&mut self) -> Result<bool, EngineError>;
     async fn quit(&mut self) -> Result<(), EngineError>;
 }
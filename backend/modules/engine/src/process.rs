@@ -147,6 +147,10 @@ impl Engine for ProcessEngine {
         self.send_command(&format!("position fen {}", fen)).await
     }
 
+    async fn set_option(&mut self, name: &str, value: &str) -> Result<(), EngineError> {
+        self.send_command(&format!("setoption name {} value {}", name, value)).await
+    }
+
     async fn is_ready(&mut self) -> Result<bool, EngineError> {
         self.send_command("isready").await?;
         let result = tokio::time::timeout(std::time::Duration::from_secs(5), async {
@@ -23,6 +23,8 @@ pub enum ApiError {
     BadRequest(String),
     Forbidden(String),
     NotImplemented(String),
+    /// The change would break a uniqueness rule, e.g. an account linked twice
+    Conflict(String),
 }
 
 impl From<DbErr> for ApiError {
@@ -95,6 +97,7 @@ impl fmt::Display for ApiError {
             ApiError::BadRequest(msg) => write!(f, "{}", msg),
             ApiError::Forbidden(msg) => write!(f, "{}", msg),
             ApiError::NotImplemented(msg) => write!(f, "{}", msg),
+            ApiError::Conflict(msg) => write!(f, "{}", msg),
         }
     }
 }
@@ -142,6 +145,10 @@ impl ApiError {
                 "error": self.to_string(),
                 "code": 501
             })),
+            ApiError::Conflict(_) => HttpResponse::Conflict().json(json!({
+                "error": self.to_string(),
+                "code": 409
+            })),
         }
     }
 }
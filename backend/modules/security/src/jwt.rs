@@ -10,6 +10,7 @@ use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header,
 use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
 
 /// JWT Claims structure containing user identification and expiration
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -28,6 +29,9 @@ pub struct Claims {
     pub jti: Option<String>,
     /// Token type (access or reconnect)
     pub token_type: TokenType,
+    /// Player row, for sessions started with a wallet (SEP-10)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_id: Option<Uuid>,
 }
 
 /// Token type enumeration
@@ -71,6 +75,34 @@ impl JwtService {
             iat: now,
             jti: None,
             token_type: TokenType::Access,
+            player_id: None,
+        };
+
+        let token = encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.secret_key.as_ref()),
+        )?;
+
+        Ok(token)
+    }
+
+    /// Generate an access token for a player signed in with their wallet
+    pub fn generate_player_token(&self, user_id: i32, username: &str, player_id: Uuid) -> Result<String, jsonwebtoken::errors::Error> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as usize;
+
+        let claims = Claims {
+            sub: user_id.to_string(),
+            user_id,
+            username: username.to_string(),
+            exp: now + self.expiration_time,
+            iat: now,
+            jti: None,
+            token_type: TokenType::Access,
+            player_id: Some(player_id),
         };
 
         let token = encode(
@@ -97,6 +129,7 @@ impl JwtService {
             iat: now,
             jti: Some(session_id.to_string()),
             token_type: TokenType::Reconnect,
+            player_id: None,
         };
 
         let token = encode(
@@ -210,7 +243,11 @@ where
                     let jwt_service = JwtService::new((*secret_key).clone(), expiration_time);
                     match jwt_service.validate_token(&token) {
                         Ok(claims) => {
-                            // Store claims in request extensions
+                            // Store claims in request extensions, and the player
+                            // id where handlers look for it
+                            if let Some(player_id) = claims.player_id {
+                                req.extensions_mut().insert(player_id);
+                            }
                             req.extensions_mut().insert(claims);
                             let fut = self.service.call(req);
                             Box::pin(async move { 
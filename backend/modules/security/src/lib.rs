@@ -1,5 +1,9 @@
 pub mod jwt;
+pub mod sep10;
+pub mod signer;
 pub mod token_service;
 
 pub use jwt::{JwtAuthMiddleware, JwtService, Claims};
+pub use sep10::{Sep10Challenge, Sep10Error, Sep10Service};
+pub use signer::{SettlementPayload, SettlementSignature, SettlementSigner, SignerError};
 pub use token_service::{TokenService, TokenServiceError};
@@ -0,0 +1,483 @@
+//! SEP-10 Stellar Web Authentication.
+//!
+//! A wallet proves it controls an account by signing a challenge
+//! transaction. The challenge is built and signed by the server key: sequence
+//! number 0, so it can never be submitted, time bounds of `timeout`, and a
+//! `ManageData` operation sourced from the client account whose value is a
+//! random nonce. `verify` checks both signatures, the time bounds and the
+//! operations, and accepts each nonce once.
+//!
+//! Unlike `game_contract`'s `issue_sep10_challenge`, this is the flow wallets
+//! implement, and it costs no contract call. The client signature is checked
+//! against the account's master key; accounts whose master key has been
+//! removed in favour of other signers cannot sign in.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::Engine;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use stellar_strkey::{ed25519, Strkey};
+use stellar_xdr::curr::{
+    DataValue, DecoratedSignature, Hash, Limits, ManageDataOp, Memo, MuxedAccount, Operation,
+    OperationBody, Preconditions, ReadXdr, SequenceNumber, SignatureHint, String64, TimeBounds,
+    TimePoint, Transaction, TransactionEnvelope, TransactionExt, TransactionSignaturePayload,
+    TransactionSignaturePayloadTaggedTransaction, TransactionV1Envelope, Uint256, WriteXdr,
+};
+
+use crate::signer::{parse_secret, sign_transaction};
+
+/// Stellar secret seed of the server account that signs challenges
+pub const SIGNING_KEY_ENV: &str = "SEP10_SIGNING_KEY";
+/// Domain named in the challenge's first operation (`<domain> auth`)
+pub const HOME_DOMAIN_ENV: &str = "SEP10_HOME_DOMAIN";
+/// Domain serving the auth endpoint; defaults to the home domain
+pub const WEB_AUTH_DOMAIN_ENV: &str = "SEP10_WEB_AUTH_DOMAIN";
+/// Overrides the passphrase picked from `STELLAR_NETWORK`
+pub const NETWORK_PASSPHRASE_ENV: &str = "STELLAR_NETWORK_PASSPHRASE";
+
+pub const TESTNET_PASSPHRASE: &str = "Test SDF Network ; September 2015";
+pub const PUBLIC_PASSPHRASE: &str = "Public Global Stellar Network ; September 2015";
+
+/// How long a challenge can be answered
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(900);
+
+/// Bytes of randomness in a nonce; base64 makes them the 64 bytes SEP-10 asks for
+const NONCE_BYTES: usize = 48;
+const BASE_FEE: u32 = 100;
+const WEB_AUTH_DOMAIN_KEY: &str = "web_auth_domain";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sep10Error {
+    InvalidKey(String),
+    InvalidAccount(String),
+    Config(String),
+    /// The transaction is not a well-formed challenge from this server
+    InvalidChallenge(String),
+    Expired,
+    /// The challenge was already exchanged for a token
+    Replayed,
+    MissingSignature(&'static str),
+}
+
+impl fmt::Display for Sep10Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidKey(e) => write!(f, "Invalid SEP-10 signing key: {}", e),
+            Self::InvalidAccount(a) => write!(f, "Invalid Stellar account: {}", a),
+            Self::Config(e) => write!(f, "Invalid SEP-10 configuration: {}", e),
+            Self::InvalidChallenge(e) => write!(f, "Invalid challenge transaction: {}", e),
+            Self::Expired => write!(f, "Challenge transaction has expired"),
+            Self::Replayed => write!(f, "Challenge transaction was already used"),
+            Self::MissingSignature(who) => write!(f, "Challenge is not signed by the {}", who),
+        }
+    }
+}
+
+impl std::error::Error for Sep10Error {}
+
+/// A challenge as returned to the wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sep10Challenge {
+    /// Base64 `TransactionEnvelope` XDR
+    pub transaction: String,
+    pub network_passphrase: String,
+}
+
+pub struct Sep10Service {
+    signing_key: SigningKey,
+    home_domain: String,
+    web_auth_domain: String,
+    network_passphrase: String,
+    timeout: Duration,
+    /// Nonces already exchanged, with the time their challenge expires
+    used: Mutex<HashMap<String, u64>>,
+}
+
+impl fmt::Debug for Sep10Service {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sep10Service")
+            .field("server_account", &self.server_account())
+            .field("home_domain", &self.home_domain)
+            .field("web_auth_domain", &self.web_auth_domain)
+            .field("network_passphrase", &self.network_passphrase)
+            .finish()
+    }
+}
+
+impl Sep10Service {
+    pub fn new(
+        secret: &str,
+        home_domain: &str,
+        web_auth_domain: &str,
+        network_passphrase: &str,
+    ) -> Result<Self, Sep10Error> {
+        let signing_key = parse_secret(secret).map_err(|e| Sep10Error::InvalidKey(e.to_string()))?;
+        // `<home_domain> auth` has to fit the 64 byte data name.
+        if home_domain.is_empty() || home_domain.len() > 64 - " auth".len() {
+            return Err(Sep10Error::Config(format!("bad home domain {:?}", home_domain)));
+        }
+        if web_auth_domain.is_empty() || web_auth_domain.len() > 64 {
+            return Err(Sep10Error::Config(format!("bad web auth domain {:?}", web_auth_domain)));
+        }
+        Ok(Self {
+            signing_key,
+            home_domain: home_domain.to_string(),
+            web_auth_domain: web_auth_domain.to_string(),
+            network_passphrase: network_passphrase.to_string(),
+            timeout: DEFAULT_TIMEOUT,
+            used: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Read `SEP10_SIGNING_KEY`, `SEP10_HOME_DOMAIN`, `SEP10_WEB_AUTH_DOMAIN`
+    /// and the network from `STELLAR_NETWORK_PASSPHRASE` or `STELLAR_NETWORK`.
+    /// `None` if no signing key is configured.
+    pub fn from_env() -> Result<Option<Self>, Sep10Error> {
+        let Ok(secret) = std::env::var(SIGNING_KEY_ENV) else {
+            return Ok(None);
+        };
+        let home_domain = std::env::var(HOME_DOMAIN_ENV)
+            .map_err(|_| Sep10Error::Config(format!("{} must be set", HOME_DOMAIN_ENV)))?;
+        let web_auth_domain =
+            std::env::var(WEB_AUTH_DOMAIN_ENV).unwrap_or_else(|_| home_domain.clone());
+        let passphrase = network_passphrase_from_env()?;
+        Self::new(&secret, &home_domain, &web_auth_domain, &passphrase).map(Some)
+    }
+
+    /// `G...` address of the server key
+    pub fn server_account(&self) -> String {
+        public_key_strkey(&self.signing_key.verifying_key())
+    }
+
+    pub fn network_passphrase(&self) -> &str {
+        &self.network_passphrase
+    }
+
+    /// Build and sign a challenge for `account`.
+    pub fn challenge(&self, account: &str) -> Result<Sep10Challenge, Sep10Error> {
+        let mut nonce = [0u8; NONCE_BYTES];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        self.challenge_at(account, now(), &nonce)
+    }
+
+    fn challenge_at(
+        &self,
+        account: &str,
+        now: u64,
+        nonce: &[u8; NONCE_BYTES],
+    ) -> Result<Sep10Challenge, Sep10Error> {
+        let client = parse_account(account)?;
+        let server = muxed(&self.signing_key.verifying_key());
+        let nonce = base64::engine::general_purpose::STANDARD.encode(nonce);
+
+        let operations = vec![
+            manage_data(
+                Some(MuxedAccount::Ed25519(Uint256(client.to_bytes()))),
+                &format!("{} auth", self.home_domain),
+                nonce.as_bytes(),
+            )?,
+            manage_data(
+                Some(server.clone()),
+                WEB_AUTH_DOMAIN_KEY,
+                self.web_auth_domain.as_bytes(),
+            )?,
+        ];
+        let tx = Transaction {
+            source_account: server,
+            fee: BASE_FEE * operations.len() as u32,
+            seq_num: SequenceNumber(0),
+            cond: Preconditions::Time(TimeBounds {
+                min_time: TimePoint(now),
+                max_time: TimePoint(now + self.timeout.as_secs()),
+            }),
+            memo: Memo::None,
+            operations: operations.try_into().map_err(xdr_error)?,
+            ext: TransactionExt::V0,
+        };
+
+        let signature = self.signing_key.sign(&self.tx_hash(&tx)?);
+        let envelope = TransactionEnvelope::Tx(TransactionV1Envelope {
+            signatures: vec![decorated(&self.signing_key.verifying_key(), &signature)?]
+                .try_into()
+                .map_err(xdr_error)?,
+            tx,
+        });
+        Ok(Sep10Challenge {
+            transaction: envelope.to_xdr_base64(Limits::none()).map_err(xdr_error)?,
+            network_passphrase: self.network_passphrase.clone(),
+        })
+    }
+
+    /// Check a challenge signed by the wallet and return the `G...` account
+    /// it authenticates. A challenge is accepted once.
+    pub fn verify(&self, transaction: &str) -> Result<String, Sep10Error> {
+        self.verify_at(transaction, now())
+    }
+
+    fn verify_at(&self, transaction: &str, now: u64) -> Result<String, Sep10Error> {
+        let envelope = TransactionEnvelope::from_xdr_base64(transaction, Limits::len(64 * 1024))
+            .map_err(|e| Sep10Error::InvalidChallenge(e.to_string()))?;
+        let TransactionEnvelope::Tx(TransactionV1Envelope { tx, signatures }) = envelope else {
+            return Err(Sep10Error::InvalidChallenge("not a v1 transaction".into()));
+        };
+
+        let server_key = self.signing_key.verifying_key();
+        if tx.source_account != muxed(&server_key) {
+            return Err(Sep10Error::InvalidChallenge("source is not the server account".into()));
+        }
+        if tx.seq_num != SequenceNumber(0) {
+            return Err(Sep10Error::InvalidChallenge("sequence number is not 0".into()));
+        }
+        let Preconditions::Time(bounds) = &tx.cond else {
+            return Err(Sep10Error::InvalidChallenge("missing time bounds".into()));
+        };
+        if now < bounds.min_time.0 || now > bounds.max_time.0 {
+            return Err(Sep10Error::Expired);
+        }
+
+        let (first, rest) = tx
+            .operations
+            .split_first()
+            .ok_or_else(|| Sep10Error::InvalidChallenge("no operations".into()))?;
+        let (client, nonce) = match (&first.source_account, &first.body) {
+            (Some(MuxedAccount::Ed25519(client)), OperationBody::ManageData(op))
+                if op.data_name.0.as_slice() == format!("{} auth", self.home_domain).as_bytes() =>
+            {
+                let nonce = op.data_value.as_ref().map(|v| v.0.to_vec()).unwrap_or_default();
+                if nonce.len() != 64 {
+                    return Err(Sep10Error::InvalidChallenge("nonce is not 64 bytes".into()));
+                }
+                let client = VerifyingKey::from_bytes(&client.0)
+                    .map_err(|e| Sep10Error::InvalidChallenge(e.to_string()))?;
+                (client, String::from_utf8_lossy(&nonce).into_owned())
+            }
+            _ => {
+                return Err(Sep10Error::InvalidChallenge(
+                    "first operation is not the client's auth operation".into(),
+                ))
+            }
+        };
+        for op in rest {
+            let OperationBody::ManageData(data) = &op.body else {
+                return Err(Sep10Error::InvalidChallenge("unexpected operation".into()));
+            };
+            if op.source_account.as_ref() != Some(&muxed(&server_key)) {
+                return Err(Sep10Error::InvalidChallenge(
+                    "operation is not sourced from the server account".into(),
+                ));
+            }
+            if data.data_name.0.as_slice() == WEB_AUTH_DOMAIN_KEY.as_bytes()
+                && data.data_value.as_ref().map(|v| v.0.as_slice())
+                    != Some(self.web_auth_domain.as_bytes())
+            {
+                return Err(Sep10Error::InvalidChallenge("wrong web_auth_domain".into()));
+            }
+        }
+
+        // Exactly the server's and the client's signatures
+        let hash = self.tx_hash(&tx)?;
+        let signed_by = |key: &VerifyingKey| {
+            signatures.iter().any(|s| {
+                s.hint.0 == hint(key)
+                    && Signature::from_slice(s.signature.0.as_slice())
+                        .is_ok_and(|sig| key.verify(&hash, &sig).is_ok())
+            })
+        };
+        if !signed_by(&server_key) {
+            return Err(Sep10Error::MissingSignature("server"));
+        }
+        if !signed_by(&client) {
+            return Err(Sep10Error::MissingSignature("client account"));
+        }
+        if signatures.len() != 2 {
+            return Err(Sep10Error::InvalidChallenge("unexpected signatures".into()));
+        }
+
+        let mut used = self.used.lock().unwrap_or_else(|e| e.into_inner());
+        used.retain(|_, expires| *expires >= now);
+        if used.insert(nonce, bounds.max_time.0).is_some() {
+            return Err(Sep10Error::Replayed);
+        }
+        Ok(public_key_strkey(&client))
+    }
+
+    /// SHA-256 of the network id and the transaction, which is what signers
+    /// sign
+    fn tx_hash(&self, tx: &Transaction) -> Result<[u8; 32], Sep10Error> {
+        let payload = TransactionSignaturePayload {
+            network_id: Hash(Sha256::digest(self.network_passphrase.as_bytes()).into()),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(tx.clone()),
+        };
+        let bytes = payload.to_xdr(Limits::none()).map_err(xdr_error)?;
+        Ok(Sha256::digest(bytes).into())
+    }
+}
+
+/// Sign a challenge as the wallet would
+pub fn sign_challenge(
+    transaction: &str,
+    network_passphrase: &str,
+    key: &SigningKey,
+) -> Result<String, Sep10Error> {
+    sign_transaction(transaction, network_passphrase, key)
+        .map_err(|e| Sep10Error::InvalidChallenge(e.to_string()))
+}
+
+/// `STELLAR_NETWORK_PASSPHRASE`, or the passphrase of `STELLAR_NETWORK`
+/// (testnet when unset)
+pub fn network_passphrase_from_env() -> Result<String, Sep10Error> {
+    if let Ok(passphrase) = std::env::var(NETWORK_PASSPHRASE_ENV) {
+        return Ok(passphrase);
+    }
+    match std::env::var("STELLAR_NETWORK").as_deref() {
+        Ok("public") | Ok("mainnet") => Ok(PUBLIC_PASSPHRASE.to_string()),
+        Ok("testnet") | Err(_) => Ok(TESTNET_PASSPHRASE.to_string()),
+        Ok(other) => Err(Sep10Error::Config(format!(
+            "unknown STELLAR_NETWORK {}; set {}",
+            other, NETWORK_PASSPHRASE_ENV
+        ))),
+    }
+}
+
+fn parse_account(account: &str) -> Result<VerifyingKey, Sep10Error> {
+    match Strkey::from_string(account.trim()) {
+        Ok(Strkey::PublicKeyEd25519(key)) => VerifyingKey::from_bytes(&key.0)
+            .map_err(|_| Sep10Error::InvalidAccount(account.to_string())),
+        _ => Err(Sep10Error::InvalidAccount(account.to_string())),
+    }
+}
+
+fn public_key_strkey(key: &VerifyingKey) -> String {
+    Strkey::PublicKeyEd25519(ed25519::PublicKey(key.to_bytes())).to_string()
+}
+
+fn muxed(key: &VerifyingKey) -> MuxedAccount {
+    MuxedAccount::Ed25519(Uint256(key.to_bytes()))
+}
+
+fn manage_data(
+    source: Option<MuxedAccount>,
+    name: &str,
+    value: &[u8],
+) -> Result<Operation, Sep10Error> {
+    Ok(Operation {
+        source_account: source,
+        body: OperationBody::ManageData(ManageDataOp {
+            data_name: String64(name.try_into().map_err(xdr_error)?),
+            data_value: Some(DataValue(value.to_vec().try_into().map_err(xdr_error)?)),
+        }),
+    })
+}
+
+/// Signature hint: the last four bytes of the public key
+fn hint(key: &VerifyingKey) -> [u8; 4] {
+    let bytes = key.to_bytes();
+    [bytes[28], bytes[29], bytes[30], bytes[31]]
+}
+
+fn decorated(key: &VerifyingKey, signature: &Signature) -> Result<DecoratedSignature, Sep10Error> {
+    Ok(DecoratedSignature {
+        hint: SignatureHint(hint(key)),
+        signature: stellar_xdr::curr::Signature(
+            signature.to_bytes().to_vec().try_into().map_err(xdr_error)?,
+        ),
+    })
+}
+
+fn xdr_error(e: stellar_xdr::curr::Error) -> Sep10Error {
+    Sep10Error::InvalidChallenge(e.to_string())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVER_SEED: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+    const NOW: u64 = 1_750_000_000;
+
+    fn service() -> Sep10Service {
+        Sep10Service::new(SERVER_SEED, "xlmate.example", "api.xlmate.example", TESTNET_PASSPHRASE)
+            .unwrap()
+    }
+
+    fn wallet(seed: u8) -> (SigningKey, String) {
+        let key = SigningKey::from_bytes(&[seed; 32]);
+        let account = public_key_strkey(&key.verifying_key());
+        (key, account)
+    }
+
+    #[test]
+    fn test_signed_challenge_authenticates_account_once() {
+        let service = service();
+        let (key, account) = wallet(9);
+
+        let challenge = service.challenge_at(&account, NOW, &[7; NONCE_BYTES]).unwrap();
+        assert_eq!(challenge.network_passphrase, TESTNET_PASSPHRASE);
+        // Only the server has signed so far.
+        assert_eq!(
+            service.verify_at(&challenge.transaction, NOW + 1),
+            Err(Sep10Error::MissingSignature("client account"))
+        );
+
+        let signed = sign_challenge(&challenge.transaction, TESTNET_PASSPHRASE, &key).unwrap();
+        assert_eq!(service.verify_at(&signed, NOW + 1), Ok(account));
+        assert_eq!(service.verify_at(&signed, NOW + 2), Err(Sep10Error::Replayed));
+    }
+
+    #[test]
+    fn test_rejects_expired_foreign_and_wrongly_signed_challenges() {
+        let service = service();
+        let (key, account) = wallet(9);
+        let (other_key, _) = wallet(10);
+        let challenge = service.challenge_at(&account, NOW, &[7; NONCE_BYTES]).unwrap();
+
+        let signed = sign_challenge(&challenge.transaction, TESTNET_PASSPHRASE, &key).unwrap();
+        assert_eq!(
+            service.verify_at(&signed, NOW + DEFAULT_TIMEOUT.as_secs() + 1),
+            Err(Sep10Error::Expired)
+        );
+
+        // Signed by someone else, or for another network
+        let wrong_key =
+            sign_challenge(&challenge.transaction, TESTNET_PASSPHRASE, &other_key).unwrap();
+        assert!(service.verify_at(&wrong_key, NOW).is_err());
+        let wrong_network =
+            sign_challenge(&challenge.transaction, PUBLIC_PASSPHRASE, &key).unwrap();
+        assert!(service.verify_at(&wrong_network, NOW).is_err());
+
+        // Issued by a different server
+        let other_server = Sep10Service::new(
+            "0202020202020202020202020202020202020202020202020202020202020202",
+            "xlmate.example",
+            "api.xlmate.example",
+            TESTNET_PASSPHRASE,
+        )
+        .unwrap();
+        assert!(matches!(
+            other_server.verify_at(&signed, NOW),
+            Err(Sep10Error::InvalidChallenge(_))
+        ));
+
+        assert!(matches!(
+            service.challenge("not-an-account"),
+            Err(Sep10Error::InvalidAccount(_))
+        ));
+    }
+}
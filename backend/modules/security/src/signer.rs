@@ -0,0 +1,595 @@
+//! Signs the settlement payloads `game_contract` checks against its
+//! `ADMIN_KEY`: `claim_win`, `claim_draw` and `claim_puzzle_reward`.
+//!
+//! Keys are held in a keyring. The first key signs new payloads. Older keys
+//! stay active until retired, so deployments still configured with an old
+//! `ADMIN_KEY` can be served with `sign_for` while a rotation is rolled out.
+//! The contract switches keys with `rotate_admin_key`, or a `SetAdminKey`
+//! governance proposal once multisig is on; retire the old key after that.
+//! Every signature is written to the audit log before it is returned.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use stellar_strkey::Strkey;
+use stellar_xdr::curr::{
+    DecoratedSignature, Hash, Limits, ReadXdr, SignatureHint, TransactionEnvelope,
+    TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction, WriteXdr,
+};
+
+/// Comma-separated signing keys; the first one signs
+pub const SIGNING_KEYS_ENV: &str = "SETTLEMENT_SIGNING_KEYS";
+/// File with one signing key per line, read when `SETTLEMENT_SIGNING_KEYS` is unset
+pub const SIGNING_KEYS_FILE_ENV: &str = "SETTLEMENT_SIGNING_KEYS_FILE";
+/// File the audit log is appended to; without it records only go to `log`
+pub const AUDIT_LOG_ENV: &str = "SETTLEMENT_AUDIT_LOG";
+
+/// Errors that can occur while loading keys or signing
+#[derive(Debug)]
+pub enum SignerError {
+    InvalidKey(String),
+    NoKeys,
+    UnknownKey(String),
+    LastKey,
+    InvalidAddress(String),
+    InvalidAmount(i64),
+    InvalidTransaction(String),
+    Io(String),
+    AuditFailed(String),
+}
+
+impl fmt::Display for SignerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidKey(e) => write!(f, "Invalid signing key: {}", e),
+            Self::NoKeys => write!(f, "No signing keys configured"),
+            Self::UnknownKey(id) => write!(f, "No active signing key {}", id),
+            Self::LastKey => write!(f, "Cannot retire the only signing key"),
+            Self::InvalidAddress(a) => write!(f, "Invalid Stellar address: {}", a),
+            Self::InvalidAmount(a) => write!(f, "Reward amount must be positive, got {}", a),
+            Self::InvalidTransaction(e) => write!(f, "Invalid transaction: {}", e),
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::AuditFailed(e) => write!(f, "Could not write audit record: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SignerError {}
+
+/// A message `game_contract` verifies with `ed25519_verify`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum SettlementPayload {
+    /// `claim_win`: `game_id_le_u64 || winner_strkey`
+    ClaimWin { game_id: u64, winner: String },
+    /// `claim_draw`: `game_id_le_u64 || "DRAW"`
+    ClaimDraw { game_id: u64 },
+    /// `claim_puzzle_reward`: `recipient_strkey || amount_le_i64 || nonce_le_u64`
+    ClaimPuzzleReward {
+        recipient: String,
+        reward_amount: i64,
+        nonce: u64,
+    },
+}
+
+impl SettlementPayload {
+    pub fn claim_win(game_id: u64, winner: &str) -> Result<Self, SignerError> {
+        Ok(Self::ClaimWin {
+            game_id,
+            winner: canonical_address(winner)?,
+        })
+    }
+
+    pub fn claim_draw(game_id: u64) -> Self {
+        Self::ClaimDraw { game_id }
+    }
+
+    pub fn claim_puzzle_reward(
+        recipient: &str,
+        reward_amount: i64,
+        nonce: u64,
+    ) -> Result<Self, SignerError> {
+        if reward_amount <= 0 {
+            return Err(SignerError::InvalidAmount(reward_amount));
+        }
+        Ok(Self::ClaimPuzzleReward {
+            recipient: canonical_address(recipient)?,
+            reward_amount,
+            nonce,
+        })
+    }
+
+    /// Name of the contract function the signature is for
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::ClaimWin { .. } => "claim_win",
+            Self::ClaimDraw { .. } => "claim_draw",
+            Self::ClaimPuzzleReward { .. } => "claim_puzzle_reward",
+        }
+    }
+
+    /// The bytes the contract hashes
+    pub fn preimage(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        match self {
+            Self::ClaimWin { game_id, winner } => {
+                bytes.extend_from_slice(&game_id.to_le_bytes());
+                bytes.extend_from_slice(winner.as_bytes());
+            }
+            Self::ClaimDraw { game_id } => {
+                bytes.extend_from_slice(&game_id.to_le_bytes());
+                bytes.extend_from_slice(b"DRAW");
+            }
+            Self::ClaimPuzzleReward {
+                recipient,
+                reward_amount,
+                nonce,
+            } => {
+                bytes.extend_from_slice(recipient.as_bytes());
+                bytes.extend_from_slice(&reward_amount.to_le_bytes());
+                bytes.extend_from_slice(&nonce.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// The signed message: `SHA256(preimage)`
+    pub fn digest(&self) -> [u8; 32] {
+        Sha256::digest(self.preimage()).into()
+    }
+}
+
+/// A signature over a `SettlementPayload`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementSignature {
+    /// Hex of the public key that signed, as stored in `ADMIN_KEY`
+    pub key_id: String,
+    pub public_key: [u8; 32],
+    pub signature: [u8; 64],
+}
+
+impl SettlementSignature {
+    pub fn signature_hex(&self) -> String {
+        encode_hex(&self.signature)
+    }
+}
+
+/// One line of the signature audit log
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub issued_at: DateTime<Utc>,
+    pub key_id: String,
+    #[serde(flatten)]
+    pub payload: SettlementPayload,
+    pub digest: String,
+    pub signature: String,
+}
+
+/// Where audit records go. A signature is only returned once `record`
+/// succeeds.
+pub trait AuditLog: Send + Sync {
+    fn record(&self, record: &AuditRecord) -> Result<(), SignerError>;
+}
+
+/// Writes records to the `log` crate at info level
+#[derive(Debug, Default)]
+pub struct LogAuditLog;
+
+impl AuditLog for LogAuditLog {
+    fn record(&self, record: &AuditRecord) -> Result<(), SignerError> {
+        let line =
+            serde_json::to_string(record).map_err(|e| SignerError::AuditFailed(e.to_string()))?;
+        log::info!(target: "settlement_audit", "{}", line);
+        Ok(())
+    }
+}
+
+/// Appends records to a file as JSON lines, and to `log`
+#[derive(Debug)]
+pub struct FileAuditLog {
+    file: Mutex<File>,
+}
+
+impl FileAuditLog {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SignerError> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| SignerError::Io(e.to_string()))?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+}
+
+impl AuditLog for FileAuditLog {
+    fn record(&self, record: &AuditRecord) -> Result<(), SignerError> {
+        let line =
+            serde_json::to_string(record).map_err(|e| SignerError::AuditFailed(e.to_string()))?;
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(file, "{}", line)
+            .and_then(|_| file.sync_data())
+            .map_err(|e| SignerError::AuditFailed(e.to_string()))?;
+        log::info!(target: "settlement_audit", "{}", line);
+        Ok(())
+    }
+}
+
+/// Keeps records in memory, for tests
+#[derive(Debug, Default)]
+pub struct MemoryAuditLog {
+    records: Mutex<Vec<AuditRecord>>,
+}
+
+impl MemoryAuditLog {
+    pub fn records(&self) -> Vec<AuditRecord> {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl AuditLog for MemoryAuditLog {
+    fn record(&self, record: &AuditRecord) -> Result<(), SignerError> {
+        self.records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(record.clone());
+        Ok(())
+    }
+}
+
+/// Signs settlement payloads with the backend's admin keys
+pub struct SettlementSigner {
+    /// Active keys, newest first
+    keys: RwLock<Vec<SigningKey>>,
+    audit: Arc<dyn AuditLog>,
+}
+
+impl fmt::Debug for SettlementSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SettlementSigner")
+            .field("key_ids", &self.key_ids())
+            .finish()
+    }
+}
+
+impl SettlementSigner {
+    /// Build a signer from Stellar secret seeds (`S...`) or 64 hex
+    /// characters. The first key signs; the rest stay active.
+    pub fn from_secrets<S: AsRef<str>>(
+        secrets: &[S],
+        audit: Arc<dyn AuditLog>,
+    ) -> Result<Self, SignerError> {
+        let keys = secrets
+            .iter()
+            .map(|secret| parse_secret(secret.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err(SignerError::NoKeys);
+        }
+        Ok(Self {
+            keys: RwLock::new(keys),
+            audit,
+        })
+    }
+
+    /// Read the keys from `SETTLEMENT_SIGNING_KEYS` or the file named by
+    /// `SETTLEMENT_SIGNING_KEYS_FILE`, and the audit log from
+    /// `SETTLEMENT_AUDIT_LOG`. `None` if no keys are configured.
+    pub fn from_env() -> Result<Option<Self>, SignerError> {
+        let secrets = if let Ok(keys) = std::env::var(SIGNING_KEYS_ENV) {
+            split_secrets(&keys, ',')
+        } else if let Ok(path) = std::env::var(SIGNING_KEYS_FILE_ENV) {
+            let contents =
+                std::fs::read_to_string(&path).map_err(|e| SignerError::Io(e.to_string()))?;
+            split_secrets(&contents, '\n')
+        } else {
+            return Ok(None);
+        };
+
+        let audit: Arc<dyn AuditLog> = match std::env::var(AUDIT_LOG_ENV) {
+            Ok(path) => Arc::new(FileAuditLog::open(path)?),
+            Err(_) => Arc::new(LogAuditLog),
+        };
+        Self::from_secrets(&secrets, audit).map(Some)
+    }
+
+    /// Id of the key new payloads are signed with
+    pub fn primary_key_id(&self) -> String {
+        key_id(&self.read_keys()[0])
+    }
+
+    /// Ids of all active keys, newest first
+    pub fn key_ids(&self) -> Vec<String> {
+        self.read_keys().iter().map(key_id).collect()
+    }
+
+    /// Sign with the primary key.
+    pub fn sign(&self, payload: &SettlementPayload) -> Result<SettlementSignature, SignerError> {
+        let key = self.read_keys()[0].clone();
+        self.sign_with(&key, payload)
+    }
+
+    /// Sign with the active key whose public key is `admin_key`, the
+    /// `ADMIN_KEY` of the contract the signature will be submitted to.
+    pub fn sign_for(
+        &self,
+        admin_key: &[u8; 32],
+        payload: &SettlementPayload,
+    ) -> Result<SettlementSignature, SignerError> {
+        let key = self
+            .read_keys()
+            .iter()
+            .find(|key| key.verifying_key().as_bytes() == admin_key)
+            .cloned()
+            .ok_or_else(|| SignerError::UnknownKey(encode_hex(admin_key)))?;
+        self.sign_with(&key, payload)
+    }
+
+    /// Check `signature` against every active key.
+    pub fn verify(&self, payload: &SettlementPayload, signature: &[u8; 64]) -> bool {
+        let digest = payload.digest();
+        let signature = Signature::from_bytes(signature);
+        self.read_keys()
+            .iter()
+            .any(|key| key.verifying_key().verify(&digest, &signature).is_ok())
+    }
+
+    /// Make `secret` the primary key. Previous keys stay active.
+    pub fn rotate(&self, secret: &str) -> Result<String, SignerError> {
+        let key = parse_secret(secret)?;
+        let id = key_id(&key);
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        keys.retain(|existing| key_id(existing) != id);
+        keys.insert(0, key);
+        log::info!("Settlement signing key rotated to {}", id);
+        Ok(id)
+    }
+
+    /// Stop signing with `key_id`. The last active key cannot be retired.
+    pub fn retire(&self, key_id_hex: &str) -> Result<(), SignerError> {
+        let mut keys = self.keys.write().unwrap_or_else(|e| e.into_inner());
+        let index = keys
+            .iter()
+            .position(|key| key_id(key) == key_id_hex)
+            .ok_or_else(|| SignerError::UnknownKey(key_id_hex.to_string()))?;
+        if keys.len() == 1 {
+            return Err(SignerError::LastKey);
+        }
+        keys.remove(index);
+        log::info!("Settlement signing key {} retired", key_id_hex);
+        Ok(())
+    }
+
+    fn sign_with(
+        &self,
+        key: &SigningKey,
+        payload: &SettlementPayload,
+    ) -> Result<SettlementSignature, SignerError> {
+        let digest = payload.digest();
+        let signature = SettlementSignature {
+            key_id: key_id(key),
+            public_key: key.verifying_key().to_bytes(),
+            signature: key.sign(&digest).to_bytes(),
+        };
+        self.audit.record(&AuditRecord {
+            issued_at: Utc::now(),
+            key_id: signature.key_id.clone(),
+            payload: payload.clone(),
+            digest: encode_hex(&digest),
+            signature: signature.signature_hex(),
+        })?;
+        Ok(signature)
+    }
+
+    fn read_keys(&self) -> std::sync::RwLockReadGuard<'_, Vec<SigningKey>> {
+        self.keys.read().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Parse a Stellar secret seed (`S...`) or 32 hex-encoded bytes.
+pub fn parse_secret(secret: &str) -> Result<SigningKey, SignerError> {
+    let secret = secret.trim();
+    let seed = match Strkey::from_string(secret) {
+        Ok(Strkey::PrivateKeyEd25519(key)) => key.0,
+        _ => decode_hex32(secret)
+            .ok_or_else(|| SignerError::InvalidKey("expected S... or 64 hex characters".into()))?,
+    };
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// Add `key`'s signature to a v1 transaction envelope (base64 XDR), signing
+/// its hash for the network named by `network_passphrase`.
+pub fn sign_transaction(
+    envelope_xdr: &str,
+    network_passphrase: &str,
+    key: &SigningKey,
+) -> Result<String, SignerError> {
+    let xdr_error = |e: stellar_xdr::curr::Error| SignerError::InvalidTransaction(e.to_string());
+    let envelope = TransactionEnvelope::from_xdr_base64(envelope_xdr, Limits::len(1024 * 1024))
+        .map_err(xdr_error)?;
+    let TransactionEnvelope::Tx(mut envelope) = envelope else {
+        return Err(SignerError::InvalidTransaction("not a v1 transaction".into()));
+    };
+    let payload = TransactionSignaturePayload {
+        network_id: Hash(Sha256::digest(network_passphrase.as_bytes()).into()),
+        tagged_transaction: TransactionSignaturePayloadTaggedTransaction::Tx(envelope.tx.clone()),
+    };
+    let hash: [u8; 32] = Sha256::digest(payload.to_xdr(Limits::none()).map_err(xdr_error)?).into();
+
+    let public_key = key.verifying_key().to_bytes();
+    let signature = DecoratedSignature {
+        // The hint is the last four bytes of the public key
+        hint: SignatureHint([public_key[28], public_key[29], public_key[30], public_key[31]]),
+        signature: stellar_xdr::curr::Signature(
+            key.sign(&hash).to_bytes().to_vec().try_into().map_err(xdr_error)?,
+        ),
+    };
+    let mut signatures = envelope.signatures.to_vec();
+    signatures.push(signature);
+    envelope.signatures = signatures.try_into().map_err(xdr_error)?;
+    TransactionEnvelope::Tx(envelope)
+        .to_xdr_base64(Limits::none())
+        .map_err(xdr_error)
+}
+
+/// `G...` address of a signing key
+pub fn account_id(key: &SigningKey) -> String {
+    Strkey::PublicKeyEd25519(stellar_strkey::ed25519::PublicKey(key.verifying_key().to_bytes()))
+        .to_string()
+}
+
+/// Account (`G...`) and contract (`C...`) addresses in the form the
+/// contract's `Address::to_string` produces.
+pub fn canonical_address(address: &str) -> Result<String, SignerError> {
+    match Strkey::from_string(address.trim()) {
+        Ok(key @ (Strkey::PublicKeyEd25519(_) | Strkey::Contract(_))) => Ok(key.to_string()),
+        _ => Err(SignerError::InvalidAddress(address.to_string())),
+    }
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex32(s: &str) -> Option<[u8; 32]> {
+    if s.len() != 64 || !s.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(out)
+}
+
+fn key_id(key: &SigningKey) -> String {
+    encode_hex(key.verifying_key().as_bytes())
+}
+
+/// Non-empty entries, ignoring `#` comments.
+fn split_secrets(s: &str, separator: char) -> Vec<String> {
+    s.split(separator)
+        .map(|entry| entry.split('#').next().unwrap_or("").trim())
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::VerifyingKey;
+
+    // Digests computed by game_contract for game 7, the account below,
+    // a 100_000 stroop reward and nonce 42.
+    const ACCOUNT: &str = "GAEQSCIJBEEQSCIJBEEQSCIJBEEQSCIJBEEQSCIJBEEQSCIJBEEQSH7S";
+    const WIN_DIGEST: &str = "747f5400664c065847ea87f5eaf79f0175dc96968ee3ac8e16eab2871a51438b";
+    const DRAW_DIGEST: &str = "19e5df7d73a09415cdee6c4a7424e13837ef511bc9a9b891c2d80709de784910";
+    const PUZZLE_DIGEST: &str = "cfb8a2e4aa0d788feba4231bd8b98a644ec00bc35980bac7048baa01e97734d1";
+
+    const OLD_SEED: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+    const NEW_SEED: &str = "0202020202020202020202020202020202020202020202020202020202020202";
+
+    #[test]
+    fn test_payloads_match_contract_digests() {
+        let win = SettlementPayload::claim_win(7, ACCOUNT).unwrap();
+        assert_eq!(encode_hex(&win.digest()), WIN_DIGEST);
+        assert_eq!(
+            encode_hex(&SettlementPayload::claim_draw(7).digest()),
+            DRAW_DIGEST
+        );
+        let reward = SettlementPayload::claim_puzzle_reward(ACCOUNT, 100_000, 42).unwrap();
+        assert_eq!(encode_hex(&reward.digest()), PUZZLE_DIGEST);
+
+        assert!(matches!(
+            SettlementPayload::claim_win(7, "not-an-address"),
+            Err(SignerError::InvalidAddress(_))
+        ));
+        assert!(matches!(
+            SettlementPayload::claim_puzzle_reward(ACCOUNT, 0, 42),
+            Err(SignerError::InvalidAmount(0))
+        ));
+    }
+
+    #[test]
+    fn test_signatures_are_audited_and_verify() {
+        let audit = Arc::new(MemoryAuditLog::default());
+        let signer = SettlementSigner::from_secrets(&[OLD_SEED], audit.clone()).unwrap();
+        let payload = SettlementPayload::claim_win(7, ACCOUNT).unwrap();
+
+        let signed = signer.sign(&payload).unwrap();
+        let verifying_key = VerifyingKey::from_bytes(&signed.public_key).unwrap();
+        assert!(verifying_key
+            .verify(&payload.digest(), &Signature::from_bytes(&signed.signature))
+            .is_ok());
+        assert!(signer.verify(&payload, &signed.signature));
+        assert!(!signer.verify(&SettlementPayload::claim_draw(7), &signed.signature));
+
+        let records = audit.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].key_id, signed.key_id);
+        assert_eq!(records[0].payload, payload);
+        assert_eq!(records[0].digest, WIN_DIGEST);
+        assert_eq!(records[0].signature, signed.signature_hex());
+        let line = serde_json::to_value(&records[0]).unwrap();
+        assert_eq!(line["action"], "claim_win");
+        assert_eq!(line["game_id"], 7);
+    }
+
+    #[test]
+    fn test_rotation_keeps_old_keys_until_retired() {
+        let signer =
+            SettlementSigner::from_secrets(&[OLD_SEED], Arc::new(MemoryAuditLog::default()))
+                .unwrap();
+        let old_id = signer.primary_key_id();
+        let old_key: [u8; 32] = parse_secret(OLD_SEED).unwrap().verifying_key().to_bytes();
+        let payload = SettlementPayload::claim_draw(7);
+
+        let new_id = signer.rotate(NEW_SEED).unwrap();
+        assert_eq!(signer.key_ids(), vec![new_id.clone(), old_id.clone()]);
+        assert_eq!(signer.sign(&payload).unwrap().key_id, new_id);
+        // A contract still configured with the old ADMIN_KEY is still served.
+        let old_signature = signer.sign_for(&old_key, &payload).unwrap();
+        assert_eq!(old_signature.key_id, old_id);
+        assert!(signer.verify(&payload, &old_signature.signature));
+
+        signer.retire(&old_id).unwrap();
+        assert!(matches!(
+            signer.sign_for(&old_key, &payload),
+            Err(SignerError::UnknownKey(_))
+        ));
+        assert!(!signer.verify(&payload, &old_signature.signature));
+        assert!(matches!(signer.retire(&new_id), Err(SignerError::LastKey)));
+    }
+
+    #[test]
+    fn test_secrets_parse_from_seed_or_hex_and_file_lines() {
+        let strkey = stellar_strkey::ed25519::PrivateKey([1; 32]).to_string();
+        assert_eq!(
+            key_id(&parse_secret(&strkey).unwrap()),
+            key_id(&parse_secret(OLD_SEED).unwrap())
+        );
+        assert!(matches!(
+            parse_secret("abcd"),
+            Err(SignerError::InvalidKey(_))
+        ));
+
+        let file = format!("# current\n{}\n\n{}  # previous\n", NEW_SEED, OLD_SEED);
+        assert_eq!(split_secrets(&file, '\n'), vec![NEW_SEED, OLD_SEED]);
+        assert!(matches!(
+            SettlementSigner::from_secrets::<&str>(&[], Arc::new(LogAuditLog)),
+            Err(SignerError::NoKeys)
+        ));
+    }
+}
@@ -0,0 +1,57 @@
+//! Anchor finished games' proof hashes in `game_registry`.
+//!
+//! Usage: anchor_proofs [LIMIT]
+//!
+//! Anchors one Merkle batch of up to LIMIT (default and at most
+//! `proof_anchor::MAX_BATCH`) proofs that are not anchored yet, and stores
+//! each game's epoch and path. A batch an interrupted run claimed is finished
+//! first (see `proof_anchor::CLAIM_LEASE_SECS`). Needs
+//! `GAME_REGISTRY_CONTRACT_ID` and `GAME_REGISTRY_SERVER_KEY`. Meant to run
+//! periodically, e.g. from cron; overlapping runs are safe.
+
+use std::process::ExitCode;
+
+use db::db::db::get_db;
+use service::proof_anchor::{anchor_pending, ChainProofRegistry, MAX_BATCH};
+use st_core::StellarRpcClient;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let limit = match std::env::args().nth(1).map(|arg| arg.parse::<u64>()) {
+        None => MAX_BATCH,
+        Some(Ok(limit)) if limit > 0 => limit,
+        Some(_) => {
+            eprintln!("LIMIT must be a positive number");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Also loads .env
+    let db = get_db().await;
+    let registry = match ChainProofRegistry::from_env(StellarRpcClient::from_env()) {
+        Ok(Some(registry)) => registry,
+        Ok(None) => {
+            eprintln!("GAME_REGISTRY_CONTRACT_ID and GAME_REGISTRY_SERVER_KEY must be set");
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            eprintln!("Cannot set up game_registry: {:#}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match anchor_pending(&db, &registry, limit).await {
+        Ok(Some(epoch)) => {
+            eprintln!("Anchored pending proofs as epoch {}", epoch);
+            ExitCode::SUCCESS
+        }
+        Ok(None) => {
+            eprintln!("No proofs to anchor");
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Anchoring failed: {:#}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
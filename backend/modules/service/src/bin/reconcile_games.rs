@@ -0,0 +1,72 @@
+//! Reconcile the `game` table with indexed `game_contract` state.
+//!
+//! Usage: reconcile_games [--enqueue]
+//!
+//! Prints the report as JSON. With `--enqueue`, corrections are queued in
+//! `chain_corrections`; `claim_win` / `claim_draw` need the settlement
+//! signing keys (see `SettlementSigner::from_env`). Exits with 2 when drift
+//! was found.
+
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use db::db::db::get_db;
+use security::SettlementSigner;
+use service::reconciliation::ReconciliationService;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let enqueue = match std::env::args().nth(1).as_deref() {
+        None => false,
+        Some("--enqueue") => true,
+        Some(other) => {
+            eprintln!("unknown argument {}", other);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let signer = if enqueue {
+        match SettlementSigner::from_env() {
+            Ok(signer) => signer.map(Arc::new),
+            Err(e) => {
+                eprintln!("Cannot load settlement signer: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        None
+    };
+
+    let db = get_db().await;
+    let service = ReconciliationService::new(&db, signer);
+    let report = match service.report().await {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("Reconciliation failed: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&report).expect("report serializes")
+    );
+
+    if enqueue {
+        match service.enqueue(&report).await {
+            Ok(summary) => eprintln!(
+                "Queued {} corrections ({} already queued, {} skipped)",
+                summary.queued, summary.duplicates, summary.skipped
+            ),
+            Err(e) => {
+                eprintln!("Queueing corrections failed: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if report.is_clean() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(2)
+    }
+}
@@ -0,0 +1,434 @@
+//! Disconnect slashing, ported from `pers-store/disconnectSlashing.ts`.
+//!
+//! When a player's last socket for a game closes, a timer starts. If the same
+//! player reconnects to the same game within `RECONNECT_GRACE`, the timer is
+//! cancelled. Otherwise the `DisconnectSlasher` is asked to slash them. In
+//! production that is `ChainSlasher`, which calls
+//! `game_contract::slash_disconnect`. The contract ends the game and moves the
+//! slashed share of the absent player's stake (25 % by default) to the
+//! opponent.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use db_entity::{game, player};
+use ed25519_dalek::SigningKey;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use security::signer::{account_id, parse_secret, sign_transaction};
+use st_core::contracts::Address;
+use st_core::{GameContractClient, StellarRpcClient, TransactionStatus};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// How long a disconnected player has to come back before being slashed.
+pub const RECONNECT_GRACE: Duration = Duration::from_secs(30);
+
+/// `game_contract` the slashes are submitted to
+pub const GAME_CONTRACT_ENV: &str = "GAME_CONTRACT_ID";
+/// Secret seed of the contract's slash reporter; it signs and pays for slashes
+pub const SLASH_REPORTER_KEY_ENV: &str = "SLASH_REPORTER_KEY";
+
+#[async_trait]
+pub trait DisconnectSlasher: Send + Sync {
+    /// Slash `user_id` for abandoning `game_id`. Called at most once per
+    /// disconnect, after the grace period.
+    async fn slash(&self, game_id: &str, user_id: i32);
+}
+
+/// Logs the slash it would make. Used when no slash reporter is configured.
+pub struct LoggingSlasher;
+
+#[async_trait]
+impl DisconnectSlasher for LoggingSlasher {
+    async fn slash(&self, game_id: &str, user_id: i32) {
+        log::warn!(
+            "player {} did not reconnect to game {} within {}s; slash pending",
+            user_id,
+            game_id,
+            RECONNECT_GRACE.as_secs()
+        );
+    }
+}
+
+/// Submits `slash_disconnect` for wagered games, signed by the reporter key.
+pub struct ChainSlasher {
+    db: Arc<DatabaseConnection>,
+    contract: GameContractClient,
+    reporter: SigningKey,
+    network_passphrase: String,
+}
+
+impl ChainSlasher {
+    pub fn new(
+        db: Arc<DatabaseConnection>,
+        rpc: StellarRpcClient,
+        contract_id: &str,
+        reporter: SigningKey,
+        network_passphrase: &str,
+    ) -> anyhow::Result<Self> {
+        let contract = GameContractClient::new(rpc, contract_id, &account_id(&reporter))?;
+        Ok(Self {
+            db,
+            contract,
+            reporter,
+            network_passphrase: network_passphrase.to_string(),
+        })
+    }
+
+    /// Read `GAME_CONTRACT_ID`, `SLASH_REPORTER_KEY` and the network
+    /// passphrase. `None` unless both are set.
+    pub fn from_env(
+        db: Arc<DatabaseConnection>,
+        rpc: StellarRpcClient,
+    ) -> anyhow::Result<Option<Self>> {
+        let (Ok(contract_id), Ok(secret)) = (
+            std::env::var(GAME_CONTRACT_ENV),
+            std::env::var(SLASH_REPORTER_KEY_ENV),
+        ) else {
+            return Ok(None);
+        };
+        let reporter = parse_secret(&secret)?;
+        let passphrase = security::sep10::network_passphrase_from_env()?;
+        Self::new(db, rpc, &contract_id, reporter, &passphrase).map(Some)
+    }
+
+    async fn try_slash(&self, game_id: &str, user_id: i32) -> anyhow::Result<()> {
+        let Some((chain_game_id, absent)) = absent_player(&self.db, game_id, user_id).await? else {
+            log::info!(
+                "game {} is not an open wagered game; not slashing player {}",
+                game_id,
+                user_id
+            );
+            return Ok(());
+        };
+        let prepared = self.contract.slash_disconnect(chain_game_id, &absent).await?;
+        let signed = sign_transaction(
+            &prepared.envelope_xdr,
+            &self.network_passphrase,
+            &self.reporter,
+        )?;
+        match self.contract.invoker().submit(&signed).await? {
+            TransactionStatus::Success { ledger, .. } => {
+                log::warn!(
+                    "slashed {} for leaving game {} (chain game {}) in ledger {}",
+                    absent,
+                    game_id,
+                    chain_game_id,
+                    ledger
+                );
+                Ok(())
+            }
+            other => Err(anyhow!("slash_disconnect failed: {:?}", other)),
+        }
+    }
+}
+
+#[async_trait]
+impl DisconnectSlasher for ChainSlasher {
+    async fn slash(&self, game_id: &str, user_id: i32) {
+        if let Err(e) = self.try_slash(game_id, user_id).await {
+            log::error!("could not slash player {} in game {}: {:#}", user_id, game_id, e);
+        }
+    }
+}
+
+/// The `game_contract` game id and the address `user_id` plays it with, for
+/// an unfinished game that is linked to the chain.
+pub async fn absent_player(
+    db: &DatabaseConnection,
+    game_id: &str,
+    user_id: i32,
+) -> anyhow::Result<Option<(u64, Address)>> {
+    let game_id = Uuid::parse_str(game_id).context("game id is not a UUID")?;
+    let Some(game) = game::Entity::find_by_id(game_id).one(db).await? else {
+        return Ok(None);
+    };
+    let Some(chain_game_id) = game.chain_game_id.filter(|_| game.result.is_none()) else {
+        return Ok(None);
+    };
+    let player = player::Entity::find()
+        .filter(player::Column::UserId.eq(user_id))
+        .one(db)
+        .await?
+        .ok_or_else(|| anyhow!("no player for user {}", user_id))?;
+    let address = if player.id == game.white_player {
+        game.white_address
+    } else if player.id == game.black_player {
+        game.black_address
+    } else {
+        return Err(anyhow!("user {} does not play game {}", user_id, game_id));
+    };
+    let address = address.ok_or_else(|| anyhow!("game {} has no address for user {}", game_id, user_id))?;
+    Ok(Some((chain_game_id as u64, address.parse()?)))
+}
+
+/// (game id, user id) -> (timer generation, timer task)
+type Pending = Arc<Mutex<HashMap<(String, i32), (u64, JoinHandle<()>)>>>;
+
+/// Pending slashes keyed by game and player.
+pub struct DisconnectTracker {
+    slasher: Arc<dyn DisconnectSlasher>,
+    grace: Duration,
+    pending: Pending,
+    generation: Mutex<u64>,
+    /// Open sockets per (game id, user id); a player may have several tabs
+    connections: Mutex<HashMap<(String, i32), usize>>,
+}
+
+impl DisconnectTracker {
+    pub fn new(slasher: Arc<dyn DisconnectSlasher>) -> Self {
+        Self::with_grace(slasher, RECONNECT_GRACE)
+    }
+
+    pub fn with_grace(slasher: Arc<dyn DisconnectSlasher>, grace: Duration) -> Self {
+        Self {
+            slasher,
+            grace,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            generation: Mutex::new(0),
+            connections: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a socket closing. Once a player's last socket for a game is
+    /// gone, start the grace timer. A second disconnect restarts it.
+    pub fn record_disconnect(&self, game_id: &str, user_id: i32) {
+        let key = (game_id.to_string(), user_id);
+        {
+            let mut connections = self.connections.lock().unwrap();
+            match connections.get_mut(&key) {
+                Some(open) if *open > 1 => {
+                    *open -= 1;
+                    return;
+                }
+                _ => {
+                    connections.remove(&key);
+                }
+            }
+        }
+        let generation = {
+            let mut generation = self.generation.lock().unwrap();
+            *generation += 1;
+            *generation
+        };
+
+        let slasher = self.slasher.clone();
+        let pending = self.pending.clone();
+        let grace = self.grace;
+        let task_key = key.clone();
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(grace).await;
+            // Only slash if this timer is still the current one for the key.
+            let expired = {
+                let mut pending = pending.lock().unwrap();
+                match pending.get(&task_key) {
+                    Some((current, _)) if *current == generation => {
+                        pending.remove(&task_key);
+                        true
+                    }
+                    _ => false,
+                }
+            };
+            if expired {
+                slasher.slash(&task_key.0, task_key.1).await;
+            }
+        });
+
+        let previous = self
+            .pending
+            .lock()
+            .unwrap()
+            .insert(key, (generation, handle));
+        if let Some((_, previous)) = previous {
+            previous.abort();
+        }
+    }
+
+    /// Count a socket opening, and cancel the pending slash for a returning
+    /// player. Returns whether they came back within the grace period.
+    pub fn record_connect(&self, game_id: &str, user_id: i32) -> bool {
+        let key = (game_id.to_string(), user_id);
+        *self.connections.lock().unwrap().entry(key.clone()).or_default() += 1;
+        match self.pending.lock().unwrap().remove(&key) {
+            Some((_, handle)) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drop all pending slashes for a game that has ended.
+    pub fn clear_game(&self, game_id: &str) {
+        self.pending
+            .lock()
+            .unwrap()
+            .retain(|(game, _), (_, handle)| {
+                if game == game_id {
+                    handle.abort();
+                    false
+                } else {
+                    true
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use sea_orm::{DatabaseBackend, MockDatabase};
+
+    const WHITE: &str = "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H";
+    const BLACK: &str = "GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA";
+
+    fn wagered_game(black_player: Uuid, result: Option<game::ResultSide>) -> game::Model {
+        let now = Utc::now().fixed_offset();
+        game::Model {
+            id: Uuid::new_v4(),
+            white_player: Uuid::new_v4(),
+            black_player,
+            fen: String::new(),
+            pgn: serde_json::json!([]),
+            result,
+            variant: game::GameVariant::Standard,
+            started_at: now,
+            duration_sec: 600,
+            created_at: now,
+            updated_at: now,
+            is_imported: false,
+            original_pgn: None,
+            move_hash: None,
+            meta_hash: None,
+            proof_hash: None,
+            chain_game_id: Some(42),
+            white_address: Some(WHITE.to_string()),
+            black_address: Some(BLACK.to_string()),
+            proof_epoch: None,
+            proof_path: None,
+            proof_root: None,
+            proof_claimed_at: None,
+        }
+    }
+
+    fn player(id: Uuid, user_id: i32) -> player::Model {
+        player::Model {
+            id,
+            username: "black".to_string(),
+            email: "black@example.com".to_string(),
+            password_hash: Vec::new(),
+            biography: String::new(),
+            country: String::new(),
+            flair: String::new(),
+            real_name: String::new(),
+            location: None,
+            fide_rating: None,
+            elo_rating: 1200,
+            social_links: None,
+            is_enabled: true,
+            stellar_address: None,
+            user_id,
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingSlasher {
+        slashed: Mutex<Vec<(String, i32)>>,
+    }
+
+    #[async_trait]
+    impl DisconnectSlasher for RecordingSlasher {
+        async fn slash(&self, game_id: &str, user_id: i32) {
+            self.slashed
+                .lock()
+                .unwrap()
+                .push((game_id.to_string(), user_id));
+        }
+    }
+
+    const GRACE: Duration = Duration::from_millis(50);
+
+    #[tokio::test]
+    async fn test_slashes_after_grace_unless_reconnected() {
+        let slasher = Arc::new(RecordingSlasher::default());
+        let tracker = DisconnectTracker::with_grace(slasher.clone(), GRACE);
+
+        tracker.record_disconnect("g1", 1);
+        tracker.record_disconnect("g1", 2);
+        assert!(tracker.record_connect("g1", 2));
+        assert!(!tracker.record_connect("g1", 3));
+
+        tokio::time::sleep(GRACE * 3).await;
+        assert_eq!(
+            *slasher.slashed.lock().unwrap(),
+            vec![("g1".to_string(), 1)]
+        );
+        // The slash is spent; coming back now is too late.
+        assert!(!tracker.record_connect("g1", 1));
+    }
+
+    #[tokio::test]
+    async fn test_closing_one_of_several_sockets_is_not_a_disconnect() {
+        let slasher = Arc::new(RecordingSlasher::default());
+        let tracker = DisconnectTracker::with_grace(slasher.clone(), GRACE);
+
+        tracker.record_connect("g1", 1);
+        tracker.record_connect("g1", 1);
+        tracker.record_disconnect("g1", 1);
+
+        tokio::time::sleep(GRACE * 3).await;
+        assert!(slasher.slashed.lock().unwrap().is_empty());
+
+        tracker.record_disconnect("g1", 1);
+        tokio::time::sleep(GRACE * 3).await;
+        assert_eq!(
+            *slasher.slashed.lock().unwrap(),
+            vec![("g1".to_string(), 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repeat_disconnect_restarts_timer_and_clear_game_cancels() {
+        let slasher = Arc::new(RecordingSlasher::default());
+        let tracker = DisconnectTracker::with_grace(slasher.clone(), GRACE);
+
+        tracker.record_disconnect("g1", 1);
+        tracker.record_disconnect("g1", 1);
+        tracker.record_disconnect("g2", 1);
+        tracker.clear_game("g2");
+
+        tokio::time::sleep(GRACE * 3).await;
+        assert_eq!(
+            *slasher.slashed.lock().unwrap(),
+            vec![("g1".to_string(), 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_absent_player_maps_user_to_chain_game_and_address() {
+        let black = Uuid::new_v4();
+        let game = wagered_game(black, None);
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![game.clone()]])
+            .append_query_results([vec![player(black, 7)]])
+            .into_connection();
+
+        let absent = absent_player(&db, &game.id.to_string(), 7).await.unwrap();
+        assert_eq!(absent, Some((42, BLACK.parse().unwrap())));
+    }
+
+    #[tokio::test]
+    async fn test_absent_player_skips_finished_games() {
+        let black = Uuid::new_v4();
+        let game = wagered_game(black, Some(game::ResultSide::WhiteWins));
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![game.clone()]])
+            .into_connection();
+
+        assert_eq!(absent_player(&db, &game.id.to_string(), 7).await.unwrap(), None);
+    }
+}
@@ -1,12 +1,72 @@
-use engine::{Engine, process::ProcessEngine, GoParams, EngineResult, EngineError};
+use engine::{Engine, config::EngineConfig, process::ProcessEngine, GoParams, EngineResult, EngineError};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use uuid::Uuid;
+use async_trait::async_trait;
+use st_core::contracts::AiNftError;
+use st_core::{AiNftClient, StellarRpcClient};
+
+/// Read access to the `ai_nft` contract for agent play.
+#[async_trait]
+pub trait AgentDirectory: Send + Sync {
+    /// Address allowed to use the agent: its renter while a rental runs,
+    /// otherwise its owner.
+    async fn user_of(&self, nft_id: u64) -> Result<String, EngineError>;
+    /// Engine config hash the NFT committed to, if any.
+    async fn engine_config(&self, nft_id: u64) -> Result<Option<[u8; 32]>, EngineError>;
+}
+
+/// `AgentDirectory` backed by the deployed `ai_nft` contract.
+pub struct ChainAgentDirectory {
+    nft: AiNftClient,
+}
+
+impl ChainAgentDirectory {
+    pub fn new(nft: AiNftClient) -> Self {
+        Self { nft }
+    }
+
+    /// Read `AGENT_NFT_CONTRACT_ID` and `AGENT_SOURCE_ACCOUNT` (any account;
+    /// it only pays for simulated reads). `None` when agent play is not
+    /// configured.
+    pub fn from_env(rpc: StellarRpcClient) -> Result<Option<Self>, EngineError> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        let Some(contract_id) = var("AGENT_NFT_CONTRACT_ID") else {
+            return Ok(None);
+        };
+        let source_account = var("AGENT_SOURCE_ACCOUNT")
+            .ok_or_else(|| EngineError::Unknown("AGENT_SOURCE_ACCOUNT must be set".to_string()))?;
+        let nft = AiNftClient::new(rpc, contract_id.trim(), source_account.trim())
+            .map_err(|e| EngineError::Unknown(e.to_string()))?;
+        Ok(Some(Self::new(nft)))
+    }
+}
+
+fn directory_error(nft_id: u64, error: anyhow::Error) -> EngineError {
+    match error.downcast_ref::<AiNftError>() {
+        Some(AiNftError::NFTNotFound) => EngineError::AgentUnavailable(format!("agent {} does not exist", nft_id)),
+        _ => EngineError::Unknown(error.to_string()),
+    }
+}
+
+#[async_trait]
+impl AgentDirectory for ChainAgentDirectory {
+    async fn user_of(&self, nft_id: u64) -> Result<String, EngineError> {
+        let user = self.nft.user_of(nft_id).await.map_err(|e| directory_error(nft_id, e))?;
+        Ok(user.to_string())
+    }
+
+    async fn engine_config(&self, nft_id: u64) -> Result<Option<[u8; 32]>, EngineError> {
+        self.nft.engine_config(nft_id).await.map_err(|e| directory_error(nft_id, e))
+    }
+}
 
 pub struct EngineService {
     engines: Arc<Mutex<HashMap<Uuid, Box<dyn Engine>>>>,
     engine_path: String,
+    book_dir: Option<PathBuf>,
 }
 
 impl EngineService {
@@ -14,9 +74,17 @@ impl EngineService {
         Self {
             engines: Arc::new(Mutex::new(HashMap::new())),
             engine_path,
+            book_dir: None,
         }
     }
 
+    /// Directory agent opening books are looked up in. Without one, agents
+    /// whose config names a book are refused.
+    pub fn with_book_dir(mut self, book_dir: impl Into<PathBuf>) -> Self {
+        self.book_dir = Some(book_dir.into());
+        self
+    }
+
     pub async fn get_suggestion(&self, fen: &str, depth: Option<u8>, time_limit_ms: Option<u32>) -> Result<EngineResult, EngineError> {
         // For now, we'll create a new engine instance for each request
         // In a real scenario, we might want to pool them
@@ -36,6 +104,64 @@ impl EngineService {
         Ok(result)
     }
 
+    /// Parse an agent's engine config and check it against the hash its NFT
+    /// committed to on chain.
+    pub fn load_agent_config(json: &str, expected_hash: &[u8; 32]) -> Result<EngineConfig, EngineError> {
+        let config: EngineConfig = serde_json::from_str(json)
+            .map_err(|e| EngineError::ParseError(e.to_string()))?;
+        if &config.config_hash() != expected_hash {
+            return Err(EngineError::ParseError("engine config does not match NFT hash".to_string()));
+        }
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Like `get_suggestion`, but with the agent's options applied. The config
+    /// must name the engine this service runs; it never selects the binary.
+    pub async fn get_suggestion_as_agent(&self, config: &EngineConfig, fen: &str, depth: Option<u8>, time_limit_ms: Option<u32>) -> Result<EngineResult, EngineError> {
+        config.validate()?;
+        let engine_name = std::path::Path::new(&self.engine_path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or_default();
+        if !config.engine.eq_ignore_ascii_case(engine_name) {
+            return Err(EngineError::Unknown(format!("agent engine {} is not available", config.engine)));
+        }
+
+        let options = config.uci_options(self.book_dir.as_deref())?;
+        let mut engine: ProcessEngine = ProcessEngine::new(&self.engine_path).await?;
+        for (name, value) in options {
+            engine.set_option(&name, &value).await?;
+        }
+        engine.is_ready().await?;
+        engine.set_position(fen).await?;
+
+        let params = GoParams {
+            depth,
+            time_limit_ms,
+            search_moves: None,
+        };
+
+        let result = engine.go(params).await?;
+        engine.quit().await?;
+
+        Ok(result)
+    }
+
+    /// Check that `player` may play as agent `nft_id` and return its engine
+    /// config. The player must be the NFT's current user on chain, and
+    /// `config_json` must match the NFT's engine config hash. Pass the result
+    /// to `get_suggestion_as_agent`.
+    pub async fn authorize_agent(directory: &dyn AgentDirectory, player: &str, nft_id: u64, config_json: &str) -> Result<EngineConfig, EngineError> {
+        if directory.user_of(nft_id).await? != player {
+            return Err(EngineError::AgentUnavailable(format!("{} is not the current user of agent {}", player, nft_id)));
+        }
+        let config_hash = directory.engine_config(nft_id).await?
+            .ok_or_else(|| EngineError::AgentUnavailable(format!("agent {} has no engine config", nft_id)))?;
+
+        Self::load_agent_config(config_json, &config_hash)
+    }
+
     pub async fn analyze_position(&self, fen: &str, depth: u8) -> Result<EngineResult, EngineError> {
         self.get_suggestion(fen, Some(depth), None).await
     }
@@ -1,4 +1,4 @@
-use db_entity::{game, prelude::Game};
+use db_entity::{chain_game, game, player, prelude::Game};
 use sea_orm::{
     ColumnTrait, DbErr, EntityTrait, Order, QueryFilter,
     QueryOrder, QuerySelect, ActiveModelTrait, Set, TransactionTrait,
@@ -10,10 +10,21 @@ use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
 use dto::games::{GameStatus, CreateGameRequest, MakeMoveRequest, GameDisplayDTO};
 use error::error::ApiError;
 use chess::pgn::ValidatedGame;
-use chess::{RatingService, RatingConfig};
+use chess::{RatingService, RatingConfig, GameProof};
+use crate::proof_anchor::{verify_anchor, ProofRegistry};
 
 pub struct GameService;
 
+/// Outcome of `GameService::get_game_proof`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProofCheck {
+    pub valid: bool,
+    /// `game_registry` confirmed the proof is in its batch
+    pub anchored: bool,
+    /// Batch the proof was anchored in, if it has been
+    pub epoch: Option<u64>,
+}
+
 impl GameService {
     pub async fn create_game(
         _db: &DatabaseConnection,
@@ -77,8 +88,9 @@ impl GameService {
     /// 
     /// This method:
     /// 1. Updates the game result in a transaction
-    /// 2. Calculates and updates player ratings atomically
-    /// 3. Ensures no race conditions during rapid consecutive games
+    /// 2. Stores the game's proof hashes (see `chess::proof`) if it has a move list
+    /// 3. Calculates and updates player ratings atomically
+    /// 4. Ensures no race conditions during rapid consecutive games
     pub async fn complete_game(
         db: &DatabaseConnection,
         game_id: Uuid,
@@ -104,10 +116,19 @@ impl GameService {
             return Err(ApiError::BadRequest("Game is already completed".to_string()));
         }
 
-        // Update game with result
-        let mut game_active_model: game::ActiveModel = game_model.into();
+        // Update game with result and its proof of game
+        let mut finished = game_model;
+        finished.result = Some(result.clone());
+        let proof = chess::prove_game(&finished).ok();
+
+        let mut game_active_model: game::ActiveModel = finished.into();
         game_active_model.result = Set(Some(result.clone()));
         game_active_model.updated_at = Set(Utc::now().into());
+        if let Some(proof) = proof {
+            game_active_model.move_hash = Set(Some(proof.move_hash));
+            game_active_model.meta_hash = Set(Some(proof.meta_hash));
+            game_active_model.proof_hash = Set(Some(proof.proof_hash));
+        }
 
         game_active_model.update(&txn).await.map_err(ApiError::from)?;
 
@@ -128,6 +149,139 @@ impl GameService {
         }
     }
 
+    /// Link a wagered game to the `game_contract` game it is played under.
+    ///
+    /// A player calls this once their `create_game` or `join_game`
+    /// transaction is in, and again after the opponent joins. The indexed
+    /// chain game must be between the two players' Stellar addresses;
+    /// player2 is only checked once someone has joined. Records the chain id
+    /// and the address playing each side, which settlement, disconnect
+    /// slashing and reconciliation read.
+    pub async fn link_chain_game(
+        db: &DatabaseConnection,
+        game_id: Uuid,
+        player_id: Uuid,
+        chain_game_id: u64,
+    ) -> Result<game::Model, ApiError> {
+        let chain_id = i64::try_from(chain_game_id)
+            .map_err(|_| ApiError::BadRequest("Invalid chain game id".to_string()))?;
+
+        let game_model = game::Entity::find_by_id(game_id)
+            .one(db)
+            .await
+            .map_err(ApiError::from)?
+            .ok_or_else(|| ApiError::NotFound("Game not found".to_string()))?;
+        if player_id != game_model.white_player && player_id != game_model.black_player {
+            return Err(ApiError::Forbidden("Not a player in this game".to_string()));
+        }
+        if game_model.result.is_some() {
+            return Err(ApiError::BadRequest("Game is already completed".to_string()));
+        }
+        if game_model.chain_game_id.is_some_and(|linked| linked != chain_id) {
+            return Err(ApiError::BadRequest(
+                "Game is linked to another chain game".to_string(),
+            ));
+        }
+        let taken = game::Entity::find()
+            .filter(game::Column::ChainGameId.eq(chain_id))
+            .filter(game::Column::Id.ne(game_id))
+            .one(db)
+            .await
+            .map_err(ApiError::from)?;
+        if taken.is_some() {
+            return Err(ApiError::BadRequest(
+                "Chain game is linked to another game".to_string(),
+            ));
+        }
+
+        let chain = chain_game::Entity::find_by_id(chain_id)
+            .one(db)
+            .await
+            .map_err(ApiError::from)?
+            .ok_or_else(|| ApiError::BadRequest("Chain game has not been indexed yet".to_string()))?;
+
+        let address = |id: Uuid| async move {
+            player::Entity::find_by_id(id)
+                .one(db)
+                .await
+                .map_err(ApiError::from)?
+                .and_then(|p| p.stellar_address)
+                .ok_or_else(|| {
+                    ApiError::BadRequest("Both players need a linked Stellar address".to_string())
+                })
+        };
+        let white_address = address(game_model.white_player).await?;
+        let black_address = address(game_model.black_player).await?;
+
+        let opponent = if chain.player1 == white_address {
+            &black_address
+        } else if chain.player1 == black_address {
+            &white_address
+        } else {
+            return Err(ApiError::BadRequest(
+                "Chain game was not created by a player in this game".to_string(),
+            ));
+        };
+        if chain.player2.as_ref().is_some_and(|player2| player2 != opponent) {
+            return Err(ApiError::BadRequest(
+                "Chain game was joined by someone outside this game".to_string(),
+            ));
+        }
+
+        let mut linked: game::ActiveModel = game_model.into();
+        linked.chain_game_id = Set(Some(chain_id));
+        linked.white_address = Set(Some(white_address));
+        linked.black_address = Set(Some(black_address));
+        linked.updated_at = Set(Utc::now().into());
+        linked.update(db).await.map_err(ApiError::from)
+    }
+
+    /// Get the stored proof of a finished game and check it. The proof is
+    /// valid if it still matches the game as recorded now and `registry`
+    /// confirms it is in the batch it was anchored in (see `proof_anchor`).
+    /// Proofs not anchored yet, or with no registry to ask, are not valid.
+    pub async fn get_game_proof(
+        db: &DatabaseConnection,
+        game_id: Uuid,
+        registry: Option<&dyn ProofRegistry>,
+    ) -> Result<(GameProof, ProofCheck), ApiError> {
+        let game_model = game::Entity::find_by_id(game_id)
+            .one(db)
+            .await
+            .map_err(ApiError::from)?
+            .ok_or_else(|| ApiError::NotFound("Game not found".to_string()))?;
+
+        let stored = match (&game_model.move_hash, &game_model.meta_hash, &game_model.proof_hash) {
+            (Some(move_hash), Some(meta_hash), Some(proof_hash)) => GameProof {
+                game_id,
+                move_hash: move_hash.clone(),
+                meta_hash: meta_hash.clone(),
+                proof_hash: proof_hash.clone(),
+            },
+            _ => return Err(ApiError::NotFound("Game has no proof".to_string())),
+        };
+
+        let matches_game = chess::verify_game(&game_model, &stored.proof_hash).unwrap_or(false);
+        let anchored = match registry {
+            Some(registry) => match verify_anchor(registry, &game_model).await {
+                Ok(anchored) => anchored,
+                Err(e) => {
+                    log::warn!("could not verify the proof of game {} on chain: {:#}", game_id, e);
+                    false
+                }
+            },
+            None => false,
+        };
+        Ok((
+            stored,
+            ProofCheck {
+                valid: matches_game && anchored,
+                anchored,
+                epoch: game_model.proof_epoch.map(|epoch| epoch as u64),
+            },
+        ))
+    }
+
     /// Get a player's rating for a specific game (helper method for rating calculations)
     pub async fn get_player_rating_for_game(
         db: &DatabaseConnection,
@@ -331,6 +485,16 @@ mod tests {
                     updated_at: Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()),
                     is_imported: false,
                     original_pgn: None,
+                    move_hash: None,
+                    meta_hash: None,
+                    proof_hash: None,
+                    chain_game_id: None,
+                    white_address: None,
+                    black_address: None,
+                    proof_epoch: None,
+                    proof_path: None,
+                    proof_root: None,
+                    proof_claimed_at: None,
                 }],
             ])
             .into_connection();
@@ -386,6 +550,16 @@ mod tests {
                     updated_at: Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap()),
                     is_imported: false,
                     original_pgn: None,
+                    move_hash: None,
+                    meta_hash: None,
+                    proof_hash: None,
+                    chain_game_id: None,
+                    white_address: None,
+                    black_address: None,
+                    proof_epoch: None,
+                    proof_path: None,
+                    proof_root: None,
+                    proof_claimed_at: None,
             }]])
             .into_connection();
             
@@ -407,4 +581,130 @@ mod tests {
         assert!(log_str.contains(r#"\"game\".\"created_at\" = $2"#));
         assert!(log_str.contains(r#"\"game\".\"id\" < $3"#));
     }
+
+    const WHITE: &str = "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H";
+    const BLACK: &str = "GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA";
+    const STRANGER: &str = "GABQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQHGPC";
+
+    fn open_game() -> game::Model {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        game::Model {
+            id: Uuid::new_v4(),
+            white_player: Uuid::new_v4(),
+            black_player: Uuid::new_v4(),
+            fen: "fen".to_string(),
+            pgn: serde_json::json!({}),
+            result: None,
+            variant: db_entity::game::GameVariant::Standard,
+            started_at: now,
+            duration_sec: 600,
+            created_at: now,
+            updated_at: now,
+            is_imported: false,
+            original_pgn: None,
+            move_hash: None,
+            meta_hash: None,
+            proof_hash: None,
+            chain_game_id: None,
+            white_address: None,
+            black_address: None,
+            proof_epoch: None,
+            proof_path: None,
+            proof_root: None,
+            proof_claimed_at: None,
+        }
+    }
+
+    fn player_with_address(id: Uuid, address: &str) -> player::Model {
+        player::Model {
+            id,
+            username: address[..8].to_string(),
+            email: format!("{}@example.com", &address[..8]),
+            password_hash: Vec::new(),
+            biography: String::new(),
+            country: String::new(),
+            flair: String::new(),
+            real_name: String::new(),
+            location: None,
+            fide_rating: None,
+            elo_rating: 1200,
+            social_links: None,
+            is_enabled: true,
+            stellar_address: Some(address.to_string()),
+            user_id: 0,
+        }
+    }
+
+    fn chain_game(player1: &str, player2: Option<&str>) -> chain_game::Model {
+        chain_game::Model {
+            game_id: 42,
+            state: "InProgress".to_string(),
+            player1: player1.to_string(),
+            player2: player2.map(str::to_string),
+            token: "CADQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQP5KR".to_string(),
+            wager_amount: sea_orm::prelude::Decimal::from(100),
+            winner: None,
+            updated_ledger: 1,
+            last_event_id: "0".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_link_chain_game_records_chain_id_and_addresses() {
+        let game = open_game();
+        let mut linked = game.clone();
+        linked.chain_game_id = Some(42);
+        linked.white_address = Some(WHITE.to_string());
+        linked.black_address = Some(BLACK.to_string());
+
+        // Black created the wager on chain and white joined it.
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![game.clone()]])
+            .append_query_results([Vec::<game::Model>::new()])
+            .append_query_results([vec![chain_game(BLACK, Some(WHITE))]])
+            .append_query_results([vec![player_with_address(game.white_player, WHITE)]])
+            .append_query_results([vec![player_with_address(game.black_player, BLACK)]])
+            .append_query_results([vec![linked.clone()]])
+            .into_connection();
+
+        let result = GameService::link_chain_game(&db, game.id, game.white_player, 42)
+            .await
+            .unwrap();
+        assert_eq!(result, linked);
+
+        let log = format!("{:?}", db.into_transaction_log());
+        assert!(log.contains(r#"UPDATE \"smdb\".\"game\""#));
+        assert!(log.contains(WHITE) && log.contains(BLACK));
+    }
+
+    #[tokio::test]
+    async fn test_link_chain_game_rejects_other_players() {
+        let game = open_game();
+        let players = |db: MockDatabase| {
+            db.append_query_results([vec![player_with_address(game.white_player, WHITE)]])
+                .append_query_results([vec![player_with_address(game.black_player, BLACK)]])
+        };
+
+        // Joined on chain by someone outside the game
+        let db = players(
+            MockDatabase::new(DbBackend::Postgres)
+                .append_query_results([vec![game.clone()]])
+                .append_query_results([Vec::<game::Model>::new()])
+                .append_query_results([vec![chain_game(WHITE, Some(STRANGER))]]),
+        )
+        .into_connection();
+        assert!(matches!(
+            GameService::link_chain_game(&db, game.id, game.white_player, 42).await,
+            Err(ApiError::BadRequest(_))
+        ));
+
+        // Asked for by someone who is not playing
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![game.clone()]])
+            .into_connection();
+        assert!(matches!(
+            GameService::link_chain_game(&db, game.id, Uuid::new_v4(), 42).await,
+            Err(ApiError::Forbidden(_))
+        ));
+    }
 }
@@ -1,4 +1,7 @@
 pub mod helper;
 pub mod players;
 pub mod engine_service;
+pub mod disconnect;
 pub mod games;
+pub mod proof_anchor;
+pub mod reconciliation;
@@ -3,7 +3,10 @@ use db::db::db::get_db;
 use dto::players::{NewPlayer, UpdatePlayer};
 use db_entity::player::{self, Model};
 use error::error::ApiError;
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, Set};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set, SqlErr,
+};
 use uuid::Uuid;
 
 async fn is_username_taken(username: String) -> bool {
@@ -46,6 +49,19 @@ pub async fn find_player_by_id(id: Uuid) -> Result<player::Model, ApiError> {
     }
 }
 
+/// Stellar account linked to an enabled player, if any.
+pub async fn stellar_address_of(
+    db: &DatabaseConnection,
+    id: Uuid,
+) -> Result<Option<String>, ApiError> {
+    let player = player::Entity::find()
+        .filter(player::Column::Id.eq(id))
+        .filter(player::Column::IsEnabled.eq(true))
+        .one(db)
+        .await?;
+    Ok(player.and_then(|player| player.stellar_address))
+}
+
 pub async fn get_player_by_username(username: String) -> Result<Option<Model>, ApiError> {
     let db = get_db().await;
 
@@ -77,6 +93,8 @@ pub async fn add_player(payload: NewPlayer) -> Result<player::Model, ApiError> {
             elo_rating: 1200,
             social_links: None,
             is_enabled: true,
+            stellar_address: None,
+            user_id: 0,
         };
         return Ok(model);
     }
@@ -104,6 +122,105 @@ pub async fn add_player(payload: NewPlayer) -> Result<player::Model, ApiError> {
     }
 }
 
+/// Player linked to a Stellar account proven through SEP-10, created on the
+/// account's first sign-in. Wallet players have no password, and their
+/// address stands in for the username and email until they set their own.
+pub async fn find_or_create_wallet_player(
+    db: &DatabaseConnection,
+    account: &str,
+) -> Result<player::Model, ApiError> {
+    let find = || {
+        player::Entity::find()
+            .filter(player::Column::StellarAddress.eq(account))
+            .one(db)
+    };
+
+    let player = match find().await? {
+        Some(player) => player,
+        None => {
+            let new_player = player::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                username: Set(account.to_string()),
+                email: Set(format!("{}@stellar.invalid", account.to_lowercase())),
+                password_hash: Set(Vec::new()),
+                biography: Set(String::new()),
+                country: Set(String::new()),
+                flair: Set(String::new()),
+                real_name: Set(String::new()),
+                elo_rating: Set(1200),
+                is_enabled: Set(true),
+                stellar_address: Set(Some(account.to_string())),
+                ..Default::default()
+            };
+            // A concurrent first sign-in may have created it in the meantime.
+            player::Entity::insert(new_player)
+                .on_conflict(
+                    OnConflict::column(player::Column::StellarAddress)
+                        .do_nothing()
+                        .to_owned(),
+                )
+                .exec_without_returning(db)
+                .await?;
+            find()
+                .await?
+                .ok_or_else(|| ApiError::NotFound(format!("Player for {}", account)))?
+        }
+    };
+
+    if !player.is_enabled {
+        return Err(ApiError::Forbidden("Player is disabled".to_string()));
+    }
+    Ok(player)
+}
+
+/// Link a Stellar account proven through SEP-10 to an existing player, so a
+/// player who signed up with a password can wager. An account belongs to one
+/// player, and a linked player keeps their account; both are conflicts.
+pub async fn link_stellar_address(
+    db: &DatabaseConnection,
+    player_id: Uuid,
+    account: &str,
+) -> Result<player::Model, ApiError> {
+    let player = player::Entity::find()
+        .filter(player::Column::Id.eq(player_id))
+        .filter(player::Column::IsEnabled.eq(true))
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Player {}", player_id)))?;
+
+    match player.stellar_address.as_deref() {
+        Some(linked) if linked == account => return Ok(player),
+        Some(_) => {
+            return Err(ApiError::Conflict(
+                "Player is already linked to another Stellar account".to_string(),
+            ))
+        }
+        None => {}
+    }
+
+    let already_linked = || {
+        ApiError::Conflict(format!(
+            "Stellar account {} is already linked to another player",
+            account
+        ))
+    };
+    let owner = player::Entity::find()
+        .filter(player::Column::StellarAddress.eq(account))
+        .one(db)
+        .await?;
+    if owner.is_some() {
+        return Err(already_linked());
+    }
+
+    let mut active_model: player::ActiveModel = player.into();
+    active_model.stellar_address = Set(Some(account.to_string()));
+    // The unique index still catches a link made concurrently.
+    active_model.update(db).await.map_err(|err| match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => already_linked(),
+        _ => ApiError::DatabaseError(err),
+    })
+}
+
 pub async fn update_player(id: Uuid, payload: UpdatePlayer) -> Result<player::Model, ApiError> {
     let db = get_db().await;
     let existing_player = find_player_by_id(id).await?;
@@ -168,3 +285,65 @@ pub async fn delete_player(id: Uuid) -> Result<(), ApiError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sea_orm::{DbBackend, MockDatabase};
+
+    const ACCOUNT: &str = "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H";
+
+    fn player(stellar_address: Option<&str>) -> player::Model {
+        player::Model {
+            id: Uuid::new_v4(),
+            username: "alice".to_string(),
+            email: "alice@example.com".to_string(),
+            password_hash: Vec::new(),
+            biography: String::new(),
+            country: String::new(),
+            flair: String::new(),
+            real_name: String::new(),
+            location: None,
+            fide_rating: None,
+            elo_rating: 1200,
+            social_links: None,
+            is_enabled: true,
+            stellar_address: stellar_address.map(str::to_string),
+            user_id: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_link_stellar_address_sets_the_account() {
+        let unlinked = player(None);
+        let mut linked = unlinked.clone();
+        linked.stellar_address = Some(ACCOUNT.to_string());
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![unlinked.clone()]])
+            .append_query_results([Vec::<player::Model>::new()])
+            .append_query_results([vec![linked.clone()]])
+            .into_connection();
+
+        let player = link_stellar_address(&db, unlinked.id, ACCOUNT).await.unwrap();
+        assert_eq!(player.stellar_address.as_deref(), Some(ACCOUNT));
+    }
+
+    #[tokio::test]
+    async fn test_link_stellar_address_refuses_an_account_linked_elsewhere() {
+        let unlinked = player(None);
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![unlinked.clone()]])
+            .append_query_results([vec![player(Some(ACCOUNT))]])
+            .into_connection();
+        let err = link_stellar_address(&db, unlinked.id, ACCOUNT).await.unwrap_err();
+        assert!(matches!(err, ApiError::Conflict(_)));
+
+        // A player keeps the account they linked first.
+        let other = player(Some("GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA"));
+        let db = MockDatabase::new(DbBackend::Postgres)
+            .append_query_results([vec![other.clone()]])
+            .into_connection();
+        let err = link_stellar_address(&db, other.id, ACCOUNT).await.unwrap_err();
+        assert!(matches!(err, ApiError::Conflict(_)));
+    }
+}
@@ -0,0 +1,534 @@
+//! Anchoring of proof of game hashes in `game_registry`.
+//!
+//! `GameService::complete_game` stores each finished game's proof hash
+//! (`chess::proof`). `anchor_pending` collects the proofs not yet anchored,
+//! builds their Merkle tree (`st_core::merkle`) and anchors its root with
+//! `game_registry::anchor_batch`. Each game keeps the epoch it went into and
+//! its path to the root, so `GameService::get_game_proof` can have the
+//! registry check it with `verify_inclusion`.
+//!
+//! Batching keeps this to one contract call per run, and covers games
+//! without Stellar addresses, which `record_game` cannot take.
+//!
+//! A run first claims its proofs in a transaction: the rows are selected with
+//! `FOR UPDATE SKIP LOCKED` and marked with the batch root before anything is
+//! submitted, so concurrent runs never batch the same proof. A claim that is
+//! still unrecorded after `CLAIM_LEASE_SECS` is resumed by a later run, which
+//! first asks the registry whether the root was already anchored, so an
+//! interrupted run never anchors its batch twice.
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use db_entity::game;
+use ed25519_dalek::SigningKey;
+use sea_orm::sea_query::{Expr, LockBehavior, LockType};
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, Order, QueryFilter,
+    QueryOrder, QuerySelect, TransactionTrait,
+};
+use security::signer::{account_id, encode_hex, parse_secret, sign_transaction};
+use serde_json::Value;
+use st_core::contracts::FromScVal;
+use st_core::merkle::{Hash, MerkleBatch};
+use st_core::{GameRegistryClient, StellarRpcClient};
+use uuid::Uuid;
+
+/// `game_registry` the batches are anchored in
+pub const GAME_REGISTRY_ENV: &str = "GAME_REGISTRY_CONTRACT_ID";
+/// Secret seed of the registry's server account, which may anchor batches
+pub const REGISTRY_SERVER_KEY_ENV: &str = "GAME_REGISTRY_SERVER_KEY";
+
+/// Most proofs anchored in one batch
+pub const MAX_BATCH: u64 = 4096;
+
+/// A claimed batch still unrecorded after this long is taken over by the
+/// next run
+pub const CLAIM_LEASE_SECS: i64 = 600;
+
+/// Most recent epochs searched for a resumed batch's root
+const RESUME_SCAN_EPOCHS: u64 = 64;
+
+/// The `game_registry` calls used for anchoring and checking proofs
+#[async_trait]
+pub trait ProofRegistry: Send + Sync {
+    /// Anchor a batch root. Returns the batch's epoch.
+    async fn anchor_batch(&self, root: &Hash, leaf_count: u32) -> anyhow::Result<u64>;
+
+    /// The epoch `root` was recently anchored as, if any.
+    async fn find_batch(&self, root: &Hash) -> anyhow::Result<Option<u64>>;
+
+    /// Whether `proof_hash` is in the batch anchored as `epoch`.
+    async fn verify_inclusion(
+        &self,
+        epoch: u64,
+        proof_hash: &Hash,
+        path: &[Hash],
+    ) -> anyhow::Result<bool>;
+}
+
+/// `ProofRegistry` backed by the contract, signing as the registry server.
+pub struct ChainProofRegistry {
+    registry: GameRegistryClient,
+    server: SigningKey,
+    network_passphrase: String,
+}
+
+impl ChainProofRegistry {
+    pub fn new(
+        rpc: StellarRpcClient,
+        contract_id: &str,
+        server: SigningKey,
+        network_passphrase: &str,
+    ) -> anyhow::Result<Self> {
+        let registry = GameRegistryClient::new(rpc, contract_id, &account_id(&server))?;
+        Ok(Self {
+            registry,
+            server,
+            network_passphrase: network_passphrase.to_string(),
+        })
+    }
+
+    /// Read `GAME_REGISTRY_CONTRACT_ID`, `GAME_REGISTRY_SERVER_KEY` and the
+    /// network passphrase. `None` unless both are set.
+    pub fn from_env(rpc: StellarRpcClient) -> anyhow::Result<Option<Self>> {
+        let (Ok(contract_id), Ok(secret)) = (
+            std::env::var(GAME_REGISTRY_ENV),
+            std::env::var(REGISTRY_SERVER_KEY_ENV),
+        ) else {
+            return Ok(None);
+        };
+        let server = parse_secret(&secret)?;
+        let passphrase = security::sep10::network_passphrase_from_env()?;
+        Self::new(rpc, &contract_id, server, &passphrase).map(Some)
+    }
+}
+
+#[async_trait]
+impl ProofRegistry for ChainProofRegistry {
+    async fn anchor_batch(&self, root: &Hash, leaf_count: u32) -> anyhow::Result<u64> {
+        let prepared = self.registry.anchor_batch(root, leaf_count).await?;
+        let signed = sign_transaction(
+            &prepared.envelope_xdr,
+            &self.network_passphrase,
+            &self.server,
+        )?;
+        let status = self.registry.invoker().submit(&signed).await?;
+        let epoch = status
+            .return_value()?
+            .ok_or_else(|| anyhow!("anchor_batch returned no epoch"))?;
+        u64::from_scval(&epoch)
+    }
+
+    async fn find_batch(&self, root: &Hash) -> anyhow::Result<Option<u64>> {
+        let latest = self.registry.epoch_count().await?;
+        let oldest = latest.saturating_sub(RESUME_SCAN_EPOCHS) + 1;
+        for epoch in (oldest..=latest).rev() {
+            if self.registry.get_batch(epoch).await?.root == *root {
+                return Ok(Some(epoch));
+            }
+        }
+        Ok(None)
+    }
+
+    async fn verify_inclusion(
+        &self,
+        epoch: u64,
+        proof_hash: &Hash,
+        path: &[Hash],
+    ) -> anyhow::Result<bool> {
+        self.registry.verify_inclusion(epoch, proof_hash, path).await
+    }
+}
+
+/// Anchor up to `limit` proofs that are not in a batch yet. Returns the new
+/// epoch, or `None` if there was nothing to anchor. A stale claim left by an
+/// earlier run is finished first, instead of new proofs.
+pub async fn anchor_pending(
+    db: &DatabaseConnection,
+    registry: &dyn ProofRegistry,
+    limit: u64,
+) -> anyhow::Result<Option<u64>> {
+    let stale_before = Utc::now() - Duration::seconds(CLAIM_LEASE_SECS);
+    if let Some(claim) = resume_claim(db, stale_before.fixed_offset()).await? {
+        let root = claim.batch.root();
+        // The earlier run may have anchored the batch before it stopped.
+        let epoch = match registry.find_batch(&root).await? {
+            Some(epoch) => epoch,
+            None => registry.anchor_batch(&root, claim.batch.leaf_count()).await?,
+        };
+        store_anchor(db, &claim, epoch).await?;
+        return Ok(Some(epoch));
+    }
+
+    // If the submit fails the claim stays, and is resumed once it is stale.
+    let Some(claim) = claim_pending(db, limit.min(MAX_BATCH)).await? else {
+        return Ok(None);
+    };
+    let epoch = registry
+        .anchor_batch(&claim.batch.root(), claim.batch.leaf_count())
+        .await?;
+    store_anchor(db, &claim, epoch).await?;
+    Ok(Some(epoch))
+}
+
+/// Proofs claimed for one batch
+struct Claim {
+    root: String,
+    batch: MerkleBatch,
+    games: Vec<(Uuid, Hash)>,
+}
+
+impl Claim {
+    /// Leaves are ordered by game id, so a resumed claim rebuilds the same
+    /// tree from its rows.
+    fn new(mut games: Vec<(Uuid, Hash)>) -> Option<Self> {
+        games.sort_by_key(|(id, _)| *id);
+        let batch = MerkleBatch::new(games.iter().map(|(_, hash)| *hash).collect())?;
+        Some(Self {
+            root: encode_hex(&batch.root()),
+            batch,
+            games,
+        })
+    }
+}
+
+/// Finished games with a proof hash but no epoch or claim, oldest first.
+pub async fn pending_proofs(
+    db: &DatabaseConnection,
+    limit: u64,
+) -> Result<Vec<(Uuid, Hash)>, DbErr> {
+    let games = pending_query(limit).all(db).await?;
+    Ok(decode_proofs(games))
+}
+
+fn pending_query(limit: u64) -> sea_orm::Select<game::Entity> {
+    game::Entity::find()
+        .filter(game::Column::ProofHash.is_not_null())
+        .filter(game::Column::ProofEpoch.is_null())
+        .filter(game::Column::ProofRoot.is_null())
+        .order_by(game::Column::UpdatedAt, Order::Asc)
+        .order_by(game::Column::Id, Order::Asc)
+        .limit(limit)
+}
+
+fn decode_proofs(games: Vec<game::Model>) -> Vec<(Uuid, Hash)> {
+    games
+        .into_iter()
+        .filter_map(|game| {
+            let hash = game.proof_hash.as_deref().and_then(decode_hash);
+            if hash.is_none() {
+                log::warn!("game {} has a malformed proof hash; not anchoring it", game.id);
+            }
+            hash.map(|hash| (game.id, hash))
+        })
+        .collect()
+}
+
+/// Claim up to `limit` pending proofs for a new batch. Rows another run has
+/// locked are skipped.
+async fn claim_pending(db: &DatabaseConnection, limit: u64) -> Result<Option<Claim>, DbErr> {
+    let txn = db.begin().await?;
+    let games = pending_query(limit)
+        .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+        .all(&txn)
+        .await?;
+    let claim = Claim::new(decode_proofs(games));
+    if let Some(claim) = &claim {
+        mark_claimed(&txn, claim).await?;
+    }
+    txn.commit().await?;
+    Ok(claim)
+}
+
+/// Take over one claim made before `stale_before` and not yet recorded.
+async fn resume_claim(
+    db: &DatabaseConnection,
+    stale_before: chrono::DateTime<chrono::FixedOffset>,
+) -> Result<Option<Claim>, DbErr> {
+    let txn = db.begin().await?;
+    let stale = game::Entity::find()
+        .filter(game::Column::ProofEpoch.is_null())
+        .filter(game::Column::ProofRoot.is_not_null())
+        .filter(game::Column::ProofClaimedAt.lt(stale_before))
+        .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+        .one(&txn)
+        .await?;
+    let Some(root) = stale.and_then(|game| game.proof_root) else {
+        txn.commit().await?;
+        return Ok(None);
+    };
+
+    let games = game::Entity::find()
+        .filter(game::Column::ProofEpoch.is_null())
+        .filter(game::Column::ProofRoot.eq(root.as_str()))
+        .lock(LockType::Update)
+        .all(&txn)
+        .await?;
+    let claim = Claim::new(decode_proofs(games)).filter(|claim| claim.root == root);
+    match &claim {
+        Some(claim) => mark_claimed(&txn, claim).await?,
+        None => {
+            // The rows no longer hash to the claimed root, so the batch
+            // cannot have been anchored as claimed; batch them afresh.
+            log::warn!("releasing proof claim {}, its proofs changed", root);
+            game::Entity::update_many()
+                .col_expr(game::Column::ProofRoot, Expr::value(Option::<String>::None))
+                .col_expr(
+                    game::Column::ProofClaimedAt,
+                    Expr::value(Option::<chrono::DateTime<chrono::FixedOffset>>::None),
+                )
+                .filter(game::Column::ProofRoot.eq(root.as_str()))
+                .filter(game::Column::ProofEpoch.is_null())
+                .exec(&txn)
+                .await?;
+        }
+    }
+    txn.commit().await?;
+    Ok(claim)
+}
+
+/// Mark the claim's games with its root, starting or renewing its lease.
+async fn mark_claimed<C: ConnectionTrait>(db: &C, claim: &Claim) -> Result<(), DbErr> {
+    game::Entity::update_many()
+        .col_expr(game::Column::ProofRoot, Expr::value(claim.root.clone()))
+        .col_expr(game::Column::ProofClaimedAt, Expr::value(Utc::now().fixed_offset()))
+        .filter(game::Column::Id.is_in(claim.games.iter().map(|(id, _)| *id)))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Record the epoch and Merkle path of every game in an anchored batch.
+/// Games already recorded are left alone.
+async fn store_anchor(db: &DatabaseConnection, claim: &Claim, epoch: u64) -> Result<(), DbErr> {
+    let txn = db.begin().await?;
+    for (game_id, hash) in &claim.games {
+        let path = claim.batch.proof(hash).unwrap_or_default();
+        let path = Value::from(path.iter().map(|h| encode_hex(h)).collect::<Vec<_>>());
+        game::Entity::update_many()
+            .col_expr(game::Column::ProofEpoch, Expr::value(epoch as i64))
+            .col_expr(game::Column::ProofPath, Expr::value(path))
+            .filter(game::Column::Id.eq(*game_id))
+            .filter(game::Column::ProofRoot.eq(claim.root.as_str()))
+            .filter(game::Column::ProofEpoch.is_null())
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await
+}
+
+/// Whether a stored proof is in the batch its game says it was anchored in.
+/// `false` for games not anchored yet.
+pub async fn verify_anchor(
+    registry: &dyn ProofRegistry,
+    game: &game::Model,
+) -> anyhow::Result<bool> {
+    let (Some(epoch), Some(path), Some(hash)) = (
+        game.proof_epoch,
+        game.proof_path.as_ref(),
+        game.proof_hash.as_deref().and_then(decode_hash),
+    ) else {
+        return Ok(false);
+    };
+    let path = path
+        .as_array()
+        .and_then(|path| {
+            path.iter()
+                .map(|h| h.as_str().and_then(decode_hash))
+                .collect::<Option<Vec<_>>>()
+        })
+        .ok_or_else(|| anyhow!("game {} has a malformed proof path", game.id))?;
+    registry.verify_inclusion(epoch as u64, &hash, &path).await
+}
+
+fn decode_hash(hex: &str) -> Option<Hash> {
+    if hex.len() != 64 || !hex.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use sea_orm::{DatabaseBackend, MockDatabase, MockExecResult};
+    use std::sync::Mutex;
+
+    /// Keeps anchored roots in memory and checks paths with `merkle::verify`.
+    #[derive(Default)]
+    struct MemoryRegistry {
+        roots: Mutex<Vec<Hash>>,
+    }
+
+    #[async_trait]
+    impl ProofRegistry for MemoryRegistry {
+        async fn anchor_batch(&self, root: &Hash, _leaf_count: u32) -> anyhow::Result<u64> {
+            let mut roots = self.roots.lock().unwrap();
+            roots.push(*root);
+            Ok(roots.len() as u64)
+        }
+
+        async fn find_batch(&self, root: &Hash) -> anyhow::Result<Option<u64>> {
+            let roots = self.roots.lock().unwrap();
+            Ok(roots.iter().position(|r| r == root).map(|i| i as u64 + 1))
+        }
+
+        async fn verify_inclusion(
+            &self,
+            epoch: u64,
+            proof_hash: &Hash,
+            path: &[Hash],
+        ) -> anyhow::Result<bool> {
+            let roots = self.roots.lock().unwrap();
+            let root = roots
+                .get(epoch as usize - 1)
+                .ok_or_else(|| anyhow!("no epoch {}", epoch))?;
+            Ok(st_core::merkle::verify(root, proof_hash, path))
+        }
+    }
+
+    fn proven_game(seed: u8) -> game::Model {
+        let now = Utc::now().fixed_offset();
+        game::Model {
+            id: Uuid::from_bytes([seed; 16]),
+            white_player: Uuid::new_v4(),
+            black_player: Uuid::new_v4(),
+            fen: String::new(),
+            pgn: serde_json::json!({ "moves": "e4 e5" }),
+            result: Some(game::ResultSide::Draw),
+            variant: game::GameVariant::Standard,
+            started_at: now,
+            duration_sec: 600,
+            created_at: now,
+            updated_at: now,
+            is_imported: false,
+            original_pgn: None,
+            move_hash: None,
+            meta_hash: None,
+            proof_hash: Some(encode_hex(&[seed; 32])),
+            chain_game_id: None,
+            white_address: None,
+            black_address: None,
+            proof_epoch: None,
+            proof_path: None,
+            proof_root: None,
+            proof_claimed_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pending_proofs_are_anchored_and_verify_on_chain() {
+        let games: Vec<_> = (1..=3).map(proven_game).collect();
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<game::Model>::new()])
+            .append_query_results([games.clone()])
+            .append_exec_results(vec![MockExecResult { last_insert_id: 0, rows_affected: 3 }])
+            .append_exec_results(vec![MockExecResult { last_insert_id: 0, rows_affected: 1 }; 3])
+            .into_connection();
+        let registry = MemoryRegistry::default();
+
+        let epoch = anchor_pending(&db, &registry, 100).await.unwrap();
+        assert_eq!(epoch, Some(1));
+
+        // The stored paths are what get_game_proof hands to verify_inclusion.
+        let batch = MerkleBatch::new((1..=3).map(|i| [i; 32]).collect()).unwrap();
+        for game in &games {
+            let mut anchored = game.clone();
+            anchored.proof_epoch = Some(1);
+            anchored.proof_path = Some(Value::from(
+                batch
+                    .proof(&[game.id.as_bytes()[0]; 32])
+                    .unwrap()
+                    .iter()
+                    .map(|h| encode_hex(h))
+                    .collect::<Vec<_>>(),
+            ));
+            assert!(verify_anchor(&registry, &anchored).await.unwrap());
+
+            // A rewritten proof hash no longer matches the anchored root.
+            anchored.proof_hash = Some(encode_hex(&[0xee; 32]));
+            assert!(!verify_anchor(&registry, &anchored).await.unwrap());
+        }
+        assert!(!verify_anchor(&registry, &games[0]).await.unwrap());
+
+        // The proofs are claimed, with their root, before the submit.
+        let log = format!("{:?}", db.into_transaction_log());
+        assert!(log.contains(r#"\"proof_epoch\" IS NULL"#));
+        assert!(log.contains("FOR UPDATE SKIP LOCKED"));
+        assert!(log.contains(&encode_hex(&batch.root())));
+        assert_eq!(log.matches(r#"UPDATE \"smdb\".\"game\""#).count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_stale_claim_is_resumed_without_anchoring_twice() {
+        let batch = MerkleBatch::new((1..=3).map(|i| [i; 32]).collect()).unwrap();
+        let claimed: Vec<_> = (1..=3)
+            .map(|seed| {
+                let mut game = proven_game(seed);
+                game.proof_root = Some(encode_hex(&batch.root()));
+                game.proof_claimed_at = Some(Utc::now().fixed_offset() - Duration::hours(1));
+                game
+            })
+            .collect();
+        // The earlier run anchored the batch, then stopped before recording it.
+        let registry = MemoryRegistry::default();
+        registry.roots.lock().unwrap().push([0xaa; 32]);
+        registry.roots.lock().unwrap().push(batch.root());
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![claimed[1].clone()]])
+            .append_query_results([claimed.clone()])
+            .append_exec_results(vec![MockExecResult { last_insert_id: 0, rows_affected: 3 }])
+            .append_exec_results(vec![MockExecResult { last_insert_id: 0, rows_affected: 1 }; 3])
+            .into_connection();
+
+        assert_eq!(anchor_pending(&db, &registry, 100).await.unwrap(), Some(2));
+        assert_eq!(registry.roots.lock().unwrap().len(), 2);
+
+        let log = format!("{:?}", db.into_transaction_log());
+        assert!(log.contains(r#"\"proof_claimed_at\" <"#));
+        assert_eq!(log.matches(r#"UPDATE \"smdb\".\"game\""#).count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_stale_claim_that_never_reached_the_chain_is_anchored() {
+        let batch = MerkleBatch::new((1..=2).map(|i| [i; 32]).collect()).unwrap();
+        let claimed: Vec<_> = (1..=2)
+            .map(|seed| {
+                let mut game = proven_game(seed);
+                game.proof_root = Some(encode_hex(&batch.root()));
+                game.proof_claimed_at = Some(Utc::now().fixed_offset() - Duration::hours(1));
+                game
+            })
+            .collect();
+        let registry = MemoryRegistry::default();
+
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([vec![claimed[0].clone()]])
+            .append_query_results([claimed.clone()])
+            .append_exec_results(vec![MockExecResult { last_insert_id: 0, rows_affected: 2 }])
+            .append_exec_results(vec![MockExecResult { last_insert_id: 0, rows_affected: 1 }; 2])
+            .into_connection();
+
+        assert_eq!(anchor_pending(&db, &registry, 100).await.unwrap(), Some(1));
+        assert_eq!(*registry.roots.lock().unwrap(), vec![batch.root()]);
+    }
+
+    #[tokio::test]
+    async fn test_nothing_pending_anchors_nothing() {
+        let db = MockDatabase::new(DatabaseBackend::Postgres)
+            .append_query_results([Vec::<game::Model>::new()])
+            .append_query_results([Vec::<game::Model>::new()])
+            .into_connection();
+        let registry = MemoryRegistry::default();
+
+        assert_eq!(anchor_pending(&db, &registry, 100).await.unwrap(), None);
+        assert!(registry.roots.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,701 @@
+//! Reconciliation of the `game` table against `game_contract`.
+//!
+//! Wagered games store the id of their `game_contract` game in
+//! `game.chain_game_id`. The on-chain side is read from the `chain_*` tables
+//! kept by the chain indexer (`db::chain_indexer`), so a report is only as
+//! fresh as the indexer's cursor.
+//!
+//! Three kinds of drift are reported:
+//!
+//! * a result that differs between the two records,
+//! * a game that finished off-chain but is still open on-chain,
+//! * a tournament escrow still holding stakes for a finished game, or for a
+//!   game no off-chain record points to.
+//!
+//! Where the contract offers a way to fix the drift, the report carries a
+//! `Correction`. `ReconciliationService::enqueue` signs it if needed and
+//! queues it in `chain_corrections`, once per problem, for the submitter.
+//! Results that are already final on both sides but disagree are only
+//! reported; they need a dispute. So are games nobody joined on-chain: the
+//! refund, `cancel_game`, needs player1's own signature.
+//!
+//! Games with an open on-chain dispute are left to the arbitrator: they are
+//! reported as `Disputed` and never corrected, whatever their state.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use db_entity::game::ResultSide;
+use db_entity::{chain_correction, chain_dispute, chain_escrow, chain_game, game};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::{ActiveValue::Set, ColumnTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use security::{SettlementPayload, SettlementSigner};
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+/// Chain ids are looked up in batches of this many
+const LOOKUP_BATCH: usize = 1000;
+
+/// `GameState` variants as written by the indexer
+const CREATED: &str = "Created";
+const IN_PROGRESS: &str = "InProgress";
+const COMPLETED: &str = "Completed";
+const SETTLED: &str = "Settled";
+const DRAWN: &str = "Drawn";
+const FORFEITED: &str = "Forfeited";
+
+/// Closed `DisputeStatus` variants as written by the indexer; any other
+/// status is an open dispute
+const DISPUTE_RESOLVED: &str = "Resolved";
+const DISPUTE_REJECTED: &str = "Rejected";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancyKind {
+    /// `chain_game_id` points at a game the indexer has not seen
+    MissingOnChain,
+    /// Both records have a result and they differ
+    ResultMismatch,
+    /// Finished off-chain, still open on-chain
+    Unsettled,
+    /// Unreleased tournament escrow for a finished or unlinked game
+    OrphanEscrow,
+    /// Has an open dispute; left to the arbitrator
+    Disputed,
+}
+
+/// A `game_contract` call that would bring the chain in line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Correction {
+    /// Pay the wager out to the off-chain winner; needs an admin signature
+    ClaimWin { winner: String },
+    /// Return both stakes; needs an admin signature
+    ClaimDraw { player: String },
+    /// Pay an escrow out to the winner, or back to the players
+    ReleaseTournamentEscrow {
+        escrow_id: i64,
+        winners: Vec<String>,
+        percentages: Vec<u32>,
+    },
+}
+
+impl Correction {
+    pub fn method(&self) -> &'static str {
+        match self {
+            Correction::ClaimWin { .. } => "claim_win",
+            Correction::ClaimDraw { .. } => "claim_draw",
+            Correction::ReleaseTournamentEscrow { .. } => "release_tournament_escrow",
+        }
+    }
+
+    /// Key that stops the same problem being queued twice
+    pub fn dedupe_key(&self, chain_game_id: i64) -> String {
+        match self {
+            Correction::ReleaseTournamentEscrow { escrow_id, .. } => {
+                format!("{}:{}", self.method(), escrow_id)
+            }
+            _ => format!("{}:{}", self.method(), chain_game_id),
+        }
+    }
+
+    fn settlement_payload(&self, chain_game_id: u64) -> Option<Result<SettlementPayload, String>> {
+        match self {
+            Correction::ClaimWin { winner } => {
+                Some(SettlementPayload::claim_win(chain_game_id, winner).map_err(|e| e.to_string()))
+            }
+            Correction::ClaimDraw { .. } => Some(Ok(SettlementPayload::claim_draw(chain_game_id))),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Discrepancy {
+    pub kind: DiscrepancyKind,
+    pub game_id: Option<Uuid>,
+    pub chain_game_id: i64,
+    pub escrow_id: Option<i64>,
+    pub off_chain_result: Option<ResultSide>,
+    pub on_chain_state: Option<String>,
+    pub detail: String,
+    pub correction: Option<Correction>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReconciliationReport {
+    pub checked_games: usize,
+    pub checked_escrows: usize,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl ReconciliationReport {
+    pub fn count(&self, kind: DiscrepancyKind) -> usize {
+        self.discrepancies.iter().filter(|d| d.kind == kind).count()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// What `ReconciliationService::enqueue` did with the report's corrections
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EnqueueSummary {
+    pub queued: usize,
+    /// Already queued by an earlier run
+    pub duplicates: usize,
+    /// Could not be signed, e.g. no signer configured
+    pub skipped: usize,
+}
+
+/// Compare linked games with their on-chain state and check unreleased
+/// escrows. Pure, so it can be tested without a database.
+pub fn reconcile(
+    games: &[game::Model],
+    chain_games: &[chain_game::Model],
+    escrows: &[chain_escrow::Model],
+    disputes: &[chain_dispute::Model],
+) -> ReconciliationReport {
+    let on_chain: HashMap<i64, &chain_game::Model> =
+        chain_games.iter().map(|g| (g.game_id, g)).collect();
+    let disputed: HashMap<i64, &chain_dispute::Model> = disputes
+        .iter()
+        .filter(|d| is_open(&d.status))
+        .map(|d| (d.game_id, d))
+        .collect();
+    let mut report = ReconciliationReport {
+        checked_games: 0,
+        checked_escrows: 0,
+        discrepancies: Vec::new(),
+    };
+
+    let mut linked = HashMap::new();
+    for game in games {
+        let Some(chain_game_id) = game.chain_game_id else {
+            continue;
+        };
+        linked.insert(chain_game_id, game);
+        report.checked_games += 1;
+        match on_chain.get(&chain_game_id) {
+            Some(chain) if disputed.contains_key(&chain_game_id) => {
+                report.discrepancies.push(Discrepancy {
+                    kind: DiscrepancyKind::Disputed,
+                    game_id: Some(game.id),
+                    chain_game_id,
+                    escrow_id: None,
+                    off_chain_result: game.result.clone(),
+                    on_chain_state: Some(chain.state.clone()),
+                    detail: format!(
+                        "dispute {} is {}; the arbitrator settles the game",
+                        disputed[&chain_game_id].dispute_id,
+                        disputed[&chain_game_id].status
+                    ),
+                    correction: None,
+                })
+            }
+            Some(chain) => report.discrepancies.extend(compare(game, chain)),
+            None => report.discrepancies.push(Discrepancy {
+                kind: DiscrepancyKind::MissingOnChain,
+                game_id: Some(game.id),
+                chain_game_id,
+                escrow_id: None,
+                off_chain_result: game.result.clone(),
+                on_chain_state: None,
+                detail: "no such game_contract game has been indexed".to_string(),
+                correction: None,
+            }),
+        }
+    }
+
+    for escrow in escrows.iter().filter(|e| !e.released) {
+        report.checked_escrows += 1;
+        let game = linked.get(&escrow.game_id);
+        let chain = on_chain.get(&escrow.game_id).copied();
+        let finished = chain.is_some_and(|c| is_final(&c.state));
+        if game.is_some() && !finished {
+            continue;
+        }
+
+        let detail = match (game, chain) {
+            (_, Some(c)) if finished => format!("game is {} but the escrow is locked", c.state),
+            (_, Some(_)) => "no off-chain game is linked to this escrow".to_string(),
+            (_, None) => "escrow game has not been indexed".to_string(),
+        };
+        report.discrepancies.push(Discrepancy {
+            kind: DiscrepancyKind::OrphanEscrow,
+            game_id: game.map(|g| g.id),
+            chain_game_id: escrow.game_id,
+            escrow_id: Some(escrow.escrow_id),
+            off_chain_result: game.and_then(|g| g.result.clone()),
+            on_chain_state: chain.map(|c| c.state.clone()),
+            detail,
+            correction: chain
+                .filter(|_| finished)
+                .map(|c| release_escrow(escrow, c)),
+        });
+    }
+
+    report
+}
+
+fn compare(game: &game::Model, chain: &chain_game::Model) -> Option<Discrepancy> {
+    let result = game.result.clone().unwrap_or(ResultSide::Ongoing);
+    let state = chain.state.as_str();
+    let winner = match result {
+        ResultSide::WhiteWins => game.white_address.as_deref(),
+        ResultSide::BlackWins => game.black_address.as_deref(),
+        _ => None,
+    };
+
+    let issue = |kind, detail: String, correction| {
+        Some(Discrepancy {
+            kind,
+            game_id: Some(game.id),
+            chain_game_id: chain.game_id,
+            escrow_id: None,
+            off_chain_result: Some(result.clone()),
+            on_chain_state: Some(chain.state.clone()),
+            detail,
+            correction,
+        })
+    };
+
+    match (&result, state) {
+        (ResultSide::Ongoing, CREATED | IN_PROGRESS) => None,
+        (ResultSide::Ongoing, _) => issue(
+            DiscrepancyKind::ResultMismatch,
+            format!("game is ongoing but {} on-chain", state),
+            None,
+        ),
+
+        // Nobody joined on-chain, so there is no pot to settle. Only
+        // player1 can sign the refund, so there is nothing to queue.
+        (_, CREATED) => issue(
+            DiscrepancyKind::Unsettled,
+            "game finished but was never joined on-chain; player1 must cancel_game".to_string(),
+            None,
+        ),
+
+        (ResultSide::WhiteWins | ResultSide::BlackWins, IN_PROGRESS) => match winner {
+            Some(winner) => issue(
+                DiscrepancyKind::Unsettled,
+                "game finished but the wager was not paid out".to_string(),
+                Some(Correction::ClaimWin {
+                    winner: winner.to_string(),
+                }),
+            ),
+            None => issue(
+                DiscrepancyKind::Unsettled,
+                "game finished but the winner has no Stellar address".to_string(),
+                None,
+            ),
+        },
+        (ResultSide::WhiteWins | ResultSide::BlackWins, SETTLED | FORFEITED)
+            if winner.is_some() && winner == chain.winner.as_deref() =>
+        {
+            None
+        }
+        (ResultSide::WhiteWins | ResultSide::BlackWins, _) => issue(
+            DiscrepancyKind::ResultMismatch,
+            format!(
+                "winner is {} off-chain but the game is {} on-chain with winner {}",
+                winner.unwrap_or("unknown"),
+                state,
+                chain.winner.as_deref().unwrap_or("none")
+            ),
+            None,
+        ),
+
+        (ResultSide::Draw, IN_PROGRESS) => issue(
+            DiscrepancyKind::Unsettled,
+            "game drawn but the stakes were not returned".to_string(),
+            Some(Correction::ClaimDraw {
+                player: chain.player1.clone(),
+            }),
+        ),
+        (ResultSide::Draw, DRAWN) => None,
+        (ResultSide::Draw, _) => issue(
+            DiscrepancyKind::ResultMismatch,
+            format!("game drawn off-chain but {} on-chain", state),
+            None,
+        ),
+
+        // An abandoned game ends on-chain through forfeit, timeout or
+        // slashing, which need a player or the clock; only report it.
+        (ResultSide::Abandoned, IN_PROGRESS) => issue(
+            DiscrepancyKind::Unsettled,
+            "game abandoned but still in progress on-chain".to_string(),
+            None,
+        ),
+        (ResultSide::Abandoned, _) => None,
+    }
+}
+
+fn is_final(state: &str) -> bool {
+    matches!(state, COMPLETED | SETTLED | DRAWN | FORFEITED)
+}
+
+fn is_open(dispute_status: &str) -> bool {
+    !matches!(dispute_status, DISPUTE_RESOLVED | DISPUTE_REJECTED)
+}
+
+/// Release an escrow to the game's winner, or split it between the players
+/// when there is none.
+fn release_escrow(escrow: &chain_escrow::Model, chain: &chain_game::Model) -> Correction {
+    let (winners, percentages) = match (&chain.winner, &chain.player2) {
+        (Some(winner), _) => (vec![winner.clone()], vec![100]),
+        (None, Some(player2)) => (vec![chain.player1.clone(), player2.clone()], vec![50, 50]),
+        (None, None) => (vec![chain.player1.clone()], vec![100]),
+    };
+    Correction::ReleaseTournamentEscrow {
+        escrow_id: escrow.escrow_id,
+        winners,
+        percentages,
+    }
+}
+
+pub struct ReconciliationService<'a> {
+    db: &'a DatabaseConnection,
+    signer: Option<Arc<SettlementSigner>>,
+}
+
+impl<'a> ReconciliationService<'a> {
+    /// Without a signer, `claim_win` and `claim_draw` corrections are
+    /// reported but not queued.
+    pub fn new(db: &'a DatabaseConnection, signer: Option<Arc<SettlementSigner>>) -> Self {
+        Self { db, signer }
+    }
+
+    pub async fn report(&self) -> Result<ReconciliationReport, DbErr> {
+        let games = game::Entity::find()
+            .filter(game::Column::ChainGameId.is_not_null())
+            .all(self.db)
+            .await?;
+        let escrows = chain_escrow::Entity::find()
+            .filter(chain_escrow::Column::Released.eq(false))
+            .all(self.db)
+            .await?;
+
+        let ids: Vec<i64> = games
+            .iter()
+            .filter_map(|g| g.chain_game_id)
+            .chain(escrows.iter().map(|e| e.game_id))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let mut chain_games = Vec::with_capacity(ids.len());
+        for batch in ids.chunks(LOOKUP_BATCH) {
+            chain_games.extend(
+                chain_game::Entity::find()
+                    .filter(chain_game::Column::GameId.is_in(batch.iter().copied()))
+                    .all(self.db)
+                    .await?,
+            );
+        }
+
+        let disputes = chain_dispute::Entity::find()
+            .filter(chain_dispute::Column::Status.is_not_in([DISPUTE_RESOLVED, DISPUTE_REJECTED]))
+            .all(self.db)
+            .await?;
+
+        Ok(reconcile(&games, &chain_games, &escrows, &disputes))
+    }
+
+    /// Queue the report's corrections in `chain_corrections`. Problems that
+    /// already have a row, whatever its status, are not queued again.
+    pub async fn enqueue(&self, report: &ReconciliationReport) -> Result<EnqueueSummary, DbErr> {
+        let mut summary = EnqueueSummary::default();
+        for discrepancy in &report.discrepancies {
+            let Some(correction) = &discrepancy.correction else {
+                continue;
+            };
+            let args = match self.args(discrepancy.chain_game_id, correction) {
+                Ok(args) => args,
+                Err(e) => {
+                    log::warn!(
+                        "not queueing {} for game {}: {}",
+                        correction.method(),
+                        discrepancy.chain_game_id,
+                        e
+                    );
+                    summary.skipped += 1;
+                    continue;
+                }
+            };
+
+            let inserted = chain_correction::Entity::insert(chain_correction::ActiveModel {
+                dedupe_key: Set(correction.dedupe_key(discrepancy.chain_game_id)),
+                game_id: Set(discrepancy.game_id),
+                chain_game_id: Set(discrepancy.chain_game_id),
+                method: Set(correction.method().to_string()),
+                args: Set(args),
+                reason: Set(discrepancy.detail.clone()),
+                status: Set("pending".to_string()),
+                ..Default::default()
+            })
+            .on_conflict(
+                OnConflict::column(chain_correction::Column::DedupeKey)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec_without_returning(self.db)
+            .await?;
+            if inserted == 0 {
+                summary.duplicates += 1;
+            } else {
+                summary.queued += 1;
+            }
+        }
+        Ok(summary)
+    }
+
+    /// Contract arguments for `correction`, signed where the contract wants
+    /// an admin signature
+    fn args(&self, chain_game_id: i64, correction: &Correction) -> Result<Value, String> {
+        let game_id = u64::try_from(chain_game_id).map_err(|_| "negative game id".to_string())?;
+        let mut args = serde_json::to_value(correction).map_err(|e| e.to_string())?;
+        args["game_id"] = json!(game_id);
+
+        if let Some(payload) = correction.settlement_payload(game_id) {
+            let signer = self
+                .signer
+                .as_ref()
+                .ok_or("no settlement signer configured")?;
+            let signature = signer.sign(&payload?).map_err(|e| e.to_string())?;
+            args["signature"] = json!(signature.signature_hex());
+            args["key_id"] = json!(signature.key_id);
+        }
+        Ok(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, Utc};
+    use sea_orm::prelude::Decimal;
+    use security::signer::MemoryAuditLog;
+
+    const WHITE: &str = "GAEQSCIJBEEQSCIJBEEQSCIJBEEQSCIJBEEQSCIJBEEQSCIJBEEQSH7S";
+    const BLACK: &str = "GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA";
+    const TOKEN: &str = "CADQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQP5KR";
+
+    fn game(chain_game_id: i64, result: ResultSide) -> game::Model {
+        let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+        game::Model {
+            id: Uuid::new_v4(),
+            white_player: Uuid::new_v4(),
+            black_player: Uuid::new_v4(),
+            fen: "fen".to_string(),
+            pgn: json!({}),
+            result: Some(result),
+            variant: game::GameVariant::Standard,
+            started_at: now,
+            duration_sec: 600,
+            created_at: now,
+            updated_at: now,
+            is_imported: false,
+            original_pgn: None,
+            move_hash: None,
+            meta_hash: None,
+            proof_hash: None,
+            chain_game_id: Some(chain_game_id),
+            white_address: Some(WHITE.to_string()),
+            black_address: Some(BLACK.to_string()),
+            proof_epoch: None,
+            proof_path: None,
+            proof_root: None,
+            proof_claimed_at: None,
+        }
+    }
+
+    fn chain(game_id: i64, state: &str, winner: Option<&str>) -> chain_game::Model {
+        chain_game::Model {
+            game_id,
+            state: state.to_string(),
+            player1: WHITE.to_string(),
+            player2: Some(BLACK.to_string()),
+            token: TOKEN.to_string(),
+            wager_amount: Decimal::from(100),
+            winner: winner.map(str::to_string),
+            updated_ledger: 1,
+            last_event_id: "0".to_string(),
+        }
+    }
+
+    fn escrow(escrow_id: i64, game_id: i64) -> chain_escrow::Model {
+        chain_escrow::Model {
+            escrow_id,
+            game_id,
+            locked_until: 10,
+            released: false,
+            updated_ledger: 1,
+            last_event_id: "0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_reconcile_classifies_drift() {
+        let games = vec![
+            game(1, ResultSide::WhiteWins), // settled to white: fine
+            game(2, ResultSide::WhiteWins), // settled to black
+            game(3, ResultSide::BlackWins), // still in progress
+            game(4, ResultSide::Draw),      // still in progress
+            game(5, ResultSide::Draw),      // never joined
+            game(6, ResultSide::Ongoing),   // drawn on-chain
+            game(7, ResultSide::Draw),      // not indexed
+        ];
+        let chain_games = vec![
+            chain(1, SETTLED, Some(WHITE)),
+            chain(2, SETTLED, Some(BLACK)),
+            chain(3, IN_PROGRESS, None),
+            chain(4, IN_PROGRESS, None),
+            chain(5, CREATED, None),
+            chain(6, DRAWN, None),
+            chain(8, DRAWN, None),
+            chain(9, IN_PROGRESS, None),
+        ];
+        // Escrow 1 is for a settled game, 2 for an unlinked finished game,
+        // 3 for an unlinked open one, 4 for an open linked one.
+        let escrows = vec![escrow(1, 1), escrow(2, 8), escrow(3, 9), escrow(4, 3)];
+
+        let report = reconcile(&games, &chain_games, &escrows, &[]);
+        assert_eq!(report.checked_games, 7);
+        assert_eq!(report.checked_escrows, 4);
+
+        let found: Vec<_> = report
+            .discrepancies
+            .iter()
+            .map(|d| (d.kind, d.chain_game_id, d.correction.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (DiscrepancyKind::ResultMismatch, 2, None),
+                (
+                    DiscrepancyKind::Unsettled,
+                    3,
+                    Some(Correction::ClaimWin {
+                        winner: BLACK.to_string()
+                    })
+                ),
+                (
+                    DiscrepancyKind::Unsettled,
+                    4,
+                    Some(Correction::ClaimDraw {
+                        player: WHITE.to_string()
+                    })
+                ),
+                (DiscrepancyKind::Unsettled, 5, None),
+                (DiscrepancyKind::ResultMismatch, 6, None),
+                (DiscrepancyKind::MissingOnChain, 7, None),
+                (
+                    DiscrepancyKind::OrphanEscrow,
+                    1,
+                    Some(Correction::ReleaseTournamentEscrow {
+                        escrow_id: 1,
+                        winners: vec![WHITE.to_string()],
+                        percentages: vec![100]
+                    })
+                ),
+                (
+                    DiscrepancyKind::OrphanEscrow,
+                    8,
+                    Some(Correction::ReleaseTournamentEscrow {
+                        escrow_id: 2,
+                        winners: vec![WHITE.to_string(), BLACK.to_string()],
+                        percentages: vec![50, 50]
+                    })
+                ),
+                (DiscrepancyKind::OrphanEscrow, 9, None),
+            ]
+        );
+        assert_eq!(report.count(DiscrepancyKind::Unsettled), 3);
+    }
+
+    fn dispute(dispute_id: i64, game_id: i64, status: &str) -> chain_dispute::Model {
+        chain_dispute::Model {
+            dispute_id,
+            game_id,
+            filer: WHITE.to_string(),
+            against: BLACK.to_string(),
+            status: status.to_string(),
+            winner: None,
+            updated_ledger: 1,
+            last_event_id: "0".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_reconcile_leaves_disputed_games_to_the_arbitrator() {
+        let games = vec![
+            game(1, ResultSide::WhiteWins), // open dispute
+            game(2, ResultSide::WhiteWins), // dispute rejected
+        ];
+        let chain_games = vec![chain(1, IN_PROGRESS, None), chain(2, IN_PROGRESS, None)];
+        let disputes = vec![dispute(10, 1, "Pending"), dispute(11, 2, DISPUTE_REJECTED)];
+
+        let report = reconcile(&games, &chain_games, &[], &disputes);
+        let found: Vec<_> = report
+            .discrepancies
+            .iter()
+            .map(|d| (d.kind, d.chain_game_id, d.correction.clone()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (DiscrepancyKind::Disputed, 1, None),
+                (
+                    DiscrepancyKind::Unsettled,
+                    2,
+                    Some(Correction::ClaimWin {
+                        winner: WHITE.to_string()
+                    })
+                ),
+            ]
+        );
+        assert!(report.discrepancies[0].detail.contains("dispute 10"));
+    }
+
+    #[test]
+    fn test_correction_args_are_signed_for_claims() {
+        let db = DatabaseConnection::Disconnected;
+        let signer = SettlementSigner::from_secrets(
+            &["0101010101010101010101010101010101010101010101010101010101010101"],
+            Arc::new(MemoryAuditLog::default()),
+        )
+        .unwrap();
+        let service = ReconciliationService::new(&db, Some(Arc::new(signer)));
+
+        let draw = Correction::ClaimDraw {
+            player: WHITE.to_string(),
+        };
+        let args = service.args(7, &draw).unwrap();
+        assert_eq!(args["method"], "claim_draw");
+        assert_eq!(args["game_id"], 7);
+        assert_eq!(args["player"], WHITE);
+        assert_eq!(args["signature"].as_str().unwrap().len(), 128);
+        assert_eq!(draw.dedupe_key(7), "claim_draw:7");
+
+        // An escrow release needs no signature, so works without a signer.
+        let unsigned = ReconciliationService::new(&db, None);
+        let release = Correction::ReleaseTournamentEscrow {
+            escrow_id: 3,
+            winners: vec![WHITE.to_string()],
+            percentages: vec![100],
+        };
+        assert!(unsigned
+            .args(7, &release)
+            .unwrap()
+            .get("signature")
+            .is_none());
+        assert!(unsigned.args(7, &draw).is_err());
+        // Invalid winner addresses are caught before signing.
+        let bad = Correction::ClaimWin {
+            winner: "GBLACK".to_string(),
+        };
+        assert!(service.args(7, &bad).is_err());
+    }
+}
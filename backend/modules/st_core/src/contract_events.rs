@@ -0,0 +1,749 @@
+//! Decoder for the events published by the `game_contract` Soroban contract.
+//!
+//! The contract publishes every state transition with topics
+//! `(category: Symbol, action: Symbol)` and a versioned payload struct, which
+//! arrives here as an `ScMap` keyed by field name. See
+//! `contracts/game_contract/src/events.rs` for the publishing side.
+//!
+//! Tournament escrow events (`tl_escrow`) carry plain tuples instead, and
+//! `decode_nft_event` covers the ownership changes published by `ai_nft`.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use stellar_xdr::curr::{Limits, ReadXdr, ScMap, ScVal};
+
+/// Highest payload version this decoder understands.
+pub const SUPPORTED_EVENT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameEvent {
+    pub version: u32,
+    pub game_id: u64,
+    pub state: String,
+    pub player1: String,
+    pub player2: Option<String>,
+    pub token: String,
+    pub wager_amount: i128,
+    pub winner: Option<String>,
+    pub ledger: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveEvent {
+    pub version: u32,
+    pub game_id: u64,
+    pub player: String,
+    pub move_number: u32,
+    pub move_data: Vec<u32>,
+    pub ledger: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutEvent {
+    pub version: u32,
+    pub game_id: u64,
+    pub token: String,
+    pub recipient: String,
+    pub amount: i128,
+    pub fee: i128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisputeEvent {
+    pub version: u32,
+    pub dispute_id: u64,
+    pub game_id: u64,
+    pub filer: String,
+    pub against: String,
+    pub status: String,
+    pub winner: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeProposalEvent {
+    pub version: u32,
+    pub signer: String,
+    pub new_fee_bips: u32,
+    pub new_treasury_address: String,
+    pub approvals: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GovernanceEvent {
+    pub version: u32,
+    pub proposal_id: u64,
+    pub signer: String,
+    pub action: String,    // GovernanceAction variant, e.g. "SetMaxStake"
+    pub args: Vec<String>, // variant arguments; addresses as strkeys, hashes as hex
+    pub approvals: u32,
+    pub expires_at: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PredictionEvent {
+    pub version: u32,
+    pub game_id: u64,
+    pub bettor: Option<String>,
+    pub amount: i128,
+    pub status: String,
+    pub outcome: Option<String>, // set once the pool is Resolved
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscrowEvent {
+    pub escrow_id: u64,
+    pub game_id: Option<u64>,      // only in `created`
+    pub locked_until: Option<u64>, // only in `created`
+}
+
+/// An `ai_nft` ownership change. Mints have no `from`, burns no `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftTransferEvent {
+    pub nft_id: u64,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum ContractEvent {
+    GameCreated(GameEvent),
+    GameJoined(GameEvent),
+    MoveSubmitted(MoveEvent),
+    GameDrawn(GameEvent),
+    GameSettled(GameEvent),
+    GameCancelled(GameEvent),
+    GameForfeited(GameEvent),
+    GameTimedOut(GameEvent),
+    GameAbandoned(GameEvent),
+    PayoutToWinner(PayoutEvent),
+    Refund(PayoutEvent),
+    DisputeFiled(DisputeEvent),
+    DisputeResolved(DisputeEvent),
+    DisputeRejected(DisputeEvent),
+    FeeChangeProposed(FeeProposalEvent),
+    FeeChangeApproved(FeeProposalEvent),
+    FeeChangeExecuted(FeeProposalEvent),
+    FeeChangeCancelled(FeeProposalEvent),
+    GovernanceProposed(GovernanceEvent),
+    GovernanceApproved(GovernanceEvent),
+    GovernanceExecuted(GovernanceEvent),
+    GovernanceCancelled(GovernanceEvent),
+    SideBetPlaced(PredictionEvent),
+    PredictionPoolResolved(PredictionEvent),
+    EscrowCreated(EscrowEvent),
+    EscrowReleased(EscrowEvent),
+}
+
+impl ContractEvent {
+    /// The game this event belongs to, if any.
+    pub fn game_id(&self) -> Option<u64> {
+        match self {
+            Self::GameCreated(e)
+            | Self::GameJoined(e)
+            | Self::GameDrawn(e)
+            | Self::GameSettled(e)
+            | Self::GameCancelled(e)
+            | Self::GameForfeited(e)
+            | Self::GameTimedOut(e)
+            | Self::GameAbandoned(e) => Some(e.game_id),
+            Self::MoveSubmitted(e) => Some(e.game_id),
+            Self::PayoutToWinner(e) | Self::Refund(e) => Some(e.game_id),
+            Self::DisputeFiled(e) | Self::DisputeResolved(e) | Self::DisputeRejected(e) => {
+                Some(e.game_id)
+            }
+            Self::SideBetPlaced(e) | Self::PredictionPoolResolved(e) => Some(e.game_id),
+            Self::EscrowCreated(e) | Self::EscrowReleased(e) => e.game_id,
+            Self::FeeChangeProposed(_)
+            | Self::FeeChangeApproved(_)
+            | Self::FeeChangeExecuted(_)
+            | Self::FeeChangeCancelled(_)
+            | Self::GovernanceProposed(_)
+            | Self::GovernanceApproved(_)
+            | Self::GovernanceExecuted(_)
+            | Self::GovernanceCancelled(_) => None,
+        }
+    }
+}
+
+/// Decodes a contract event from its topics and data.
+///
+/// Returns `Ok(None)` for topics this decoder does not index (e.g. SEP-10 or
+/// puzzle-reward events) and an error for a known topic whose payload is
+/// malformed or newer than `SUPPORTED_EVENT_VERSION`.
+pub fn decode_event(topics: &[ScVal], data: &ScVal) -> Result<Option<ContractEvent>> {
+    let (category, action) = match topics {
+        [ScVal::Symbol(category), ScVal::Symbol(action), ..] => (
+            category.to_utf8_string_lossy(),
+            action.to_utf8_string_lossy(),
+        ),
+        _ => return Ok(None),
+    };
+
+    let event = match (category.as_str(), action.as_str()) {
+        ("game", "move") => ContractEvent::MoveSubmitted(decode_move(data)?),
+        ("game", action) => {
+            let event = decode_game(data)?;
+            match action {
+                "created" => ContractEvent::GameCreated(event),
+                "joined" => ContractEvent::GameJoined(event),
+                "drawn" => ContractEvent::GameDrawn(event),
+                "settled" => ContractEvent::GameSettled(event),
+                "cancelled" => ContractEvent::GameCancelled(event),
+                "forfeit" => ContractEvent::GameForfeited(event),
+                "timeout" => ContractEvent::GameTimedOut(event),
+                "abandoned" => ContractEvent::GameAbandoned(event),
+                _ => return Ok(None),
+            }
+        }
+        ("payout", action) => {
+            let event = decode_payout(data)?;
+            match action {
+                "winner" => ContractEvent::PayoutToWinner(event),
+                "refund" => ContractEvent::Refund(event),
+                _ => return Ok(None),
+            }
+        }
+        ("dispute", action) => {
+            let event = decode_dispute(data)?;
+            match action {
+                "filed" => ContractEvent::DisputeFiled(event),
+                "solved" => ContractEvent::DisputeResolved(event),
+                "reject" => ContractEvent::DisputeRejected(event),
+                _ => return Ok(None),
+            }
+        }
+        ("multisig", action) => {
+            let event = decode_fee_proposal(data)?;
+            match action {
+                "proposed" => ContractEvent::FeeChangeProposed(event),
+                "approved" => ContractEvent::FeeChangeApproved(event),
+                "executed" => ContractEvent::FeeChangeExecuted(event),
+                "cancel" => ContractEvent::FeeChangeCancelled(event),
+                _ => return Ok(None),
+            }
+        }
+        ("gov", action) => {
+            let event = decode_governance(data)?;
+            match action {
+                "proposed" => ContractEvent::GovernanceProposed(event),
+                "approved" => ContractEvent::GovernanceApproved(event),
+                "executed" => ContractEvent::GovernanceExecuted(event),
+                "cancel" => ContractEvent::GovernanceCancelled(event),
+                _ => return Ok(None),
+            }
+        }
+        ("predict", action) => {
+            let event = decode_prediction(data)?;
+            match action {
+                "bet" => ContractEvent::SideBetPlaced(event),
+                "resolved" => ContractEvent::PredictionPoolResolved(event),
+                _ => return Ok(None),
+            }
+        }
+        ("tl_escrow", "created") => ContractEvent::EscrowCreated(decode_escrow_created(data)?),
+        ("tl_escrow", "released") => ContractEvent::EscrowReleased(EscrowEvent {
+            escrow_id: scalar_u64(data, "escrow id")?,
+            game_id: None,
+            locked_until: None,
+        }),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(event))
+}
+
+/// Same as [`decode_event`] but takes base64 XDR, as returned by Soroban RPC
+/// `getEvents`.
+pub fn decode_event_xdr(topics: &[String], data: &str) -> Result<Option<ContractEvent>> {
+    let topics = topics
+        .iter()
+        .map(|topic| ScVal::from_xdr_base64(topic, Limits::none()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow!("Invalid topic XDR: {}", e))?;
+    let data = ScVal::from_xdr_base64(data, Limits::none())
+        .map_err(|e| anyhow!("Invalid event data XDR: {}", e))?;
+
+    decode_event(&topics, &data)
+}
+
+/// Decodes an `ai_nft` event that moves a token: `transfer` (also emitted
+/// by marketplace sales), `mint` and `burn`. Other topics give `Ok(None)`.
+pub fn decode_nft_event(topics: &[ScVal], data: &ScVal) -> Result<Option<NftTransferEvent>> {
+    let nft_id = || scalar_u64(data, "nft id");
+    let address = |value: &ScVal| match value {
+        ScVal::Address(address) => Ok(address.to_string()),
+        _ => Err(anyhow!("Expected address topic")),
+    };
+
+    let event = match topics {
+        [ScVal::Symbol(name), from, to] if name.to_utf8_string_lossy() == "transfer" => {
+            NftTransferEvent {
+                nft_id: nft_id()?,
+                from: Some(address(from)?),
+                to: Some(address(to)?),
+            }
+        }
+        [ScVal::Symbol(name), minter] if name.to_utf8_string_lossy() == "mint" => {
+            NftTransferEvent {
+                nft_id: nft_id()?,
+                from: None,
+                to: Some(address(minter)?),
+            }
+        }
+        [ScVal::Symbol(name), owner] if name.to_utf8_string_lossy() == "burn" => NftTransferEvent {
+            nft_id: nft_id()?,
+            from: Some(address(owner)?),
+            to: None,
+        },
+        _ => return Ok(None),
+    };
+    Ok(Some(event))
+}
+
+/// Same as [`decode_nft_event`] but takes base64 XDR.
+pub fn decode_nft_event_xdr(topics: &[String], data: &str) -> Result<Option<NftTransferEvent>> {
+    let topics = topics
+        .iter()
+        .map(|topic| ScVal::from_xdr_base64(topic, Limits::none()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow!("Invalid topic XDR: {}", e))?;
+    let data = ScVal::from_xdr_base64(data, Limits::none())
+        .map_err(|e| anyhow!("Invalid event data XDR: {}", e))?;
+
+    decode_nft_event(&topics, &data)
+}
+
+// ── Payload decoding ──────────────────────────────────────────────────────────
+
+fn decode_game(data: &ScVal) -> Result<GameEvent> {
+    let fields = Fields::new(data)?;
+    Ok(GameEvent {
+        version: fields.version()?,
+        game_id: fields.u64("game_id")?,
+        state: fields.variant("state")?,
+        player1: fields.address("player1")?,
+        player2: fields.opt_address("player2")?,
+        token: fields.address("token")?,
+        wager_amount: fields.i128("wager_amount")?,
+        winner: fields.opt_address("winner")?,
+        ledger: fields.u64("ledger")?,
+    })
+}
+
+fn decode_move(data: &ScVal) -> Result<MoveEvent> {
+    let fields = Fields::new(data)?;
+    Ok(MoveEvent {
+        version: fields.version()?,
+        game_id: fields.u64("game_id")?,
+        player: fields.address("player")?,
+        move_number: fields.u32("move_number")?,
+        move_data: fields.u32_vec("move_data")?,
+        ledger: fields.u64("ledger")?,
+    })
+}
+
+fn decode_payout(data: &ScVal) -> Result<PayoutEvent> {
+    let fields = Fields::new(data)?;
+    Ok(PayoutEvent {
+        version: fields.version()?,
+        game_id: fields.u64("game_id")?,
+        token: fields.address("token")?,
+        recipient: fields.address("recipient")?,
+        amount: fields.i128("amount")?,
+        fee: fields.i128("fee")?,
+    })
+}
+
+fn decode_dispute(data: &ScVal) -> Result<DisputeEvent> {
+    let fields = Fields::new(data)?;
+    Ok(DisputeEvent {
+        version: fields.version()?,
+        dispute_id: fields.u64("dispute_id")?,
+        game_id: fields.u64("game_id")?,
+        filer: fields.address("filer")?,
+        against: fields.address("against")?,
+        status: fields.variant("status")?,
+        winner: fields.opt_address("winner")?,
+    })
+}
+
+fn decode_fee_proposal(data: &ScVal) -> Result<FeeProposalEvent> {
+    let fields = Fields::new(data)?;
+    Ok(FeeProposalEvent {
+        version: fields.version()?,
+        signer: fields.address("signer")?,
+        new_fee_bips: fields.u32("new_fee_bips")?,
+        new_treasury_address: fields.address("new_treasury_address")?,
+        approvals: fields.u32("approvals")?,
+    })
+}
+
+fn decode_governance(data: &ScVal) -> Result<GovernanceEvent> {
+    let fields = Fields::new(data)?;
+    let (action, args) = match fields.get("action")? {
+        ScVal::Vec(Some(items)) => match items.split_first() {
+            Some((ScVal::Symbol(name), args)) => (
+                name.to_utf8_string_lossy(),
+                args.iter().map(scalar_string).collect::<Result<Vec<_>>>()?,
+            ),
+            _ => return Err(anyhow!("Field `action` is not an enum")),
+        },
+        _ => return Err(anyhow!("Field `action` is not an enum")),
+    };
+    Ok(GovernanceEvent {
+        version: fields.version()?,
+        proposal_id: fields.u64("proposal_id")?,
+        signer: fields.address("signer")?,
+        action,
+        args,
+        approvals: fields.u32("approvals")?,
+        expires_at: fields.u64("expires_at")?,
+    })
+}
+
+fn decode_prediction(data: &ScVal) -> Result<PredictionEvent> {
+    let fields = Fields::new(data)?;
+    let (status, outcome) = variant_parts(fields.get("status")?)?;
+    Ok(PredictionEvent {
+        version: fields.version()?,
+        game_id: fields.u64("game_id")?,
+        bettor: fields.opt_address("bettor")?,
+        amount: fields.i128("amount")?,
+        status,
+        outcome,
+    })
+}
+
+/// `(escrow_id, game_id, locked_until)`
+fn decode_escrow_created(data: &ScVal) -> Result<EscrowEvent> {
+    match data {
+        ScVal::Vec(Some(items)) => match items.as_slice() {
+            [ScVal::U64(escrow_id), ScVal::U64(game_id), ScVal::U64(locked_until)] => {
+                Ok(EscrowEvent {
+                    escrow_id: *escrow_id,
+                    game_id: Some(*game_id),
+                    locked_until: Some(*locked_until),
+                })
+            }
+            _ => Err(anyhow!("Malformed escrow payload")),
+        },
+        _ => Err(anyhow!("Expected escrow tuple payload")),
+    }
+}
+
+fn scalar_u64(data: &ScVal, what: &str) -> Result<u64> {
+    match data {
+        ScVal::U64(value) => Ok(*value),
+        _ => Err(anyhow!("Expected u64 {}", what)),
+    }
+}
+
+/// Field accessor over a contracttype struct encoded as an `ScMap`.
+struct Fields<'a>(&'a ScMap);
+
+impl<'a> Fields<'a> {
+    fn new(data: &'a ScVal) -> Result<Self> {
+        match data {
+            ScVal::Map(Some(map)) => Ok(Self(map)),
+            other => Err(anyhow!(
+                "Expected struct payload, got {:?}",
+                other.discriminant()
+            )),
+        }
+    }
+
+    fn get(&self, name: &str) -> Result<&'a ScVal> {
+        self.0
+            .iter()
+            .find(|entry| matches!(&entry.key, ScVal::Symbol(key) if key.to_utf8_string_lossy() == name))
+            .map(|entry| &entry.val)
+            .ok_or_else(|| anyhow!("Missing event field `{}`", name))
+    }
+
+    fn version(&self) -> Result<u32> {
+        let version = self.u32("version")?;
+        if version == 0 || version > SUPPORTED_EVENT_VERSION {
+            return Err(anyhow!("Unsupported event version {}", version));
+        }
+        Ok(version)
+    }
+
+    fn u32(&self, name: &str) -> Result<u32> {
+        match self.get(name)? {
+            ScVal::U32(value) => Ok(*value),
+            _ => Err(anyhow!("Field `{}` is not a u32", name)),
+        }
+    }
+
+    fn u64(&self, name: &str) -> Result<u64> {
+        match self.get(name)? {
+            ScVal::U64(value) => Ok(*value),
+            _ => Err(anyhow!("Field `{}` is not a u64", name)),
+        }
+    }
+
+    fn i128(&self, name: &str) -> Result<i128> {
+        match self.get(name)? {
+            ScVal::I128(parts) => Ok(i128::from(parts)),
+            _ => Err(anyhow!("Field `{}` is not an i128", name)),
+        }
+    }
+
+    fn address(&self, name: &str) -> Result<String> {
+        match self.get(name)? {
+            ScVal::Address(address) => Ok(address.to_string()),
+            _ => Err(anyhow!("Field `{}` is not an address", name)),
+        }
+    }
+
+    fn opt_address(&self, name: &str) -> Result<Option<String>> {
+        match self.get(name)? {
+            ScVal::Void => Ok(None),
+            ScVal::Address(address) => Ok(Some(address.to_string())),
+            _ => Err(anyhow!("Field `{}` is not an optional address", name)),
+        }
+    }
+
+    fn u32_vec(&self, name: &str) -> Result<Vec<u32>> {
+        match self.get(name)? {
+            ScVal::Vec(Some(items)) => items
+                .iter()
+                .map(|item| match item {
+                    ScVal::U32(value) => Ok(*value),
+                    _ => Err(anyhow!("Field `{}` contains a non-u32 element", name)),
+                })
+                .collect(),
+            _ => Err(anyhow!("Field `{}` is not a vector", name)),
+        }
+    }
+
+    fn variant(&self, name: &str) -> Result<String> {
+        variant_parts(self.get(name)?)
+            .map(|(variant, _)| variant)
+            .map_err(|_| anyhow!("Field `{}` is not an enum", name))
+    }
+}
+
+/// Renders a scalar enum argument for display and storage.
+fn scalar_string(value: &ScVal) -> Result<String> {
+    match value {
+        ScVal::U32(value) => Ok(value.to_string()),
+        ScVal::U64(value) => Ok(value.to_string()),
+        ScVal::I128(parts) => Ok(i128::from(parts).to_string()),
+        ScVal::Address(address) => Ok(address.to_string()),
+        ScVal::Bytes(bytes) => Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect()),
+        other => Err(anyhow!(
+            "Unsupported argument type {:?}",
+            other.discriminant()
+        )),
+    }
+}
+
+/// Splits a contracttype enum (`Vec[Symbol, payload?]`) into its variant name
+/// and, for single-field variants wrapping a unit enum, the inner variant name.
+fn variant_parts(value: &ScVal) -> Result<(String, Option<String>)> {
+    let items = match value {
+        ScVal::Vec(Some(items)) => items,
+        _ => return Err(anyhow!("Expected enum variant")),
+    };
+    let name = match items.first() {
+        Some(ScVal::Symbol(symbol)) => symbol.to_utf8_string_lossy(),
+        _ => return Err(anyhow!("Expected enum variant")),
+    };
+    let inner = match items.get(1) {
+        Some(inner) => Some(variant_parts(inner)?.0),
+        None => None,
+    };
+    Ok((name, inner))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_xdr::curr::{Hash, Int128Parts, ScAddress, ScMapEntry, ScSymbol, ScVec, WriteXdr};
+
+    fn sym(s: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(s.try_into().unwrap()))
+    }
+
+    fn addr(byte: u8) -> ScVal {
+        ScVal::Address(ScAddress::Contract(Hash([byte; 32])))
+    }
+
+    fn variant(name: &str) -> ScVal {
+        ScVal::Vec(Some(ScVec(vec![sym(name)].try_into().unwrap())))
+    }
+
+    fn strukt(fields: Vec<(&str, ScVal)>) -> ScVal {
+        let entries: Vec<ScMapEntry> = fields
+            .into_iter()
+            .map(|(key, val)| ScMapEntry { key: sym(key), val })
+            .collect();
+        ScVal::Map(Some(ScMap(entries.try_into().unwrap())))
+    }
+
+    fn game_payload(version: u32) -> ScVal {
+        strukt(vec![
+            ("game_id", ScVal::U64(7)),
+            ("ledger", ScVal::U64(1234)),
+            ("player1", addr(1)),
+            ("player2", addr(2)),
+            ("state", variant("Settled")),
+            ("token", addr(9)),
+            ("version", ScVal::U32(version)),
+            ("wager_amount", ScVal::I128(Int128Parts { hi: 0, lo: 500 })),
+            ("winner", ScVal::Void),
+        ])
+    }
+
+    #[test]
+    fn test_decode_game_settled_event() {
+        let event = decode_event(&[sym("game"), sym("settled")], &game_payload(1))
+            .unwrap()
+            .unwrap();
+
+        match event {
+            ContractEvent::GameSettled(game) => {
+                assert_eq!(game.game_id, 7);
+                assert_eq!(game.state, "Settled");
+                assert_eq!(game.wager_amount, 500);
+                assert!(game.player1.starts_with('C'));
+                assert!(game.player2.is_some());
+                assert_eq!(game.winner, None);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_from_base64_xdr() {
+        let topics = vec![
+            sym("game").to_xdr_base64(Limits::none()).unwrap(),
+            sym("created").to_xdr_base64(Limits::none()).unwrap(),
+        ];
+        let data = game_payload(1).to_xdr_base64(Limits::none()).unwrap();
+
+        let event = decode_event_xdr(&topics, &data).unwrap().unwrap();
+        assert!(matches!(event, ContractEvent::GameCreated(_)));
+        assert_eq!(event.game_id(), Some(7));
+    }
+
+    #[test]
+    fn test_decode_prediction_resolution_outcome() {
+        let status = ScVal::Vec(Some(ScVec(
+            vec![sym("Resolved"), variant("Player2Wins")]
+                .try_into()
+                .unwrap(),
+        )));
+        let data = strukt(vec![
+            ("amount", ScVal::I128(Int128Parts { hi: 0, lo: 90 })),
+            ("bettor", ScVal::Void),
+            ("game_id", ScVal::U64(3)),
+            ("status", status),
+            ("version", ScVal::U32(1)),
+        ]);
+
+        match decode_event(&[sym("predict"), sym("resolved")], &data).unwrap() {
+            Some(ContractEvent::PredictionPoolResolved(pool)) => {
+                assert_eq!(pool.status, "Resolved");
+                assert_eq!(pool.outcome.as_deref(), Some("Player2Wins"));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_governance_action_arguments() {
+        let action = ScVal::Vec(Some(ScVec(
+            vec![
+                sym("ConfigureDisputeSystem"),
+                addr(4),
+                ScVal::I128(Int128Parts { hi: 0, lo: 25 }),
+            ]
+            .try_into()
+            .unwrap(),
+        )));
+        let data = strukt(vec![
+            ("action", action),
+            ("approvals", ScVal::U32(2)),
+            ("expires_at", ScVal::U64(18_000)),
+            ("proposal_id", ScVal::U64(1)),
+            ("signer", addr(5)),
+            ("version", ScVal::U32(1)),
+        ]);
+
+        match decode_event(&[sym("gov"), sym("executed")], &data).unwrap() {
+            Some(ContractEvent::GovernanceExecuted(proposal)) => {
+                assert_eq!(proposal.action, "ConfigureDisputeSystem");
+                assert_eq!(proposal.args.len(), 2);
+                assert!(proposal.args[0].starts_with('C'));
+                assert_eq!(proposal.args[1], "25");
+                assert_eq!(proposal.approvals, 2);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_topics_are_skipped_and_future_versions_rejected() {
+        assert!(decode_event(&[sym("sep10"), sym("issued")], &ScVal::Void)
+            .unwrap()
+            .is_none());
+        assert!(decode_event(&[sym("game"), sym("settled")], &game_payload(2)).is_err());
+        assert!(decode_event(&[sym("game"), sym("settled")], &ScVal::U32(1)).is_err());
+    }
+
+    #[test]
+    fn test_decode_tournament_escrow_events() {
+        let created = ScVal::Vec(Some(ScVec(
+            vec![ScVal::U64(3), ScVal::U64(7), ScVal::U64(900)]
+                .try_into()
+                .unwrap(),
+        )));
+        let event = decode_event(&[sym("tl_escrow"), sym("created")], &created)
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.game_id(), Some(7));
+        assert_eq!(
+            event,
+            ContractEvent::EscrowCreated(EscrowEvent {
+                escrow_id: 3,
+                game_id: Some(7),
+                locked_until: Some(900),
+            })
+        );
+
+        let released = decode_event(&[sym("tl_escrow"), sym("released")], &ScVal::U64(3))
+            .unwrap()
+            .unwrap();
+        assert!(matches!(released, ContractEvent::EscrowReleased(e) if e.escrow_id == 3));
+        assert!(decode_event(&[sym("tl_escrow"), sym("created")], &ScVal::U64(3)).is_err());
+    }
+
+    #[test]
+    fn test_decode_nft_transfers_mints_and_burns() {
+        let transfer = decode_nft_event(&[sym("transfer"), addr(1), addr(2)], &ScVal::U64(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!(transfer.nft_id, 5);
+        assert!(transfer.from.is_some() && transfer.to.is_some());
+        assert_ne!(transfer.from, transfer.to);
+
+        let mint = decode_nft_event(&[sym("mint"), addr(1)], &ScVal::U64(6))
+            .unwrap()
+            .unwrap();
+        assert_eq!((mint.from, mint.to), (None, transfer.from.clone()));
+
+        let burn = decode_nft_event(&[sym("burn"), addr(2)], &ScVal::U64(5))
+            .unwrap()
+            .unwrap();
+        assert_eq!((burn.from, burn.to), (transfer.to, None));
+
+        assert!(
+            decode_nft_event(&[sym("listed"), ScVal::U64(5), addr(1)], &ScVal::U64(5))
+                .unwrap()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Client for `contracts/ai_nft`: AI agent NFTs and their match records.
+
+use anyhow::Result;
+
+use super::scval::{contract_enum, contract_errors, contract_struct};
+use super::{args, Address, ContractInvoker, PreparedInvocation};
+use crate::rpc::StellarRpcClient;
+
+contract_errors! {
+    /// `ai_nft::ContractError`
+    pub enum AiNftError {
+        NotAuthorized = 1,
+        NFTNotFound = 2,
+        InvalidMetadataHash = 3,
+        AlreadyTransferred = 4,
+        InvalidOwner = 5,
+        MinterMismatch = 6,
+        UpgradeAuthorityNotSet = 7,
+        SchemaTooNew = 8,
+        BaseUriTooLong = 9,
+        NotListed = 10,
+        InvalidPrice = 11,
+        MarketNotConfigured = 12,
+        WrongListingKind = 13,
+        AuctionEnded = 14,
+        AuctionNotEnded = 15,
+        BidTooLow = 16,
+        HasBids = 17,
+        InvalidFee = 18,
+        TreasuryNotSet = 19,
+        EngineConfigLocked = 20,
+        ReporterNotSet = 21,
+        NoRental = 22,
+        RentalActive = 23,
+        InvalidDuration = 24,
+    }
+}
+
+contract_struct! {
+    pub struct AiNftMetadata {
+        pub owner: Address,
+        pub nft_id: u64,
+        /// IPFS / content hash
+        pub metadata_hash: [u8; 32],
+        /// JSON describing the agent's personality
+        pub personality_traits: String,
+        pub created_at: u64,
+        pub minter: Address,
+    }
+}
+
+contract_enum! {
+    pub enum MatchOutcome {
+        Win,
+        Loss,
+        Draw,
+    }
+}
+
+contract_struct! {
+    pub struct MatchRecord {
+        pub wins: u32,
+        pub losses: u32,
+        pub draws: u32,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AiNftClient {
+    invoker: ContractInvoker<AiNftError>,
+}
+
+impl AiNftClient {
+    pub fn new(rpc: StellarRpcClient, contract_id: &str, source_account: &str) -> Result<Self> {
+        Ok(Self {
+            invoker: ContractInvoker::new(rpc, contract_id, source_account)?,
+        })
+    }
+
+    pub fn invoker(&self) -> &ContractInvoker<AiNftError> {
+        &self.invoker
+    }
+
+    pub async fn total_supply(&self) -> Result<u64> {
+        self.invoker.read("total_supply", Vec::new()).await
+    }
+
+    pub async fn owner_of(&self, nft_id: u64) -> Result<Address> {
+        self.invoker.read("owner_of", args(&[&nft_id])?).await
+    }
+
+    /// The owner, or the renter while the NFT is rented out.
+    pub async fn user_of(&self, nft_id: u64) -> Result<Address> {
+        self.invoker.read("user_of", args(&[&nft_id])?).await
+    }
+
+    pub async fn metadata(&self, nft_id: u64) -> Result<AiNftMetadata> {
+        self.invoker.read("metadata", args(&[&nft_id])?).await
+    }
+
+    pub async fn token_uri(&self, nft_id: u64) -> Result<String> {
+        self.invoker.read("token_uri", args(&[&nft_id])?).await
+    }
+
+    pub async fn balance_of(&self, owner: &Address) -> Result<u32> {
+        self.invoker.read("balance_of", args(&[owner])?).await
+    }
+
+    pub async fn tokens_of_owner(&self, owner: &Address) -> Result<Vec<u64>> {
+        self.invoker.read("tokens_of_owner", args(&[owner])?).await
+    }
+
+    /// Hash of the engine config the agent plays with, if one was set.
+    pub async fn engine_config(&self, nft_id: u64) -> Result<Option<[u8; 32]>> {
+        self.invoker.read("engine_config", args(&[&nft_id])?).await
+    }
+
+    pub async fn match_record(&self, nft_id: u64) -> Result<MatchRecord> {
+        self.invoker.read("match_record", args(&[&nft_id])?).await
+    }
+
+    /// Returns the new NFT's id via `PreparedInvocation::result`.
+    pub async fn mint(
+        &self,
+        minter: &Address,
+        metadata_hash: &[u8; 32],
+        personality_traits: &str,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("mint", args(&[minter, metadata_hash, &personality_traits])?)
+            .await
+    }
+
+    pub async fn transfer(&self, nft_id: u64, to: &Address) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("transfer", args(&[&nft_id, to])?)
+            .await
+    }
+
+    pub async fn transfer_from(
+        &self,
+        spender: &Address,
+        from: &Address,
+        to: &Address,
+        nft_id: u64,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("transfer_from", args(&[spender, from, to, &nft_id])?)
+            .await
+    }
+
+    pub async fn burn(&self, spender: &Address, nft_id: u64) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("burn", args(&[spender, &nft_id])?)
+            .await
+    }
+
+    /// Add a settled game to an NFT's record. Needs the result reporter's
+    /// authorization.
+    pub async fn record_result(
+        &self,
+        nft_id: u64,
+        outcome: MatchOutcome,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("record_result", args(&[&nft_id, &outcome])?)
+            .await
+    }
+}
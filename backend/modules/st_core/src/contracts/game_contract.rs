@@ -0,0 +1,534 @@
+//! Client for `contracts/game_contract`: wagered games, disputes,
+//! tournament escrow and puzzle rewards.
+
+use anyhow::Result;
+
+use super::scval::{contract_enum, contract_errors, contract_struct};
+use super::{args, Address, Bytes, ContractInvoker, PreparedInvocation};
+use crate::rpc::StellarRpcClient;
+
+contract_errors! {
+    /// `game_contract::ContractError`
+    pub enum GameContractError {
+        GameNotFound = 1,
+        NotYourTurn = 2,
+        GameNotInProgress = 3,
+        InvalidMove = 4,
+        InsufficientFunds = 5,
+        AlreadyJoined = 6,
+        GameFull = 7,
+        NotPlayer = 8,
+        GameAlreadyCompleted = 9,
+        DrawNotAvailable = 10,
+        ForfeitNotAllowed = 11,
+        InvalidPercentage = 12,
+        MismatchedLengths = 13,
+        Unauthorized = 14,
+        StakeLimitExceeded = 15,
+        TimeoutNotReached = 16,
+        TimeoutNotConfigured = 17,
+        NotDisputable = 18,
+        DisputeNotFound = 19,
+        NotArbitrator = 20,
+        InsufficientDisputeFee = 21,
+        InvalidTimeoutClaimant = 22,
+        AlreadySettled = 23,
+        InvalidAmount = 24,
+        ChallengeExpired = 25,
+        ChallengeAlreadyUsed = 26,
+        NotVerified = 27,
+        NotASigner = 28,
+        NoProposal = 29,
+        AlreadyApproved = 30,
+        InvalidThreshold = 31,
+        OracleNotConfigured = 32,
+        EscrowNotFound = 33,
+        EscrowStillLocked = 34,
+        EscrowAlreadyReleased = 35,
+        TokenNotAllowed = 36,
+        NotInvited = 37,
+        InviteExpired = 38,
+        RematchNotAvailable = 39,
+        PredictionsNotConfigured = 40,
+        BettingClosed = 41,
+        InvalidPrediction = 42,
+        PoolFull = 43,
+        Paused = 44,
+        ProposalExpired = 45,
+        GovernanceRequired = 46,
+        SchemaTooNew = 47,
+        AgentsNotConfigured = 48,
+        AgentNotOwned = 49,
+        SlashingNotConfigured = 50,
+    }
+}
+
+contract_enum! {
+    pub enum GameState {
+        Created,
+        InProgress,
+        Completed,
+        Settled,
+        Drawn,
+        Forfeited,
+    }
+}
+
+contract_struct! {
+    pub struct ChessMove {
+        pub player: Address,
+        pub move_data: Vec<u32>,
+        pub timestamp: u64,
+    }
+}
+
+contract_struct! {
+    pub struct Game {
+        pub id: u64,
+        pub player1: Address,
+        pub player2: Option<Address>,
+        pub state: GameState,
+        pub wager_amount: i128,
+        pub token: Address,
+        /// 1 = player1, 2 = player2
+        pub current_turn: u32,
+        pub moves: Vec<ChessMove>,
+        pub created_at: u64,
+        pub winner: Option<Address>,
+        /// Ledger sequence of the last move
+        pub last_move_at: u64,
+        /// Directed challenge: only this address may join
+        pub opponent: Option<Address>,
+        /// Ledger sequence after which an unjoined game can be refunded
+        pub expires_at: Option<u64>,
+    }
+}
+
+contract_struct! {
+    pub struct GamePage {
+        pub games: Vec<Game>,
+        pub next_cursor: Option<u64>,
+    }
+}
+
+contract_enum! {
+    pub enum DisputeStatus {
+        Pending,
+        Resolved,
+        Rejected,
+    }
+}
+
+contract_struct! {
+    pub struct Dispute {
+        pub id: u64,
+        pub game_id: u64,
+        pub filer: Address,
+        pub against: Address,
+        pub reason: Bytes,
+        pub status: DisputeStatus,
+        /// Ledger sequence
+        pub filed_at: u64,
+        pub resolution: Option<Bytes>,
+    }
+}
+
+contract_struct! {
+    pub struct TournamentEscrow {
+        pub escrow_id: u64,
+        pub game_id: u64,
+        pub token: Address,
+        pub total_amount: i128,
+        /// Ledger sequence when the funds can be released
+        pub locked_until: u64,
+        pub released: bool,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameContractClient {
+    invoker: ContractInvoker<GameContractError>,
+}
+
+impl GameContractClient {
+    pub fn new(rpc: StellarRpcClient, contract_id: &str, source_account: &str) -> Result<Self> {
+        Ok(Self {
+            invoker: ContractInvoker::new(rpc, contract_id, source_account)?,
+        })
+    }
+
+    pub fn invoker(&self) -> &ContractInvoker<GameContractError> {
+        &self.invoker
+    }
+
+    // Games
+
+    pub async fn get_game(&self, game_id: u64) -> Result<Game> {
+        self.invoker.read("get_game", args(&[&game_id])?).await
+    }
+
+    pub async fn get_open_games(&self, cursor: u64, limit: u32) -> Result<GamePage> {
+        self.invoker
+            .read("get_open_games", args(&[&cursor, &limit])?)
+            .await
+    }
+
+    pub async fn get_player_games(
+        &self,
+        player: &Address,
+        active_only: bool,
+        cursor: u64,
+        limit: u32,
+    ) -> Result<GamePage> {
+        self.invoker
+            .read(
+                "get_player_games",
+                args(&[player, &active_only, &cursor, &limit])?,
+            )
+            .await
+    }
+
+    pub async fn get_escrow_balance(&self, token: &Address, address: &Address) -> Result<i128> {
+        self.invoker
+            .read("get_escrow_balance", args(&[token, address])?)
+            .await
+    }
+
+    /// Returns the new game's id via `PreparedInvocation::result`.
+    pub async fn create_game(
+        &self,
+        player1: &Address,
+        wager_amount: i128,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("create_game", args(&[player1, &wager_amount])?)
+            .await
+    }
+
+    pub async fn create_game_with_token(
+        &self,
+        player1: &Address,
+        token: &Address,
+        wager_amount: i128,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare(
+                "create_game_with_token",
+                args(&[player1, token, &wager_amount])?,
+            )
+            .await
+    }
+
+    pub async fn join_game(&self, game_id: u64, player2: &Address) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("join_game", args(&[&game_id, player2])?)
+            .await
+    }
+
+    pub async fn submit_move(
+        &self,
+        game_id: u64,
+        player: &Address,
+        move_data: &[u32],
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("submit_move", args(&[&game_id, player, &move_data])?)
+            .await
+    }
+
+    pub async fn forfeit(&self, game_id: u64, player: &Address) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("forfeit", args(&[&game_id, player])?)
+            .await
+    }
+
+    pub async fn cancel_game(&self, game_id: u64, player: &Address) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("cancel_game", args(&[&game_id, player])?)
+            .await
+    }
+
+    /// Settle a finished game in favour of `winner`. Needs the oracle's
+    /// authorization.
+    pub async fn payout(&self, game_id: u64, winner: &Address) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("payout", args(&[&game_id, winner])?)
+            .await
+    }
+
+    /// Settle in favour of `winner` with a backend signature from
+    /// `security::signer` (`SettlementPayload::ClaimWin`).
+    pub async fn claim_win(
+        &self,
+        game_id: u64,
+        winner: &Address,
+        signature: &[u8; 64],
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("claim_win", args(&[&game_id, winner, signature])?)
+            .await
+    }
+
+    /// Settle as a draw with a backend signature (`SettlementPayload::ClaimDraw`).
+    pub async fn claim_draw(
+        &self,
+        game_id: u64,
+        player: &Address,
+        signature: &[u8; 64],
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("claim_draw", args(&[&game_id, player, signature])?)
+            .await
+    }
+
+    pub async fn claim_timeout_win(
+        &self,
+        game_id: u64,
+        claimant: &Address,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("claim_timeout_win", args(&[&game_id, claimant])?)
+            .await
+    }
+
+    /// Returns the slashed amount via `PreparedInvocation::result`.
+    pub async fn slash_disconnect(
+        &self,
+        game_id: u64,
+        absent: &Address,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("slash_disconnect", args(&[&game_id, absent])?)
+            .await
+    }
+
+    // Disputes
+
+    pub async fn get_dispute(&self, dispute_id: u64) -> Result<Dispute> {
+        self.invoker
+            .read("get_dispute", args(&[&dispute_id])?)
+            .await
+    }
+
+    pub async fn file_dispute(
+        &self,
+        game_id: u64,
+        filer: &Address,
+        against: &Address,
+        reason: &Bytes,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("file_dispute", args(&[&game_id, filer, against, reason])?)
+            .await
+    }
+
+    pub async fn resolve_dispute(
+        &self,
+        dispute_id: u64,
+        arbitrator: &Address,
+        winner: &Option<Address>,
+        resolution: &Bytes,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare(
+                "resolve_dispute",
+                args(&[&dispute_id, arbitrator, winner, resolution])?,
+            )
+            .await
+    }
+
+    pub async fn reject_dispute(
+        &self,
+        dispute_id: u64,
+        arbitrator: &Address,
+        reason: &Bytes,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("reject_dispute", args(&[&dispute_id, arbitrator, reason])?)
+            .await
+    }
+
+    // Tournaments
+
+    pub async fn get_tournament_escrow(&self, escrow_id: u64) -> Result<TournamentEscrow> {
+        self.invoker
+            .read("get_tournament_escrow", args(&[&escrow_id])?)
+            .await
+    }
+
+    pub async fn create_tournament_escrow(&self, game_id: u64) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("create_tournament_escrow", args(&[&game_id])?)
+            .await
+    }
+
+    /// `percentages` are whole percent per winner, summing to 100.
+    pub async fn release_tournament_escrow(
+        &self,
+        escrow_id: u64,
+        winners: &[Address],
+        percentages: &[u32],
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare(
+                "release_tournament_escrow",
+                args(&[&escrow_id, &winners, &percentages])?,
+            )
+            .await
+    }
+
+    pub async fn payout_tournament(
+        &self,
+        game_id: u64,
+        winners: &[Address],
+        percentages: &[u32],
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare(
+                "payout_tournament",
+                args(&[&game_id, &winners, &percentages])?,
+            )
+            .await
+    }
+
+    // Puzzle rewards
+
+    /// The key settlement signatures are checked against; sign with it via
+    /// `SettlementSigner::sign_for`.
+    pub async fn get_admin_key(&self) -> Result<Option<[u8; 32]>> {
+        self.invoker.read("get_admin_key", Vec::new()).await
+    }
+
+    pub async fn reward_balance(&self, address: &Address) -> Result<i128> {
+        self.invoker.read("reward_balance", args(&[address])?).await
+    }
+
+    /// Redeem a voucher from `challenge::voucher`.
+    pub async fn claim_puzzle_reward(
+        &self,
+        recipient: &Address,
+        reward_amount: i128,
+        nonce: u64,
+        signature: &[u8; 64],
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare(
+                "claim_puzzle_reward",
+                args(&[recipient, &reward_amount, &nonce, signature])?,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contracts::{FromScVal, ToScVal};
+    use crate::rpc::RpcConfig;
+    use serde_json::json;
+    use stellar_xdr::curr::{Limits, WriteXdr};
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const PLAYER1: &str = "GAB35A2WLFSK64P6EWSGVFXZYU6E5K2INGTTLMDEDSIPYOH7NZVV6GIG";
+    const TOKEN: &str = "CADQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQP5KR";
+
+    #[test]
+    fn test_game_and_dispute_round_trip() {
+        let player1: Address = PLAYER1.parse().unwrap();
+        let game = Game {
+            id: 7,
+            player1: player1.clone(),
+            player2: None,
+            state: GameState::InProgress,
+            wager_amount: 50_000_000,
+            token: TOKEN.parse().unwrap(),
+            current_turn: 1,
+            moves: vec![ChessMove {
+                player: player1.clone(),
+                move_data: vec![12, 28],
+                timestamp: 100,
+            }],
+            created_at: 90,
+            winner: None,
+            last_move_at: 100,
+            opponent: Some(player1.clone()),
+            expires_at: None,
+        };
+        assert_eq!(Game::from_scval(&game.to_scval().unwrap()).unwrap(), game);
+
+        let dispute = Dispute {
+            id: 1,
+            game_id: 7,
+            filer: player1.clone(),
+            against: player1,
+            reason: Bytes(b"illegal move".to_vec()),
+            status: DisputeStatus::Pending,
+            filed_at: 120,
+            resolution: None,
+        };
+        assert_eq!(
+            Dispute::from_scval(&dispute.to_scval().unwrap()).unwrap(),
+            dispute
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_decodes_reads_and_contract_errors() {
+        let server = MockServer::start().await;
+        let escrow = TournamentEscrow {
+            escrow_id: 3,
+            game_id: 7,
+            token: TOKEN.parse().unwrap(),
+            total_amount: 1_000,
+            locked_until: 500,
+            released: false,
+        };
+        let return_value = escrow
+            .to_scval()
+            .unwrap()
+            .to_xdr_base64(Limits::none())
+            .unwrap();
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({ "method": "simulateTransaction" }),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "transactionData": "",
+                    "minResourceFee": "0",
+                    "results": [{ "auth": [], "xdr": return_value }],
+                    "latestLedger": 1200,
+                },
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "error": "HostError: Error(Contract, #33)",
+                    "latestLedger": 1200,
+                },
+            })))
+            .mount(&server)
+            .await;
+
+        let rpc = StellarRpcClient::new(RpcConfig {
+            horizon_url: server.uri(),
+            soroban_rpc_url: server.uri(),
+        });
+        let client = GameContractClient::new(rpc, TOKEN, PLAYER1).unwrap();
+        assert_eq!(client.get_tournament_escrow(3).await.unwrap(), escrow);
+
+        let error = client.get_tournament_escrow(4).await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<GameContractError>(),
+            Some(&GameContractError::EscrowNotFound)
+        );
+    }
+}
@@ -0,0 +1,253 @@
+//! Client for `contracts/game_registry`: recorded results, proof-of-game
+//! anchoring and tournaments.
+
+use anyhow::Result;
+
+use super::scval::{contract_enum, contract_errors, contract_struct};
+use super::{args, Address, ContractInvoker, PreparedInvocation};
+use crate::rpc::StellarRpcClient;
+
+contract_errors! {
+    /// `game_registry::RegistryError`
+    pub enum RegistryError {
+        AlreadyInitialized = 1,
+        NotInitialized = 2,
+        GameAlreadyRecorded = 3,
+        GameNotFound = 4,
+        TournamentFull = 5,
+        AlreadyRegistered = 6,
+        InsufficientEntryFee = 7,
+        TournamentNotFound = 8,
+        TournamentAlreadyExists = 9,
+        Paused = 10,
+        UpgradeAuthorityNotSet = 11,
+        SchemaTooNew = 12,
+        InvalidPayouts = 13,
+        InvalidTournamentStatus = 14,
+        NotRegistered = 15,
+        InvalidRanking = 16,
+        EmptyBatch = 17,
+        EpochNotFound = 18,
+        InvalidProof = 19,
+    }
+}
+
+contract_struct! {
+    pub struct GameResult {
+        pub winner: Address,
+        pub white: Address,
+        pub black: Address,
+        pub timestamp: u64,
+    }
+}
+
+contract_struct! {
+    pub struct Tournament {
+        pub id: String,
+        pub capacity: u32,
+        pub entry_fee: i128,
+        pub token_address: Address,
+        pub participants: u32,
+    }
+}
+
+contract_enum! {
+    pub enum TournamentStatus {
+        Open,
+        InProgress,
+        Finalized,
+        Cancelled,
+    }
+}
+
+contract_struct! {
+    pub struct TournamentState {
+        pub status: TournamentStatus,
+        pub min_participants: u32,
+        /// Share of the pool per rank in basis points, summing to 10 000
+        pub payout_bips: Vec<u32>,
+        pub prize_pool: i128,
+    }
+}
+
+contract_struct! {
+    pub struct Placement {
+        pub rank: u32,
+        pub player: Address,
+        pub prize: i128,
+    }
+}
+
+contract_struct! {
+    /// Merkle root of a batch of game proofs (see `merkle`)
+    pub struct BatchAnchor {
+        pub root: [u8; 32],
+        pub leaf_count: u32,
+        pub timestamp: u64,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GameRegistryClient {
+    invoker: ContractInvoker<RegistryError>,
+}
+
+impl GameRegistryClient {
+    pub fn new(rpc: StellarRpcClient, contract_id: &str, source_account: &str) -> Result<Self> {
+        Ok(Self {
+            invoker: ContractInvoker::new(rpc, contract_id, source_account)?,
+        })
+    }
+
+    pub fn invoker(&self) -> &ContractInvoker<RegistryError> {
+        &self.invoker
+    }
+
+    // Games and proofs
+
+    pub async fn get_game(&self, game_id: &str) -> Result<GameResult> {
+        self.invoker.read("get_game", args(&[&game_id])?).await
+    }
+
+    pub async fn get_game_proof(&self, game_id: &str) -> Result<Option<[u8; 32]>> {
+        self.invoker
+            .read("get_game_proof", args(&[&game_id])?)
+            .await
+    }
+
+    pub async fn verify_game(&self, game_id: &str, proof_hash: &[u8; 32]) -> Result<bool> {
+        self.invoker
+            .read("verify_game", args(&[&game_id, proof_hash])?)
+            .await
+    }
+
+    /// Record a finished game with its proof hash (`chess::proof`). Needs
+    /// the registry server's authorization.
+    pub async fn record_game(
+        &self,
+        game_id: &str,
+        winner: &Address,
+        white: &Address,
+        black: &Address,
+        timestamp: u64,
+        proof_hash: &[u8; 32],
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare(
+                "record_game",
+                args(&[&game_id, winner, white, black, &timestamp, proof_hash])?,
+            )
+            .await
+    }
+
+    pub async fn epoch_count(&self) -> Result<u64> {
+        self.invoker.read("epoch_count", Vec::new()).await
+    }
+
+    pub async fn get_batch(&self, epoch: u64) -> Result<BatchAnchor> {
+        self.invoker.read("get_batch", args(&[&epoch])?).await
+    }
+
+    pub async fn verify_inclusion(
+        &self,
+        epoch: u64,
+        proof_hash: &[u8; 32],
+        proof: &[[u8; 32]],
+    ) -> Result<bool> {
+        self.invoker
+            .read("verify_inclusion", args(&[&epoch, proof_hash, &proof])?)
+            .await
+    }
+
+    /// Returns the new epoch via `PreparedInvocation::result`.
+    pub async fn anchor_batch(
+        &self,
+        root: &[u8; 32],
+        leaf_count: u32,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("anchor_batch", args(&[root, &leaf_count])?)
+            .await
+    }
+
+    // Tournaments
+
+    pub async fn get_tournament(&self, tournament_id: &str) -> Result<Tournament> {
+        self.invoker
+            .read("get_tournament", args(&[&tournament_id])?)
+            .await
+    }
+
+    pub async fn get_tournament_state(&self, tournament_id: &str) -> Result<TournamentState> {
+        self.invoker
+            .read("get_tournament_state", args(&[&tournament_id])?)
+            .await
+    }
+
+    pub async fn get_tournament_results(
+        &self,
+        tournament_id: &str,
+    ) -> Result<Option<Vec<Placement>>> {
+        self.invoker
+            .read("get_tournament_results", args(&[&tournament_id])?)
+            .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_tournament(
+        &self,
+        id: &str,
+        capacity: u32,
+        entry_fee: i128,
+        token_address: &Address,
+        min_participants: u32,
+        payout_bips: &[u32],
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare(
+                "create_tournament",
+                args(&[
+                    &id,
+                    &capacity,
+                    &entry_fee,
+                    token_address,
+                    &min_participants,
+                    &payout_bips,
+                ])?,
+            )
+            .await
+    }
+
+    pub async fn register_tournament(
+        &self,
+        player: &Address,
+        tournament_id: &str,
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("register_tournament", args(&[player, &tournament_id])?)
+            .await
+    }
+
+    pub async fn start_tournament(&self, tournament_id: &str) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("start_tournament", args(&[&tournament_id])?)
+            .await
+    }
+
+    pub async fn cancel_tournament(&self, tournament_id: &str) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("cancel_tournament", args(&[&tournament_id])?)
+            .await
+    }
+
+    /// Returns the placements via `PreparedInvocation::result`.
+    pub async fn finalize_tournament(
+        &self,
+        tournament_id: &str,
+        ranking: &[Address],
+    ) -> Result<PreparedInvocation> {
+        self.invoker
+            .prepare("finalize_tournament", args(&[&tournament_id, &ranking])?)
+            .await
+    }
+}
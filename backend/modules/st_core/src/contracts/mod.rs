@@ -0,0 +1,200 @@
+//! Typed clients for the XLMate Soroban contracts.
+//!
+//! Each client mirrors its contract's types (see `scval`) and entrypoints.
+//! Read-only calls are simulated and their return value decoded. Calls that
+//! change state are simulated and assembled into an unsigned transaction
+//! (`PreparedInvocation`) from the client's source account, to be signed and
+//! passed to `submit`.
+//!
+//! When the contract returns one of its errors, the `anyhow::Error` wraps the
+//! client's error enum, so callers can `downcast_ref::<GameContractError>()`.
+
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use stellar_xdr::curr::{
+    HostFunction, InvokeContractArgs, InvokeHostFunctionOp, Limits, Memo, MuxedAccount, Operation,
+    OperationBody, Preconditions, ScAddress, ScSymbol, ScVal, SequenceNumber, Transaction,
+    TransactionEnvelope, TransactionExt, TransactionV1Envelope, WriteXdr,
+};
+
+use crate::rpc::{assemble_transaction, Simulation, StellarRpcClient, TransactionStatus};
+
+pub mod scval;
+
+pub mod ai_nft;
+pub mod game_contract;
+pub mod game_registry;
+
+pub use ai_nft::{AiNftClient, AiNftError};
+pub use game_contract::{GameContractClient, GameContractError};
+pub use game_registry::{GameRegistryClient, RegistryError};
+pub use scval::{Address, Bytes, FromScVal, ToScVal};
+
+/// Inclusion fee offered on top of the simulated resource fee, in stroops
+pub const BASE_FEE: u32 = 100;
+
+/// A contract's error enum, decoded from `Error(Contract, #code)`
+pub trait ContractErrorCode: std::error::Error + Send + Sync + Sized + 'static {
+    fn from_code(code: u32) -> Option<Self>;
+}
+
+/// An unsigned, simulated contract call, ready to sign
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedInvocation {
+    pub function: String,
+    /// Transaction envelope with fee, footprint and auth applied, base64 XDR
+    pub envelope_xdr: String,
+    pub simulation: Simulation,
+}
+
+impl PreparedInvocation {
+    /// The simulated return value of the call.
+    pub fn result<T: FromScVal>(&self) -> Result<T> {
+        decode_return(&self.simulation)
+    }
+}
+
+/// Builds, simulates and submits calls to one contract
+#[derive(Debug, Clone)]
+pub struct ContractInvoker<E> {
+    rpc: StellarRpcClient,
+    contract_id: String,
+    source_account: String,
+    _errors: PhantomData<fn() -> E>,
+}
+
+impl<E: ContractErrorCode> ContractInvoker<E> {
+    /// `source_account` pays for and, once signed, submits state-changing
+    /// calls.
+    pub fn new(rpc: StellarRpcClient, contract_id: &str, source_account: &str) -> Result<Self> {
+        ScAddress::from_str(contract_id)
+            .map_err(|_| anyhow!("invalid contract id {}", contract_id))?;
+        MuxedAccount::from_str(source_account)
+            .map_err(|_| anyhow!("invalid source account {}", source_account))?;
+        Ok(Self {
+            rpc,
+            contract_id: contract_id.to_string(),
+            source_account: source_account.to_string(),
+            _errors: PhantomData,
+        })
+    }
+
+    pub fn contract_id(&self) -> &str {
+        &self.contract_id
+    }
+
+    pub fn source_account(&self) -> &str {
+        &self.source_account
+    }
+
+    pub fn rpc(&self) -> &StellarRpcClient {
+        &self.rpc
+    }
+
+    /// Simulate a read-only call and decode its return value.
+    pub async fn read<T: FromScVal>(&self, function: &str, args: Vec<ScVal>) -> Result<T> {
+        // Simulation does not check the sequence number.
+        let envelope = self.envelope(0, function, args)?;
+        let simulation = self.simulate(&envelope).await?;
+        decode_return(&simulation)
+    }
+
+    /// Simulate a state-changing call and assemble it for signing.
+    pub async fn prepare(&self, function: &str, args: Vec<ScVal>) -> Result<PreparedInvocation> {
+        let sequence = self.rpc.next_sequence_number(&self.source_account).await?;
+        let envelope = self.envelope(sequence, function, args)?;
+        let simulation = self.simulate(&envelope).await?;
+        Ok(PreparedInvocation {
+            function: function.to_string(),
+            envelope_xdr: assemble_transaction(&envelope, &simulation)?,
+            simulation,
+        })
+    }
+
+    /// Submit a signed `PreparedInvocation` envelope and wait for the result.
+    pub async fn submit(&self, signed_envelope_xdr: &str) -> Result<TransactionStatus> {
+        self.rpc.submit_and_wait(signed_envelope_xdr).await
+    }
+
+    async fn simulate(&self, envelope: &str) -> Result<Simulation> {
+        self.rpc.simulate_transaction(envelope).await.map_err(|e| {
+            match contract_error_code(&e.to_string()).and_then(E::from_code) {
+                Some(error) => anyhow::Error::new(error),
+                None => e,
+            }
+        })
+    }
+
+    fn envelope(&self, sequence: i64, function: &str, args: Vec<ScVal>) -> Result<String> {
+        let invoke = InvokeHostFunctionOp {
+            host_function: HostFunction::InvokeContract(InvokeContractArgs {
+                contract_address: ScAddress::from_str(&self.contract_id)?,
+                function_name: ScSymbol(function.try_into()?),
+                args: args.try_into()?,
+            }),
+            auth: Default::default(),
+        };
+        let tx = Transaction {
+            source_account: MuxedAccount::from_str(&self.source_account)?,
+            fee: BASE_FEE,
+            seq_num: SequenceNumber(sequence),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::InvokeHostFunction(invoke),
+            }]
+            .try_into()?,
+            ext: TransactionExt::V0,
+        };
+        Ok(TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx,
+            signatures: Default::default(),
+        })
+        .to_xdr_base64(Limits::none())?)
+    }
+}
+
+/// Encode call arguments.
+pub fn args(values: &[&(dyn ToScVal + Sync)]) -> Result<Vec<ScVal>> {
+    values.iter().map(|value| value.to_scval()).collect()
+}
+
+fn decode_return<T: FromScVal>(simulation: &Simulation) -> Result<T> {
+    let value = simulation.return_value()?.unwrap_or(ScVal::Void);
+    T::from_scval(&value)
+}
+
+/// The code in a host error like `HostError: Error(Contract, #12)`.
+fn contract_error_code(message: &str) -> Option<u32> {
+    let start = message.find("Error(Contract, #")? + "Error(Contract, #".len();
+    let digits: String = message[start..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contract_error_code_is_parsed_from_host_error() {
+        assert_eq!(
+            contract_error_code("HostError: Error(Contract, #12)\n\nEvent log: ..."),
+            Some(12)
+        );
+        assert_eq!(
+            contract_error_code("HostError: Error(Auth, InvalidAction)"),
+            None
+        );
+        assert_eq!(
+            GameContractError::from_code(1),
+            Some(GameContractError::GameNotFound)
+        );
+        assert_eq!(GameContractError::from_code(999), None);
+    }
+}
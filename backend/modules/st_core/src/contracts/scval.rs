@@ -0,0 +1,527 @@
+//! `ScVal` encoding of contract arguments and return values.
+//!
+//! Mirrors how `soroban-sdk` lays out `#[contracttype]` values:
+//!
+//! * structs are maps from field name (a symbol) to value, sorted by name
+//! * unit enum variants are a one-element vector holding the variant name
+//! * `Option::None` is `Void`
+//! * `BytesN<N>` and `Bytes` are both `Bytes`
+
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use stellar_xdr::curr::{
+    Int128Parts, ScAddress, ScBytes, ScMap, ScMapEntry, ScString, ScSymbol, ScVal, ScVec,
+};
+
+pub trait ToScVal {
+    fn to_scval(&self) -> Result<ScVal>;
+}
+
+pub trait FromScVal: Sized {
+    fn from_scval(val: &ScVal) -> Result<Self>;
+}
+
+/// A Stellar address: an account (`G...`) or a contract (`C...`)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Address(String);
+
+impl Address {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn to_sc_address(&self) -> Result<ScAddress> {
+        ScAddress::from_str(&self.0).map_err(|_| anyhow!("invalid address {}", self.0))
+    }
+}
+
+impl FromStr for Address {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let address = ScAddress::from_str(s).map_err(|_| anyhow!("invalid address {}", s))?;
+        Ok(Self(address.to_string()))
+    }
+}
+
+impl TryFrom<String> for Address {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        s.parse()
+    }
+}
+
+impl From<Address> for String {
+    fn from(address: Address) -> Self {
+        address.0
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn unexpected<T>(expected: &str, val: &ScVal) -> Result<T> {
+    Err(anyhow!(
+        "expected {}, got {:?}",
+        expected,
+        val.discriminant()
+    ))
+}
+
+impl ToScVal for ScVal {
+    fn to_scval(&self) -> Result<ScVal> {
+        Ok(self.clone())
+    }
+}
+
+impl FromScVal for ScVal {
+    fn from_scval(val: &ScVal) -> Result<Self> {
+        Ok(val.clone())
+    }
+}
+
+impl ToScVal for () {
+    fn to_scval(&self) -> Result<ScVal> {
+        Ok(ScVal::Void)
+    }
+}
+
+impl FromScVal for () {
+    fn from_scval(val: &ScVal) -> Result<Self> {
+        match val {
+            ScVal::Void => Ok(()),
+            other => unexpected("void", other),
+        }
+    }
+}
+
+impl ToScVal for bool {
+    fn to_scval(&self) -> Result<ScVal> {
+        Ok(ScVal::Bool(*self))
+    }
+}
+
+impl FromScVal for bool {
+    fn from_scval(val: &ScVal) -> Result<Self> {
+        match val {
+            ScVal::Bool(b) => Ok(*b),
+            other => unexpected("bool", other),
+        }
+    }
+}
+
+macro_rules! scval_number {
+    ($ty:ty, $variant:ident) => {
+        impl ToScVal for $ty {
+            fn to_scval(&self) -> Result<ScVal> {
+                Ok(ScVal::$variant(*self))
+            }
+        }
+
+        impl FromScVal for $ty {
+            fn from_scval(val: &ScVal) -> Result<Self> {
+                match val {
+                    ScVal::$variant(n) => Ok(*n),
+                    other => unexpected(stringify!($ty), other),
+                }
+            }
+        }
+    };
+}
+
+scval_number!(u32, U32);
+scval_number!(i32, I32);
+scval_number!(u64, U64);
+scval_number!(i64, I64);
+
+impl ToScVal for i128 {
+    fn to_scval(&self) -> Result<ScVal> {
+        Ok(ScVal::I128(Int128Parts {
+            hi: (*self >> 64) as i64,
+            lo: *self as u64,
+        }))
+    }
+}
+
+impl FromScVal for i128 {
+    fn from_scval(val: &ScVal) -> Result<Self> {
+        match val {
+            ScVal::I128(parts) => Ok((i128::from(parts.hi) << 64) | i128::from(parts.lo)),
+            other => unexpected("i128", other),
+        }
+    }
+}
+
+impl ToScVal for String {
+    fn to_scval(&self) -> Result<ScVal> {
+        Ok(ScVal::String(ScString(self.as_bytes().try_into()?)))
+    }
+}
+
+impl FromScVal for String {
+    fn from_scval(val: &ScVal) -> Result<Self> {
+        match val {
+            ScVal::String(s) => Ok(String::from_utf8(s.0.to_vec())?),
+            ScVal::Symbol(s) => Ok(String::from_utf8(s.0.to_vec())?),
+            other => unexpected("string", other),
+        }
+    }
+}
+
+impl<T: ToScVal + ?Sized> ToScVal for &T {
+    fn to_scval(&self) -> Result<ScVal> {
+        (**self).to_scval()
+    }
+}
+
+impl ToScVal for str {
+    fn to_scval(&self) -> Result<ScVal> {
+        Ok(ScVal::String(ScString(self.as_bytes().try_into()?)))
+    }
+}
+
+impl ToScVal for Address {
+    fn to_scval(&self) -> Result<ScVal> {
+        Ok(ScVal::Address(self.to_sc_address()?))
+    }
+}
+
+impl FromScVal for Address {
+    fn from_scval(val: &ScVal) -> Result<Self> {
+        match val {
+            ScVal::Address(address) => Ok(Self(address.to_string())),
+            other => unexpected("address", other),
+        }
+    }
+}
+
+/// Contract `Bytes`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bytes(pub Vec<u8>);
+
+impl ToScVal for Bytes {
+    fn to_scval(&self) -> Result<ScVal> {
+        Ok(ScVal::Bytes(ScBytes(self.0.clone().try_into()?)))
+    }
+}
+
+impl FromScVal for Bytes {
+    fn from_scval(val: &ScVal) -> Result<Self> {
+        match val {
+            ScVal::Bytes(bytes) => Ok(Self(bytes.to_vec())),
+            other => unexpected("bytes", other),
+        }
+    }
+}
+
+/// Contract `BytesN<N>`
+impl<const N: usize> ToScVal for [u8; N] {
+    fn to_scval(&self) -> Result<ScVal> {
+        Ok(ScVal::Bytes(ScBytes(self.to_vec().try_into()?)))
+    }
+}
+
+impl<const N: usize> FromScVal for [u8; N] {
+    fn from_scval(val: &ScVal) -> Result<Self> {
+        match val {
+            ScVal::Bytes(bytes) => bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| anyhow!("expected {} bytes, got {}", N, bytes.len())),
+            other => unexpected("bytes", other),
+        }
+    }
+}
+
+impl<T: ToScVal> ToScVal for Option<T> {
+    fn to_scval(&self) -> Result<ScVal> {
+        match self {
+            Some(value) => value.to_scval(),
+            None => Ok(ScVal::Void),
+        }
+    }
+}
+
+impl<T: FromScVal> FromScVal for Option<T> {
+    fn from_scval(val: &ScVal) -> Result<Self> {
+        match val {
+            ScVal::Void => Ok(None),
+            other => T::from_scval(other).map(Some),
+        }
+    }
+}
+
+impl<T: ToScVal> ToScVal for Vec<T> {
+    fn to_scval(&self) -> Result<ScVal> {
+        self.as_slice().to_scval()
+    }
+}
+
+impl<T: ToScVal> ToScVal for [T] {
+    fn to_scval(&self) -> Result<ScVal> {
+        let items = self
+            .iter()
+            .map(ToScVal::to_scval)
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ScVal::Vec(Some(ScVec(items.try_into()?))))
+    }
+}
+
+impl<T: FromScVal> FromScVal for Vec<T> {
+    fn from_scval(val: &ScVal) -> Result<Self> {
+        match val {
+            ScVal::Vec(Some(items)) => items.iter().map(T::from_scval).collect(),
+            ScVal::Vec(None) => Ok(Vec::new()),
+            other => unexpected("vec", other),
+        }
+    }
+}
+
+pub fn symbol(name: &str) -> Result<ScVal> {
+    Ok(ScVal::Symbol(ScSymbol(name.try_into()?)))
+}
+
+/// Encode a `#[contracttype]` struct from its fields.
+pub fn struct_to_scval(mut fields: Vec<(&str, ScVal)>) -> Result<ScVal> {
+    fields.sort_by(|a, b| a.0.cmp(b.0));
+    let entries = fields
+        .into_iter()
+        .map(|(name, val)| {
+            Ok(ScMapEntry {
+                key: symbol(name)?,
+                val,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ScVal::Map(Some(ScMap(entries.try_into()?))))
+}
+
+/// Fields of a decoded `#[contracttype]` struct
+pub struct StructFields<'a> {
+    name: &'static str,
+    map: &'a ScMap,
+}
+
+impl<'a> StructFields<'a> {
+    pub fn new(val: &'a ScVal, name: &'static str) -> Result<Self> {
+        match val {
+            ScVal::Map(Some(map)) => Ok(Self { name, map }),
+            other => unexpected(name, other),
+        }
+    }
+
+    pub fn get<T: FromScVal>(&self, field: &str) -> Result<T> {
+        let entry = self
+            .map
+            .iter()
+            .find(|entry| matches!(&entry.key, ScVal::Symbol(s) if s.0.as_slice() == field.as_bytes()))
+            .ok_or_else(|| anyhow!("{} has no field {}", self.name, field))?;
+        T::from_scval(&entry.val).map_err(|e| anyhow!("{}.{}: {}", self.name, field, e))
+    }
+}
+
+/// Encode a unit enum variant.
+pub fn variant_to_scval(name: &str) -> Result<ScVal> {
+    Ok(ScVal::Vec(Some(ScVec(vec![symbol(name)?].try_into()?))))
+}
+
+/// Name of a decoded unit enum variant.
+pub fn variant_name(val: &ScVal) -> Result<String> {
+    match val {
+        ScVal::Vec(Some(items)) if items.len() == 1 => String::from_scval(&items[0]),
+        other => unexpected("enum variant", other),
+    }
+}
+
+/// Mirror a `#[contracttype]` struct: declares it and implements
+/// `ToScVal` / `FromScVal` with the field names as map keys.
+macro_rules! contract_struct {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $($(#[$field_meta:meta])* pub $field:ident: $ty:ty,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        pub struct $name {
+            $($(#[$field_meta])* pub $field: $ty,)*
+        }
+
+        impl $crate::contracts::scval::ToScVal for $name {
+            fn to_scval(&self) -> anyhow::Result<stellar_xdr::curr::ScVal> {
+                $crate::contracts::scval::struct_to_scval(vec![
+                    $((stringify!($field), $crate::contracts::scval::ToScVal::to_scval(&self.$field)?),)*
+                ])
+            }
+        }
+
+        impl $crate::contracts::scval::FromScVal for $name {
+            fn from_scval(val: &stellar_xdr::curr::ScVal) -> anyhow::Result<Self> {
+                let fields = $crate::contracts::scval::StructFields::new(val, stringify!($name))?;
+                Ok(Self {
+                    $($field: fields.get(stringify!($field))?,)*
+                })
+            }
+        }
+    };
+}
+
+/// Mirror a `#[contracttype]` enum whose variants carry no data.
+macro_rules! contract_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+        }
+
+        impl $crate::contracts::scval::ToScVal for $name {
+            fn to_scval(&self) -> anyhow::Result<stellar_xdr::curr::ScVal> {
+                let name = match self {
+                    $($name::$variant => stringify!($variant),)*
+                };
+                $crate::contracts::scval::variant_to_scval(name)
+            }
+        }
+
+        impl $crate::contracts::scval::FromScVal for $name {
+            fn from_scval(val: &stellar_xdr::curr::ScVal) -> anyhow::Result<Self> {
+                match $crate::contracts::scval::variant_name(val)?.as_str() {
+                    $(stringify!($variant) => Ok($name::$variant),)*
+                    other => Err(anyhow::anyhow!(
+                        "unknown {} variant {}",
+                        stringify!($name),
+                        other
+                    )),
+                }
+            }
+        }
+    };
+}
+
+/// Mirror a `#[contracterror]` enum. Codes must match the contract.
+macro_rules! contract_errors {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($variant:ident = $code:literal,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+        #[repr(u32)]
+        pub enum $name {
+            $($variant = $code,)*
+        }
+
+        impl $name {
+            pub fn code(self) -> u32 {
+                self as u32
+            }
+        }
+
+        impl $crate::contracts::ContractErrorCode for $name {
+            fn from_code(code: u32) -> Option<Self> {
+                match code {
+                    $($code => Some($name::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let name = match self {
+                    $($name::$variant => stringify!($variant),)*
+                };
+                write!(f, "{} (contract error #{})", name, self.code())
+            }
+        }
+
+        impl std::error::Error for $name {}
+    };
+}
+
+pub(crate) use {contract_enum, contract_errors, contract_struct};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    contract_enum! {
+        pub enum Colour {
+            White,
+            Black,
+        }
+    }
+
+    contract_struct! {
+        pub struct Sample {
+            pub winner: Option<Address>,
+            pub amount: i128,
+            pub colour: Colour,
+            pub hash: [u8; 32],
+        }
+    }
+
+    const ACCOUNT: &str = "GAB35A2WLFSK64P6EWSGVFXZYU6E5K2INGTTLMDEDSIPYOH7NZVV6GIG";
+
+    #[test]
+    fn test_struct_round_trips_with_sorted_field_names() {
+        let sample = Sample {
+            winner: Some(ACCOUNT.parse().unwrap()),
+            amount: -170_141_183_460_469_231_731_687_303_715_884_105_728,
+            colour: Colour::Black,
+            hash: [7; 32],
+        };
+        let val = sample.to_scval().unwrap();
+
+        let ScVal::Map(Some(map)) = &val else {
+            panic!("expected a map");
+        };
+        let keys: Vec<String> = map
+            .iter()
+            .map(|entry| String::from_scval(&entry.key).unwrap())
+            .collect();
+        assert_eq!(keys, vec!["amount", "colour", "hash", "winner"]);
+        assert_eq!(
+            map[1].val,
+            ScVal::Vec(Some(ScVec(
+                vec![symbol("Black").unwrap()].try_into().unwrap()
+            )))
+        );
+        assert_eq!(Sample::from_scval(&val).unwrap(), sample);
+
+        let none = Sample {
+            winner: None,
+            ..sample
+        };
+        assert_eq!(Sample::from_scval(&none.to_scval().unwrap()).unwrap(), none);
+    }
+
+    #[test]
+    fn test_rejects_wrong_types_and_bad_addresses() {
+        assert!(u64::from_scval(&ScVal::U32(1)).is_err());
+        assert!(<[u8; 32]>::from_scval(&[1u8; 16].to_scval().unwrap()).is_err());
+        assert!(Colour::from_scval(&variant_to_scval("Grey").unwrap()).is_err());
+        assert!("GABC".parse::<Address>().is_err());
+        assert_eq!(
+            i128::from_scval(&i128::MAX.to_scval().unwrap()).unwrap(),
+            i128::MAX
+        );
+    }
+}
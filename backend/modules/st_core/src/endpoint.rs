@@ -1,6 +1,6 @@
 use actix_web::{web, HttpResponse, Result};
 use serde::{Deserialize, Serialize};
-use crate::{NFTService, AIMetadata, NFTMintRequest};
+use crate::{NFTService, AIMetadata, NFTMintRequest, StellarRpcClient};
 use utoipa::ToSchema;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -31,6 +31,7 @@ pub struct MintNFTResponse {
     )
 )]
 pub async fn mint_nft(
+    rpc: web::Data<StellarRpcClient>,
     request: web::Json<MintNFTRequest>,
 ) -> Result<HttpResponse> {
     // Get issuer account from environment or configuration
@@ -44,7 +45,7 @@ pub async fn mint_nft(
         network: request.network.clone(),
     };
 
-    match NFTService::create_nft_mint_transaction(mint_request).await {
+    match NFTService::create_nft_mint_transaction(&rpc, mint_request).await {
         Ok(response) => Ok(HttpResponse::Ok().json(MintNFTResponse {
             success: true,
             xdr_transaction: Some(response.xdr_transaction),
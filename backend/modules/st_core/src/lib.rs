@@ -1,12 +1,24 @@
+pub mod contract_events;
+pub mod contracts;
+pub mod merkle;
 pub mod nft;
 pub mod models;
+pub mod rpc;
 pub mod transaction_builder;
 
 #[cfg(feature = "api")]
 pub mod endpoint;
 
+pub use contract_events::{
+    decode_event, decode_event_xdr, decode_nft_event, decode_nft_event_xdr, ContractEvent,
+    NftTransferEvent,
+};
+pub use contracts::{AiNftClient, GameContractClient, GameRegistryClient, PreparedInvocation};
 pub use nft::*;
 pub use models::*;
+pub use rpc::{
+    EventPage, EventsFrom, LedgerInfo, RpcConfig, RpcEvent, StellarRpcClient, TransactionStatus,
+};
 pub use transaction_builder::*;
 
 #[cfg(feature = "api")]
@@ -1,4 +1,4 @@
-use st_core::{NFTService, AIMetadata, NFTMintRequest};
+use st_core::{NFTService, AIMetadata, NFTMintRequest, StellarRpcClient};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -25,7 +25,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         network: "testnet".to_string(),
     };
     
-    match NFTService::create_nft_mint_transaction(mint_request).await {
+    // Sequence numbers come from HORIZON_URL (testnet by default)
+    let rpc = StellarRpcClient::from_env();
+    
+    match NFTService::create_nft_mint_transaction(&rpc, mint_request).await {
         Ok(response) => {
             println!("✅ NFT Mint Transaction Created Successfully!");
             println!("Network: {}", response.network);
@@ -0,0 +1,147 @@
+//! Merkle batching of finished games for `game_registry::anchor_batch`.
+//!
+//! Leaves are game proof hashes (the `proofHash` of `pers-store/proofOfGame.ts`).
+//! Hashing matches the contract's `verify_inclusion`:
+//!
+//! * leaf = `sha256(0x00 || proof_hash)`
+//! * node = `sha256(0x01 || min(a, b) || max(a, b))`
+//! * an odd node at the end of a level is carried up unchanged
+//!
+//! Sorting each pair means a proof is just the list of siblings from the leaf
+//! up; no left/right flags are needed.
+
+use sha2::{Digest, Sha256};
+
+pub type Hash = [u8; 32];
+
+const LEAF_TAG: u8 = 0x00;
+const NODE_TAG: u8 = 0x01;
+
+pub fn leaf_hash(proof_hash: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_TAG]);
+    hasher.update(proof_hash);
+    hasher.finalize().into()
+}
+
+pub fn node_hash(a: &Hash, b: &Hash) -> Hash {
+    let (lo, hi) = if b < a { (b, a) } else { (a, b) };
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_TAG]);
+    hasher.update(lo);
+    hasher.update(hi);
+    hasher.finalize().into()
+}
+
+/// A batch of game proof hashes and every level of its tree.
+#[derive(Debug, Clone)]
+pub struct MerkleBatch {
+    proof_hashes: Vec<Hash>,
+    levels: Vec<Vec<Hash>>, // levels[0] = leaves, last = [root]
+}
+
+impl MerkleBatch {
+    /// Build the tree. Returns `None` for an empty batch, which the contract
+    /// refuses to anchor.
+    pub fn new(proof_hashes: Vec<Hash>) -> Option<Self> {
+        if proof_hashes.is_empty() {
+            return None;
+        }
+
+        let mut levels = vec![proof_hashes.iter().map(leaf_hash).collect::<Vec<_>>()];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node_hash(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        Some(Self {
+            proof_hashes,
+            levels,
+        })
+    }
+
+    pub fn root(&self) -> Hash {
+        self.levels.last().unwrap()[0]
+    }
+
+    pub fn leaf_count(&self) -> u32 {
+        self.proof_hashes.len() as u32
+    }
+
+    /// Sibling hashes from the leaf of `proof_hash` up to the root, or `None`
+    /// if the game is not in this batch.
+    pub fn proof(&self, proof_hash: &Hash) -> Option<Vec<Hash>> {
+        let mut index = self.proof_hashes.iter().position(|h| h == proof_hash)?;
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if sibling < level.len() {
+                proof.push(level[sibling]);
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
+
+/// Recompute the root from a proof, as the contract does.
+pub fn verify(root: &Hash, proof_hash: &Hash, proof: &[Hash]) -> bool {
+    let computed = proof.iter().fold(leaf_hash(proof_hash), |node, sibling| {
+        node_hash(&node, sibling)
+    });
+    &computed == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hashes(n: u8) -> Vec<Hash> {
+        (1..=n).map(|i| [i; 32]).collect()
+    }
+
+    #[test]
+    fn test_every_leaf_proves_for_any_batch_size() {
+        for n in 1..=9 {
+            let batch = MerkleBatch::new(hashes(n)).unwrap();
+            for h in hashes(n) {
+                let proof = batch.proof(&h).unwrap();
+                assert!(verify(&batch.root(), &h, &proof), "n={} leaf={}", n, h[0]);
+            }
+            assert!(batch.proof(&[0xff; 32]).is_none());
+        }
+    }
+
+    #[test]
+    fn test_tampered_proof_fails() {
+        let batch = MerkleBatch::new(hashes(5)).unwrap();
+        let mut proof = batch.proof(&[3; 32]).unwrap();
+        assert!(!verify(&batch.root(), &[4; 32], &proof));
+        proof[0][0] ^= 1;
+        assert!(!verify(&batch.root(), &[3; 32], &proof));
+        assert!(MerkleBatch::new(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_root_matches_contract_vector() {
+        // Same batch as game_registry's `test_anchor_batch_and_prove_game`.
+        let batch = MerkleBatch::new(hashes(3)).unwrap();
+        assert_eq!(
+            hex(&batch.root()),
+            "f49772894c14ce4b584bb71fab253ff9106d8fb60739c32b8731ae557a745c45"
+        );
+    }
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
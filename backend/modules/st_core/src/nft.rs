@@ -1,4 +1,5 @@
 use crate::models::{AIMetadata, NFTMintRequest, NFTMintResponse};
+use crate::rpc::StellarRpcClient;
 use crate::transaction_builder::StellarTransactionBuilder;
 use anyhow::{anyhow, Result};
 use serde_json::json;
@@ -9,13 +10,18 @@ pub struct NFTService;
 impl NFTService {
     /// Creates an NFT minting transaction following Stellar SEP-0039 standards
     pub async fn create_nft_mint_transaction(
+        rpc: &StellarRpcClient,
         request: NFTMintRequest,
     ) -> Result<NFTMintResponse> {
         // Validate request
         Self::validate_mint_request(&request)?;
         
+        // The issuer is the transaction's source account
+        let sequence_number = rpc.next_sequence_number(&request.ai_metadata.issuer).await?;
+        
         // Create the transaction
-        let response = StellarTransactionBuilder::create_nft_mint_transaction(&request)?;
+        let response =
+            StellarTransactionBuilder::create_nft_mint_transaction(&request, sequence_number)?;
         
         Ok(response)
     }
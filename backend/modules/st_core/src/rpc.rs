@@ -0,0 +1,820 @@
+//! Clients for Horizon and Soroban RPC.
+//!
+//! Horizon supplies account sequence numbers. Soroban RPC simulates contract
+//! invocations (fees, footprint and auth), accepts signed transactions, and
+//! reports their outcome. `StellarRpcClient::submit_and_wait` polls
+//! `getTransaction` until the transaction is in a ledger or the timeout
+//! passes. `getEvents`, `getLatestLedger` and `getLedgers` let indexers follow
+//! contract events ledger by ledger.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use stellar_xdr::curr::{
+    Limits, Operation, OperationBody, ReadXdr, ScVal, SorobanAuthorizationEntry,
+    SorobanTransactionData, TransactionEnvelope, TransactionExt, TransactionMeta, WriteXdr,
+};
+
+pub const DEFAULT_HORIZON_URL: &str = "https://horizon-testnet.stellar.org";
+pub const DEFAULT_SOROBAN_RPC_URL: &str = "https://soroban-testnet.stellar.org:443";
+
+/// How often `submit_and_wait` asks for the transaction status
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// How long `submit_and_wait` waits before giving up. A transaction that
+/// has not landed by then has most likely expired.
+pub const DEFAULT_POLL_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcConfig {
+    pub horizon_url: String,
+    pub soroban_rpc_url: String,
+}
+
+impl RpcConfig {
+    /// Read `HORIZON_URL` and `SOROBAN_RPC_URL`, defaulting to testnet.
+    pub fn from_env() -> Self {
+        Self {
+            horizon_url: std::env::var("HORIZON_URL")
+                .unwrap_or_else(|_| DEFAULT_HORIZON_URL.to_string()),
+            soroban_rpc_url: std::env::var("SOROBAN_RPC_URL")
+                .unwrap_or_else(|_| DEFAULT_SOROBAN_RPC_URL.to_string()),
+        }
+    }
+}
+
+/// Result of `simulateTransaction`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    /// Resource fee to add to the transaction's inclusion fee, in stroops
+    pub min_resource_fee: i64,
+    /// `SorobanTransactionData` (footprint and resources), base64 XDR
+    pub transaction_data: String,
+    /// Authorization entries the invocation needs, base64 XDR
+    pub auth: Vec<String>,
+    /// Return value of the invocation, base64 `ScVal`
+    pub return_value: Option<String>,
+    pub latest_ledger: u32,
+}
+
+impl Simulation {
+    pub fn return_value(&self) -> Result<Option<ScVal>> {
+        self.return_value
+            .as_deref()
+            .map(|xdr| ScVal::from_xdr_base64(xdr, Limits::none()).context("invalid return value"))
+            .transpose()
+    }
+}
+
+/// Status of a transaction as reported by `getTransaction`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionStatus {
+    /// Not (yet) in a ledger known to the RPC server
+    NotFound,
+    Success {
+        ledger: u32,
+        /// Return value of a contract invocation, base64 `ScVal`
+        return_value: Option<String>,
+    },
+    Failed {
+        ledger: u32,
+        /// `TransactionResult`, base64 XDR
+        result_xdr: String,
+    },
+}
+
+impl TransactionStatus {
+    /// Return value of a successful contract invocation.
+    pub fn return_value(&self) -> Result<Option<ScVal>> {
+        match self {
+            TransactionStatus::Success { return_value, .. } => return_value
+                .as_deref()
+                .map(|xdr| {
+                    ScVal::from_xdr_base64(xdr, Limits::none()).context("invalid return value")
+                })
+                .transpose(),
+            other => Err(anyhow!("transaction did not succeed: {:?}", other)),
+        }
+    }
+}
+
+/// A closed ledger
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LedgerInfo {
+    pub sequence: u32,
+    /// Ledger header hash, hex
+    pub hash: String,
+}
+
+/// A contract event as returned by `getEvents`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcEvent {
+    /// Unique, and ordered by ledger, transaction and event index
+    pub id: String,
+    pub ledger: u32,
+    pub ledger_closed_at: String,
+    pub contract_id: String,
+    #[serde(default)]
+    pub tx_hash: String,
+    /// Topics, base64 `ScVal`
+    pub topic: Vec<String>,
+    /// Data, base64 `ScVal`
+    pub value: String,
+    /// Newer servers omit this and only return successful calls' events
+    #[serde(default = "successful_by_default")]
+    pub in_successful_contract_call: bool,
+}
+
+fn successful_by_default() -> bool {
+    true
+}
+
+/// Where `get_events` starts reading
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventsFrom {
+    Ledger(u32),
+    /// The `cursor` of a previous page
+    Cursor(String),
+}
+
+/// One page of `getEvents`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventPage {
+    pub events: Vec<RpcEvent>,
+    /// Pass as `EventsFrom::Cursor` to read the next page
+    pub cursor: Option<String>,
+    pub latest_ledger: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawEvents {
+    #[serde(default)]
+    events: Vec<RpcEvent>,
+    #[serde(default)]
+    cursor: Option<String>,
+    latest_ledger: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawLedgers {
+    #[serde(default)]
+    ledgers: Vec<LedgerInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSimulation {
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    transaction_data: Option<String>,
+    #[serde(default)]
+    min_resource_fee: Option<String>,
+    #[serde(default)]
+    results: Vec<RawSimulationResult>,
+    latest_ledger: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawSimulationResult {
+    #[serde(default)]
+    auth: Vec<String>,
+    xdr: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawSend {
+    status: String,
+    hash: String,
+    #[serde(default)]
+    error_result_xdr: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTransaction {
+    status: String,
+    #[serde(default)]
+    ledger: Option<u32>,
+    #[serde(default)]
+    result_xdr: Option<String>,
+    #[serde(default)]
+    result_meta_xdr: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+/// Horizon and Soroban RPC over HTTP. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct StellarRpcClient {
+    http: reqwest::Client,
+    config: RpcConfig,
+    poll_interval: Duration,
+    poll_timeout: Duration,
+}
+
+impl StellarRpcClient {
+    pub fn new(config: RpcConfig) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            config,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            poll_timeout: DEFAULT_POLL_TIMEOUT,
+        }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(RpcConfig::from_env())
+    }
+
+    pub fn with_polling(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.poll_interval = interval;
+        self.poll_timeout = timeout;
+        self
+    }
+
+    pub fn config(&self) -> &RpcConfig {
+        &self.config
+    }
+
+    /// Current sequence number of `account_id`, from Horizon
+    pub async fn account_sequence(&self, account_id: &str) -> Result<i64> {
+        #[derive(Deserialize)]
+        struct Account {
+            sequence: String,
+        }
+
+        let url = format!(
+            "{}/accounts/{}",
+            self.config.horizon_url.trim_end_matches('/'),
+            account_id
+        );
+        let response = self.http.get(&url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(anyhow!("Account {} does not exist", account_id));
+        }
+        let account: Account = response.error_for_status()?.json().await?;
+        account
+            .sequence
+            .parse()
+            .with_context(|| format!("invalid sequence number {:?}", account.sequence))
+    }
+
+    /// Sequence number for the next transaction from `account_id`
+    pub async fn next_sequence_number(&self, account_id: &str) -> Result<i64> {
+        Ok(self.account_sequence(account_id).await? + 1)
+    }
+
+    /// Simulate an unsigned transaction with one `InvokeHostFunction`
+    /// operation.
+    pub async fn simulate_transaction(&self, envelope_xdr: &str) -> Result<Simulation> {
+        let raw: RawSimulation = self
+            .call(
+                "simulateTransaction",
+                json!({ "transaction": envelope_xdr }),
+            )
+            .await?;
+        if let Some(error) = raw.error {
+            return Err(anyhow!("Simulation failed: {}", error));
+        }
+
+        let min_resource_fee = raw
+            .min_resource_fee
+            .ok_or_else(|| anyhow!("simulation returned no resource fee"))?
+            .parse()
+            .context("invalid resource fee")?;
+        let transaction_data = raw
+            .transaction_data
+            .ok_or_else(|| anyhow!("simulation returned no transaction data"))?;
+        let result = raw.results.into_iter().next();
+
+        Ok(Simulation {
+            min_resource_fee,
+            transaction_data,
+            auth: result.as_ref().map(|r| r.auth.clone()).unwrap_or_default(),
+            return_value: result.map(|r| r.xdr),
+            latest_ledger: raw.latest_ledger,
+        })
+    }
+
+    /// Submit a signed transaction. Returns its hash once the server has
+    /// accepted it for inclusion.
+    pub async fn send_transaction(&self, envelope_xdr: &str) -> Result<String> {
+        let raw: RawSend = self
+            .call("sendTransaction", json!({ "transaction": envelope_xdr }))
+            .await?;
+        match raw.status.as_str() {
+            "PENDING" | "DUPLICATE" => Ok(raw.hash),
+            status => Err(anyhow!(
+                "Transaction {} rejected with status {}{}",
+                raw.hash,
+                status,
+                raw.error_result_xdr
+                    .map(|xdr| format!(": {}", xdr))
+                    .unwrap_or_default()
+            )),
+        }
+    }
+
+    pub async fn get_transaction(&self, hash: &str) -> Result<TransactionStatus> {
+        let raw: RawTransaction = self.call("getTransaction", json!({ "hash": hash })).await?;
+        match raw.status.as_str() {
+            "NOT_FOUND" => Ok(TransactionStatus::NotFound),
+            "SUCCESS" => Ok(TransactionStatus::Success {
+                ledger: raw.ledger.unwrap_or_default(),
+                return_value: raw
+                    .result_meta_xdr
+                    .as_deref()
+                    .map(return_value_of)
+                    .transpose()?
+                    .flatten(),
+            }),
+            "FAILED" => Ok(TransactionStatus::Failed {
+                ledger: raw.ledger.unwrap_or_default(),
+                result_xdr: raw.result_xdr.unwrap_or_default(),
+            }),
+            status => Err(anyhow!("Unknown transaction status {}", status)),
+        }
+    }
+
+    /// Submit a signed transaction and wait for it to land in a ledger.
+    pub async fn submit_and_wait(&self, envelope_xdr: &str) -> Result<TransactionStatus> {
+        let hash = self.send_transaction(envelope_xdr).await?;
+        let deadline = tokio::time::Instant::now() + self.poll_timeout;
+        loop {
+            match self.get_transaction(&hash).await? {
+                TransactionStatus::NotFound if tokio::time::Instant::now() < deadline => {
+                    tokio::time::sleep(self.poll_interval).await;
+                }
+                TransactionStatus::NotFound => {
+                    return Err(anyhow!(
+                        "Transaction {} not found after {}s",
+                        hash,
+                        self.poll_timeout.as_secs()
+                    ))
+                }
+                status => return Ok(status),
+            }
+        }
+    }
+
+    /// The most recent ledger the RPC server has closed
+    pub async fn get_latest_ledger(&self) -> Result<LedgerInfo> {
+        #[derive(Deserialize)]
+        struct Latest {
+            id: String,
+            sequence: u32,
+        }
+
+        let latest: Latest = self.call("getLatestLedger", json!({})).await?;
+        Ok(LedgerInfo {
+            sequence: latest.sequence,
+            hash: latest.id,
+        })
+    }
+
+    /// Ledger `sequence`, or `None` if it is outside the server's retention
+    /// window or not closed yet
+    pub async fn get_ledger(&self, sequence: u32) -> Result<Option<LedgerInfo>> {
+        let raw: RawLedgers = self
+            .call(
+                "getLedgers",
+                json!({ "startLedger": sequence, "pagination": { "limit": 1 } }),
+            )
+            .await?;
+        Ok(raw
+            .ledgers
+            .into_iter()
+            .find(|ledger| ledger.sequence == sequence))
+    }
+
+    /// Events emitted by `contract_ids` from `from` up to, but excluding,
+    /// `end_ledger`.
+    pub async fn get_events(
+        &self,
+        contract_ids: &[String],
+        from: EventsFrom,
+        end_ledger: Option<u32>,
+        limit: u32,
+    ) -> Result<EventPage> {
+        let mut params = json!({
+            "filters": [{ "type": "contract", "contractIds": contract_ids }],
+            "pagination": { "limit": limit },
+        });
+        match from {
+            EventsFrom::Ledger(start) => params["startLedger"] = json!(start),
+            EventsFrom::Cursor(cursor) => params["pagination"]["cursor"] = json!(cursor),
+        }
+        if let Some(end) = end_ledger {
+            params["endLedger"] = json!(end);
+        }
+
+        let raw: RawEvents = self.call("getEvents", params).await?;
+        Ok(EventPage {
+            events: raw.events,
+            cursor: raw.cursor,
+            latest_ledger: raw.latest_ledger,
+        })
+    }
+
+    async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+        let response: RpcResponse<T> = self
+            .http
+            .post(&self.config.soroban_rpc_url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("invalid {} response", method))?;
+
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(anyhow!(
+                "{} failed ({}): {}",
+                method,
+                error.code,
+                error.message
+            )),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(anyhow!("{} returned no result", method)),
+        }
+    }
+}
+
+/// Apply a simulation to an unsigned transaction: add the resource fee, set
+/// the footprint and, if the invocation has none yet, its auth entries.
+/// Returns the envelope, ready to sign, as base64 XDR.
+pub fn assemble_transaction(envelope_xdr: &str, simulation: &Simulation) -> Result<String> {
+    let envelope = TransactionEnvelope::from_xdr_base64(envelope_xdr, Limits::none())
+        .context("invalid transaction envelope")?;
+    let TransactionEnvelope::Tx(mut v1) = envelope else {
+        return Err(anyhow!("only v1 transaction envelopes can be assembled"));
+    };
+
+    let resource_fee = u32::try_from(simulation.min_resource_fee)
+        .map_err(|_| anyhow!("resource fee {} out of range", simulation.min_resource_fee))?;
+    v1.tx.fee = v1
+        .tx
+        .fee
+        .checked_add(resource_fee)
+        .ok_or_else(|| anyhow!("transaction fee overflows"))?;
+    v1.tx.ext = TransactionExt::V1(
+        SorobanTransactionData::from_xdr_base64(&simulation.transaction_data, Limits::none())
+            .context("invalid transaction data")?,
+    );
+
+    let mut operations: Vec<Operation> = v1.tx.operations.to_vec();
+    if let [Operation {
+        body: OperationBody::InvokeHostFunction(invoke),
+        ..
+    }] = operations.as_mut_slice()
+    {
+        if invoke.auth.is_empty() {
+            let auth = simulation
+                .auth
+                .iter()
+                .map(|xdr| SorobanAuthorizationEntry::from_xdr_base64(xdr, Limits::none()))
+                .collect::<Result<Vec<_>, _>>()
+                .context("invalid auth entry")?;
+            invoke.auth = auth.try_into()?;
+        }
+    }
+    v1.tx.operations = operations.try_into()?;
+
+    Ok(TransactionEnvelope::Tx(v1).to_xdr_base64(Limits::none())?)
+}
+
+/// Return value of a contract invocation from its `TransactionMeta`
+fn return_value_of(meta_xdr: &str) -> Result<Option<String>> {
+    let meta = TransactionMeta::from_xdr_base64(meta_xdr, Limits::none())
+        .context("invalid transaction meta")?;
+    match meta {
+        TransactionMeta::V3(v3) => v3
+            .soroban_meta
+            .map(|soroban| soroban.return_value.to_xdr_base64(Limits::none()))
+            .transpose()
+            .map_err(Into::into),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stellar_xdr::curr::{
+        ExtensionPoint, Hash, HostFunction, InvokeContractArgs, InvokeHostFunctionOp,
+        LedgerFootprint, Memo, MuxedAccount, Preconditions, ScAddress, ScSymbol, SequenceNumber,
+        SorobanResources, Transaction, TransactionV1Envelope, Uint256,
+    };
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const ACCOUNT: &str = "GAB35A2WLFSK64P6EWSGVFXZYU6E5K2INGTTLMDEDSIPYOH7NZVV6GIG";
+
+    fn client(server: &MockServer) -> StellarRpcClient {
+        StellarRpcClient::new(RpcConfig {
+            horizon_url: server.uri(),
+            soroban_rpc_url: server.uri(),
+        })
+        .with_polling(Duration::from_millis(10), Duration::from_millis(500))
+    }
+
+    fn rpc_result(result: Value) -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .set_body_json(json!({ "jsonrpc": "2.0", "id": 1, "result": result }))
+    }
+
+    fn invoke_envelope() -> String {
+        let invoke = InvokeHostFunctionOp {
+            host_function: HostFunction::InvokeContract(InvokeContractArgs {
+                contract_address: ScAddress::Contract(Hash([7; 32])),
+                function_name: ScSymbol("profile_count".try_into().unwrap()),
+                args: Default::default(),
+            }),
+            auth: Default::default(),
+        };
+        let tx = Transaction {
+            source_account: MuxedAccount::Ed25519(Uint256([1; 32])),
+            fee: 100,
+            seq_num: SequenceNumber(43),
+            cond: Preconditions::None,
+            memo: Memo::None,
+            operations: vec![Operation {
+                source_account: None,
+                body: OperationBody::InvokeHostFunction(invoke),
+            }]
+            .try_into()
+            .unwrap(),
+            ext: TransactionExt::V0,
+        };
+        TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx,
+            signatures: Default::default(),
+        })
+        .to_xdr_base64(Limits::none())
+        .unwrap()
+    }
+
+    fn transaction_data() -> String {
+        SorobanTransactionData {
+            ext: ExtensionPoint::V0,
+            resources: SorobanResources {
+                footprint: LedgerFootprint {
+                    read_only: Default::default(),
+                    read_write: Default::default(),
+                },
+                instructions: 1_000_000,
+                read_bytes: 1_000,
+                write_bytes: 0,
+            },
+            resource_fee: 5_000,
+        }
+        .to_xdr_base64(Limits::none())
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_fetches_account_sequence_from_horizon() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/accounts/{}", ACCOUNT)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": ACCOUNT,
+                "sequence": "4294967298",
+            })))
+            .mount(&server)
+            .await;
+
+        let rpc = client(&server);
+        assert_eq!(rpc.account_sequence(ACCOUNT).await.unwrap(), 4_294_967_298);
+        assert_eq!(
+            rpc.next_sequence_number(ACCOUNT).await.unwrap(),
+            4_294_967_299
+        );
+
+        let missing = rpc.account_sequence("GMISSING").await.unwrap_err();
+        assert!(missing.to_string().contains("does not exist"));
+    }
+
+    #[tokio::test]
+    async fn test_simulation_is_applied_to_transaction() {
+        let server = MockServer::start().await;
+        let return_value = ScVal::U32(3).to_xdr_base64(Limits::none()).unwrap();
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({ "method": "simulateTransaction" }),
+            ))
+            .respond_with(rpc_result(json!({
+                "transactionData": transaction_data(),
+                "minResourceFee": "5000",
+                "results": [{ "auth": [], "xdr": return_value }],
+                "latestLedger": 1200,
+            })))
+            .mount(&server)
+            .await;
+
+        let envelope = invoke_envelope();
+        let simulation = client(&server)
+            .simulate_transaction(&envelope)
+            .await
+            .unwrap();
+        assert_eq!(simulation.min_resource_fee, 5_000);
+        assert_eq!(simulation.return_value().unwrap(), Some(ScVal::U32(3)));
+
+        let assembled = assemble_transaction(&envelope, &simulation).unwrap();
+        let TransactionEnvelope::Tx(v1) =
+            TransactionEnvelope::from_xdr_base64(assembled, Limits::none()).unwrap()
+        else {
+            panic!("expected a v1 envelope");
+        };
+        assert_eq!(v1.tx.fee, 5_100);
+        assert!(matches!(v1.tx.ext, TransactionExt::V1(_)));
+    }
+
+    #[tokio::test]
+    async fn test_simulation_error_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(rpc_result(json!({
+                "error": "HostError: contract call failed",
+                "latestLedger": 1200,
+            })))
+            .mount(&server)
+            .await;
+
+        let error = client(&server)
+            .simulate_transaction(&invoke_envelope())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("contract call failed"));
+    }
+
+    #[tokio::test]
+    async fn test_submit_polls_until_transaction_lands() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "sendTransaction" })))
+            .respond_with(rpc_result(json!({
+                "status": "PENDING",
+                "hash": "abc123",
+                "latestLedger": 1200,
+            })))
+            .mount(&server)
+            .await;
+        // The first two polls miss, the third finds it.
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "getTransaction" })))
+            .respond_with(rpc_result(
+                json!({ "status": "NOT_FOUND", "latestLedger": 1200 }),
+            ))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "getTransaction" })))
+            .respond_with(rpc_result(json!({
+                "status": "SUCCESS",
+                "ledger": 1201,
+                "latestLedger": 1201,
+            })))
+            .mount(&server)
+            .await;
+
+        let status = client(&server).submit_and_wait("AAAA").await.unwrap();
+        assert_eq!(
+            status,
+            TransactionStatus::Success {
+                ledger: 1201,
+                return_value: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_submission_and_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "sendTransaction" })))
+            .respond_with(rpc_result(json!({
+                "status": "ERROR",
+                "hash": "abc123",
+                "errorResultXdr": "AAAAAAAAAGT////7AAAAAA==",
+                "latestLedger": 1200,
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        let rpc = client(&server);
+        let rejected = rpc.submit_and_wait("AAAA").await.unwrap_err();
+        assert!(rejected.to_string().contains("status ERROR"));
+
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "sendTransaction" })))
+            .respond_with(rpc_result(json!({
+                "status": "PENDING",
+                "hash": "abc123",
+                "latestLedger": 1200,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "getTransaction" })))
+            .respond_with(rpc_result(
+                json!({ "status": "NOT_FOUND", "latestLedger": 1200 }),
+            ))
+            .mount(&server)
+            .await;
+        let timed_out = rpc.submit_and_wait("AAAA").await.unwrap_err();
+        assert!(timed_out.to_string().contains("not found"));
+    }
+
+    #[tokio::test]
+    async fn test_events_and_ledgers() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({
+                "method": "getEvents",
+                "params": { "startLedger": 100, "endLedger": 110 },
+            })))
+            .respond_with(rpc_result(json!({
+                "events": [{
+                    "type": "contract",
+                    "ledger": 101,
+                    "ledgerClosedAt": "2026-10-18T12:00:00Z",
+                    "contractId": "CADQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQP5KR",
+                    "id": "0000433791213568-0000000000",
+                    "topic": ["AAAADwAAAARnYW1lAAAAAA=="],
+                    "value": "AAAAAQ==",
+                    "inSuccessfulContractCall": true,
+                    "txHash": "ab12",
+                }],
+                "cursor": "0000433791213568-0000000000",
+                "latestLedger": 120,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "getLedgers" })))
+            .respond_with(rpc_result(json!({
+                "ledgers": [{ "hash": "beef", "sequence": 110, "ledgerCloseTime": "1" }],
+                "latestLedger": 120,
+                "oldestLedger": 1,
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": "getLatestLedger" })))
+            .respond_with(rpc_result(json!({
+                "id": "cafe",
+                "protocolVersion": 21,
+                "sequence": 120,
+            })))
+            .mount(&server)
+            .await;
+
+        let rpc = client(&server);
+        let contracts =
+            vec!["CADQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQOBYHA4DQP5KR".to_string()];
+        let page = rpc
+            .get_events(&contracts, EventsFrom::Ledger(100), Some(110), 50)
+            .await
+            .unwrap();
+        assert_eq!(page.latest_ledger, 120);
+        assert_eq!(page.events.len(), 1);
+        assert_eq!(page.events[0].ledger, 101);
+        assert_eq!(page.events[0].tx_hash, "ab12");
+        assert_eq!(page.cursor.as_deref(), Some("0000433791213568-0000000000"));
+
+        assert_eq!(
+            rpc.get_ledger(110).await.unwrap(),
+            Some(LedgerInfo {
+                sequence: 110,
+                hash: "beef".to_string()
+            })
+        );
+        assert_eq!(rpc.get_ledger(111).await.unwrap(), None);
+        assert_eq!(rpc.get_latest_ledger().await.unwrap().hash, "cafe");
+    }
+}
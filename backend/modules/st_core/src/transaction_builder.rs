@@ -16,7 +16,12 @@ use std::str::FromStr;
 pub struct StellarTransactionBuilder;
 
 impl StellarTransactionBuilder {
-    pub fn create_nft_mint_transaction(request: &NFTMintRequest) -> Result<NFTMintResponse> {
+    /// Build the unsigned mint transaction. `sequence_number` is the
+    /// issuer's next sequence number (see `StellarRpcClient::next_sequence_number`).
+    pub fn create_nft_mint_transaction(
+        request: &NFTMintRequest,
+        sequence_number: i64,
+    ) -> Result<NFTMintResponse> {
         // Parse accounts
         let destination_publickey = PublicKey::from_account_id(&request.destination_account)?;
         
@@ -54,13 +59,10 @@ impl StellarTransactionBuilder {
             operations.push(manage_data_op);
         }
         
-        // Get sequence number (in production, this should be fetched from Horizon)
-        let sequence_number = 1u64; // Placeholder - should be fetched from Horizon API
-        
         // Create transaction
         let mut transaction = Transaction::builder(
             issuer_publickey,
-            sequence_number as i64,
+            sequence_number,
             MIN_BASE_FEE,
         )
         .with_memo(Memo::new_text(format!("NFT Mint: {}", request.ai_metadata.name))?)
@@ -157,7 +159,7 @@ mod tests {
             network: "testnet".to_string(),
         };
         
-        let result = StellarTransactionBuilder::create_nft_mint_transaction(&request);
+        let result = StellarTransactionBuilder::create_nft_mint_transaction(&request, 4_294_967_299);
         assert!(result.is_ok());
         
         let response = result.unwrap();
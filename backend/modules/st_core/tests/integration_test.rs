@@ -1,10 +1,27 @@
 #[cfg(test)]
 mod tests {
     use super::*;
-    use st_core::{NFTService, AIMetadata, NFTMintRequest};
+    use st_core::{NFTService, AIMetadata, NFTMintRequest, RpcConfig, StellarRpcClient};
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     #[tokio::test]
     async fn test_nft_mint_transaction_creation() {
+        // Horizon stand-in serving the issuer's account
+        let horizon = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/accounts/GAB35A2WLFSK64P6EWSGVFXZYU6E5K2INGTTLMDEDSIPYOH7NZVV6GIG"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "sequence": "4294967298"
+            })))
+            .expect(1)
+            .mount(&horizon)
+            .await;
+        let rpc = StellarRpcClient::new(RpcConfig {
+            horizon_url: horizon.uri(),
+            soroban_rpc_url: horizon.uri(),
+        });
+
         let ai_metadata = AIMetadata {
             name: "Test AI Agent".to_string(),
             description: "A test AI agent for chess".to_string(),
@@ -25,7 +42,7 @@ mod tests {
             network: "testnet".to_string(),
         };
 
-        let result = NFTService::create_nft_mint_transaction(mint_request).await;
+        let result = NFTService::create_nft_mint_transaction(&rpc, mint_request).await;
         assert!(result.is_ok());
 
         let response = result.unwrap();
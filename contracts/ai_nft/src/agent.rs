@@ -0,0 +1,123 @@
+// ────────────────────────────────────────────────────────────────────────────
+// Agent engine config and match record
+// ────────────────────────────────────────────────────────────────────────────
+//
+// Each NFT can commit to the engine configuration that plays as it: the
+// SHA-256 of the canonical JSON `engine::EngineConfig` (engine, skill level,
+// opening book, style options). The backend only plays as the agent with a
+// config whose hash matches. The hash is set once by the owner and is then
+// fixed, so the match record always describes the same player.
+//
+// Results are reported by game_contract when a game it has the NFT assigned
+// to settles; see `set_result_reporter`.
+
+use soroban_sdk::{contractimpl, contracttype, symbol_short, Address, BytesN, Env, Map, Symbol};
+
+use crate::{AINFTContract, AINFTContractClient, ContractError};
+
+const ENGINE_CFG: Symbol = symbol_short!("ENGINE"); // Map<u64, BytesN<32>>
+const RECORDS: Symbol = symbol_short!("RECORDS"); // Map<u64, MatchRecord>
+const REPORTER: Symbol = symbol_short!("REPORTER"); // Address allowed to record results
+
+/// Result of a settled game from the agent's point of view. game_contract
+/// mirrors this enum; variant names are part of that interface.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MatchOutcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MatchRecord {
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+}
+
+#[contractimpl]
+impl AINFTContract {
+    /// Commit an NFT to an engine configuration hash. Owner only, and only
+    /// once.
+    pub fn set_engine_config(
+        env: Env,
+        nft_id: u64,
+        config_hash: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        let owner = Self::owner_of(env.clone(), nft_id)?;
+        owner.require_auth();
+
+        let mut configs: Map<u64, BytesN<32>> = env
+            .storage()
+            .instance()
+            .get(&ENGINE_CFG)
+            .unwrap_or(Map::new(&env));
+        if configs.contains_key(nft_id) {
+            return Err(ContractError::EngineConfigLocked);
+        }
+        configs.set(nft_id, config_hash.clone());
+        env.storage().instance().set(&ENGINE_CFG, &configs);
+
+        env.events()
+            .publish((symbol_short!("engine"), nft_id), config_hash);
+        Ok(())
+    }
+
+    /// Get the engine configuration hash of an NFT, if committed
+    pub fn engine_config(env: Env, nft_id: u64) -> Option<BytesN<32>> {
+        let configs: Map<u64, BytesN<32>> = env.storage().instance().get(&ENGINE_CFG)?;
+        configs.get(nft_id)
+    }
+
+    /// Set the contract allowed to record results (the game contract).
+    /// Admin only.
+    pub fn set_result_reporter(env: Env, reporter: Address) {
+        Self::admin(env.clone()).require_auth();
+        env.storage().instance().set(&REPORTER, &reporter);
+    }
+
+    /// Add a settled game to an NFT's record. Reporter only.
+    pub fn record_result(
+        env: Env,
+        nft_id: u64,
+        outcome: MatchOutcome,
+    ) -> Result<(), ContractError> {
+        let reporter: Address = env
+            .storage()
+            .instance()
+            .get(&REPORTER)
+            .ok_or(ContractError::ReporterNotSet)?;
+        reporter.require_auth();
+        Self::owner_of(env.clone(), nft_id)?;
+
+        let mut records: Map<u64, MatchRecord> = env
+            .storage()
+            .instance()
+            .get(&RECORDS)
+            .unwrap_or(Map::new(&env));
+        let mut record = records.get(nft_id).unwrap_or_default();
+        match outcome {
+            MatchOutcome::Win => record.wins += 1,
+            MatchOutcome::Loss => record.losses += 1,
+            MatchOutcome::Draw => record.draws += 1,
+        }
+        records.set(nft_id, record);
+        env.storage().instance().set(&RECORDS, &records);
+
+        env.events()
+            .publish((symbol_short!("result"), nft_id), outcome);
+        Ok(())
+    }
+
+    /// Get the games won, lost and drawn by an NFT
+    pub fn match_record(env: Env, nft_id: u64) -> MatchRecord {
+        let records: Map<u64, MatchRecord> = env
+            .storage()
+            .instance()
+            .get(&RECORDS)
+            .unwrap_or(Map::new(&env));
+        records.get(nft_id).unwrap_or_default()
+    }
+}
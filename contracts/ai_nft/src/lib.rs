@@ -1,9 +1,17 @@
 #![no_std]
 use soroban_sdk::{
     contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env, Map,
-    String, Symbol,
+    String, Symbol, Vec,
 };
 
+mod agent;
+mod marketplace;
+mod rental;
+
+pub use agent::{MatchOutcome, MatchRecord};
+pub use marketplace::{Listing, ListingKind, MarketConfig, Treasury, TreasuryClient};
+pub use rental::Rental;
+
 // AI NFT metadata structure
 #[contracttype]
 #[derive(Clone, Debug)]
@@ -22,6 +30,19 @@ const NFT_COUNTER: Symbol = symbol_short!("NFT_CNT");
 const NFT_OWNERS: Symbol = symbol_short!("OWNERS");
 const NFT_METADATA: Symbol = symbol_short!("METADATA");
 const MINTER_REGISTRY: Symbol = symbol_short!("MINTER");
+const UPGRADER: Symbol = symbol_short!("UPGRADER");
+const SCHEMA_VER: Symbol = symbol_short!("SCHEMA");
+const BURNED: Symbol = symbol_short!("BURNED"); // u64
+const OWNED: Symbol = symbol_short!("OWNED"); // Map<Address, Vec<u64>>
+const APPROVALS: Symbol = symbol_short!("APPROVED"); // Map<u64, Address>
+const OPERATORS: Symbol = symbol_short!("OPERATORS"); // Map<(owner, operator), bool>
+const BASE_URI: Symbol = symbol_short!("BASE_URI"); // String
+
+const MAX_BASE_URI_LEN: usize = 200;
+
+/// Storage layout version this code reads and writes; see `migrate`
+/// v2: per-owner token index (`OWNED`)
+pub const SCHEMA_VERSION: u32 = 2;
 
 // Contract errors
 #[contracterror]
@@ -33,6 +54,24 @@ pub enum ContractError {
     AlreadyTransferred = 4,
     InvalidOwner = 5,
     MinterMismatch = 6,
+    UpgradeAuthorityNotSet = 7,
+    SchemaTooNew = 8,
+    BaseUriTooLong = 9,
+    NotListed = 10,
+    InvalidPrice = 11,
+    MarketNotConfigured = 12,
+    WrongListingKind = 13,
+    AuctionEnded = 14,
+    AuctionNotEnded = 15,
+    BidTooLow = 16,
+    HasBids = 17,
+    InvalidFee = 18,
+    TreasuryNotSet = 19,
+    EngineConfigLocked = 20,
+    ReporterNotSet = 21,
+    NoRental = 22,
+    RentalActive = 23,
+    InvalidDuration = 24,
 }
 
 #[contract]
@@ -40,14 +79,18 @@ pub struct AINFTContract;
 
 #[contractimpl]
 impl AINFTContract {
-    /// Initialize the AI NFT contract with an admin address
-    pub fn initialize(env: Env, admin: Address) {
+    /// Initialize the AI NFT contract with an admin address and the address
+    /// allowed to upgrade it (normally the game contract, so upgrades need an
+    /// `UpgradeContract` governance proposal)
+    pub fn initialize(env: Env, admin: Address, upgrade_authority: Address) {
         if env.storage().instance().has(&ADMIN) {
             panic!("Contract already initialized");
         }
         admin.require_auth();
         env.storage().instance().set(&ADMIN, &admin);
+        env.storage().instance().set(&UPGRADER, &upgrade_authority);
         env.storage().instance().set(&NFT_COUNTER, &0u64);
+        env.storage().instance().set(&SCHEMA_VER, &SCHEMA_VERSION);
     }
 
     /// Get the current admin
@@ -96,6 +139,7 @@ impl AINFTContract {
             .unwrap_or(Map::new(&env));
         owners.set(nft_counter, minter.clone());
         env.storage().instance().set(&NFT_OWNERS, &owners);
+        Self::index_add(&env, &minter, nft_counter);
 
         // Record minter for this NFT
         let mut minter_registry: Map<u64, Address> = env
@@ -103,29 +147,153 @@ impl AINFTContract {
             .instance()
             .get(&MINTER_REGISTRY)
             .unwrap_or(Map::new(&env));
-        minter_registry.set(nft_counter, minter);
+        minter_registry.set(nft_counter, minter.clone());
         env.storage()
             .instance()
             .set(&MINTER_REGISTRY, &minter_registry);
 
+        env.events()
+            .publish((symbol_short!("mint"), minter), nft_counter);
+
         nft_counter
     }
 
     /// Transfer NFT from current owner to a new owner
     pub fn transfer(env: Env, nft_id: u64, to: Address) -> Result<(), ContractError> {
-        let mut owners: Map<u64, Address> = env
+        let current_owner = Self::owner_of(env.clone(), nft_id)?;
+        current_owner.require_auth();
+        Self::ensure_not_rented(&env, nft_id)?;
+
+        Self::move_token(&env, &current_owner, &to, nft_id);
+        Ok(())
+    }
+
+    /// Transfer on behalf of `from`. `spender` must be the owner, the
+    /// token's approved address or an operator for the owner.
+    pub fn transfer_from(
+        env: Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        nft_id: u64,
+    ) -> Result<(), ContractError> {
+        spender.require_auth();
+
+        let owner = Self::owner_of(env.clone(), nft_id)?;
+        if owner != from {
+            return Err(ContractError::InvalidOwner);
+        }
+        if !Self::can_spend(&env, &spender, &owner, nft_id) {
+            return Err(ContractError::NotAuthorized);
+        }
+        Self::ensure_not_rented(&env, nft_id)?;
+
+        Self::move_token(&env, &owner, &to, nft_id);
+        Ok(())
+    }
+
+    /// Approve `approved` to transfer a single NFT, or clear the approval
+    /// with `None`. Callable by the owner or one of its operators. Cleared
+    /// automatically on transfer.
+    pub fn approve(
+        env: Env,
+        caller: Address,
+        approved: Option<Address>,
+        nft_id: u64,
+    ) -> Result<(), ContractError> {
+        caller.require_auth();
+
+        let owner = Self::owner_of(env.clone(), nft_id)?;
+        if caller != owner && !Self::is_approved_for_all(env.clone(), owner.clone(), caller) {
+            return Err(ContractError::NotAuthorized);
+        }
+
+        let mut approvals: Map<u64, Address> = env
             .storage()
             .instance()
-            .get(&NFT_OWNERS)
-            .ok_or(ContractError::NFTNotFound)?;
+            .get(&APPROVALS)
+            .unwrap_or(Map::new(&env));
+        match &approved {
+            Some(address) => approvals.set(nft_id, address.clone()),
+            None => {
+                approvals.remove(nft_id);
+            }
+        }
+        env.storage().instance().set(&APPROVALS, &approvals);
 
-        let current_owner = owners.get(nft_id).ok_or(ContractError::NFTNotFound)?;
-        current_owner.require_auth();
+        env.events()
+            .publish((symbol_short!("approve"), owner, nft_id), approved);
+        Ok(())
+    }
 
-        // Update owner
-        owners.set(nft_id, to.clone());
+    /// Get the address approved for a single NFT, if any
+    pub fn get_approved(env: Env, nft_id: u64) -> Option<Address> {
+        let approvals: Map<u64, Address> = env.storage().instance().get(&APPROVALS)?;
+        approvals.get(nft_id)
+    }
+
+    /// Let `operator` transfer and approve every NFT `owner` holds, now and
+    /// in future, or revoke that with `approved = false`.
+    pub fn set_approval_for_all(env: Env, owner: Address, operator: Address, approved: bool) {
+        owner.require_auth();
+
+        let mut operators: Map<(Address, Address), bool> = env
+            .storage()
+            .instance()
+            .get(&OPERATORS)
+            .unwrap_or(Map::new(&env));
+        let key = (owner.clone(), operator.clone());
+        if approved {
+            operators.set(key, true);
+        } else {
+            operators.remove(key);
+        }
+        env.storage().instance().set(&OPERATORS, &operators);
+
+        env.events()
+            .publish((symbol_short!("appr_all"), owner, operator), approved);
+    }
+
+    /// Check whether `operator` may act for all of `owner`'s NFTs
+    pub fn is_approved_for_all(env: Env, owner: Address, operator: Address) -> bool {
+        let operators: Map<(Address, Address), bool> = env
+            .storage()
+            .instance()
+            .get(&OPERATORS)
+            .unwrap_or(Map::new(&env));
+        operators.get((owner, operator)).unwrap_or(false)
+    }
+
+    /// Destroy an NFT. `spender` must be the owner, approved or an operator.
+    /// The minter record is kept so `minter_of` still answers for burned ids.
+    pub fn burn(env: Env, spender: Address, nft_id: u64) -> Result<(), ContractError> {
+        spender.require_auth();
+
+        let owner = Self::owner_of(env.clone(), nft_id)?;
+        if !Self::can_spend(&env, &spender, &owner, nft_id) {
+            return Err(ContractError::NotAuthorized);
+        }
+        Self::ensure_not_rented(&env, nft_id)?;
+
+        let mut owners: Map<u64, Address> = env.storage().instance().get(&NFT_OWNERS).unwrap();
+        owners.remove(nft_id);
         env.storage().instance().set(&NFT_OWNERS, &owners);
 
+        let mut nft_metadata: Map<u64, AINFTMetadata> = env
+            .storage()
+            .instance()
+            .get(&NFT_METADATA)
+            .unwrap_or(Map::new(&env));
+        nft_metadata.remove(nft_id);
+        env.storage().instance().set(&NFT_METADATA, &nft_metadata);
+
+        Self::clear_approval(&env, nft_id);
+        Self::index_remove(&env, &owner, nft_id);
+
+        let burned: u64 = env.storage().instance().get(&BURNED).unwrap_or(0);
+        env.storage().instance().set(&BURNED, &(burned + 1));
+
+        env.events().publish((symbol_short!("burn"), owner), nft_id);
         Ok(())
     }
 
@@ -164,17 +332,241 @@ impl AINFTContract {
         nft_metadata.get(nft_id).ok_or(ContractError::NFTNotFound)
     }
 
-    /// Get total number of NFTs minted
+    /// Get number of NFTs in circulation (minted minus burned)
     pub fn total_supply(env: Env) -> u64 {
-        env.storage().instance().get(&NFT_COUNTER).unwrap_or(0)
+        let minted: u64 = env.storage().instance().get(&NFT_COUNTER).unwrap_or(0);
+        let burned: u64 = env.storage().instance().get(&BURNED).unwrap_or(0);
+        minted - burned
+    }
+
+    /// Get number of NFTs held by `owner`
+    pub fn balance_of(env: Env, owner: Address) -> u32 {
+        Self::tokens_of_owner(env, owner).len()
+    }
+
+    /// Get ids of the NFTs held by `owner`
+    pub fn tokens_of_owner(env: Env, owner: Address) -> Vec<u64> {
+        let owned: Map<Address, Vec<u64>> = env
+            .storage()
+            .instance()
+            .get(&OWNED)
+            .unwrap_or(Map::new(&env));
+        owned.get(owner).unwrap_or(Vec::new(&env))
+    }
+
+    /// Collection name shown by wallets
+    pub fn name(env: Env) -> String {
+        String::from_str(&env, "XLMate AI Agents")
+    }
+
+    /// Collection symbol shown by wallets
+    pub fn symbol(env: Env) -> String {
+        String::from_str(&env, "XLMAI")
+    }
+
+    /// Get the metadata URI of an NFT: the base URI followed by the id
+    pub fn token_uri(env: Env, nft_id: u64) -> Result<String, ContractError> {
+        Self::owner_of(env.clone(), nft_id)?;
+
+        let base_uri: String = env
+            .storage()
+            .instance()
+            .get(&BASE_URI)
+            .unwrap_or(String::from_str(&env, ""));
+        let base_len = base_uri.len() as usize;
+
+        // Base URI plus up to 20 decimal digits of a u64
+        let mut buf = [0u8; MAX_BASE_URI_LEN + 20];
+        base_uri.copy_into_slice(&mut buf[..base_len]);
+
+        let mut digits = [0u8; 20];
+        let mut n = nft_id;
+        let mut count = 0;
+        loop {
+            digits[count] = b'0' + (n % 10) as u8;
+            count += 1;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+        for i in 0..count {
+            buf[base_len + i] = digits[count - 1 - i];
+        }
+
+        Ok(String::from_bytes(&env, &buf[..base_len + count]))
+    }
+
+    /// Set the prefix `token_uri` puts in front of each id, e.g.
+    /// `https://api.xlmate.io/nft/`. Admin only.
+    pub fn set_base_uri(env: Env, base_uri: String) -> Result<(), ContractError> {
+        Self::admin(env.clone()).require_auth();
+        if base_uri.len() as usize > MAX_BASE_URI_LEN {
+            return Err(ContractError::BaseUriTooLong);
+        }
+        env.storage().instance().set(&BASE_URI, &base_uri);
+        Ok(())
+    }
+
+    /// Hand the upgrade authority over to another address. Only the current
+    /// authority can do this; the admin has no say once `initialize` has set it.
+    pub fn set_upgrade_authority(env: Env, authority: Address) -> Result<(), ContractError> {
+        let current: Address = env
+            .storage()
+            .instance()
+            .get(&UPGRADER)
+            .ok_or(ContractError::UpgradeAuthorityNotSet)?;
+        current.require_auth();
+        env.storage().instance().set(&UPGRADER, &authority);
+        Ok(())
+    }
+
+    /// Get the upgrade authority, if one has been set
+    pub fn upgrade_authority(env: Env) -> Option<Address> {
+        env.storage().instance().get(&UPGRADER)
+    }
+
+    /// Replace the contract code with already-uploaded WASM. State is kept;
+    /// call `migrate` afterwards if the new code bumps `SCHEMA_VERSION`.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), ContractError> {
+        let authority: Address = env
+            .storage()
+            .instance()
+            .get(&UPGRADER)
+            .ok_or(ContractError::UpgradeAuthorityNotSet)?;
+        authority.require_auth();
+
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    /// Storage layout version currently persisted (v1 for deployments that
+    /// predate versioning)
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage().instance().get(&SCHEMA_VER).unwrap_or(1)
+    }
+
+    /// Bring persisted state up to `SCHEMA_VERSION`. Idempotent, so anyone
+    /// may call it after an upgrade.
+    pub fn migrate(env: Env) -> Result<u32, ContractError> {
+        let from = Self::schema_version(env.clone());
+        if from > SCHEMA_VERSION {
+            return Err(ContractError::SchemaTooNew);
+        }
+
+        // v1 -> v2: build the per-owner index and repair `metadata.owner`,
+        // which v1 `transfer` left pointing at the minter.
+        if from < 2 {
+            let owners: Map<u64, Address> = env
+                .storage()
+                .instance()
+                .get(&NFT_OWNERS)
+                .unwrap_or(Map::new(&env));
+            let mut nft_metadata: Map<u64, AINFTMetadata> = env
+                .storage()
+                .instance()
+                .get(&NFT_METADATA)
+                .unwrap_or(Map::new(&env));
+            let mut owned: Map<Address, Vec<u64>> = Map::new(&env);
+            for (nft_id, owner) in owners.iter() {
+                let mut ids = owned.get(owner.clone()).unwrap_or(Vec::new(&env));
+                ids.push_back(nft_id);
+                owned.set(owner.clone(), ids);
+
+                if let Some(mut nft) = nft_metadata.get(nft_id) {
+                    nft.owner = owner;
+                    nft_metadata.set(nft_id, nft);
+                }
+            }
+            env.storage().instance().set(&OWNED, &owned);
+            env.storage().instance().set(&NFT_METADATA, &nft_metadata);
+        }
+
+        env.storage().instance().set(&SCHEMA_VER, &SCHEMA_VERSION);
+        Ok(SCHEMA_VERSION)
+    }
+
+    fn can_spend(env: &Env, spender: &Address, owner: &Address, nft_id: u64) -> bool {
+        spender == owner
+            || Self::get_approved(env.clone(), nft_id).as_ref() == Some(spender)
+            || Self::is_approved_for_all(env.clone(), owner.clone(), spender.clone())
+    }
+
+    /// Reassign ownership, keeping the owner map, metadata and per-owner
+    /// index in step, and drop any single-token approval.
+    fn move_token(env: &Env, from: &Address, to: &Address, nft_id: u64) {
+        let mut owners: Map<u64, Address> = env.storage().instance().get(&NFT_OWNERS).unwrap();
+        owners.set(nft_id, to.clone());
+        env.storage().instance().set(&NFT_OWNERS, &owners);
+
+        let mut nft_metadata: Map<u64, AINFTMetadata> = env
+            .storage()
+            .instance()
+            .get(&NFT_METADATA)
+            .unwrap_or(Map::new(env));
+        if let Some(mut nft) = nft_metadata.get(nft_id) {
+            nft.owner = to.clone();
+            nft_metadata.set(nft_id, nft);
+            env.storage().instance().set(&NFT_METADATA, &nft_metadata);
+        }
+
+        Self::clear_approval(env, nft_id);
+        Self::index_remove(env, from, nft_id);
+        Self::index_add(env, to, nft_id);
+
+        env.events().publish(
+            (symbol_short!("transfer"), from.clone(), to.clone()),
+            nft_id,
+        );
+    }
+
+    fn clear_approval(env: &Env, nft_id: u64) {
+        let approvals: Option<Map<u64, Address>> = env.storage().instance().get(&APPROVALS);
+        if let Some(mut approvals) = approvals {
+            if approvals.contains_key(nft_id) {
+                approvals.remove(nft_id);
+                env.storage().instance().set(&APPROVALS, &approvals);
+            }
+        }
+    }
+
+    fn index_add(env: &Env, owner: &Address, nft_id: u64) {
+        let mut owned: Map<Address, Vec<u64>> = env
+            .storage()
+            .instance()
+            .get(&OWNED)
+            .unwrap_or(Map::new(env));
+        let mut ids = owned.get(owner.clone()).unwrap_or(Vec::new(env));
+        ids.push_back(nft_id);
+        owned.set(owner.clone(), ids);
+        env.storage().instance().set(&OWNED, &owned);
+    }
+
+    fn index_remove(env: &Env, owner: &Address, nft_id: u64) {
+        let mut owned: Map<Address, Vec<u64>> = env
+            .storage()
+            .instance()
+            .get(&OWNED)
+            .unwrap_or(Map::new(env));
+        let mut ids = owned.get(owner.clone()).unwrap_or(Vec::new(env));
+        if let Some(pos) = ids.first_index_of(nft_id) {
+            ids.remove(pos);
+        }
+        if ids.is_empty() {
+            owned.remove(owner.clone());
+        } else {
+            owned.set(owner.clone(), ids);
+        }
+        env.storage().instance().set(&OWNED, &owned);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::testutils::Address as _;
-    use soroban_sdk::{Address, BytesN, Env};
+    use soroban_sdk::testutils::{Address as _, Events as _, Ledger as _};
+    use soroban_sdk::token::{StellarAssetClient, TokenClient};
+    use soroban_sdk::{contract, Address, BytesN, Env, FromVal, IntoVal};
 
     #[test]
     fn test_ai_nft_mint_and_transfer() {
@@ -188,7 +580,7 @@ mod tests {
         // Initialize contract
         let contract_id = env.register_contract(None, AINFTContract);
         let client = AINFTContractClient::new(&env, &contract_id);
-        client.initialize(&admin);
+        client.initialize(&admin, &Address::generate(&env));
 
         // Create metadata hash
         let metadata_hash: BytesN<32> = BytesN::from_array(&env, &[1u8; 32]);
@@ -238,7 +630,7 @@ mod tests {
 
         let contract_id = env.register_contract(None, AINFTContract);
         let client = AINFTContractClient::new(&env, &contract_id);
-        client.initialize(&admin);
+        client.initialize(&admin, &Address::generate(&env));
 
         let metadata_hash1: BytesN<32> = BytesN::from_array(&env, &[1u8; 32]);
         let metadata_hash2: BytesN<32> = BytesN::from_array(&env, &[2u8; 32]);
@@ -272,6 +664,400 @@ mod tests {
         assert_eq!(client.total_supply(), 2u64);
     }
 
+    #[test]
+    fn test_upgrade_requires_authority_and_migrate_is_idempotent() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let admin = Address::generate(&env);
+        let authority = Address::generate(&env);
+        let successor = Address::generate(&env);
+
+        let contract_id = env.register_contract(None, AINFTContract);
+        let client = AINFTContractClient::new(&env, &contract_id);
+        client.initialize(&admin, &authority);
+        assert_eq!(client.upgrade_authority(), Some(authority.clone()));
+
+        // Handing it over is signed by the current authority, not the admin.
+        client.set_upgrade_authority(&successor);
+        assert_eq!(env.auths()[0].0, authority);
+        assert_eq!(client.upgrade_authority(), Some(successor));
+
+        assert_eq!(client.schema_version(), SCHEMA_VERSION);
+        assert_eq!(client.migrate(), SCHEMA_VERSION);
+        assert_eq!(client.migrate(), SCHEMA_VERSION);
+    }
+
+    fn setup_minted(env: &Env) -> (AINFTContractClient<'_>, Address, u64) {
+        let contract_id = env.register_contract(None, AINFTContract);
+        let client = AINFTContractClient::new(env, &contract_id);
+        client.initialize(&Address::generate(env), &Address::generate(env));
+
+        let owner = Address::generate(env);
+        let nft_id = client.mint(
+            &owner,
+            &BytesN::from_array(env, &[1u8; 32]),
+            &String::from_str(env, "bot"),
+        );
+        (client, owner, nft_id)
+    }
+
+    #[test]
+    fn test_approve_and_operator_transfers() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, owner, nft_id) = setup_minted(&env);
+        let spender = Address::generate(&env);
+        let operator = Address::generate(&env);
+        let buyer = Address::generate(&env);
+
+        assert_eq!(
+            client.try_transfer_from(&spender, &owner, &buyer, &nft_id),
+            Err(Ok(ContractError::NotAuthorized))
+        );
+
+        client.approve(&owner, &Some(spender.clone()), &nft_id);
+        assert_eq!(client.get_approved(&nft_id), Some(spender.clone()));
+        client.transfer_from(&spender, &owner, &buyer, &nft_id);
+
+        // Approval is cleared and ownership fully moved
+        assert_eq!(client.get_approved(&nft_id), None);
+        assert_eq!(client.owner_of(&nft_id), buyer);
+        assert_eq!(client.metadata(&nft_id).owner, buyer);
+        assert_eq!(client.balance_of(&owner), 0);
+        assert_eq!(
+            client.tokens_of_owner(&buyer),
+            Vec::from_array(&env, [nft_id])
+        );
+
+        // An operator can move any of the owner's tokens
+        client.set_approval_for_all(&buyer, &operator, &true);
+        assert!(client.is_approved_for_all(&buyer, &operator));
+        client.transfer_from(&operator, &buyer, &owner, &nft_id);
+        assert_eq!(client.owner_of(&nft_id), owner);
+
+        client.set_approval_for_all(&buyer, &operator, &false);
+        assert!(!client.is_approved_for_all(&buyer, &operator));
+    }
+
+    #[test]
+    fn test_burn_and_token_uri() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, owner, nft_id) = setup_minted(&env);
+
+        assert_eq!(client.token_uri(&nft_id), String::from_str(&env, "1"));
+        client.set_base_uri(&String::from_str(&env, "https://api.xlmate.io/nft/"));
+        assert_eq!(
+            client.token_uri(&nft_id),
+            String::from_str(&env, "https://api.xlmate.io/nft/1")
+        );
+
+        assert_eq!(
+            client.try_burn(&Address::generate(&env), &nft_id),
+            Err(Ok(ContractError::NotAuthorized))
+        );
+        client.burn(&owner, &nft_id);
+
+        assert_eq!(
+            client.try_owner_of(&nft_id),
+            Err(Ok(ContractError::NFTNotFound))
+        );
+        assert_eq!(
+            client.try_token_uri(&nft_id),
+            Err(Ok(ContractError::NFTNotFound))
+        );
+        assert_eq!(client.minter_of(&nft_id), owner);
+        assert_eq!(client.balance_of(&owner), 0);
+        assert_eq!(client.total_supply(), 0);
+    }
+
+    #[test]
+    fn test_transfer_publishes_event() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, owner, nft_id) = setup_minted(&env);
+        let to = Address::generate(&env);
+
+        client.transfer(&nft_id, &to);
+
+        let (contract, topics, data) = env.events().all().last().unwrap();
+        assert_eq!(contract, client.address);
+        assert_eq!(
+            topics,
+            (symbol_short!("transfer"), owner, to).into_val(&env)
+        );
+        assert_eq!(u64::from_val(&env, &data), nft_id);
+    }
+
+    #[test]
+    fn test_migrate_builds_owner_index() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, owner, nft_id) = setup_minted(&env);
+
+        // Roll storage back to the v1 layout
+        env.as_contract(&client.address, || {
+            env.storage().instance().remove(&OWNED);
+            env.storage().instance().set(&SCHEMA_VER, &1u32);
+        });
+        assert_eq!(client.balance_of(&owner), 0);
+
+        assert_eq!(client.migrate(), 2);
+        assert_eq!(
+            client.tokens_of_owner(&owner),
+            Vec::from_array(&env, [nft_id])
+        );
+    }
+
+    /// Stand-in for game_contract's `get_treasury_address`
+    #[contract]
+    struct TreasuryStub;
+
+    #[contractimpl]
+    impl TreasuryStub {
+        pub fn set_treasury(env: Env, treasury: Address) {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("treasury"), &treasury);
+        }
+
+        pub fn get_treasury_address(env: Env) -> Option<Address> {
+            env.storage().instance().get(&symbol_short!("treasury"))
+        }
+    }
+
+    /// Mint an NFT from `minter`, hand it to a separate seller and open the
+    /// marketplace with a 5 % royalty and 2.5 % platform fee.
+    fn setup_market(
+        env: &Env,
+    ) -> (
+        AINFTContractClient<'_>,
+        StellarAssetClient<'_>,
+        TokenClient<'_>,
+        Address,
+        Address,
+        Address,
+        u64,
+    ) {
+        let (client, minter, nft_id) = setup_minted(env);
+        let seller = Address::generate(env);
+        client.transfer(&nft_id, &seller);
+
+        let token = env.register_stellar_asset_contract_v2(Address::generate(env));
+        let treasury = Address::generate(env);
+        let stub_id = env.register_contract(None, TreasuryStub);
+        TreasuryStubClient::new(env, &stub_id).set_treasury(&treasury);
+
+        client.configure_marketplace(&MarketConfig {
+            payment_token: token.address(),
+            game_contract: stub_id,
+            platform_fee_bips: 250,
+            royalty_bips: 500,
+        });
+
+        (
+            client,
+            StellarAssetClient::new(env, &token.address()),
+            TokenClient::new(env, &token.address()),
+            minter,
+            seller,
+            treasury,
+            nft_id,
+        )
+    }
+
+    #[test]
+    fn test_fixed_price_sale_pays_royalty_and_platform_fee() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, asset, token, minter, seller, treasury, nft_id) = setup_market(&env);
+        let buyer = Address::generate(&env);
+        asset.mint(&buyer, &1_000);
+
+        client.list_fixed_price(&seller, &nft_id, &1_000);
+        assert_eq!(client.owner_of(&nft_id), client.address);
+        assert_eq!(client.get_listings().len(), 1);
+
+        client.buy(&buyer, &nft_id);
+
+        assert_eq!(client.owner_of(&nft_id), buyer);
+        assert_eq!(token.balance(&minter), 50);
+        assert_eq!(token.balance(&treasury), 25);
+        assert_eq!(token.balance(&seller), 925);
+        assert_eq!(token.balance(&buyer), 0);
+        assert!(client.get_listing(&nft_id).is_none());
+    }
+
+    #[test]
+    fn test_auction_refunds_outbid_and_settles_to_highest_bidder() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, asset, token, minter, seller, _treasury, nft_id) = setup_market(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        asset.mint(&alice, &500);
+        asset.mint(&bob, &500);
+
+        let end_ledger = env.ledger().sequence() as u64 + 10;
+        client.list_auction(&seller, &nft_id, &200, &end_ledger);
+
+        assert_eq!(
+            client.try_bid(&alice, &nft_id, &100),
+            Err(Ok(ContractError::BidTooLow))
+        );
+        client.bid(&alice, &nft_id, &200);
+        client.bid(&bob, &nft_id, &400);
+        assert_eq!(token.balance(&alice), 500);
+        assert_eq!(token.balance(&client.address), 400);
+
+        assert_eq!(
+            client.try_cancel_listing(&seller, &nft_id),
+            Err(Ok(ContractError::HasBids))
+        );
+        assert_eq!(
+            client.try_settle_auction(&nft_id),
+            Err(Ok(ContractError::AuctionNotEnded))
+        );
+
+        env.ledger().with_mut(|l| l.sequence_number += 11);
+        client.settle_auction(&nft_id);
+
+        assert_eq!(client.owner_of(&nft_id), bob);
+        assert_eq!(token.balance(&minter), 20);
+        assert_eq!(token.balance(&seller), 370);
+        assert_eq!(token.balance(&client.address), 0);
+    }
+
+    #[test]
+    fn test_listing_keeps_payment_token_after_reconfiguration() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, asset, token, _minter, seller, _treasury, nft_id) = setup_market(&env);
+        let alice = Address::generate(&env);
+        let bob = Address::generate(&env);
+        asset.mint(&alice, &500);
+        asset.mint(&bob, &500);
+
+        let end_ledger = env.ledger().sequence() as u64 + 10;
+        client.list_auction(&seller, &nft_id, &200, &end_ledger);
+        client.bid(&alice, &nft_id, &200);
+
+        let mut config = client.get_marketplace_config().unwrap();
+        config.payment_token = env
+            .register_stellar_asset_contract_v2(Address::generate(&env))
+            .address();
+        client.configure_marketplace(&config);
+
+        // The refund and the payout stay in the token alice bid in.
+        client.bid(&bob, &nft_id, &400);
+        assert_eq!(token.balance(&alice), 500);
+        env.ledger().with_mut(|l| l.sequence_number += 11);
+        client.settle_auction(&nft_id);
+
+        assert_eq!(client.owner_of(&nft_id), bob);
+        assert_eq!(token.balance(&seller), 370);
+        assert_eq!(token.balance(&client.address), 0);
+    }
+
+    #[test]
+    fn test_cancel_listing_returns_nft() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _asset, _token, _minter, seller, _treasury, nft_id) = setup_market(&env);
+
+        assert_eq!(
+            client.try_list_fixed_price(&seller, &nft_id, &0),
+            Err(Ok(ContractError::InvalidPrice))
+        );
+        client.list_fixed_price(&seller, &nft_id, &100);
+        client.cancel_listing(&seller, &nft_id);
+
+        assert_eq!(client.owner_of(&nft_id), seller);
+        assert_eq!(
+            client.tokens_of_owner(&seller),
+            Vec::from_array(&env, [nft_id])
+        );
+        assert!(client.get_listings().is_empty());
+    }
+
+    #[test]
+    fn test_rental_grants_use_until_expiry_and_escrows_payment() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, asset, token, _minter, owner, _treasury, nft_id) = setup_market(&env);
+        let renter = Address::generate(&env);
+        asset.mint(&renter, &300);
+
+        client.offer_rental(&owner, &nft_id, &300, &100);
+        assert_eq!(client.user_of(&nft_id), owner);
+        assert_eq!(
+            client.try_transfer(&nft_id, &renter),
+            Err(Ok(ContractError::RentalActive))
+        );
+        assert_eq!(
+            client.try_list_fixed_price(&owner, &nft_id, &1_000),
+            Err(Ok(ContractError::RentalActive))
+        );
+
+        let expires_at = client.rent(&renter, &nft_id);
+        assert_eq!(expires_at, env.ledger().sequence() as u64 + 100);
+        assert_eq!(client.user_of(&nft_id), renter);
+        assert_eq!(client.owner_of(&nft_id), owner);
+        assert_eq!(token.balance(&client.address), 300);
+        assert_eq!(
+            client.try_cancel_rental(&owner, &nft_id),
+            Err(Ok(ContractError::RentalActive))
+        );
+        assert_eq!(
+            client.try_end_rental(&nft_id),
+            Err(Ok(ContractError::RentalActive))
+        );
+
+        // The grant lapses on its own; settling only releases the escrow.
+        env.ledger().with_mut(|l| l.sequence_number += 101);
+        assert_eq!(client.user_of(&nft_id), owner);
+        client.end_rental(&nft_id);
+        assert_eq!(token.balance(&owner), 300);
+        assert!(client.get_rental(&nft_id).is_none());
+
+        client.transfer(&nft_id, &renter);
+        assert_eq!(client.user_of(&nft_id), renter);
+    }
+
+    #[test]
+    fn test_engine_config_is_locked_and_results_accumulate() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _owner, nft_id) = setup_minted(&env);
+        let config_hash = BytesN::from_array(&env, &[4u8; 32]);
+
+        client.set_engine_config(&nft_id, &config_hash);
+        assert_eq!(client.engine_config(&nft_id), Some(config_hash.clone()));
+        assert_eq!(
+            client.try_set_engine_config(&nft_id, &BytesN::from_array(&env, &[5u8; 32])),
+            Err(Ok(ContractError::EngineConfigLocked))
+        );
+
+        assert_eq!(
+            client.try_record_result(&nft_id, &MatchOutcome::Win),
+            Err(Ok(ContractError::ReporterNotSet))
+        );
+        client.set_result_reporter(&Address::generate(&env));
+        client.record_result(&nft_id, &MatchOutcome::Win);
+        client.record_result(&nft_id, &MatchOutcome::Win);
+        client.record_result(&nft_id, &MatchOutcome::Draw);
+
+        assert_eq!(
+            client.match_record(&nft_id),
+            MatchRecord {
+                wins: 2,
+                losses: 0,
+                draws: 1
+            }
+        );
+    }
+
     #[test]
     fn test_metadata_hash_association() {
         let env = Env::default();
@@ -282,7 +1068,7 @@ mod tests {
 
         let contract_id = env.register_contract(None, AINFTContract);
         let client = AINFTContractClient::new(&env, &contract_id);
-        client.initialize(&admin);
+        client.initialize(&admin, &Address::generate(&env));
 
         // Create specific metadata hash
         let metadata_hash: BytesN<32> = BytesN::from_array(&env, &[42u8; 32]);
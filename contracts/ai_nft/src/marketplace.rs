@@ -0,0 +1,329 @@
+// ────────────────────────────────────────────────────────────────────────────
+// Marketplace
+// ────────────────────────────────────────────────────────────────────────────
+//
+// Fixed-price and auction listings for AI agent NFTs, settled in a single
+// configured token. A listed NFT is held by this contract until it sells or
+// the listing is cancelled, so it cannot be transferred or burned meanwhile.
+// Auction bids are escrowed here as well and the previous bid is refunded as
+// soon as it is beaten.
+//
+// Every sale pays, out of the price:
+//   royalty      = price * royalty_bips / 10_000  -> minter (`minter_of`)
+//   platform fee = price * platform_fee_bips / 10_000 -> game_contract treasury
+//   remainder                                     -> seller
+
+use soroban_sdk::{
+    contractclient, contractimpl, contracttype, symbol_short, token, Address, Env, Map, Symbol, Vec,
+};
+
+use crate::{AINFTContract, AINFTContractClient, ContractError};
+
+const MARKET: Symbol = symbol_short!("MARKET"); // MarketConfig
+const LISTINGS: Symbol = symbol_short!("LISTINGS"); // Map<u64, Listing>
+
+const BIPS_DENOMINATOR: i128 = 10_000;
+const MAX_FEE_BIPS: u32 = 1_000; // 10 % cap on each of royalty and platform fee
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketConfig {
+    pub payment_token: Address,
+    pub game_contract: Address, // treasury address is read from here at sale time
+    pub platform_fee_bips: u32,
+    pub royalty_bips: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ListingKind {
+    FixedPrice,
+    Auction(u64), // last ledger sequence that accepts bids
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Listing {
+    pub nft_id: u64,
+    pub seller: Address,
+    pub token: Address, // payment token at listing time
+    pub price: i128,    // fixed price, or reserve for auctions
+    pub kind: ListingKind,
+    pub highest_bid: i128,
+    pub highest_bidder: Option<Address>,
+}
+
+#[contractclient(name = "TreasuryClient")]
+pub trait Treasury {
+    fn get_treasury_address(env: Env) -> Option<Address>;
+}
+
+#[contractimpl]
+impl AINFTContract {
+    /// Set the payment token, fee split and game contract used for the
+    /// treasury. Admin only; fees are in basis points and capped at 10 %.
+    pub fn configure_marketplace(env: Env, config: MarketConfig) -> Result<(), ContractError> {
+        Self::admin(env.clone()).require_auth();
+        if config.platform_fee_bips > MAX_FEE_BIPS || config.royalty_bips > MAX_FEE_BIPS {
+            return Err(ContractError::InvalidFee);
+        }
+        env.storage().instance().set(&MARKET, &config);
+        Ok(())
+    }
+
+    /// Get the marketplace configuration, if set
+    pub fn get_marketplace_config(env: Env) -> Option<MarketConfig> {
+        env.storage().instance().get(&MARKET)
+    }
+
+    /// List an NFT for sale at a fixed price
+    pub fn list_fixed_price(
+        env: Env,
+        seller: Address,
+        nft_id: u64,
+        price: i128,
+    ) -> Result<(), ContractError> {
+        Self::open_listing(&env, seller, nft_id, price, ListingKind::FixedPrice)
+    }
+
+    /// List an NFT for auction. Bids are accepted up to and including
+    /// `end_ledger`; bids below `reserve_price` are rejected.
+    pub fn list_auction(
+        env: Env,
+        seller: Address,
+        nft_id: u64,
+        reserve_price: i128,
+        end_ledger: u64,
+    ) -> Result<(), ContractError> {
+        if end_ledger <= env.ledger().sequence() as u64 {
+            return Err(ContractError::AuctionEnded);
+        }
+        Self::open_listing(
+            &env,
+            seller,
+            nft_id,
+            reserve_price,
+            ListingKind::Auction(end_ledger),
+        )
+    }
+
+    /// Withdraw a listing and return the NFT. Auctions can only be cancelled
+    /// before the first bid.
+    pub fn cancel_listing(env: Env, seller: Address, nft_id: u64) -> Result<(), ContractError> {
+        let listing = Self::load_listing(&env, nft_id)?;
+        if listing.seller != seller {
+            return Err(ContractError::NotAuthorized);
+        }
+        seller.require_auth();
+        if listing.highest_bidder.is_some() {
+            return Err(ContractError::HasBids);
+        }
+
+        Self::close_listing(&env, nft_id);
+        Self::move_token(&env, &env.current_contract_address(), &seller, nft_id);
+
+        env.events()
+            .publish((symbol_short!("delist"), nft_id), seller);
+        Ok(())
+    }
+
+    /// Buy a fixed-price listing
+    pub fn buy(env: Env, buyer: Address, nft_id: u64) -> Result<(), ContractError> {
+        buyer.require_auth();
+
+        let listing = Self::load_listing(&env, nft_id)?;
+        if listing.kind != ListingKind::FixedPrice {
+            return Err(ContractError::WrongListingKind);
+        }
+
+        Self::close_listing(&env, nft_id);
+        Self::settle_sale(&env, &listing, &buyer, &buyer, listing.price)
+    }
+
+    /// Bid on an auction. The amount is escrowed and the previous highest
+    /// bidder refunded.
+    pub fn bid(env: Env, bidder: Address, nft_id: u64, amount: i128) -> Result<(), ContractError> {
+        bidder.require_auth();
+
+        let mut listing = Self::load_listing(&env, nft_id)?;
+        let end_ledger = match listing.kind {
+            ListingKind::Auction(end_ledger) => end_ledger,
+            ListingKind::FixedPrice => return Err(ContractError::WrongListingKind),
+        };
+        if env.ledger().sequence() as u64 > end_ledger {
+            return Err(ContractError::AuctionEnded);
+        }
+        if amount < listing.price || amount <= listing.highest_bid {
+            return Err(ContractError::BidTooLow);
+        }
+
+        let token = token::Client::new(&env, &listing.token);
+        let contract = env.current_contract_address();
+        token.transfer(&bidder, &contract, &amount);
+        if let Some(previous) = &listing.highest_bidder {
+            token.transfer(&contract, previous, &listing.highest_bid);
+        }
+
+        listing.highest_bid = amount;
+        listing.highest_bidder = Some(bidder.clone());
+        Self::store_listing(&env, &listing);
+
+        env.events()
+            .publish((symbol_short!("bid"), nft_id, bidder), amount);
+        Ok(())
+    }
+
+    /// Close an auction after its end ledger. Anyone may call this. The NFT
+    /// goes to the highest bidder, or back to the seller if nobody bid.
+    pub fn settle_auction(env: Env, nft_id: u64) -> Result<(), ContractError> {
+        let listing = Self::load_listing(&env, nft_id)?;
+        let end_ledger = match listing.kind {
+            ListingKind::Auction(end_ledger) => end_ledger,
+            ListingKind::FixedPrice => return Err(ContractError::WrongListingKind),
+        };
+        if env.ledger().sequence() as u64 <= end_ledger {
+            return Err(ContractError::AuctionNotEnded);
+        }
+
+        Self::close_listing(&env, nft_id);
+        let contract = env.current_contract_address();
+        match &listing.highest_bidder {
+            Some(winner) => {
+                Self::settle_sale(&env, &listing, &contract, winner, listing.highest_bid)
+            }
+            None => {
+                Self::move_token(&env, &contract, &listing.seller, nft_id);
+                env.events()
+                    .publish((symbol_short!("delist"), nft_id), listing.seller);
+                Ok(())
+            }
+        }
+    }
+
+    /// Get an open listing
+    pub fn get_listing(env: Env, nft_id: u64) -> Option<Listing> {
+        let listings: Map<u64, Listing> = env.storage().instance().get(&LISTINGS)?;
+        listings.get(nft_id)
+    }
+
+    /// Get all open listings
+    pub fn get_listings(env: Env) -> Vec<Listing> {
+        let listings: Map<u64, Listing> = env
+            .storage()
+            .instance()
+            .get(&LISTINGS)
+            .unwrap_or(Map::new(&env));
+        listings.values()
+    }
+}
+
+impl AINFTContract {
+    fn market_config(env: &Env) -> Result<MarketConfig, ContractError> {
+        env.storage()
+            .instance()
+            .get(&MARKET)
+            .ok_or(ContractError::MarketNotConfigured)
+    }
+
+    fn open_listing(
+        env: &Env,
+        seller: Address,
+        nft_id: u64,
+        price: i128,
+        kind: ListingKind,
+    ) -> Result<(), ContractError> {
+        let config = Self::market_config(env)?;
+        if price <= 0 {
+            return Err(ContractError::InvalidPrice);
+        }
+
+        let owner = Self::owner_of(env.clone(), nft_id)?;
+        if owner != seller {
+            return Err(ContractError::InvalidOwner);
+        }
+        seller.require_auth();
+        Self::ensure_not_rented(env, nft_id)?;
+
+        // Escrow the NFT; a listed token is owned by this contract, so it can
+        // never be listed twice.
+        Self::move_token(env, &seller, &env.current_contract_address(), nft_id);
+
+        Self::store_listing(
+            env,
+            &Listing {
+                nft_id,
+                seller: seller.clone(),
+                token: config.payment_token,
+                price,
+                kind,
+                highest_bid: 0,
+                highest_bidder: None,
+            },
+        );
+
+        env.events()
+            .publish((symbol_short!("listed"), nft_id, seller), price);
+        Ok(())
+    }
+
+    fn load_listing(env: &Env, nft_id: u64) -> Result<Listing, ContractError> {
+        Self::get_listing(env.clone(), nft_id).ok_or(ContractError::NotListed)
+    }
+
+    fn store_listing(env: &Env, listing: &Listing) {
+        let mut listings: Map<u64, Listing> = env
+            .storage()
+            .instance()
+            .get(&LISTINGS)
+            .unwrap_or(Map::new(env));
+        listings.set(listing.nft_id, listing.clone());
+        env.storage().instance().set(&LISTINGS, &listings);
+    }
+
+    fn close_listing(env: &Env, nft_id: u64) {
+        let mut listings: Map<u64, Listing> = env
+            .storage()
+            .instance()
+            .get(&LISTINGS)
+            .unwrap_or(Map::new(env));
+        listings.remove(nft_id);
+        env.storage().instance().set(&LISTINGS, &listings);
+    }
+
+    /// Pay royalty, platform fee and seller out of `price` held by `payer`,
+    /// then hand the NFT to `buyer`.
+    fn settle_sale(
+        env: &Env,
+        listing: &Listing,
+        payer: &Address,
+        buyer: &Address,
+        price: i128,
+    ) -> Result<(), ContractError> {
+        let config = Self::market_config(env)?;
+        let token = token::Client::new(env, &listing.token);
+        let nft_id = listing.nft_id;
+
+        let royalty = price * config.royalty_bips as i128 / BIPS_DENOMINATOR;
+        let platform_fee = price * config.platform_fee_bips as i128 / BIPS_DENOMINATOR;
+
+        if royalty > 0 {
+            let minter = Self::minter_of(env.clone(), nft_id)?;
+            token.transfer(payer, &minter, &royalty);
+            env.events()
+                .publish((symbol_short!("royalty"), nft_id, minter), royalty);
+        }
+        if platform_fee > 0 {
+            let treasury = TreasuryClient::new(env, &config.game_contract)
+                .get_treasury_address()
+                .ok_or(ContractError::TreasuryNotSet)?;
+            token.transfer(payer, &treasury, &platform_fee);
+        }
+        token.transfer(payer, &listing.seller, &(price - royalty - platform_fee));
+
+        Self::move_token(env, &env.current_contract_address(), buyer, nft_id);
+
+        env.events()
+            .publish((symbol_short!("sold"), nft_id, buyer.clone()), price);
+        Ok(())
+    }
+}
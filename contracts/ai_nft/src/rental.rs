@@ -0,0 +1,193 @@
+// ────────────────────────────────────────────────────────────────────────────
+// Rentals
+// ────────────────────────────────────────────────────────────────────────────
+//
+// An owner can offer an agent for rent: a fixed price buys `duration` ledgers
+// of use. While a rental runs, `user_of` returns the renter instead of the
+// owner. game_contract and the backend decide who may play as the agent from
+// `user_of`, so the grant expires without anyone having to act on it.
+//
+// The price is paid in the marketplace token and held here until the rental
+// ends. `end_rental` then releases it to the owner. An NFT with an open offer
+// or rental cannot be transferred, listed or burned.
+
+use soroban_sdk::{contractimpl, contracttype, symbol_short, token, Address, Env, Map, Symbol};
+
+use crate::{AINFTContract, AINFTContractClient, ContractError};
+
+const RENTALS: Symbol = symbol_short!("RENTALS"); // Map<u64, Rental>
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rental {
+    pub nft_id: u64,
+    pub owner: Address,
+    pub token: Address, // payment token at offer time
+    pub price: i128,
+    pub duration: u64, // ledgers of use bought by one rental
+    pub renter: Option<Address>,
+    pub expires_at: u64, // last ledger of use; 0 until rented
+}
+
+#[contractimpl]
+impl AINFTContract {
+    /// Offer an NFT for rent: `price` buys `duration` ledgers of use. Owner
+    /// only; needs the marketplace to be configured for the payment token.
+    pub fn offer_rental(
+        env: Env,
+        owner: Address,
+        nft_id: u64,
+        price: i128,
+        duration: u64,
+    ) -> Result<(), ContractError> {
+        let config =
+            Self::get_marketplace_config(env.clone()).ok_or(ContractError::MarketNotConfigured)?;
+        if price <= 0 {
+            return Err(ContractError::InvalidPrice);
+        }
+        if duration == 0 {
+            return Err(ContractError::InvalidDuration);
+        }
+        if Self::owner_of(env.clone(), nft_id)? != owner {
+            return Err(ContractError::InvalidOwner);
+        }
+        owner.require_auth();
+        Self::ensure_not_rented(&env, nft_id)?;
+
+        Self::store_rental(
+            &env,
+            &Rental {
+                nft_id,
+                owner: owner.clone(),
+                token: config.payment_token,
+                price,
+                duration,
+                renter: None,
+                expires_at: 0,
+            },
+        );
+
+        env.events().publish(
+            (symbol_short!("rent_off"), nft_id, owner),
+            (price, duration),
+        );
+        Ok(())
+    }
+
+    /// Withdraw a rental offer nobody has taken yet
+    pub fn cancel_rental(env: Env, owner: Address, nft_id: u64) -> Result<(), ContractError> {
+        let rental = Self::load_rental(&env, nft_id)?;
+        if rental.owner != owner {
+            return Err(ContractError::NotAuthorized);
+        }
+        owner.require_auth();
+        if rental.renter.is_some() {
+            return Err(ContractError::RentalActive);
+        }
+
+        Self::remove_rental(&env, nft_id);
+        Ok(())
+    }
+
+    /// Take a rental offer. The price is escrowed and `renter` becomes the
+    /// NFT's user through the returned ledger.
+    pub fn rent(env: Env, renter: Address, nft_id: u64) -> Result<u64, ContractError> {
+        renter.require_auth();
+
+        let mut rental = Self::load_rental(&env, nft_id)?;
+        if rental.renter.is_some() {
+            return Err(ContractError::RentalActive);
+        }
+
+        token::Client::new(&env, &rental.token).transfer(
+            &renter,
+            &env.current_contract_address(),
+            &rental.price,
+        );
+
+        rental.expires_at = env.ledger().sequence() as u64 + rental.duration;
+        rental.renter = Some(renter.clone());
+        Self::store_rental(&env, &rental);
+
+        env.events()
+            .publish((symbol_short!("rented"), nft_id, renter), rental.expires_at);
+        Ok(rental.expires_at)
+    }
+
+    /// Close an expired rental and pay the escrowed price to the owner.
+    /// Anyone may call this.
+    pub fn end_rental(env: Env, nft_id: u64) -> Result<(), ContractError> {
+        let rental = Self::load_rental(&env, nft_id)?;
+        if rental.renter.is_none() {
+            return Err(ContractError::NoRental);
+        }
+        if env.ledger().sequence() as u64 <= rental.expires_at {
+            return Err(ContractError::RentalActive);
+        }
+
+        Self::remove_rental(&env, nft_id);
+        token::Client::new(&env, &rental.token).transfer(
+            &env.current_contract_address(),
+            &rental.owner,
+            &rental.price,
+        );
+
+        env.events()
+            .publish((symbol_short!("rent_end"), nft_id), rental.owner);
+        Ok(())
+    }
+
+    /// Get the address allowed to use an NFT: the renter while a rental
+    /// runs, otherwise the owner
+    pub fn user_of(env: Env, nft_id: u64) -> Result<Address, ContractError> {
+        let owner = Self::owner_of(env.clone(), nft_id)?;
+        match Self::get_rental(env.clone(), nft_id) {
+            Some(Rental {
+                renter: Some(renter),
+                expires_at,
+                ..
+            }) if env.ledger().sequence() as u64 <= expires_at => Ok(renter),
+            _ => Ok(owner),
+        }
+    }
+
+    /// Get the rental offer or rental of an NFT, if any
+    pub fn get_rental(env: Env, nft_id: u64) -> Option<Rental> {
+        let rentals: Map<u64, Rental> = env.storage().instance().get(&RENTALS)?;
+        rentals.get(nft_id)
+    }
+}
+
+impl AINFTContract {
+    /// Fail if the NFT has an open offer or an unsettled rental
+    pub(crate) fn ensure_not_rented(env: &Env, nft_id: u64) -> Result<(), ContractError> {
+        match Self::get_rental(env.clone(), nft_id) {
+            Some(_) => Err(ContractError::RentalActive),
+            None => Ok(()),
+        }
+    }
+
+    fn load_rental(env: &Env, nft_id: u64) -> Result<Rental, ContractError> {
+        Self::get_rental(env.clone(), nft_id).ok_or(ContractError::NoRental)
+    }
+
+    fn store_rental(env: &Env, rental: &Rental) {
+        let mut rentals: Map<u64, Rental> = env
+            .storage()
+            .instance()
+            .get(&RENTALS)
+            .unwrap_or(Map::new(env));
+        rentals.set(rental.nft_id, rental.clone());
+        env.storage().instance().set(&RENTALS, &rentals);
+    }
+
+    fn remove_rental(env: &Env, nft_id: u64) {
+        let mut rentals: Map<u64, Rental> = env
+            .storage()
+            .instance()
+            .get(&RENTALS)
+            .unwrap_or(Map::new(env));
+        rentals.remove(nft_id);
+        env.storage().instance().set(&RENTALS, &rentals);
+    }
+}
@@ -1,6 +1,14 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+use soroban_sdk::{contract, contractimpl, contracttype, Address, BytesN, Env, Vec};
+
+/// Storage layout version this code reads and writes; see `migrate`
+pub const SCHEMA_VERSION: u32 = 2;
+
+// Proposals and their vetoes live in persistent storage so closed ones do not
+// grow the instance entry; closed proposals are left to expire.
+const PROPOSAL_TTL_THRESHOLD: u32 = 100_000;
+const PROPOSAL_TTL_EXTEND: u32 = 500_000;
 
 // Storage keys
 #[contracttype]
@@ -10,6 +18,59 @@ pub enum DataKey {
     Balance(Address),
     TotalSupply,
     MatchEscrow(u64),
+    CategoryPaused(PauseCategory),
+    Guardians,
+    GuardianThreshold,
+    Timelock,
+    ProposalCounter,
+    Proposal(u64),
+    ProposalQueue,
+    SchemaVersion,
+    ProposalVetoes(u64),
+}
+
+/// Groups of fund-moving operations that can be paused independently.
+/// Other contracts (game_contract, game_registry) query these through
+/// `is_category_paused`; variant names are part of that interface.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PauseCategory {
+    NewGames,
+    Payouts,
+    PuzzleRewards,
+}
+
+/// Privileged operation that needs guardian quorum once guardians are set
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GuardianAction {
+    Rescue(Address, i128),           // (to, amount)
+    RefundMatch(u64),                // match_id
+    TransferAdmin(Address),          // new admin
+    SetGuardians(Vec<Address>, u32), // (guardians, threshold)
+    UpgradeWasm(BytesN<32>),         // hash of already-uploaded WASM
+    Unpause,
+    UnpauseCategory(PauseCategory),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ProposalStatus {
+    Pending,     // Waiting for quorum
+    Queued(u64), // Quorum reached; executable from this ledger sequence
+    Executed,
+    Cancelled,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct GuardianProposal {
+    pub id: u64,
+    pub action: GuardianAction,
+    pub proposer: Address,
+    pub approvals: Vec<Address>,
+    pub created_at: u64, // Ledger sequence
+    pub status: ProposalStatus,
 }
 
 #[contracttype]
@@ -38,6 +99,10 @@ impl PausableContract {
 
         // Initialize total supply
         env.storage().instance().set(&DataKey::TotalSupply, &0i128);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
     }
 
     // ============================================
@@ -68,24 +133,15 @@ impl PausableContract {
 
     /// Unpause the contract - resumes normal operations
     /// Only callable by admin
+    /// Once guardians are configured this must go through `propose_action`
     pub fn unpause(env: Env, caller: Address) {
         caller.require_auth();
 
         // Check if caller is admin
         Self::check_admin(&env, &caller);
+        Self::check_no_guardians(&env);
 
-        // Check if not paused
-        let is_paused = Self::is_paused(&env);
-        if !is_paused {
-            panic!("Not paused");
-        }
-
-        // Set paused state to false
-        env.storage().instance().set(&DataKey::Paused, &false);
-
-        // Emit event
-        env.events()
-            .publish((soroban_sdk::symbol_short!("unpaused"),), caller);
+        Self::resume(&env, caller);
     }
 
     /// Check if contract is currently paused
@@ -96,6 +152,54 @@ impl PausableContract {
             .unwrap_or(false)
     }
 
+    // ============================================
+    // CATEGORY PAUSES (ADMIN ONLY)
+    // ============================================
+
+    /// Pause a single category of operations in integrated contracts
+    /// Only callable by admin
+    pub fn pause_category(env: Env, caller: Address, category: PauseCategory) {
+        caller.require_auth();
+
+        // Check if caller is admin
+        Self::check_admin(&env, &caller);
+
+        let key = DataKey::CategoryPaused(category);
+        if env.storage().instance().get(&key).unwrap_or(false) {
+            panic!("Already paused");
+        }
+
+        env.storage().instance().set(&key, &true);
+
+        // Emit event
+        env.events()
+            .publish((soroban_sdk::symbol_short!("paused"), category), caller);
+    }
+
+    /// Resume a paused category
+    /// Only callable by admin
+    /// Once guardians are configured this must go through `propose_action`
+    pub fn unpause_category(env: Env, caller: Address, category: PauseCategory) {
+        caller.require_auth();
+
+        // Check if caller is admin
+        Self::check_admin(&env, &caller);
+        Self::check_no_guardians(&env);
+
+        Self::resume_category(&env, category, caller);
+    }
+
+    /// Check if a category is paused, either on its own or by the global pause
+    /// This is what integrated contracts call before moving funds
+    pub fn is_category_paused(env: Env, category: PauseCategory) -> bool {
+        Self::is_paused(&env)
+            || env
+                .storage()
+                .instance()
+                .get(&DataKey::CategoryPaused(category))
+                .unwrap_or(false)
+    }
+
     // ============================================
     // STATE-CHANGING FUNCTIONS (PAUSABLE)
     // ============================================
@@ -276,14 +380,336 @@ impl PausableContract {
 
     /// Emergency rescue function - Admin can rescue funds even when paused
     /// This is crucial for recovering from critical bugs
+    /// Once guardians are configured this must go through `propose_action`
     pub fn emergency_rescue(env: Env, admin: Address, to: Address, amount: i128) {
         admin.require_auth();
 
         // Check if caller is admin
         Self::check_admin(&env, &admin);
+        Self::check_no_guardians(&env);
+
+        // NOTE: No pause check here! This works even when paused.
+        Self::rescue(&env, to, amount);
+    }
+
+    /// Admin refund match escrow - Manually unlock escrow and return funds
+    /// Only callable by admin, works even when paused
+    /// Once guardians are configured this must go through `propose_action`
+    pub fn admin_refund_match(env: Env, admin: Address, match_id: u64) {
+        admin.require_auth();
+
+        // Check if caller is admin
+        Self::check_admin(&env, &admin);
+        Self::check_no_guardians(&env);
 
         // NOTE: No pause check here! This works even when paused.
+        Self::refund_match(&env, match_id);
+    }
+
+    /// Transfer admin role (works even when paused)
+    /// Once guardians are configured this must go through `propose_action`
+    pub fn transfer_admin(env: Env, current_admin: Address, new_admin: Address) {
+        current_admin.require_auth();
+
+        // Check if caller is current admin
+        Self::check_admin(&env, &current_admin);
+        Self::check_no_guardians(&env);
+
+        // NOTE: No pause check - admin transfer should work even when paused
+        Self::set_admin(&env, new_admin);
+    }
+
+    // ============================================
+    // GUARDIAN MULTISIG + TIMELOCK
+    // ============================================
+    //
+    // Modelled on game_contract's fee-proposal multisig. Any single guardian
+    // can pause instantly; unpausing, rescue, escrow refunds, admin transfer
+    // and guardian changes need `threshold` approvals and then wait
+    // `timelock` ledgers before anyone in the set can execute them. The wait
+    // gives the remaining guardians time to veto a proposal pushed through
+    // with stolen keys, which also takes `threshold` guardians. Approvals and
+    // vetoes only count while the guardian who gave them is still in the set.
+
+    /// Install the guardian set. Admin only, and only once; later changes go
+    /// through a `SetGuardians` proposal.
+    pub fn configure_guardians(
+        env: Env,
+        admin: Address,
+        guardians: Vec<Address>,
+        threshold: u32,
+        timelock: u64,
+    ) {
+        admin.require_auth();
+
+        // Check if caller is admin
+        Self::check_admin(&env, &admin);
+        Self::check_no_guardians(&env);
+        Self::check_threshold(&guardians, threshold);
+
+        env.storage()
+            .instance()
+            .set(&DataKey::Guardians, &guardians);
+        env.storage()
+            .instance()
+            .set(&DataKey::GuardianThreshold, &threshold);
+        env.storage().instance().set(&DataKey::Timelock, &timelock);
+    }
+
+    /// Pause immediately on the word of a single guardian
+    pub fn guardian_pause(env: Env, guardian: Address) {
+        guardian.require_auth();
+        Self::check_guardian(&env, &guardian);
 
+        if Self::is_paused(&env) {
+            panic!("Already paused");
+        }
+
+        env.storage().instance().set(&DataKey::Paused, &true);
+
+        // Emit event
+        env.events()
+            .publish((soroban_sdk::symbol_short!("paused"),), guardian);
+    }
+
+    /// Propose a privileged action; the proposer's approval counts
+    pub fn propose_action(env: Env, guardian: Address, action: GuardianAction) -> u64 {
+        guardian.require_auth();
+        Self::check_guardian(&env, &guardian);
+
+        if let GuardianAction::SetGuardians(ref guardians, threshold) = action {
+            Self::check_threshold(guardians, threshold);
+        }
+
+        let id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::ProposalCounter)
+            .unwrap_or(0)
+            + 1;
+        env.storage().instance().set(&DataKey::ProposalCounter, &id);
+
+        let mut proposal = GuardianProposal {
+            id,
+            action,
+            proposer: guardian.clone(),
+            approvals: Vec::from_array(&env, [guardian.clone()]),
+            created_at: env.ledger().sequence() as u64,
+            status: ProposalStatus::Pending,
+        };
+        Self::queue_if_quorum(&env, &mut proposal);
+        Self::store_proposal(&env, &proposal);
+
+        let mut queue = Self::get_proposal_queue(env.clone());
+        queue.push_back(id);
+        env.storage()
+            .instance()
+            .set(&DataKey::ProposalQueue, &queue);
+
+        // Emit event
+        env.events()
+            .publish((soroban_sdk::symbol_short!("g_propose"), id), guardian);
+
+        id
+    }
+
+    /// Approve a pending proposal
+    pub fn approve_action(env: Env, guardian: Address, proposal_id: u64) {
+        guardian.require_auth();
+        Self::check_guardian(&env, &guardian);
+
+        let mut proposal = Self::load_proposal(&env, proposal_id);
+        if proposal.status != ProposalStatus::Pending {
+            panic!("Proposal not pending");
+        }
+        if proposal.approvals.contains(&guardian) {
+            panic!("Already approved");
+        }
+
+        proposal.approvals.push_back(guardian.clone());
+        Self::queue_if_quorum(&env, &mut proposal);
+        Self::store_proposal(&env, &proposal);
+
+        // Emit event
+        env.events().publish(
+            (soroban_sdk::symbol_short!("g_approve"), proposal_id),
+            guardian,
+        );
+    }
+
+    /// Execute a queued proposal once its timelock has elapsed
+    pub fn execute_action(env: Env, guardian: Address, proposal_id: u64) {
+        guardian.require_auth();
+        Self::check_guardian(&env, &guardian);
+
+        let mut proposal = Self::load_proposal(&env, proposal_id);
+        let eta = match proposal.status {
+            ProposalStatus::Queued(eta) => eta,
+            _ => panic!("Proposal not queued"),
+        };
+        if (env.ledger().sequence() as u64) < eta {
+            panic!("Timelock not elapsed");
+        }
+
+        match proposal.action.clone() {
+            GuardianAction::Rescue(to, amount) => Self::rescue(&env, to, amount),
+            GuardianAction::RefundMatch(match_id) => Self::refund_match(&env, match_id),
+            GuardianAction::TransferAdmin(new_admin) => Self::set_admin(&env, new_admin),
+            GuardianAction::SetGuardians(guardians, threshold) => {
+                env.storage()
+                    .instance()
+                    .set(&DataKey::Guardians, &guardians);
+                env.storage()
+                    .instance()
+                    .set(&DataKey::GuardianThreshold, &threshold);
+                Self::recount_open_proposals(&env, proposal_id);
+            }
+            // The new code takes over from the next invocation; run
+            // `migrate` afterwards if it bumps SCHEMA_VERSION.
+            GuardianAction::UpgradeWasm(wasm_hash) => {
+                env.deployer().update_current_contract_wasm(wasm_hash)
+            }
+            GuardianAction::Unpause => Self::resume(&env, guardian.clone()),
+            GuardianAction::UnpauseCategory(category) => {
+                Self::resume_category(&env, category, guardian.clone())
+            }
+        }
+
+        proposal.status = ProposalStatus::Executed;
+        Self::close_proposal(&env, &proposal);
+
+        // Emit event
+        env.events().publish(
+            (soroban_sdk::symbol_short!("g_execute"), proposal_id),
+            guardian,
+        );
+    }
+
+    /// Veto a pending or queued proposal. It is cancelled once `threshold`
+    /// guardians have vetoed it; returns whether that happened.
+    pub fn cancel_action(env: Env, guardian: Address, proposal_id: u64) -> bool {
+        guardian.require_auth();
+        Self::check_guardian(&env, &guardian);
+
+        let mut proposal = Self::load_proposal(&env, proposal_id);
+        if matches!(
+            proposal.status,
+            ProposalStatus::Executed | ProposalStatus::Cancelled
+        ) {
+            panic!("Proposal already closed");
+        }
+
+        let mut vetoes = Self::get_vetoes(env.clone(), proposal_id);
+        if vetoes.contains(&guardian) {
+            panic!("Already vetoed");
+        }
+        vetoes.push_back(guardian.clone());
+
+        // Emit event
+        env.events().publish(
+            (soroban_sdk::symbol_short!("g_veto"), proposal_id),
+            guardian.clone(),
+        );
+
+        let threshold: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::GuardianThreshold)
+            .unwrap_or(u32::MAX);
+        if Self::count_guardians(&env, &vetoes) < threshold {
+            let key = DataKey::ProposalVetoes(proposal_id);
+            env.storage().persistent().set(&key, &vetoes);
+            env.storage().persistent().extend_ttl(
+                &key,
+                PROPOSAL_TTL_THRESHOLD,
+                PROPOSAL_TTL_EXTEND,
+            );
+            return false;
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::ProposalVetoes(proposal_id));
+        proposal.status = ProposalStatus::Cancelled;
+        Self::close_proposal(&env, &proposal);
+
+        // Emit event
+        env.events().publish(
+            (soroban_sdk::symbol_short!("g_cancel"), proposal_id),
+            guardian,
+        );
+        true
+    }
+
+    // ============================================
+    // SCHEMA MIGRATION
+    // ============================================
+    //
+    // WASM upgrades only go through an `UpgradeWasm` guardian proposal, so
+    // guardians must be configured before this contract can be upgraded.
+
+    /// Storage layout version currently persisted. Deployments that predate
+    /// versioning use the v1 layout.
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::SchemaVersion)
+            .unwrap_or(1)
+    }
+
+    /// Bring persisted state up to this code's `SCHEMA_VERSION` after an
+    /// upgrade. Idempotent, so anyone may call it.
+    pub fn migrate(env: Env) -> u32 {
+        let from = Self::schema_version(env.clone());
+        if from > SCHEMA_VERSION {
+            panic!("Schema is newer than contract code");
+        }
+
+        // v1 kept proposals and vetoes in instance storage.
+        if from < 2 {
+            let count: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::ProposalCounter)
+                .unwrap_or(0);
+            for id in 1..=count {
+                let proposal: Option<GuardianProposal> =
+                    env.storage().instance().get(&DataKey::Proposal(id));
+                if let Some(proposal) = proposal {
+                    env.storage().instance().remove(&DataKey::Proposal(id));
+                    Self::store_proposal(&env, &proposal);
+                }
+                let key = DataKey::ProposalVetoes(id);
+                let vetoes: Option<Vec<Address>> = env.storage().instance().get(&key);
+                if let Some(vetoes) = vetoes {
+                    env.storage().instance().remove(&key);
+                    env.storage().persistent().set(&key, &vetoes);
+                    env.storage().persistent().extend_ttl(
+                        &key,
+                        PROPOSAL_TTL_THRESHOLD,
+                        PROPOSAL_TTL_EXTEND,
+                    );
+                }
+            }
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        if from != SCHEMA_VERSION {
+            env.events().publish(
+                (soroban_sdk::symbol_short!("migrated"), from),
+                SCHEMA_VERSION,
+            );
+        }
+        SCHEMA_VERSION
+    }
+
+    // ============================================
+    // ADMIN RESCUE IMPLEMENTATIONS (INTERNAL)
+    // ============================================
+
+    fn rescue(env: &Env, to: Address, amount: i128) {
         if amount <= 0 {
             panic!("Amount must be positive");
         }
@@ -301,16 +727,7 @@ impl PausableContract {
             .publish((soroban_sdk::symbol_short!("rescue"), to.clone()), amount);
     }
 
-    /// Admin refund match escrow - Manually unlock escrow and return funds
-    /// Only callable by admin, works even when paused
-    pub fn admin_refund_match(env: Env, admin: Address, match_id: u64) {
-        admin.require_auth();
-
-        // Check if caller is admin
-        Self::check_admin(&env, &admin);
-
-        // NOTE: No pause check here! This works even when paused.
-
+    fn refund_match(env: &Env, match_id: u64) {
         // Get match escrow data
         let escrow_key = DataKey::MatchEscrow(match_id);
         let escrow_data: MatchEscrowData = env
@@ -352,15 +769,34 @@ impl PausableContract {
         );
     }
 
-    /// Transfer admin role (works even when paused)
-    pub fn transfer_admin(env: Env, current_admin: Address, new_admin: Address) {
-        current_admin.require_auth();
+    fn resume(env: &Env, caller: Address) {
+        // Check if not paused
+        if !Self::is_paused(env) {
+            panic!("Not paused");
+        }
 
-        // Check if caller is current admin
-        Self::check_admin(&env, &current_admin);
+        // Set paused state to false
+        env.storage().instance().set(&DataKey::Paused, &false);
 
-        // NOTE: No pause check - admin transfer should work even when paused
+        // Emit event
+        env.events()
+            .publish((soroban_sdk::symbol_short!("unpaused"),), caller);
+    }
 
+    fn resume_category(env: &Env, category: PauseCategory, caller: Address) {
+        let key = DataKey::CategoryPaused(category);
+        if !env.storage().instance().get(&key).unwrap_or(false) {
+            panic!("Not paused");
+        }
+
+        env.storage().instance().remove(&key);
+
+        // Emit event
+        env.events()
+            .publish((soroban_sdk::symbol_short!("unpaused"), category), caller);
+    }
+
+    fn set_admin(env: &Env, new_admin: Address) {
         // Set new admin
         env.storage().instance().set(&DataKey::Admin, &new_admin);
 
@@ -406,6 +842,54 @@ impl PausableContract {
         Self::is_paused(&env)
     }
 
+    /// Get the guardian set (empty until configured)
+    pub fn get_guardians(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Guardians)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the guardian approval threshold (0 until configured)
+    pub fn get_guardian_threshold(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::GuardianThreshold)
+            .unwrap_or(0)
+    }
+
+    /// Get the timelock delay in ledger sequences
+    pub fn get_timelock(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::Timelock)
+            .unwrap_or(0)
+    }
+
+    /// Get a guardian proposal by id. Closed proposals stay readable until
+    /// their storage entry expires.
+    pub fn get_proposal(env: Env, proposal_id: u64) -> Option<GuardianProposal> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+    }
+
+    /// Get the guardians that have vetoed an open proposal so far
+    pub fn get_vetoes(env: Env, proposal_id: u64) -> Vec<Address> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ProposalVetoes(proposal_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the ids of all open (pending or queued) proposals, oldest first
+    pub fn get_proposal_queue(env: Env) -> Vec<u64> {
+        env.storage()
+            .instance()
+            .get(&DataKey::ProposalQueue)
+            .unwrap_or(Vec::new(&env))
+    }
+
     // ============================================
     // HELPER FUNCTIONS (INTERNAL)
     // ============================================
@@ -418,6 +902,117 @@ impl PausableContract {
         }
     }
 
+    /// Panic if guardians are configured, i.e. the single-admin path is closed
+    fn check_no_guardians(env: &Env) {
+        if env.storage().instance().has(&DataKey::Guardians) {
+            panic!("Guardian approval required");
+        }
+    }
+
+    /// Check if caller is a guardian, panic if not
+    fn check_guardian(env: &Env, caller: &Address) {
+        let guardians: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Guardians)
+            .expect("Guardians not configured");
+
+        if !guardians.contains(caller) {
+            panic!("Not guardian");
+        }
+    }
+
+    fn check_threshold(guardians: &Vec<Address>, threshold: u32) {
+        if threshold == 0 || threshold > guardians.len() {
+            panic!("Invalid threshold");
+        }
+    }
+
+    fn load_proposal(env: &Env, proposal_id: u64) -> GuardianProposal {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Proposal(proposal_id))
+            .expect("Proposal not found")
+    }
+
+    fn store_proposal(env: &Env, proposal: &GuardianProposal) {
+        let key = DataKey::Proposal(proposal.id);
+        env.storage().persistent().set(&key, proposal);
+        env.storage()
+            .persistent()
+            .extend_ttl(&key, PROPOSAL_TTL_THRESHOLD, PROPOSAL_TTL_EXTEND);
+    }
+
+    /// How many of `addresses` are guardians now. Approvals and vetoes from
+    /// guardians removed by a `SetGuardians` proposal no longer count.
+    fn count_guardians(env: &Env, addresses: &Vec<Address>) -> u32 {
+        let guardians: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Guardians)
+            .unwrap_or(Vec::new(env));
+        addresses
+            .iter()
+            .filter(|address| guardians.contains(address))
+            .count() as u32
+    }
+
+    /// Start the timelock once approvals reach the threshold
+    fn queue_if_quorum(env: &Env, proposal: &mut GuardianProposal) {
+        let threshold: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::GuardianThreshold)
+            .unwrap_or(u32::MAX);
+        if Self::count_guardians(env, &proposal.approvals) >= threshold {
+            let timelock: u64 = env
+                .storage()
+                .instance()
+                .get(&DataKey::Timelock)
+                .unwrap_or(0);
+            let eta = env.ledger().sequence() as u64 + timelock;
+            proposal.status = ProposalStatus::Queued(eta);
+        }
+    }
+
+    /// After the guardian set changes, send queued proposals that lost their
+    /// quorum back to pending and queue pending ones that now have it.
+    /// `executing` is the `SetGuardians` proposal itself, closed by the caller.
+    fn recount_open_proposals(env: &Env, executing: u64) {
+        let threshold: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::GuardianThreshold)
+            .unwrap_or(u32::MAX);
+        for id in Self::get_proposal_queue(env.clone()).iter() {
+            if id == executing {
+                continue;
+            }
+            let mut proposal = Self::load_proposal(env, id);
+            if proposal.status == ProposalStatus::Pending {
+                Self::queue_if_quorum(env, &mut proposal);
+            } else if Self::count_guardians(env, &proposal.approvals) < threshold {
+                proposal.status = ProposalStatus::Pending;
+            } else {
+                continue;
+            }
+            Self::store_proposal(env, &proposal);
+        }
+    }
+
+    /// Persist a finished proposal and drop it from the open queue
+    fn close_proposal(env: &Env, proposal: &GuardianProposal) {
+        Self::store_proposal(env, proposal);
+
+        let mut queue = Self::get_proposal_queue(env.clone());
+        if let Some(pos) = queue.first_index_of(proposal.id) {
+            queue.remove(pos);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::ProposalQueue, &queue);
+    }
+
     /// Check if caller is admin, panic if not
     fn check_admin(env: &Env, caller: &Address) {
         let admin: Address = env
@@ -435,7 +1030,10 @@ impl PausableContract {
 #[cfg(test)]
 mod test {
     use super::*;
-    use soroban_sdk::{testutils::Address as _, Address, Env};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger as _},
+        Address, Env, Vec,
+    };
 
     #[test]
     fn test_initialize() {
@@ -450,6 +1048,48 @@ mod test {
         assert_eq!(client.get_admin(), admin);
     }
 
+    #[test]
+    fn test_category_pause_is_scoped_and_follows_global_pause() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, PausableContract);
+        let client = PausableContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        client.initialize(&admin);
+
+        client.pause_category(&admin, &PauseCategory::Payouts);
+        assert!(client.is_category_paused(&PauseCategory::Payouts));
+        assert!(!client.is_category_paused(&PauseCategory::NewGames));
+        assert!(!client.paused());
+
+        // A global pause covers every category
+        client.pause(&admin);
+        assert!(client.is_category_paused(&PauseCategory::NewGames));
+        assert!(client.is_category_paused(&PauseCategory::PuzzleRewards));
+        client.unpause(&admin);
+
+        client.unpause_category(&admin, &PauseCategory::Payouts);
+        assert!(!client.is_category_paused(&PauseCategory::Payouts));
+    }
+
+    #[test]
+    #[should_panic(expected = "Not admin")]
+    fn test_pause_category_non_admin() {
+        let env = Env::default();
+        env.mock_all_auths();
+
+        let contract_id = env.register_contract(None, PausableContract);
+        let client = PausableContractClient::new(&env, &contract_id);
+
+        let admin = Address::generate(&env);
+        let attacker = Address::generate(&env);
+        client.initialize(&admin);
+
+        client.pause_category(&attacker, &PauseCategory::NewGames);
+    }
+
     #[test]
     fn test_pause_unpause() {
         let env = Env::default();
@@ -714,4 +1354,307 @@ mod test {
         // Trying to refund non-existent match should fail
         client.admin_refund_match(&admin, &999);
     }
+
+    fn setup_guardians(
+        env: &Env,
+    ) -> (
+        PausableContractClient<'_>,
+        Address,
+        Address,
+        Address,
+        Address,
+    ) {
+        let contract_id = env.register_contract(None, PausableContract);
+        let client = PausableContractClient::new(env, &contract_id);
+
+        let admin = Address::generate(env);
+        let g1 = Address::generate(env);
+        let g2 = Address::generate(env);
+        let g3 = Address::generate(env);
+
+        client.initialize(&admin);
+        let guardians = Vec::from_array(env, [g1.clone(), g2.clone(), g3.clone()]);
+        client.configure_guardians(&admin, &guardians, &2, &100);
+
+        (client, admin, g1, g2, g3)
+    }
+
+    #[test]
+    fn test_guardian_rescue_needs_quorum_and_timelock() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin, g1, g2, _g3) = setup_guardians(&env);
+        let user = Address::generate(&env);
+
+        let id = client.propose_action(&g1, &GuardianAction::Rescue(user.clone(), 500));
+        assert_eq!(
+            client.get_proposal(&id).unwrap().status,
+            ProposalStatus::Pending
+        );
+
+        client.approve_action(&g2, &id);
+        let eta = env.ledger().sequence() as u64 + 100;
+        assert_eq!(
+            client.get_proposal(&id).unwrap().status,
+            ProposalStatus::Queued(eta)
+        );
+        assert_eq!(client.get_proposal_queue(), Vec::from_array(&env, [id]));
+
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+        client.execute_action(&g1, &id);
+
+        assert_eq!(client.balance_of(&user), 500);
+        assert_eq!(
+            client.get_proposal(&id).unwrap().status,
+            ProposalStatus::Executed
+        );
+        assert!(client.get_proposal_queue().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Timelock not elapsed")]
+    fn test_guardian_execute_before_timelock() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin, g1, g2, _g3) = setup_guardians(&env);
+        let new_admin = Address::generate(&env);
+
+        let id = client.propose_action(&g1, &GuardianAction::TransferAdmin(new_admin));
+        client.approve_action(&g2, &id);
+        client.execute_action(&g1, &id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Proposal not queued")]
+    fn test_guardian_cancelled_proposal_cannot_execute() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin, g1, g2, g3) = setup_guardians(&env);
+        let new_admin = Address::generate(&env);
+
+        let id = client.propose_action(&g1, &GuardianAction::TransferAdmin(new_admin));
+        client.approve_action(&g2, &id);
+        assert!(!client.cancel_action(&g3, &id));
+        assert!(client.cancel_action(&g2, &id));
+        assert!(client.get_proposal_queue().is_empty());
+        assert!(client.get_vetoes(&id).is_empty());
+
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+        client.execute_action(&g1, &id);
+    }
+
+    #[test]
+    fn test_single_guardian_veto_does_not_cancel() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin, g1, g2, g3) = setup_guardians(&env);
+        let new_admin = Address::generate(&env);
+
+        let id = client.propose_action(&g1, &GuardianAction::TransferAdmin(new_admin.clone()));
+        client.approve_action(&g2, &id);
+        assert!(!client.cancel_action(&g3, &id));
+        assert_eq!(client.get_vetoes(&id), Vec::from_array(&env, [g3]));
+
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+        client.execute_action(&g1, &id);
+        assert_eq!(client.get_admin(), new_admin);
+    }
+
+    #[test]
+    #[should_panic(expected = "Already vetoed")]
+    fn test_guardian_cannot_veto_twice() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin, g1, _g2, g3) = setup_guardians(&env);
+
+        let id = client.propose_action(&g1, &GuardianAction::Unpause);
+        client.cancel_action(&g3, &id);
+        client.cancel_action(&g3, &id);
+    }
+
+    #[test]
+    #[should_panic(expected = "Guardian approval required")]
+    fn test_single_admin_rescue_disabled_with_guardians() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, admin, _g1, _g2, _g3) = setup_guardians(&env);
+
+        client.emergency_rescue(&admin, &admin, &1000);
+    }
+
+    #[test]
+    fn test_migrate_records_schema_version() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PausableContract);
+        let client = PausableContractClient::new(&env, &contract_id);
+        assert_eq!(client.schema_version(), 1);
+
+        client.initialize(&Address::generate(&env));
+        assert_eq!(client.migrate(), SCHEMA_VERSION);
+        assert_eq!(client.schema_version(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    #[should_panic(expected = "Schema is newer than contract code")]
+    fn test_migrate_rejects_newer_schema() {
+        let env = Env::default();
+        let contract_id = env.register_contract(None, PausableContract);
+        let client = PausableContractClient::new(&env, &contract_id);
+        client.initialize(&Address::generate(&env));
+
+        env.as_contract(&contract_id, || {
+            env.storage()
+                .instance()
+                .set(&DataKey::SchemaVersion, &(SCHEMA_VERSION + 1));
+        });
+        client.migrate();
+    }
+
+    #[test]
+    fn test_upgrade_wasm_needs_guardian_quorum() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin, g1, g2, _g3) = setup_guardians(&env);
+        let wasm_hash = BytesN::from_array(&env, &[7; 32]);
+
+        let id = client.propose_action(&g1, &GuardianAction::UpgradeWasm(wasm_hash.clone()));
+        assert_eq!(
+            client.get_proposal(&id).unwrap().status,
+            ProposalStatus::Pending
+        );
+        client.approve_action(&g2, &id);
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+
+        // The hash was never uploaded, so the host rejects the swap.
+        assert!(client.try_execute_action(&g1, &id).is_err());
+        assert_eq!(
+            client.get_proposal(&id).unwrap().action,
+            GuardianAction::UpgradeWasm(wasm_hash)
+        );
+    }
+
+    #[test]
+    fn test_removed_guardians_stop_counting() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin, g1, g2, g3) = setup_guardians(&env);
+        let g4 = Address::generate(&env);
+        let g5 = Address::generate(&env);
+        let user = Address::generate(&env);
+
+        let rescue = client.propose_action(&g1, &GuardianAction::Rescue(user.clone(), 500));
+        client.approve_action(&g2, &rescue);
+        let unpause = client.propose_action(&g3, &GuardianAction::Unpause);
+        client.cancel_action(&g1, &unpause);
+
+        let guardians = Vec::from_array(&env, [g3.clone(), g4.clone(), g5.clone()]);
+        let id = client.propose_action(&g3, &GuardianAction::SetGuardians(guardians, 2));
+        client.approve_action(&g1, &id);
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+        client.execute_action(&g3, &id);
+
+        // Both approvals came from removed guardians, so the rescue is back
+        // to pending and needs two of the new set.
+        assert_eq!(
+            client.get_proposal(&rescue).unwrap().status,
+            ProposalStatus::Pending
+        );
+        assert!(client.try_execute_action(&g3, &rescue).is_err());
+        client.approve_action(&g4, &rescue);
+        client.approve_action(&g5, &rescue);
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+        client.execute_action(&g4, &rescue);
+        assert_eq!(client.balance_of(&user), 500);
+
+        // g1's veto no longer counts towards cancelling.
+        assert!(!client.cancel_action(&g4, &unpause));
+        assert!(client.cancel_action(&g5, &unpause));
+    }
+
+    #[test]
+    fn test_migrate_moves_proposals_out_of_instance_storage() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, _admin, g1, g2, _g3) = setup_guardians(&env);
+        let id = client.propose_action(&g1, &GuardianAction::Unpause);
+        client.cancel_action(&g2, &id);
+
+        // Rewrite the proposal as a v1 deployment stored it.
+        env.as_contract(&client.address, || {
+            let proposal: GuardianProposal = env
+                .storage()
+                .persistent()
+                .get(&DataKey::Proposal(id))
+                .unwrap();
+            let vetoes: Vec<Address> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::ProposalVetoes(id))
+                .unwrap();
+            env.storage().persistent().remove(&DataKey::Proposal(id));
+            env.storage().persistent().remove(&DataKey::ProposalVetoes(id));
+            env.storage().instance().set(&DataKey::Proposal(id), &proposal);
+            env.storage().instance().set(&DataKey::ProposalVetoes(id), &vetoes);
+            env.storage().instance().set(&DataKey::SchemaVersion, &1u32);
+        });
+        assert!(client.get_proposal(&id).is_none());
+
+        assert_eq!(client.migrate(), SCHEMA_VERSION);
+        assert_eq!(client.get_proposal(&id).unwrap().approvals.len(), 1);
+        assert_eq!(client.get_vetoes(&id), Vec::from_array(&env, [g2]));
+        env.as_contract(&client.address, || {
+            assert!(!env.storage().instance().has(&DataKey::Proposal(id)));
+        });
+    }
+
+    #[test]
+    fn test_single_guardian_can_pause() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, admin, g1, _g2, _g3) = setup_guardians(&env);
+
+        client.guardian_pause(&g1);
+        assert!(client.paused());
+
+        assert!(client.try_unpause(&admin).is_err());
+        assert!(client.paused());
+    }
+
+    #[test]
+    fn test_guardian_unpause_needs_quorum_and_timelock() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, admin, g1, g2, _g3) = setup_guardians(&env);
+
+        client.guardian_pause(&g1);
+        client.pause_category(&admin, &PauseCategory::Payouts);
+
+        let id = client.propose_action(&g1, &GuardianAction::Unpause);
+        client.approve_action(&g2, &id);
+        assert!(client.try_execute_action(&g1, &id).is_err());
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+        client.execute_action(&g1, &id);
+        assert!(!client.paused());
+        assert!(client.is_category_paused(&PauseCategory::Payouts));
+
+        let id = client.propose_action(
+            &g2,
+            &GuardianAction::UnpauseCategory(PauseCategory::Payouts),
+        );
+        client.approve_action(&g1, &id);
+        env.ledger().with_mut(|li| li.sequence_number += 100);
+        client.execute_action(&g2, &id);
+        assert!(!client.is_category_paused(&PauseCategory::Payouts));
+    }
+
+    #[test]
+    #[should_panic(expected = "Guardian approval required")]
+    fn test_single_admin_unpause_category_disabled_with_guardians() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let (client, admin, _g1, _g2, _g3) = setup_guardians(&env);
+
+        client.pause_category(&admin, &PauseCategory::Payouts);
+        client.unpause_category(&admin, &PauseCategory::Payouts);
+    }
 }
@@ -0,0 +1,260 @@
+// ────────────────────────────────────────────────────────────────────────────
+// Contract events
+// ────────────────────────────────────────────────────────────────────────────
+//
+// Every state transition publishes one event so the backend can index
+// contract activity instead of polling the game queries.
+//
+//   topics = (category: Symbol, action: Symbol)
+//   data   = one of the payload structs below (an ScMap keyed by field name)
+//
+// Each payload carries `version`; fields are only ever appended, and a
+// breaking change bumps `EVENT_VERSION`. The backend decoder lives in
+// `st_core::contract_events` and must be kept in step with this file.
+
+use soroban_sdk::{Address, Env, Symbol, Vec, contracttype, symbol_short};
+
+use crate::{
+    Dispute, DisputeStatus, FeeProposal, Game, GameState, GovernanceAction, GovernanceProposal,
+    PoolStatus,
+};
+
+pub const EVENT_VERSION: u32 = 1;
+
+// Categories
+pub const GAME: Symbol = symbol_short!("game");
+pub const PAYOUT: Symbol = symbol_short!("payout");
+pub const DISPUTE: Symbol = symbol_short!("dispute");
+pub const MULTISIG: Symbol = symbol_short!("multisig");
+pub const PREDICT: Symbol = symbol_short!("predict");
+pub const GOVERN: Symbol = symbol_short!("gov");
+
+// Game actions
+pub const CREATED: Symbol = symbol_short!("created");
+pub const JOINED: Symbol = symbol_short!("joined");
+pub const MOVED: Symbol = symbol_short!("move");
+pub const DRAWN: Symbol = symbol_short!("drawn");
+pub const SETTLED: Symbol = symbol_short!("settled");
+pub const CANCELLED: Symbol = symbol_short!("cancelled");
+pub const FORFEITED: Symbol = symbol_short!("forfeit");
+pub const TIMEOUT: Symbol = symbol_short!("timeout");
+pub const ABANDONED: Symbol = symbol_short!("abandoned");
+
+// Payout actions
+pub const WINNER: Symbol = symbol_short!("winner");
+pub const REFUND: Symbol = symbol_short!("refund");
+
+// Dispute actions
+pub const FILED: Symbol = symbol_short!("filed");
+pub const SOLVED: Symbol = symbol_short!("solved");
+pub const REJECTED: Symbol = symbol_short!("reject");
+
+// Fee- and governance-proposal actions
+pub const PROPOSED: Symbol = symbol_short!("proposed");
+pub const APPROVED: Symbol = symbol_short!("approved");
+pub const EXECUTED: Symbol = symbol_short!("executed");
+pub const CANCEL: Symbol = symbol_short!("cancel");
+
+// Prediction-pool actions
+pub const BET: Symbol = symbol_short!("bet");
+pub const RESOLVED: Symbol = symbol_short!("resolved");
+pub const CLAIMED: Symbol = symbol_short!("claimed");
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct GameEvent {
+    pub version: u32,
+    pub game_id: u64,
+    pub state: GameState,
+    pub player1: Address,
+    pub player2: Option<Address>,
+    pub token: Address,
+    pub wager_amount: i128,
+    pub winner: Option<Address>,
+    pub ledger: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct MoveEvent {
+    pub version: u32,
+    pub game_id: u64,
+    pub player: Address,
+    pub move_number: u32,
+    pub move_data: Vec<u32>,
+    pub ledger: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PayoutEvent {
+    pub version: u32,
+    pub game_id: u64,
+    pub token: Address,
+    pub recipient: Address,
+    pub amount: i128,
+    pub fee: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DisputeEvent {
+    pub version: u32,
+    pub dispute_id: u64,
+    pub game_id: u64,
+    pub filer: Address,
+    pub against: Address,
+    pub status: DisputeStatus,
+    pub winner: Option<Address>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct FeeProposalEvent {
+    pub version: u32,
+    pub signer: Address,
+    pub new_fee_bips: u32,
+    pub new_treasury_address: Address,
+    pub approvals: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct GovernanceEvent {
+    pub version: u32,
+    pub proposal_id: u64,
+    pub signer: Address,
+    pub action: GovernanceAction,
+    pub approvals: u32,
+    pub expires_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PredictionEvent {
+    pub version: u32,
+    pub game_id: u64,
+    pub bettor: Option<Address>, // None on pool resolution or expiry
+    pub amount: i128,
+    pub status: PoolStatus,
+}
+
+pub fn game(env: &Env, action: Symbol, game: &Game) {
+    env.events().publish(
+        (GAME, action),
+        GameEvent {
+            version: EVENT_VERSION,
+            game_id: game.id,
+            state: game.state.clone(),
+            player1: game.player1.clone(),
+            player2: game.player2.clone(),
+            token: game.token.clone(),
+            wager_amount: game.wager_amount,
+            winner: game.winner.clone(),
+            ledger: env.ledger().sequence() as u64,
+        },
+    );
+}
+
+pub fn moved(env: &Env, game: &Game, player: &Address, move_data: &Vec<u32>) {
+    env.events().publish(
+        (GAME, MOVED),
+        MoveEvent {
+            version: EVENT_VERSION,
+            game_id: game.id,
+            player: player.clone(),
+            move_number: game.moves.len(),
+            move_data: move_data.clone(),
+            ledger: env.ledger().sequence() as u64,
+        },
+    );
+}
+
+pub fn payout(
+    env: &Env,
+    action: Symbol,
+    game: &Game,
+    recipient: &Address,
+    amount: i128,
+    fee: i128,
+) {
+    env.events().publish(
+        (PAYOUT, action),
+        PayoutEvent {
+            version: EVENT_VERSION,
+            game_id: game.id,
+            token: game.token.clone(),
+            recipient: recipient.clone(),
+            amount,
+            fee,
+        },
+    );
+}
+
+pub fn dispute(env: &Env, action: Symbol, dispute: &Dispute, winner: Option<Address>) {
+    env.events().publish(
+        (DISPUTE, action),
+        DisputeEvent {
+            version: EVENT_VERSION,
+            dispute_id: dispute.id,
+            game_id: dispute.game_id,
+            filer: dispute.filer.clone(),
+            against: dispute.against.clone(),
+            status: dispute.status.clone(),
+            winner,
+        },
+    );
+}
+
+pub fn fee_proposal(
+    env: &Env,
+    action: Symbol,
+    signer: &Address,
+    proposal: &FeeProposal,
+    approvals: u32,
+) {
+    env.events().publish(
+        (MULTISIG, action),
+        FeeProposalEvent {
+            version: EVENT_VERSION,
+            signer: signer.clone(),
+            new_fee_bips: proposal.new_fee_bips,
+            new_treasury_address: proposal.new_treasury_address.clone(),
+            approvals,
+        },
+    );
+}
+
+pub fn governance(env: &Env, action: Symbol, signer: &Address, proposal: &GovernanceProposal) {
+    env.events().publish(
+        (GOVERN, action),
+        GovernanceEvent {
+            version: EVENT_VERSION,
+            proposal_id: proposal.id,
+            signer: signer.clone(),
+            action: proposal.action.clone(),
+            approvals: proposal.approvals.len(),
+            expires_at: proposal.expires_at,
+        },
+    );
+}
+
+pub fn prediction(
+    env: &Env,
+    action: Symbol,
+    game_id: u64,
+    bettor: Option<Address>,
+    amount: i128,
+    status: PoolStatus,
+) {
+    env.events().publish(
+        (PREDICT, action),
+        PredictionEvent {
+            version: EVENT_VERSION,
+            game_id,
+            bettor,
+            amount,
+            status,
+        },
+    );
+}
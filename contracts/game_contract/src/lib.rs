@@ -1,10 +1,12 @@
 #![no_std]
 use soroban_sdk::token::TokenClient;
 use soroban_sdk::{
-    Address, Bytes, BytesN, Env, Map, Symbol, Vec, contract, contracterror, contractimpl,
-    contracttype, symbol_short,
+    Address, Bytes, BytesN, Env, IntoVal, Map, Symbol, Val, Vec, contract, contractclient,
+    contracterror, contractimpl, contracttype, symbol_short,
 };
 
+mod events;
+
 // ────────────────────────────────────────────────────────────────────────────
 // Game types (retained from the original simple contract)
 // ────────────────────────────────────────────────────────────────────────────
@@ -28,11 +30,14 @@ pub struct Game {
     pub player2: Option<Address>,
     pub state: GameState,
     pub wager_amount: i128,
+    pub token: Address, // Wager asset (SAC or custom token contract)
     pub current_turn: u32, // 1 = player1, 2 = player2
     pub moves: Vec<ChessMove>,
     pub created_at: u64,
     pub winner: Option<Address>,
     pub last_move_at: u64, // Ledger sequence of last move
+    pub opponent: Option<Address>, // Directed challenge: only this address may join
+    pub expires_at: Option<u64>,   // Ledger sequence after which an unjoined game can be refunded
 }
 
 #[contracttype]
@@ -64,6 +69,45 @@ pub struct Dispute {
     pub resolution: Option<Bytes>, // Arbitrator's resolution
 }
 
+// ────────────────────────────────────────────────────────────────────────────
+// Spectator prediction pool types
+// ────────────────────────────────────────────────────────────────────────────
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PredictionOutcome {
+    Player1Wins,
+    Player2Wins,
+    Draw,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PoolStatus {
+    Open,
+    Resolved(PredictionOutcome),
+    Voided, // No result, or nobody backed it: stakes refunded in full
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct SideBet {
+    pub outcome: PredictionOutcome,
+    pub amount: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct PredictionPool {
+    pub game_id: u64,
+    pub token: Address, // Always the game's wager token
+    pub player1_total: i128,
+    pub player2_total: i128,
+    pub draw_total: i128,
+    pub bettors: u32,
+    pub status: PoolStatus,
+}
+
 #[contracttype]
 #[derive(Clone, Debug)]
 pub struct PlayerRating {
@@ -83,10 +127,46 @@ pub struct PlayerRating {
 
 // Game / escrow
 const GAME_COUNTER: Symbol = symbol_short!("GAME_CNT");
-const GAMES: Symbol = symbol_short!("GAMES");
-const ESCROW: Symbol = symbol_short!("ESCROW");
+const GAMES: Symbol = symbol_short!("GAMES"); // persistent (GAMES, id) → Game; v1 kept an instance Map<u64, Game>
+const ESCROW: Symbol = symbol_short!("ESCROW"); // (ESCROW, token) → Map<Address, i128>
 const TOKEN_CONTRACT: Symbol = symbol_short!("TOKEN");
 
+// Directed challenges / rematches
+const REMATCHES: Symbol = symbol_short!("REMATCH"); // Map<u64, u64> finished game → rematch game
+
+// Circuit breaker
+const CIRCUIT_BREAKER: Symbol = symbol_short!("BREAKER"); // Address of emergency_circuit_breaker
+
+// AI agent NFTs
+const AGENT_NFT: Symbol = symbol_short!("AGENT_NFT"); // Address of ai_nft
+const GAME_AGENTS: Symbol = symbol_short!("G_AGENTS"); // Map<u64, Map<Address, u64>> game → player → nft_id
+
+// Query indexes (persistent)
+const PLAYER_GAMES: Symbol = symbol_short!("PL_GAMES"); // (PLAYER_GAMES, player) → u64 count; (PLAYER_GAMES, player, page) → Vec<u64> every game
+const PLAYER_ACTIVE: Symbol = symbol_short!("PL_ACTIVE"); // (PLAYER_ACTIVE, player) → Vec<u64> unfinished games
+const OPEN_GAMES: Symbol = symbol_short!("OPEN_GMS"); // (OPEN_GAMES, id / INDEX_PAGE_SIZE) → Vec<u64> public games awaiting player2
+const OPEN_HEAD: Symbol = symbol_short!("OPEN_HEAD"); // u64 - first open-games bucket that may be non-empty
+const INDEX_PAGE_SIZE: u64 = 64; // ids per history page / open-games bucket
+const OPEN_SCAN_BUCKETS: u64 = 8; // open-games buckets read per call
+const MAX_PAGE_SIZE: u32 = 25; // keeps a page within per-transaction read limits
+const MAX_GAME_SCAN: u32 = 25; // games read per get_games_by_state call
+const MAX_PAGE_SCAN: u32 = 500; // disputes examined per filtered-scan call
+const PERSISTENT_TTL_THRESHOLD: u32 = 100_000;
+const PERSISTENT_TTL_EXTEND: u32 = 500_000;
+const MIGRATE_BATCH: u32 = 2; // legacy games moved per migrate call; each touches ~10 entries
+
+// Spectator prediction pools
+const PRED_CLOSE_MOVES: Symbol = symbol_short!("PR_CLOSE"); // u32 - betting closes at this many moves
+const PRED_POOLS: Symbol = symbol_short!("PR_POOLS"); // persistent (PRED_POOLS, game_id) → PredictionPool
+const SIDE_BETS: Symbol = symbol_short!("PR_BETS"); // persistent (SIDE_BETS, game_id, bettor) → SideBet
+const MAX_SIDE_BETTORS: u32 = 200; // bettors per pool
+const MIN_SIDE_BET: i128 = 1_000_000; // 0.1 of a 7-decimal token, so the bettor slots cannot be filled with dust
+const PRED_EXPIRY_LEDGERS: u64 = 120_960; // ~7 days without a move before an open pool can be refunded
+
+// Multi-asset wagering
+const ALLOWED_TOKENS: Symbol = symbol_short!("TOKENS"); // Map<Address, bool>
+const TREASURY_FEES: Symbol = symbol_short!("TR_FEES"); // Map<Address, i128> token → fees collected
+
 // Puzzle-reward  (#199)
 const ADMIN_KEY: Symbol = symbol_short!("ADMIN_KEY"); // BytesN<32> ED25519 backend pubkey
 const TREASURY: Symbol = symbol_short!("TREASURY"); // i128 treasury reserve
@@ -108,6 +188,12 @@ const ARBITRATOR: Symbol = symbol_short!("ARBIT"); // Address - dispute arbitrat
 // Game timeout mechanism
 const TIMEOUT_DURATION: Symbol = symbol_short!("T_OUT"); // u64 - ledger sequences before timeout
 
+// Disconnect slashing
+const SLASH_RATE: Symbol = symbol_short!("SLASH_BPS"); // u32 - share of the absent player's stake, out of 10_000
+const SLASH_REPORTER: Symbol = symbol_short!("SLASH_REP"); // Address allowed to report disconnects
+const DEFAULT_SLASH_BPS: u32 = 2_500; // 25 %
+const MAX_SLASH_BPS: u32 = 10_000;
+
 // SEP-10 challenge verification (#529)
 const SEP10_CHALLENGES: Symbol = symbol_short!("S10_CHAL"); // Map<BytesN<32>, u64> nonce → expiry
 const SEP10_VERIFIED: Symbol = symbol_short!("S10_VER"); // Map<Address, bool>
@@ -118,6 +204,18 @@ const MULTISIG_THRESHOLD: Symbol = symbol_short!("MS_THRES"); // u32
 const PENDING_FEE_PROPOSAL: Symbol = symbol_short!("MS_PROP"); // Option<FeeProposal>
 const FEE_PROPOSAL_APPROVALS: Symbol = symbol_short!("MS_APPR"); // Map<Address, bool>
 
+// Governance proposals (generalised multisig)
+const GOV_COUNTER: Symbol = symbol_short!("GOV_CNT"); // u64
+const GOV_PROPOSALS: Symbol = symbol_short!("GOV_PROPS"); // Map<u64, GovernanceProposal> open proposals
+const GOV_LIFETIME: Symbol = symbol_short!("GOV_TTL"); // u64 - ledgers a proposal stays approvable
+const DEFAULT_PROPOSAL_LIFETIME: u64 = 17_280; // ~1 day of 5 s ledgers
+
+// Upgrades
+const SCHEMA_VER: Symbol = symbol_short!("SCHEMA"); // u32 storage layout version
+/// Storage layout version this code reads and writes; see `migrate`.
+/// v2 moved games and query indexes out of instance storage.
+pub const SCHEMA_VERSION: u32 = 2;
+
 // SEP-40 Oracle clock sync (#533)
 const ORACLE_CONTRACT: Symbol = symbol_short!("ORACLE"); // Address of oracle contract
 
@@ -138,6 +236,46 @@ pub struct FeeProposal {
     pub proposer: Address,
 }
 
+// ────────────────────────────────────────────────────────────────────────────
+// Governance types
+// ────────────────────────────────────────────────────────────────────────────
+
+/// A parameter change that, once multisig is configured, only takes effect
+/// through an approved governance proposal.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GovernanceAction {
+    SetFees(u32, Address), // (fee_bips, treasury_address)
+    SetMaxStake(i128),
+    ConfigureTimeout(u64),                 // ledger sequences
+    ConfigureDisputeSystem(Address, i128), // (arbitrator, dispute_fee)
+    ConfigureOracle(Address),
+    UpgradeAdmin(Address),
+    ConfigureTournamentTimelock(u64),     // ledger sequences
+    SetProposalLifetime(u64),             // ledger sequences
+    UpgradeWasm(BytesN<32>),              // hash of already-uploaded WASM for this contract
+    UpgradeContract(Address, BytesN<32>), // (contract, wasm hash); calls its `upgrade`
+    ConfigureSlashing(u32, Address),      // (slash_bps, disconnect reporter)
+    SetSigners(Vec<Address>, u32),        // (signers, threshold)
+    AllowToken(Address),
+    DisallowToken(Address),
+    SetCircuitBreaker(Address),
+    SetAgentNft(Address),
+    ConfigurePredictionPools(u32), // close_after_moves
+    SetAdminKey(BytesN<32>),       // ed25519 key settlement signatures are checked against
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct GovernanceProposal {
+    pub id: u64,
+    pub action: GovernanceAction,
+    pub proposer: Address,
+    pub approvals: Vec<Address>,
+    pub proposed_at: u64, // ledger sequence
+    pub expires_at: u64,  // last ledger sequence at which it can be approved
+}
+
 // ────────────────────────────────────────────────────────────────────────────
 // Tournament escrow type (#532)
 // ────────────────────────────────────────────────────────────────────────────
@@ -147,11 +285,73 @@ pub struct FeeProposal {
 pub struct TournamentEscrow {
     pub escrow_id: u64,
     pub game_id: u64,
+    pub token: Address,
     pub total_amount: i128,
     pub locked_until: u64, // ledger sequence when funds can be released
     pub released: bool,
 }
 
+// ────────────────────────────────────────────────────────────────────────────
+// Circuit breaker interface
+// ────────────────────────────────────────────────────────────────────────────
+//
+// Mirrors `emergency_circuit_breaker::PauseCategory`. Only the variant names
+// cross the contract boundary, so they must stay in step with that contract.
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PauseCategory {
+    NewGames,      // create / join / rematch / side bets
+    Payouts,       // winner payouts, tournament splits and escrow releases
+    PuzzleRewards, // claim_puzzle_reward
+}
+
+#[contractclient(name = "CircuitBreakerClient")]
+pub trait CircuitBreaker {
+    fn is_category_paused(env: Env, category: PauseCategory) -> bool;
+}
+
+/// Mirrors `ai_nft::MatchOutcome`; only the variant names cross the
+/// contract boundary.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MatchOutcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+#[contractclient(name = "AgentNftClient")]
+pub trait AgentNft {
+    fn user_of(env: Env, nft_id: u64) -> Address;
+    fn record_result(env: Env, nft_id: u64, outcome: MatchOutcome);
+}
+
+/// Upgrade entrypoint of contracts (ai_nft, game_registry) whose upgrade
+/// authority is set to this contract.
+#[contractclient(name = "UpgradeableClient")]
+pub trait Upgradeable {
+    fn upgrade(env: Env, new_wasm_hash: BytesN<32>);
+}
+
+// ────────────────────────────────────────────────────────────────────────────
+// Query page types
+// ────────────────────────────────────────────────────────────────────────────
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct GamePage {
+    pub games: Vec<Game>,
+    pub next_cursor: Option<u64>, // None once there is nothing left to fetch
+}
+
+#[contracttype]
+#[derive(Clone, Debug)]
+pub struct DisputePage {
+    pub disputes: Vec<Dispute>,
+    pub next_cursor: Option<u64>,
+}
+
 // ────────────────────────────────────────────────────────────────────────────
 // Errors
 // ────────────────────────────────────────────────────────────────────────────
@@ -175,7 +375,8 @@ pub enum ContractError {
     /// Invalid or already-used backend signature  (#199)
     Unauthorized = 14,
     StakeLimitExceeded = 15,
-    /// Game has not timed out yet
+    /// Game has not timed out yet (or, for a side bet claim, has neither
+    /// finished nor stalled)
     TimeoutNotReached = 16,
     /// Timeout feature not configured
     TimeoutNotConfigured = 17,
@@ -215,6 +416,37 @@ pub enum ContractError {
     EscrowStillLocked = 34,
     /// Tournament escrow already released (#532)
     EscrowAlreadyReleased = 35,
+    /// Wager token is not on the admin-managed allowlist
+    TokenNotAllowed = 36,
+    /// Directed challenge can only be joined by the named opponent
+    NotInvited = 37,
+    /// Challenge expiry has passed (or was set in the past)
+    InviteExpired = 38,
+    /// Game is unfinished, or a rematch was already created for it
+    RematchNotAvailable = 39,
+    /// Prediction pools have not been configured by the admin
+    PredictionsNotConfigured = 40,
+    /// Betting on this game is closed (not in progress or move limit reached)
+    BettingClosed = 41,
+    /// Players cannot bet on their own game, a bettor may back one outcome only,
+    /// and only an unclaimed side bet can be claimed
+    InvalidPrediction = 42,
+    /// Prediction pool has reached its bettor limit
+    PoolFull = 43,
+    /// This category of operations is paused by the circuit breaker
+    Paused = 44,
+    /// Governance proposal is past its expiry
+    ProposalExpired = 45,
+    /// Multisig is configured, so this setter must go through a governance proposal
+    GovernanceRequired = 46,
+    /// Persisted storage layout is newer than this contract code
+    SchemaTooNew = 47,
+    /// No ai_nft contract is configured for agent assignment
+    AgentsNotConfigured = 48,
+    /// Caller is not the current user (owner or renter) of the agent NFT
+    AgentNotOwned = 49,
+    /// No disconnect reporter has been configured
+    SlashingNotConfigured = 50,
 }
 
 #[contract]
@@ -239,8 +471,30 @@ impl GameContract {
             .expect("Token contract is not initialized")
     }
 
-    fn token_client(env: &Env) -> TokenClient<'_> {
-        TokenClient::new(env, &Self::token_contract_address(env))
+    /// Escrow ledger for a single wager asset. Balances are never mixed
+    /// across tokens, so every game only ever touches its own asset's map.
+    fn load_escrow(env: &Env, token: &Address) -> Map<Address, i128> {
+        env.storage()
+            .instance()
+            .get(&(ESCROW, token.clone()))
+            .unwrap_or(Map::new(env))
+    }
+
+    fn store_escrow(env: &Env, token: &Address, escrow: &Map<Address, i128>) {
+        env.storage()
+            .instance()
+            .set(&(ESCROW, token.clone()), escrow);
+    }
+
+    fn record_treasury_fee(env: &Env, token: &Address, fee: i128) {
+        let mut fees: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&TREASURY_FEES)
+            .unwrap_or(Map::new(env));
+        let collected = fees.get(token.clone()).unwrap_or(0);
+        fees.set(token.clone(), collected + fee);
+        env.storage().instance().set(&TREASURY_FEES, &fees);
     }
 
     /// Gas-optimized tournament payout — single pass, no redundant map reads.
@@ -251,13 +505,9 @@ impl GameContract {
         winners: Vec<Address>,
         percentages: Vec<u32>,
     ) -> Result<(), ContractError> {
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
+        Self::require_not_paused(&env, PauseCategory::Payouts)?;
 
-        let game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::Completed {
             return Err(ContractError::GameNotInProgress);
@@ -297,7 +547,7 @@ impl GameContract {
         // Dust to first winner
         let remainder = total_pool - distributed;
 
-        let token_client = Self::token_client(&env);
+        let token_client = TokenClient::new(&env, &game.token);
         let contract_address = env.current_contract_address();
 
         for (idx, (winner, mut amount)) in payouts.iter().enumerate() {
@@ -305,18 +555,21 @@ impl GameContract {
                 amount += remainder;
             }
             token_client.transfer(&contract_address, &winner, &amount);
+            events::payout(&env, events::WINNER, &game, &winner, amount, 0);
         }
 
         let mut settled_game = game;
         settled_game.state = GameState::Settled;
-        games.set(game_id, settled_game);
-        env.storage().instance().set(&GAMES, &games);
+        events::game(&env, events::SETTLED, &settled_game);
+        Self::finish_game(&env, &settled_game);
+        Self::store_game(&env, &settled_game);
 
         Ok(())
     }
 
     // ── Game lifecycle ────────────────────────────────────────────────────────
 
+    /// Create a game wagered in the default token set by `initialize_token`.
     pub fn create_game(
         env: Env,
         player1: Address,
@@ -327,9 +580,79 @@ impl GameContract {
             return Err(ContractError::StakeLimitExceeded);
         }
 
+        let token = Self::token_contract_address(&env);
+        Self::open_game(&env, player1, token, wager_amount, None, None)
+    }
+
+    /// Create a game wagered in `token`, which must be the default token or
+    /// on the admin-managed allowlist.
+    pub fn create_game_with_token(
+        env: Env,
+        player1: Address,
+        token: Address,
+        wager_amount: i128,
+    ) -> Result<u64, ContractError> {
+        let max_stake: i128 = env.storage().instance().get(&MAX_STAKE).unwrap_or(1_000);
+        if wager_amount > max_stake {
+            return Err(ContractError::StakeLimitExceeded);
+        }
+        if !Self::is_token_allowed(env.clone(), token.clone()) {
+            return Err(ContractError::TokenNotAllowed);
+        }
+
+        Self::open_game(&env, player1, token, wager_amount, None, None)
+    }
+
+    /// Create a directed challenge that only `opponent` may join.
+    ///
+    /// The wager is escrowed immediately. Once the ledger passes `expires_at`
+    /// the challenge can no longer be accepted, and anyone may call
+    /// `cancel_game` to refund player1.
+    pub fn create_challenge(
+        env: Env,
+        player1: Address,
+        opponent: Address,
+        token: Address,
+        wager_amount: i128,
+        expires_at: u64,
+    ) -> Result<u64, ContractError> {
+        let max_stake: i128 = env.storage().instance().get(&MAX_STAKE).unwrap_or(1_000);
+        if wager_amount > max_stake {
+            return Err(ContractError::StakeLimitExceeded);
+        }
+        if !Self::is_token_allowed(env.clone(), token.clone()) {
+            return Err(ContractError::TokenNotAllowed);
+        }
+        if opponent == player1 {
+            return Err(ContractError::AlreadyJoined);
+        }
+        if expires_at <= env.ledger().sequence() as u64 {
+            return Err(ContractError::InviteExpired);
+        }
+
+        Self::open_game(
+            &env,
+            player1,
+            token,
+            wager_amount,
+            Some(opponent),
+            Some(expires_at),
+        )
+    }
+
+    fn open_game(
+        env: &Env,
+        player1: Address,
+        token: Address,
+        wager_amount: i128,
+        opponent: Option<Address>,
+        expires_at: Option<u64>,
+    ) -> Result<u64, ContractError> {
+        Self::require_not_paused(env, PauseCategory::NewGames)?;
+
         player1.require_auth();
 
-        let token_client = Self::token_client(&env);
+        let token_client = TokenClient::new(env, &token);
         let contract_address = env.current_contract_address();
 
         if token_client.balance(&player1) < wager_amount {
@@ -338,6 +661,7 @@ impl GameContract {
 
         token_client.transfer(&player1, &contract_address, &wager_amount);
 
+        let is_public = opponent.is_none();
         let mut game_counter: u64 = env.storage().instance().get(&GAME_COUNTER).unwrap_or(0);
         game_counter += 1;
         env.storage().instance().set(&GAME_COUNTER, &game_counter);
@@ -348,41 +672,37 @@ impl GameContract {
             player2: None,
             state: GameState::Created,
             wager_amount,
+            token: token.clone(),
             current_turn: 1,
-            moves: Vec::new(&env),
+            moves: Vec::new(env),
             created_at: env.ledger().sequence() as u64,
             winner: None,
             last_move_at: env.ledger().sequence() as u64,
+            opponent,
+            expires_at,
         };
 
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .unwrap_or(Map::new(&env));
-        games.set(game_counter, game);
-        env.storage().instance().set(&GAMES, &games);
+        events::game(env, events::CREATED, &game);
+        Self::store_game(env, &game);
 
-        let mut escrow: Map<Address, i128> = env
-            .storage()
-            .instance()
-            .get(&ESCROW)
-            .unwrap_or(Map::new(&env));
+        let mut escrow = Self::load_escrow(env, &token);
         let current_escrow = escrow.get(player1.clone()).unwrap_or(0);
-        escrow.set(player1, current_escrow + wager_amount);
-        env.storage().instance().set(&ESCROW, &escrow);
+        escrow.set(player1.clone(), current_escrow + wager_amount);
+        Self::store_escrow(env, &token, &escrow);
+
+        Self::history_add(env, &player1, game_counter);
+        Self::active_add(env, &player1, game_counter);
+        if is_public {
+            Self::open_add(env, game_counter);
+        }
 
         Ok(game_counter)
     }
 
     pub fn join_game(env: Env, game_id: u64, player2: Address) -> Result<(), ContractError> {
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
+        Self::require_not_paused(&env, PauseCategory::NewGames)?;
 
-        let mut game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let mut game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::Created {
             return Err(ContractError::GameAlreadyCompleted);
@@ -393,6 +713,14 @@ impl GameContract {
         if game.player1 == player2 {
             return Err(ContractError::AlreadyJoined);
         }
+        if let Some(ref opponent) = game.opponent
+            && *opponent != player2
+        {
+            return Err(ContractError::NotInvited);
+        }
+        if Self::is_expired(&env, &game) {
+            return Err(ContractError::InviteExpired);
+        }
 
         let max_stake: i128 = env.storage().instance().get(&MAX_STAKE).unwrap_or(1_000);
         if game.wager_amount > max_stake {
@@ -400,7 +728,7 @@ impl GameContract {
         }
 
         player2.require_auth();
-        let token_client = Self::token_client(&env);
+        let token_client = TokenClient::new(&env, &game.token);
         let contract_address = env.current_contract_address();
 
         if token_client.balance(&player2) < game.wager_amount {
@@ -414,17 +742,17 @@ impl GameContract {
         game.current_turn = 1;
         game.last_move_at = env.ledger().sequence() as u64;
 
-        let mut escrow: Map<Address, i128> = env
-            .storage()
-            .instance()
-            .get(&ESCROW)
-            .unwrap_or(Map::new(&env));
+        let mut escrow = Self::load_escrow(&env, &game.token);
         let current_escrow = escrow.get(player2.clone()).unwrap_or(0);
-        escrow.set(player2, current_escrow + game.wager_amount);
-        env.storage().instance().set(&ESCROW, &escrow);
+        escrow.set(player2.clone(), current_escrow + game.wager_amount);
+        Self::store_escrow(&env, &game.token, &escrow);
+
+        Self::history_add(&env, &player2, game_id);
+        Self::active_add(&env, &player2, game_id);
+        Self::open_remove(&env, game_id);
 
-        games.set(game_id, game);
-        env.storage().instance().set(&GAMES, &games);
+        events::game(&env, events::JOINED, &game);
+        Self::store_game(&env, &game);
 
         Ok(())
     }
@@ -435,13 +763,7 @@ impl GameContract {
         player: Address,
         move_data: Vec<u32>,
     ) -> Result<(), ContractError> {
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
-
-        let mut game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let mut game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::InProgress {
             return Err(ContractError::GameNotInProgress);
@@ -467,27 +789,22 @@ impl GameContract {
 
         let chess_move = ChessMove {
             player: player.clone(),
-            move_data,
+            move_data: move_data.clone(),
             timestamp: env.ledger().sequence() as u64,
         };
         game.moves.push_back(chess_move);
         game.current_turn = if game.current_turn == 1 { 2 } else { 1 };
         game.last_move_at = env.ledger().sequence() as u64;
 
-        games.set(game_id, game);
-        env.storage().instance().set(&GAMES, &games);
+        events::moved(&env, &game, &player, &move_data);
+
+        Self::store_game(&env, &game);
 
         Ok(())
     }
 
     pub fn claim_draw(env: Env, game_id: u64, player: Address, signature: BytesN<64>) -> Result<(), ContractError> {
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
-
-        let mut game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let mut game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::InProgress {
             return Err(ContractError::GameNotInProgress);
@@ -521,8 +838,9 @@ impl GameContract {
         game.state = GameState::Drawn;
         Self::process_draw_payout(&env, &game)?;
 
-        games.set(game_id, game);
-        env.storage().instance().set(&GAMES, &games);
+        events::game(&env, events::DRAWN, &game);
+        Self::finish_game(&env, &game);
+        Self::store_game(&env, &game);
 
         Ok(())
     }
@@ -533,13 +851,9 @@ impl GameContract {
         winner: Address,
         signature: BytesN<64>,
     ) -> Result<(), ContractError> {
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
+        Self::require_not_paused(&env, PauseCategory::Payouts)?;
 
-        let mut game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let mut game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::InProgress {
             return Err(ContractError::GameNotInProgress);
@@ -579,61 +893,116 @@ impl GameContract {
         Self::process_payout(&env, &game, &winner)?;
         game.state = GameState::Settled;
 
-        games.set(game_id, game);
-        env.storage().instance().set(&GAMES, &games);
+        events::game(&env, events::SETTLED, &game);
+        Self::finish_game(&env, &game);
+        Self::store_game(&env, &game);
 
         Ok(())
     }
 
     pub fn cancel_game(env: Env, game_id: u64, player: Address) -> Result<(), ContractError> {
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
-
-        let mut game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let mut game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::Created {
             return Err(ContractError::GameAlreadyCompleted);
         }
 
-        if game.player1 != player {
-            return Err(ContractError::NotPlayer);
+        // Once a challenge has expired anyone may trigger the refund; it always
+        // goes back to player1. Before that only player1 may cancel.
+        if !Self::is_expired(&env, &game) {
+            if game.player1 != player {
+                return Err(ContractError::NotPlayer);
+            }
+            player.require_auth();
         }
-
-        player.require_auth();
+        let player = game.player1.clone();
 
         // Refund player1's staked wager
-        let mut escrow: Map<Address, i128> = env
-            .storage()
-            .instance()
-            .get(&ESCROW)
-            .unwrap_or(Map::new(&env));
-
+        let mut escrow = Self::load_escrow(&env, &game.token);
         let current_escrow = escrow.get(player.clone()).unwrap_or(0);
         escrow.set(player.clone(), current_escrow - game.wager_amount);
-        env.storage().instance().set(&ESCROW, &escrow);
+        Self::store_escrow(&env, &game.token, &escrow);
 
-        let token_client = Self::token_client(&env);
+        let token_client = TokenClient::new(&env, &game.token);
         let contract_address = env.current_contract_address();
         token_client.transfer(&contract_address, &player, &game.wager_amount);
+        events::payout(&env, events::REFUND, &game, &player, game.wager_amount, 0);
 
         game.state = GameState::Completed; // Mark as completed to prevent joining
-        games.set(game_id, game);
-        env.storage().instance().set(&GAMES, &games);
+        events::game(&env, events::CANCELLED, &game);
+        Self::finish_game(&env, &game);
+        Self::store_game(&env, &game);
 
         Ok(())
     }
 
-    pub fn forfeit(env: Env, game_id: u64, player: Address) -> Result<(), ContractError> {
-        let mut games: Map<u64, Game> = env
+    /// Start a rematch of a finished game with colours swapped.
+    ///
+    /// Both players authorize this single call; each escrows the same stake in
+    /// the same token as the original game and the new game starts
+    /// immediately, with the original player2 moving first.
+    pub fn rematch(env: Env, game_id: u64) -> Result<u64, ContractError> {
+        let previous = Self::load_game(&env, game_id)?;
+
+        if !matches!(
+            previous.state,
+            GameState::Settled | GameState::Drawn | GameState::Forfeited
+        ) {
+            return Err(ContractError::RematchNotAvailable);
+        }
+        let previous_player2 = previous
+            .player2
+            .clone()
+            .ok_or(ContractError::RematchNotAvailable)?;
+
+        let mut rematches: Map<u64, u64> = env
             .storage()
             .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
+            .get(&REMATCHES)
+            .unwrap_or(Map::new(&env));
+        if rematches.contains_key(game_id) {
+            return Err(ContractError::RematchNotAvailable);
+        }
+
+        let max_stake: i128 = env.storage().instance().get(&MAX_STAKE).unwrap_or(1_000);
+        if previous.wager_amount > max_stake {
+            return Err(ContractError::StakeLimitExceeded);
+        }
 
-        let mut game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        // Swapped colours: the old black player creates (and plays white).
+        let new_game_id = Self::open_game(
+            &env,
+            previous_player2.clone(),
+            previous.token.clone(),
+            previous.wager_amount,
+            Some(previous.player1.clone()),
+            None,
+        )?;
+        Self::join_game(env.clone(), new_game_id, previous.player1.clone())?;
+
+        rematches.set(game_id, new_game_id);
+        env.storage().instance().set(&REMATCHES, &rematches);
+
+        Ok(new_game_id)
+    }
+
+    /// The rematch created for `game_id`, if any.
+    pub fn get_rematch(env: Env, game_id: u64) -> Option<u64> {
+        let rematches: Map<u64, u64> = env.storage().instance().get(&REMATCHES)?;
+        rematches.get(game_id)
+    }
+
+    fn is_expired(env: &Env, game: &Game) -> bool {
+        match game.expires_at {
+            Some(expires_at) => env.ledger().sequence() as u64 > expires_at,
+            None => false,
+        }
+    }
+
+    pub fn forfeit(env: Env, game_id: u64, player: Address) -> Result<(), ContractError> {
+        Self::require_not_paused(&env, PauseCategory::Payouts)?;
+
+        let mut game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::InProgress {
             return Err(ContractError::GameNotInProgress);
@@ -657,20 +1026,17 @@ impl GameContract {
         Self::process_payout(&env, &game, &winner)?;
         game.state = GameState::Settled;
 
-        games.set(game_id, game);
-        env.storage().instance().set(&GAMES, &games);
+        events::game(&env, events::FORFEITED, &game);
+        Self::finish_game(&env, &game);
+        Self::store_game(&env, &game);
 
         Ok(())
     }
 
     pub fn payout(env: Env, game_id: u64, winner: Address) -> Result<(), ContractError> {
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
+        Self::require_not_paused(&env, PauseCategory::Payouts)?;
 
-        let mut game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let mut game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::Completed {
             if game.state == GameState::Settled {
@@ -687,8 +1053,9 @@ impl GameContract {
         Self::process_payout(&env, &game, &winner)?;
         game.state = GameState::Settled;
 
-        games.set(game_id, game);
-        env.storage().instance().set(&GAMES, &games);
+        events::game(&env, events::SETTLED, &game);
+        Self::finish_game(&env, &game);
+        Self::store_game(&env, &game);
 
         Ok(())
     }
@@ -699,13 +1066,9 @@ impl GameContract {
         winners: Vec<Address>,
         percentages: Vec<u32>,
     ) -> Result<(), ContractError> {
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
+        Self::require_not_paused(&env, PauseCategory::Payouts)?;
 
-        let game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::Completed {
             return Err(ContractError::GameNotInProgress);
@@ -719,11 +1082,7 @@ impl GameContract {
 
         let mut total_percentage: u32 = 0;
 
-        let mut escrow: Map<Address, i128> = env
-            .storage()
-            .instance()
-            .get(&ESCROW)
-            .unwrap_or(Map::new(&env));
+        let mut escrow = Self::load_escrow(&env, &game.token);
 
         let player1_escrow = escrow.get(game.player1.clone()).unwrap_or(0);
         if player1_escrow < game.wager_amount {
@@ -756,6 +1115,7 @@ impl GameContract {
             distributed += payout_amount;
             let winner_escrow = escrow.get(winner.clone()).unwrap_or(0);
             escrow.set(winner.clone(), winner_escrow + payout_amount);
+            events::payout(&env, events::WINNER, &game, &winner, payout_amount, 0);
         }
 
         if total_percentage != 100 {
@@ -768,96 +1128,427 @@ impl GameContract {
             let first_winner = winners.get(0).unwrap();
             let winner_escrow = escrow.get(first_winner.clone()).unwrap_or(0);
             escrow.set(first_winner.clone(), winner_escrow + remainder);
+            events::payout(&env, events::WINNER, &game, &first_winner, remainder, 0);
         }
 
+        Self::store_escrow(&env, &game.token, &escrow);
         let mut settled_game = game;
         settled_game.state = GameState::Settled;
-        env.storage().instance().set(&ESCROW, &escrow);
-        games.set(game_id, settled_game);
-        env.storage().instance().set(&GAMES, &games);
+        events::game(&env, events::SETTLED, &settled_game);
+        Self::finish_game(&env, &settled_game);
+        Self::store_game(&env, &settled_game);
 
         Ok(())
     }
 
     pub fn get_game(env: Env, game_id: u64) -> Result<Game, ContractError> {
-        let games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
+        Self::load_game(&env, game_id)
+    }
+
+    // ── Paginated queries ─────────────────────────────────────────────────────
+    //
+    // `cursor` is the last id the caller has already seen (0 to start from the
+    // beginning). Each page holds at most `limit` entries, clamped to
+    // MAX_PAGE_SIZE, and `next_cursor` is None once the end is reached.
+    // Filtered scans stop after a bounded number of reads, so a page can come
+    // back short — even empty — while `next_cursor` is still Some.
+
+    /// Games `player` has created or joined, oldest first. With `active_only`
+    /// only games that have not yet been settled, drawn or cancelled are
+    /// returned.
+    pub fn get_player_games(
+        env: Env,
+        player: Address,
+        active_only: bool,
+        cursor: u64,
+        limit: u32,
+    ) -> GamePage {
+        if active_only {
+            let ids = Self::active_ids(&env, &player);
+            return Self::page_from_ids(&env, ids.into_iter(), cursor, limit);
+        }
 
-        games.get(game_id).ok_or(ContractError::GameNotFound)
+        let pages = Self::history_pages(&env, &player);
+        let first = Self::history_seek(&env, &player, pages, cursor);
+        let ids = (first..pages).flat_map(|page| Self::history_page(&env, &player, page));
+        Self::page_from_ids(&env, ids, cursor, limit)
     }
 
-    pub fn get_all_games(env: Env) -> Map<u64, Game> {
-        env.storage()
+    /// Public games still waiting for a second player. Directed challenges
+    /// are not listed.
+    pub fn get_open_games(env: Env, cursor: u64, limit: u32) -> GamePage {
+        let last_bucket = env
+            .storage()
             .instance()
-            .get(&GAMES)
-            .unwrap_or(Map::new(&env))
+            .get::<_, u64>(&GAME_COUNTER)
+            .unwrap_or(0)
+            / INDEX_PAGE_SIZE;
+        let head: u64 = env.storage().instance().get(&OPEN_HEAD).unwrap_or(0);
+        let first = ((cursor + 1) / INDEX_PAGE_SIZE).max(head);
+        let limit = Self::page_limit(limit);
+
+        let mut page = Vec::new(&env);
+        let mut last_seen = cursor;
+        let mut bucket = first;
+        while bucket <= last_bucket && bucket < first + OPEN_SCAN_BUCKETS {
+            for id in Self::open_bucket(&env, bucket).iter() {
+                if id <= cursor {
+                    continue;
+                }
+                if page.len() == limit {
+                    return GamePage {
+                        games: page,
+                        next_cursor: Some(last_seen),
+                    };
+                }
+                if let Ok(game) = Self::load_game(&env, id) {
+                    page.push_back(game);
+                }
+                last_seen = id;
+            }
+            bucket += 1;
+        }
+
+        GamePage {
+            games: page,
+            next_cursor: if bucket <= last_bucket {
+                Some(last_seen.max(bucket * INDEX_PAGE_SIZE - 1))
+            } else {
+                None
+            },
+        }
     }
 
-    // ── Internal payout helpers ───────────────────────────────────────────────
+    /// Games currently in `state`, by ascending id.
+    pub fn get_games_by_state(env: Env, state: GameState, cursor: u64, limit: u32) -> GamePage {
+        let last_id: u64 = env.storage().instance().get(&GAME_COUNTER).unwrap_or(0);
+        let limit = Self::page_limit(limit);
+
+        let mut page = Vec::new(&env);
+        let mut id = cursor;
+        let mut scanned = 0;
+        while id < last_id && page.len() < limit && scanned < MAX_GAME_SCAN {
+            id += 1;
+            scanned += 1;
+            if let Ok(game) = Self::load_game(&env, id)
+                && game.state == state
+            {
+                page.push_back(game);
+            }
+        }
 
-    fn process_draw_payout(env: &Env, game: &Game) -> Result<(), ContractError> {
-        let token_client = Self::token_client(env);
-        let contract_address = env.current_contract_address();
+        GamePage {
+            games: page,
+            next_cursor: if id < last_id { Some(id) } else { None },
+        }
+    }
 
-        let mut escrow: Map<Address, i128> = env
+    /// Disputes currently in `status`, by ascending id.
+    pub fn get_disputes_by_status(
+        env: Env,
+        status: DisputeStatus,
+        cursor: u64,
+        limit: u32,
+    ) -> DisputePage {
+        let disputes: Map<u64, Dispute> = env
             .storage()
             .instance()
-            .get(&ESCROW)
-            .unwrap_or(Map::new(env));
+            .get(&DISPUTES)
+            .unwrap_or(Map::new(&env));
+        let last_id: u64 = env.storage().instance().get(&DISPUTE_COUNTER).unwrap_or(0);
+        let limit = Self::page_limit(limit);
+
+        let mut page = Vec::new(&env);
+        let mut id = cursor;
+        let mut scanned = 0;
+        while id < last_id && page.len() < limit && scanned < MAX_PAGE_SCAN {
+            id += 1;
+            scanned += 1;
+            if let Some(dispute) = disputes.get(id)
+                && dispute.status == status
+            {
+                page.push_back(dispute);
+            }
+        }
 
-        // Return player1's stake
-        token_client.transfer(&contract_address, &game.player1, &game.wager_amount);
-        let player1_escrow = escrow.get(game.player1.clone()).unwrap_or(0);
-        escrow.set(game.player1.clone(), player1_escrow - game.wager_amount);
+        DisputePage {
+            disputes: page,
+            next_cursor: if id < last_id { Some(id) } else { None },
+        }
+    }
 
-        // Return player2's stake
-        if let Some(ref player2) = game.player2 {
-            token_client.transfer(&contract_address, player2, &game.wager_amount);
-            let player2_escrow = escrow.get(player2.clone()).unwrap_or(0);
-            escrow.set(player2.clone(), player2_escrow - game.wager_amount);
+    fn page_from_ids(
+        env: &Env,
+        ids: impl Iterator<Item = u64>,
+        cursor: u64,
+        limit: u32,
+    ) -> GamePage {
+        let limit = Self::page_limit(limit);
+
+        let mut page = Vec::new(env);
+        let mut last_seen = cursor;
+        let mut next_cursor = None;
+        for id in ids {
+            if id <= cursor {
+                continue;
+            }
+            if page.len() == limit {
+                next_cursor = Some(last_seen);
+                break;
+            }
+            if let Ok(game) = Self::load_game(env, id) {
+                page.push_back(game);
+            }
+            last_seen = id;
         }
 
-        env.storage().instance().set(&ESCROW, &escrow);
-        Ok(())
+        GamePage {
+            games: page,
+            next_cursor,
+        }
     }
 
-    /// #200 – Treasury fee redirection in payout_winner.
-    ///
-    /// Uses Soroban-safe integer arithmetic:
-    ///   `fee    = total_pool * fee_bips / 1000`
-    ///   `payout = total_pool - fee`
-    ///
-    /// Example: 10 XLM pool, fee_bips = 20 (2 %)
-    ///   fee    = 10 * 20 / 1000 = 0.2 XLM  → Treasury
-    ///   payout = 10 - 0.2       = 9.8 XLM  → Winner
-    fn process_payout(env: &Env, game: &Game, winner: &Address) -> Result<(), ContractError> {
-        let mut escrow: Map<Address, i128> = env
-            .storage()
+    fn page_limit(limit: u32) -> u32 {
+        limit.clamp(1, MAX_PAGE_SIZE)
+    }
+
+    // ── Game storage and indexes ──────────────────────────────────────────────
+    //
+    // Every game and every index page is its own persistent entry, so a call
+    // only reads and pays rent for what it touches. Games of a v1 deployment
+    // stay in the legacy instance map, and are read from there, until
+    // `migrate` moves them.
+
+    fn load_game(env: &Env, game_id: u64) -> Result<Game, ContractError> {
+        if let Some(game) = env.storage().persistent().get(&(GAMES, game_id)) {
+            return Ok(game);
+        }
+        env.storage()
             .instance()
-            .get(&ESCROW)
-            .unwrap_or(Map::new(env));
+            .get::<_, Map<u64, Game>>(&GAMES)
+            .and_then(|legacy| legacy.get(game_id))
+            .ok_or(ContractError::GameNotFound)
+    }
 
-        let fee_bips: u32 = env.storage().instance().get(&FEE_BIPS).unwrap_or(0);
-        let treasury_addr_opt: Option<Address> = env.storage().instance().get(&TREASURY_ADDR);
+    fn store_game(env: &Env, game: &Game) {
+        Self::store_persistent(env, &(GAMES, game.id), game);
+    }
 
-        let total_pool = game.wager_amount * 2;
+    fn store_persistent<K, V>(env: &Env, key: &K, value: &V)
+    where
+        K: IntoVal<Env, Val>,
+        V: IntoVal<Env, Val>,
+    {
+        let storage = env.storage().persistent();
+        storage.set(key, value);
+        storage.extend_ttl(key, PERSISTENT_TTL_THRESHOLD, PERSISTENT_TTL_EXTEND);
+    }
 
-        // --- #200: safe fee math -------------------------------------------------
-        // Multiplying first keeps precision; dividing by 1000 rounds down (floor).
-        // fee_bips is validated to be ≤ 1000 at configuration time, so overflow
-        // cannot occur for any realistic i128 wager amount.
-        let (payout, fee) = if treasury_addr_opt.is_some() && fee_bips > 0 {
-            let fee = (total_pool * fee_bips as i128) / 1000;
-            (total_pool - fee, fee)
+    /// Store a sorted index entry, dropping it once it is empty.
+    fn store_ids<K: IntoVal<Env, Val>>(env: &Env, key: &K, ids: &Vec<u64>) {
+        if ids.is_empty() {
+            env.storage().persistent().remove(key);
         } else {
-            (total_pool, 0)
-        };
-        // -------------------------------------------------------------------------
+            Self::store_persistent(env, key, ids);
+        }
+    }
 
-        // Deduct both stakes first (clean state, prevents double-spend)
+    /// Number of history pages for `player`. Every page but the last holds
+    /// exactly INDEX_PAGE_SIZE ids.
+    fn history_pages(env: &Env, player: &Address) -> u32 {
+        let count: u64 = env
+            .storage()
+            .persistent()
+            .get(&(PLAYER_GAMES, player.clone()))
+            .unwrap_or(0);
+        count.div_ceil(INDEX_PAGE_SIZE) as u32
+    }
+
+    fn history_page(env: &Env, player: &Address, page: u32) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(PLAYER_GAMES, player.clone(), page))
+            .unwrap_or(Vec::new(env))
+    }
+
+    /// First history page holding an id greater than `after` (`pages` if
+    /// none does), found by binary search over the pages' last ids.
+    fn history_seek(env: &Env, player: &Address, pages: u32, after: u64) -> u32 {
+        let (mut low, mut high) = (0, pages);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match Self::history_page(env, player, mid).last() {
+                Some(last) if last > after => high = mid,
+                _ => low = mid + 1,
+            }
+        }
+        low
+    }
+
+    /// Add `id` to `player`'s history, keeping it sorted. Ids mostly arrive in
+    /// ascending order; joining an older game shifts the later ids along.
+    fn history_add(env: &Env, player: &Address, id: u64) {
+        let pages = Self::history_pages(env, player);
+        let mut page_no = Self::history_seek(env, player, pages, id.saturating_sub(1));
+        if page_no == pages && pages > 0 {
+            page_no = pages - 1;
+        }
+
+        let mut page = Self::history_page(env, player, page_no);
+        match page.binary_search(id) {
+            Ok(_) => return,
+            Err(pos) => page.insert(pos, id),
+        }
+        while page.len() as u64 > INDEX_PAGE_SIZE {
+            let carry = page.pop_back().unwrap();
+            Self::store_persistent(env, &(PLAYER_GAMES, player.clone(), page_no), &page);
+            page_no += 1;
+            page = Self::history_page(env, player, page_no);
+            page.push_front(carry);
+        }
+        Self::store_persistent(env, &(PLAYER_GAMES, player.clone(), page_no), &page);
+
+        let count_key = (PLAYER_GAMES, player.clone());
+        let count: u64 = env.storage().persistent().get(&count_key).unwrap_or(0);
+        Self::store_persistent(env, &count_key, &(count + 1));
+    }
+
+    /// Unfinished games of `player`. These leave the index as soon as they
+    /// end, so a single entry stays small.
+    fn active_ids(env: &Env, player: &Address) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(PLAYER_ACTIVE, player.clone()))
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn active_add(env: &Env, player: &Address, id: u64) {
+        let mut ids = Self::active_ids(env, player);
+        if let Err(pos) = ids.binary_search(id) {
+            ids.insert(pos, id);
+            Self::store_ids(env, &(PLAYER_ACTIVE, player.clone()), &ids);
+        }
+    }
+
+    fn active_remove(env: &Env, player: &Address, id: u64) {
+        let mut ids = Self::active_ids(env, player);
+        if let Ok(pos) = ids.binary_search(id) {
+            ids.remove(pos);
+            Self::store_ids(env, &(PLAYER_ACTIVE, player.clone()), &ids);
+        }
+    }
+
+    /// Open games whose ids fall in `bucket` (ids `bucket * INDEX_PAGE_SIZE`
+    /// onwards).
+    fn open_bucket(env: &Env, bucket: u64) -> Vec<u64> {
+        env.storage()
+            .persistent()
+            .get(&(OPEN_GAMES, bucket))
+            .unwrap_or(Vec::new(env))
+    }
+
+    fn open_add(env: &Env, id: u64) {
+        let bucket = id / INDEX_PAGE_SIZE;
+        let mut ids = Self::open_bucket(env, bucket);
+        if let Err(pos) = ids.binary_search(id) {
+            ids.insert(pos, id);
+            Self::store_ids(env, &(OPEN_GAMES, bucket), &ids);
+        }
+    }
+
+    /// Remove `id` from the open games. When that empties the first bucket,
+    /// move OPEN_HEAD past empty buckets (a few per call) so listings from
+    /// the start skip them.
+    fn open_remove(env: &Env, id: u64) {
+        let bucket = id / INDEX_PAGE_SIZE;
+        let mut ids = Self::open_bucket(env, bucket);
+        let Ok(pos) = ids.binary_search(id) else {
+            return;
+        };
+        ids.remove(pos);
+        Self::store_ids(env, &(OPEN_GAMES, bucket), &ids);
+
+        let mut head: u64 = env.storage().instance().get(&OPEN_HEAD).unwrap_or(0);
+        if !ids.is_empty() || bucket != head {
+            return;
+        }
+        let last_bucket = env
+            .storage()
+            .instance()
+            .get::<_, u64>(&GAME_COUNTER)
+            .unwrap_or(0)
+            / INDEX_PAGE_SIZE;
+        let stop = head + OPEN_SCAN_BUCKETS;
+        while head < last_bucket
+            && head < stop
+            && !env.storage().persistent().has(&(OPEN_GAMES, head))
+        {
+            head += 1;
+        }
+        env.storage().instance().set(&OPEN_HEAD, &head);
+    }
+
+    /// Bookkeeping for a game that has just reached a terminal state: resolve
+    /// its prediction pool and drop it from the open / active indexes.
+    fn finish_game(env: &Env, game: &Game) {
+        Self::resolve_prediction_pool(env, game);
+        Self::report_agent_results(env, game);
+        Self::open_remove(env, game.id);
+        Self::active_remove(env, &game.player1, game.id);
+        if let Some(ref player2) = game.player2 {
+            Self::active_remove(env, player2, game.id);
+        }
+    }
+
+    // ── Internal payout helpers ───────────────────────────────────────────────
+
+    fn process_draw_payout(env: &Env, game: &Game) -> Result<(), ContractError> {
+        let token_client = TokenClient::new(env, &game.token);
+        let contract_address = env.current_contract_address();
+
+        let mut escrow = Self::load_escrow(env, &game.token);
+
+        // Return player1's stake
+        token_client.transfer(&contract_address, &game.player1, &game.wager_amount);
+        events::payout(
+            env,
+            events::REFUND,
+            game,
+            &game.player1,
+            game.wager_amount,
+            0,
+        );
+        let player1_escrow = escrow.get(game.player1.clone()).unwrap_or(0);
+        escrow.set(game.player1.clone(), player1_escrow - game.wager_amount);
+
+        // Return player2's stake
+        if let Some(ref player2) = game.player2 {
+            token_client.transfer(&contract_address, player2, &game.wager_amount);
+            events::payout(env, events::REFUND, game, player2, game.wager_amount, 0);
+            let player2_escrow = escrow.get(player2.clone()).unwrap_or(0);
+            escrow.set(player2.clone(), player2_escrow - game.wager_amount);
+        }
+
+        Self::store_escrow(env, &game.token, &escrow);
+        Ok(())
+    }
+
+    /// #200 – Treasury fee redirection in payout_winner.
+    ///
+    /// Uses Soroban-safe integer arithmetic:
+    ///   `fee    = total_pool * fee_bips / 1000`
+    ///   `payout = total_pool - fee`
+    ///
+    /// Example: 10 XLM pool, fee_bips = 20 (2 %)
+    ///   fee    = 10 * 20 / 1000 = 0.2 XLM  → Treasury
+    ///   payout = 10 - 0.2       = 9.8 XLM  → Winner
+    fn process_payout(env: &Env, game: &Game, winner: &Address) -> Result<(), ContractError> {
+        let mut escrow = Self::load_escrow(env, &game.token);
+
+        let total_pool = game.wager_amount * 2;
+        let (payout, fee, treasury_addr_opt) = Self::protocol_fee(env, total_pool);
+
+        // Deduct both stakes first (clean state, prevents double-spend)
         let player1_escrow = escrow.get(game.player1.clone()).unwrap_or(0);
         escrow.set(game.player1.clone(), player1_escrow - game.wager_amount);
 
@@ -877,10 +1568,10 @@ impl GameContract {
             escrow.set(treasury_addr.clone(), treasury_escrow + fee);
         }
 
-        env.storage().instance().set(&ESCROW, &escrow);
+        Self::store_escrow(env, &game.token, &escrow);
 
         // Physical token transfers
-        let token_client = Self::token_client(env);
+        let token_client = TokenClient::new(env, &game.token);
         let contract_address = env.current_contract_address();
 
         token_client.transfer(&contract_address, winner, &payout);
@@ -888,11 +1579,31 @@ impl GameContract {
             && let Some(ref treasury_addr) = treasury_addr_opt
         {
             token_client.transfer(&contract_address, treasury_addr, &fee);
+            Self::record_treasury_fee(env, &game.token, fee);
         }
+        events::payout(env, events::WINNER, game, winner, payout, fee);
 
         Ok(())
     }
 
+    /// Split `amount` into `(net, fee, treasury)` using the configured fee.
+    fn protocol_fee(env: &Env, amount: i128) -> (i128, i128, Option<Address>) {
+        let fee_bips: u32 = env.storage().instance().get(&FEE_BIPS).unwrap_or(0);
+        let treasury_addr_opt: Option<Address> = env.storage().instance().get(&TREASURY_ADDR);
+
+        // --- #200: safe fee math -------------------------------------------------
+        // Multiplying first keeps precision; dividing by 1000 rounds down (floor).
+        // fee_bips is validated to be ≤ 1000 at configuration time, so overflow
+        // cannot occur for any realistic i128 wager amount.
+        if treasury_addr_opt.is_some() && fee_bips > 0 {
+            let fee = (amount * fee_bips as i128) / 1000;
+            (amount - fee, fee, treasury_addr_opt)
+        } else {
+            (amount, 0, treasury_addr_opt)
+        }
+        // -------------------------------------------------------------------------
+    }
+
     // ── Administration ────────────────────────────────────────────────────────
 
     /// Initialize puzzle-reward system (#199) and fee configuration (#200).
@@ -934,6 +1645,7 @@ impl GameContract {
             .instance()
             .set(&TREASURY_ADDR, &treasury_address);
         env.storage().instance().set(&MAX_STAKE, &1_000i128);
+        env.storage().instance().set(&SCHEMA_VER, &SCHEMA_VERSION);
     }
 
     pub fn set_max_stake(env: Env, admin: Address, new_limit: i128) {
@@ -946,10 +1658,13 @@ impl GameContract {
         if admin != current_admin {
             panic!("Unauthorized admin address");
         }
+        if Self::governance_enabled(&env) {
+            panic!("Governance approval required");
+        }
         if new_limit <= 0 {
             panic!("Max stake must be positive");
         }
-        env.storage().instance().set(&MAX_STAKE, &new_limit);
+        Self::apply_governance_action(&env, &GovernanceAction::SetMaxStake(new_limit));
     }
 
     pub fn configure_fees(env: Env, admin: Address, fee_bips: u32, treasury_address: Address) {
@@ -963,14 +1678,45 @@ impl GameContract {
         if admin != current_admin {
             panic!("Unauthorized admin address");
         }
+        if Self::governance_enabled(&env) {
+            panic!("Governance approval required");
+        }
         if fee_bips > 1000 {
             panic!("Fee bips must be between 0 and 1000");
         }
 
-        env.storage().instance().set(&FEE_BIPS, &fee_bips);
-        env.storage()
+        Self::apply_governance_action(&env, &GovernanceAction::SetFees(fee_bips, treasury_address));
+    }
+
+    /// Replace the ed25519 key `claim_win`, `claim_draw`, `claim_puzzle_reward`
+    /// and `verify_sep10_challenge` check signatures against. Signatures made
+    /// with the old key are rejected from the next call on.
+    pub fn rotate_admin_key(
+        env: Env,
+        admin: Address,
+        admin_public_key: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        let current_admin: Address = env
+            .storage()
             .instance()
-            .set(&TREASURY_ADDR, &treasury_address);
+            .get(&CONTRACT_ADMIN)
+            .expect("Not initialized");
+        current_admin.require_auth();
+        if admin != current_admin {
+            return Err(ContractError::Unauthorized);
+        }
+        if Self::governance_enabled(&env) {
+            return Err(ContractError::GovernanceRequired);
+        }
+
+        Self::apply_governance_action(&env, &GovernanceAction::SetAdminKey(admin_public_key));
+        Ok(())
+    }
+
+    /// The ed25519 key settlement signatures are currently checked against.
+    pub fn get_admin_key(env: Env) -> Option<BytesN<32>> {
+        let key: Option<Bytes> = env.storage().instance().get(&ADMIN_KEY);
+        key.and_then(|key| key.try_into().ok())
     }
 
     pub fn upgrade_admin(env: Env, admin: Address) {
@@ -984,6 +1730,97 @@ impl GameContract {
         env.storage().instance().set(&CONTRACT_ADMIN, &admin);
     }
 
+    // ── Multi-asset wagering ──────────────────────────────────────────────────
+    //
+    // Each game escrows a single asset. The default token from
+    // `initialize_token` is always accepted; any other SAC or custom token must
+    // be allowlisted by the admin before `create_game_with_token` accepts it.
+    // Escrow ledgers and collected treasury fees are tracked per token.
+
+    /// Add `token` to the wager allowlist.
+    pub fn allow_token(env: Env, admin: Address, token: Address) -> Result<(), ContractError> {
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&CONTRACT_ADMIN)
+            .expect("Not initialized");
+        current_admin.require_auth();
+        if admin != current_admin {
+            return Err(ContractError::Unauthorized);
+        }
+        if Self::governance_enabled(&env) {
+            return Err(ContractError::GovernanceRequired);
+        }
+
+        Self::apply_governance_action(&env, &GovernanceAction::AllowToken(token));
+        Ok(())
+    }
+
+    /// Remove `token` from the wager allowlist. Games already wagered in the
+    /// token are unaffected and still settle in it.
+    pub fn disallow_token(env: Env, admin: Address, token: Address) -> Result<(), ContractError> {
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&CONTRACT_ADMIN)
+            .expect("Not initialized");
+        current_admin.require_auth();
+        if admin != current_admin {
+            return Err(ContractError::Unauthorized);
+        }
+        if Self::governance_enabled(&env) {
+            return Err(ContractError::GovernanceRequired);
+        }
+
+        Self::apply_governance_action(&env, &GovernanceAction::DisallowToken(token));
+        Ok(())
+    }
+
+    /// Returns true if `token` may be used as a wager asset.
+    pub fn is_token_allowed(env: Env, token: Address) -> bool {
+        let allowed: Map<Address, bool> = env
+            .storage()
+            .instance()
+            .get(&ALLOWED_TOKENS)
+            .unwrap_or(Map::new(&env));
+        if allowed.get(token.clone()).unwrap_or(false) {
+            return true;
+        }
+        let default_token: Option<Address> = env.storage().instance().get(&TOKEN_CONTRACT);
+        default_token == Some(token)
+    }
+
+    /// List the allowlisted wager tokens (excluding the implicit default token).
+    pub fn get_allowed_tokens(env: Env) -> Vec<Address> {
+        let allowed: Map<Address, bool> = env
+            .storage()
+            .instance()
+            .get(&ALLOWED_TOKENS)
+            .unwrap_or(Map::new(&env));
+        allowed.keys()
+    }
+
+    /// Address protocol fees are paid to. Also read by the ai_nft
+    /// marketplace so its platform fee follows fee governance.
+    pub fn get_treasury_address(env: Env) -> Option<Address> {
+        env.storage().instance().get(&TREASURY_ADDR)
+    }
+
+    /// Total protocol fees sent to the treasury in `token`.
+    pub fn get_treasury_fees(env: Env, token: Address) -> i128 {
+        let fees: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&TREASURY_FEES)
+            .unwrap_or(Map::new(&env));
+        fees.get(token).unwrap_or(0)
+    }
+
+    /// Escrowed balance of `address` in `token`.
+    pub fn get_escrow_balance(env: Env, token: Address, address: Address) -> i128 {
+        Self::load_escrow(&env, &token).get(address).unwrap_or(0)
+    }
+
     // ── #199 – claim_puzzle_reward ────────────────────────────────────────────
     //
     // Accepts a backend ED25519 signature that proves the user solved a puzzle,
@@ -1003,6 +1840,8 @@ impl GameContract {
         nonce: u64,
         signature: BytesN<64>,
     ) -> Result<(), ContractError> {
+        Self::require_not_paused(&env, PauseCategory::PuzzleRewards)?;
+
         if reward_amount <= 0 || reward_amount > i64::MAX as i128 {
             return Err(ContractError::InvalidAmount);
         }
@@ -1105,7 +1944,8 @@ impl GameContract {
 
     /// Configure dispute resolution system
     /// * `arbitrator` - Address of the dispute arbitrator
-    /// * `dispute_fee` - Fee required to file a dispute (in tokens)
+    /// * `dispute_fee` - Fee required to file a dispute, in units of the
+    ///   disputed game's wager token
     pub fn configure_dispute_system(
         env: Env,
         admin: Address,
@@ -1122,12 +1962,17 @@ impl GameContract {
         if admin != current_admin {
             panic!("Unauthorized admin address");
         }
+        if Self::governance_enabled(&env) {
+            panic!("Governance approval required");
+        }
         if dispute_fee < 0 {
             panic!("Dispute fee must be non-negative");
         }
 
-        env.storage().instance().set(&ARBITRATOR, &arbitrator);
-        env.storage().instance().set(&DISPUTE_FEE, &dispute_fee);
+        Self::apply_governance_action(
+            &env,
+            &GovernanceAction::ConfigureDisputeSystem(arbitrator, dispute_fee),
+        );
     }
 
     /// Configure timeout duration (in ledger sequences)
@@ -1142,11 +1987,14 @@ impl GameContract {
         if admin != current_admin {
             panic!("Unauthorized admin address");
         }
+        if Self::governance_enabled(&env) {
+            panic!("Governance approval required");
+        }
         if duration == 0 {
             panic!("Timeout duration must be greater than 0");
         }
 
-        env.storage().instance().set(&TIMEOUT_DURATION, &duration);
+        Self::apply_governance_action(&env, &GovernanceAction::ConfigureTimeout(duration));
     }
 
     /// File a dispute against the opponent for a game.
@@ -1157,13 +2005,7 @@ impl GameContract {
         against: Address,
         reason: Bytes,
     ) -> Result<u64, ContractError> {
-        let games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
-
-        let game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::InProgress {
             return Err(ContractError::NotDisputable);
@@ -1181,9 +2023,10 @@ impl GameContract {
 
         filer.require_auth();
 
+        // The fee is paid in the disputed game's wager token.
         let dispute_fee: i128 = env.storage().instance().get(&DISPUTE_FEE).unwrap_or(0);
         if dispute_fee > 0 {
-            let token_client = Self::token_client(&env);
+            let token_client = TokenClient::new(&env, &game.token);
             let contract_address = env.current_contract_address();
 
             if token_client.balance(&filer) < dispute_fee {
@@ -1202,7 +2045,7 @@ impl GameContract {
         let dispute = Dispute {
             id: dispute_counter,
             game_id,
-            filer: filer.clone(),
+            filer,
             against,
             reason,
             status: DisputeStatus::Pending,
@@ -1215,14 +2058,10 @@ impl GameContract {
             .instance()
             .get(&DISPUTES)
             .unwrap_or(Map::new(&env));
+        events::dispute(&env, events::FILED, &dispute, None);
         disputes.set(dispute_counter, dispute);
         env.storage().instance().set(&DISPUTES, &disputes);
 
-        env.events().publish(
-            (symbol_short!("dispute"), symbol_short!("filed")),
-            (dispute_counter, filer),
-        );
-
         Ok(dispute_counter)
     }
 
@@ -1234,13 +2073,9 @@ impl GameContract {
         game_id: u64,
         claimant: Address,
     ) -> Result<(), ContractError> {
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
+        Self::require_not_paused(&env, PauseCategory::Payouts)?;
 
-        let mut game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let mut game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::InProgress {
             return Err(ContractError::GameNotInProgress);
@@ -1281,21 +2116,16 @@ impl GameContract {
         Self::process_payout(&env, &game, &claimant)?;
         game.state = GameState::Settled;
 
-        games.set(game_id, game);
-        env.storage().instance().set(&GAMES, &games);
-
-        env.events().publish(
-            (symbol_short!("timeout"), game_id),
-            (claimant, timeout_duration),
-        );
+        events::game(&env, events::TIMEOUT, &game);
+        Self::finish_game(&env, &game);
+        Self::store_game(&env, &game);
 
         Ok(())
     }
 
     /// Query remaining time before timeout (in ledger sequences).
     pub fn get_timeout_remaining(env: Env, game_id: u64) -> Option<u64> {
-        let games: Map<u64, Game> = env.storage().instance().get(&GAMES)?;
-        let game = games.get(game_id)?;
+        let game = Self::load_game(&env, game_id).ok()?;
 
         if game.state != GameState::InProgress {
             return None;
@@ -1344,14 +2174,7 @@ impl GameContract {
             return Err(ContractError::GameAlreadyCompleted);
         }
 
-        let mut games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
-        let mut game = games
-            .get(dispute.game_id)
-            .ok_or(ContractError::GameNotFound)?;
+        let mut game = Self::load_game(&env, dispute.game_id)?;
 
         if game.state != GameState::InProgress {
             return Err(ContractError::GameAlreadyCompleted);
@@ -1362,6 +2185,8 @@ impl GameContract {
                 if *winner_addr != game.player1 && Some(winner_addr.clone()) != game.player2 {
                     return Err(ContractError::NotPlayer);
                 }
+                // A draw only refunds stakes, so it stays available while paused.
+                Self::require_not_paused(&env, PauseCategory::Payouts)?;
                 game.state = GameState::Completed;
                 game.winner = Some(winner_addr.clone());
                 Self::process_payout(&env, &game, winner_addr)?;
@@ -1374,19 +2199,21 @@ impl GameContract {
             }
         }
 
-        games.set(dispute.game_id, game);
-        env.storage().instance().set(&GAMES, &games);
+        let action = if game.state == GameState::Drawn {
+            events::DRAWN
+        } else {
+            events::SETTLED
+        };
+        events::game(&env, action, &game);
+        Self::finish_game(&env, &game);
+        Self::store_game(&env, &game);
 
         dispute.status = DisputeStatus::Resolved;
         dispute.resolution = Some(resolution);
+        events::dispute(&env, events::SOLVED, &dispute, winner);
         disputes.set(dispute_id, dispute);
         env.storage().instance().set(&DISPUTES, &disputes);
 
-        env.events().publish(
-            (symbol_short!("dispute"), symbol_short!("solved")),
-            (dispute_id, winner),
-        );
-
         Ok(())
     }
 
@@ -1430,23 +2257,20 @@ impl GameContract {
         dispute.status = DisputeStatus::Rejected;
         dispute.resolution = Some(reason);
         let filer = dispute.filer.clone();
+        let game_id = dispute.game_id;
+        events::dispute(&env, events::REJECTED, &dispute, None);
         disputes.set(dispute_id, dispute);
         env.storage().instance().set(&DISPUTES, &disputes);
 
-        // Refund dispute fee to filer
+        // Refund dispute fee to filer, in the token it was paid in
         let dispute_fee: i128 = env.storage().instance().get(&DISPUTE_FEE).unwrap_or(0);
         if dispute_fee > 0 {
-            let token_client = Self::token_client(&env);
+            let game = Self::load_game(&env, game_id)?;
+            let token_client = TokenClient::new(&env, &game.token);
             let contract_address = env.current_contract_address();
             token_client.transfer(&contract_address, &filer, &dispute_fee);
         }
 
-        // Emit dispute rejected event
-        env.events().publish(
-            (symbol_short!("dispute"), symbol_short!("reject")),
-            (dispute_id, filer),
-        );
-
         Ok(())
     }
 
@@ -1629,7 +2453,8 @@ impl GameContract {
 
     /// Configure the multi-sig signer set and approval threshold.
     ///
-    /// Can only be called by the contract admin.
+    /// Can only be called by the contract admin, and only once; later changes
+    /// go through a `GovernanceAction::SetSigners` proposal.
     /// `threshold` must be ≥ 1 and ≤ `signers.len()`.
     pub fn configure_multisig(
         env: Env,
@@ -1646,22 +2471,21 @@ impl GameContract {
         if admin != current_admin {
             return Err(ContractError::Unauthorized);
         }
-
-        let n = signers.len();
-        if threshold == 0 || threshold > n {
-            return Err(ContractError::InvalidThreshold);
+        if Self::governance_enabled(&env) {
+            return Err(ContractError::GovernanceRequired);
         }
 
-        env.storage().instance().set(&MULTISIG_SIGNERS, &signers);
-        env.storage().instance().set(&MULTISIG_THRESHOLD, &threshold);
-
+        let action = GovernanceAction::SetSigners(signers, threshold);
+        Self::validate_governance_action(&action)?;
+        Self::apply_governance_action(&env, &action);
         Ok(())
     }
 
     /// Propose a new fee configuration.
     ///
     /// Replaces any existing pending proposal. The proposer's approval is
-    /// counted automatically.
+    /// counted automatically. Equivalent to proposing
+    /// `GovernanceAction::SetFees`; kept for existing integrations.
     pub fn propose_fee_change(
         env: Env,
         proposer: Address,
@@ -1703,10 +2527,7 @@ impl GameContract {
             .instance()
             .set(&FEE_PROPOSAL_APPROVALS, &approvals);
 
-        env.events().publish(
-            (symbol_short!("multisig"), symbol_short!("proposed")),
-            proposer,
-        );
+        events::fee_proposal(&env, events::PROPOSED, &proposer, &proposal, 1);
 
         Ok(())
     }
@@ -1748,8 +2569,14 @@ impl GameContract {
             .instance()
             .set(&FEE_PROPOSAL_APPROVALS, &approvals);
 
-        // Count approvals
-        let approval_count = approvals.len();
+        // Count approvals from the current signer set
+        let approval_count = approvals
+            .keys()
+            .iter()
+            .filter(|approver| signers.contains(approver))
+            .count() as u32;
+        events::fee_proposal(&env, events::APPROVED, &signer, &proposal, approval_count);
+
         let threshold: u32 = env
             .storage()
             .instance()
@@ -1769,19 +2596,11 @@ impl GameContract {
             env.storage().instance().remove(&PENDING_FEE_PROPOSAL);
             env.storage().instance().remove(&FEE_PROPOSAL_APPROVALS);
 
-            env.events().publish(
-                (symbol_short!("multisig"), symbol_short!("executed")),
-                proposal.new_fee_bips,
-            );
+            events::fee_proposal(&env, events::EXECUTED, &signer, &proposal, approval_count);
 
             return Ok(true); // proposal executed
         }
 
-        env.events().publish(
-            (symbol_short!("multisig"), symbol_short!("approved")),
-            signer,
-        );
-
         Ok(false) // still waiting for more approvals
     }
 
@@ -1797,23 +2616,19 @@ impl GameContract {
             return Err(ContractError::NotASigner);
         }
 
-        if !env
+        let proposal: FeeProposal = env
             .storage()
             .instance()
-            .has(&PENDING_FEE_PROPOSAL)
-        {
-            return Err(ContractError::NoProposal);
-        }
+            .get(&PENDING_FEE_PROPOSAL)
+            .ok_or(ContractError::NoProposal)?;
 
         signer.require_auth();
 
+        let approvals = Self::get_approval_count(env.clone());
         env.storage().instance().remove(&PENDING_FEE_PROPOSAL);
         env.storage().instance().remove(&FEE_PROPOSAL_APPROVALS);
 
-        env.events().publish(
-            (symbol_short!("multisig"), symbol_short!("cancel")),
-            signer,
-        );
+        events::fee_proposal(&env, events::CANCEL, &signer, &proposal, approvals);
 
         Ok(())
     }
@@ -1833,42 +2648,690 @@ impl GameContract {
         approvals.len()
     }
 
-    // ── SEP-40 Oracle Clock Sync (#533) ───────────────────────────────────────
+    // ── Governance proposals ──────────────────────────────────────────────────
     //
-    // SEP-40 defines a standard oracle interface on Stellar/Soroban.
-    // We store the oracle contract address and call its `lastprice` function
-    // to get a trusted timestamp for game clock synchronization.
+    // Generalises the fee multisig above to every admin setter. Once
+    // `configure_multisig` has been called, it and `set_max_stake`,
+    // `configure_fees`, `configure_timeout`, `configure_dispute_system`,
+    // `configure_oracle`, `configure_tournament_timelock`, `allow_token`,
+    // `disallow_token`, `rotate_admin_key`, `set_circuit_breaker`,
+    // `set_agent_nft`, `configure_slashing` and `configure_prediction_pools`
+    // refuse direct calls; the same changes are made by proposing a
+    // `GovernanceAction` and collecting approvals.
+    //
+    // Unlike the single pending fee proposal, any number of proposals can be
+    // open at once. Each can be approved until `expires_at`; the approval that
+    // reaches the threshold applies the change and closes the proposal.
 
-    /// Configure the SEP-40 oracle contract address for clock sync.
-    pub fn configure_oracle(env: Env, admin: Address, oracle: Address) -> Result<(), ContractError> {
-        let current_admin: Address = env
+    /// Open a governance proposal. The proposer's approval is counted, so with
+    /// a threshold of 1 the change is applied immediately.
+    pub fn propose_governance_action(
+        env: Env,
+        proposer: Address,
+        action: GovernanceAction,
+    ) -> Result<u64, ContractError> {
+        Self::require_signer(&env, &proposer)?;
+        Self::validate_governance_action(&action)?;
+        proposer.require_auth();
+
+        let mut counter: u64 = env.storage().instance().get(&GOV_COUNTER).unwrap_or(0);
+        counter += 1;
+        env.storage().instance().set(&GOV_COUNTER, &counter);
+
+        let lifetime: u64 = env
             .storage()
             .instance()
-            .get(&CONTRACT_ADMIN)
-            .expect("Not initialized");
-        current_admin.require_auth();
-        if admin != current_admin {
-            return Err(ContractError::Unauthorized);
-        }
-        env.storage().instance().set(&ORACLE_CONTRACT, &oracle);
-        Ok(())
+            .get(&GOV_LIFETIME)
+            .unwrap_or(DEFAULT_PROPOSAL_LIFETIME);
+        let now = env.ledger().sequence() as u64;
+        let proposal = GovernanceProposal {
+            id: counter,
+            action,
+            proposer: proposer.clone(),
+            approvals: Vec::from_array(&env, [proposer.clone()]),
+            proposed_at: now,
+            expires_at: now + lifetime,
+        };
+
+        events::governance(&env, events::PROPOSED, &proposer, &proposal);
+        Self::record_governance_approval(&env, &proposer, proposal);
+
+        Ok(counter)
     }
 
-    /// Get the current oracle contract address.
-    pub fn get_oracle(env: Env) -> Result<Address, ContractError> {
-        env.storage()
+    /// Approve an open governance proposal. Returns `true` when this approval
+    /// reached the threshold and the change was applied.
+    pub fn approve_governance_action(
+        env: Env,
+        signer: Address,
+        proposal_id: u64,
+    ) -> Result<bool, ContractError> {
+        Self::require_signer(&env, &signer)?;
+
+        let proposals: Map<u64, GovernanceProposal> = env
+            .storage()
             .instance()
-            .get(&ORACLE_CONTRACT)
-            .ok_or(ContractError::OracleNotConfigured)
+            .get(&GOV_PROPOSALS)
+            .unwrap_or(Map::new(&env));
+        let mut proposal = proposals
+            .get(proposal_id)
+            .ok_or(ContractError::NoProposal)?;
+
+        if env.ledger().sequence() as u64 > proposal.expires_at {
+            return Err(ContractError::ProposalExpired);
+        }
+        if proposal.approvals.contains(&signer) {
+            return Err(ContractError::AlreadyApproved);
+        }
+
+        signer.require_auth();
+
+        proposal.approvals.push_back(signer.clone());
+        events::governance(&env, events::APPROVED, &signer, &proposal);
+        Ok(Self::record_governance_approval(&env, &signer, proposal))
     }
 
-    /// Get the oracle-synced timestamp (ledger sequence from oracle).
-    /// Falls back to the current ledger sequence if oracle is not configured.
-    /// The oracle is called via cross-contract invocation using the SEP-40
-    /// `lastprice` interface which returns a price record with a timestamp.
-    ///
-    /// Note: This is a minimal implementation. In production, you would invoke
-    /// the oracle contract's `lastprice` method and extract the timestamp.
+    /// Discard an open governance proposal (any signer may cancel).
+    pub fn cancel_governance_action(
+        env: Env,
+        signer: Address,
+        proposal_id: u64,
+    ) -> Result<(), ContractError> {
+        Self::require_signer(&env, &signer)?;
+
+        let mut proposals: Map<u64, GovernanceProposal> = env
+            .storage()
+            .instance()
+            .get(&GOV_PROPOSALS)
+            .unwrap_or(Map::new(&env));
+        let proposal = proposals
+            .get(proposal_id)
+            .ok_or(ContractError::NoProposal)?;
+
+        signer.require_auth();
+
+        proposals.remove(proposal_id);
+        env.storage().instance().set(&GOV_PROPOSALS, &proposals);
+
+        events::governance(&env, events::CANCEL, &signer, &proposal);
+        Ok(())
+    }
+
+    /// Query an open governance proposal.
+    pub fn get_governance_proposal(env: Env, proposal_id: u64) -> Option<GovernanceProposal> {
+        let proposals: Map<u64, GovernanceProposal> =
+            env.storage().instance().get(&GOV_PROPOSALS)?;
+        proposals.get(proposal_id)
+    }
+
+    /// All open governance proposals, including expired ones that have not
+    /// been cancelled yet.
+    pub fn get_governance_proposals(env: Env) -> Vec<GovernanceProposal> {
+        let proposals: Map<u64, GovernanceProposal> = env
+            .storage()
+            .instance()
+            .get(&GOV_PROPOSALS)
+            .unwrap_or(Map::new(&env));
+        proposals.values()
+    }
+
+    fn governance_enabled(env: &Env) -> bool {
+        env.storage().instance().has(&MULTISIG_SIGNERS)
+    }
+
+    fn require_signer(env: &Env, signer: &Address) -> Result<(), ContractError> {
+        let signers: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&MULTISIG_SIGNERS)
+            .ok_or(ContractError::Unauthorized)?;
+        if !signers.contains(signer) {
+            return Err(ContractError::NotASigner);
+        }
+        Ok(())
+    }
+
+    /// Same bounds the direct setters enforce, reported as errors rather than
+    /// panics since proposals come from arbitrary signers.
+    fn validate_governance_action(action: &GovernanceAction) -> Result<(), ContractError> {
+        let valid = match action {
+            GovernanceAction::SetFees(fee_bips, _) => *fee_bips <= 1000,
+            GovernanceAction::SetMaxStake(limit) => *limit > 0,
+            GovernanceAction::ConfigureTimeout(duration)
+            | GovernanceAction::ConfigureTournamentTimelock(duration)
+            | GovernanceAction::SetProposalLifetime(duration) => *duration > 0,
+            GovernanceAction::ConfigureDisputeSystem(_, fee) => *fee >= 0,
+            GovernanceAction::ConfigureSlashing(slash_bps, _) => *slash_bps <= MAX_SLASH_BPS,
+            GovernanceAction::ConfigurePredictionPools(close_after_moves) => *close_after_moves > 0,
+            GovernanceAction::SetSigners(signers, threshold) => {
+                if *threshold == 0 || *threshold > signers.len() {
+                    return Err(ContractError::InvalidThreshold);
+                }
+                true
+            }
+            GovernanceAction::ConfigureOracle(_)
+            | GovernanceAction::UpgradeAdmin(_)
+            | GovernanceAction::UpgradeWasm(_)
+            | GovernanceAction::UpgradeContract(_, _)
+            | GovernanceAction::AllowToken(_)
+            | GovernanceAction::DisallowToken(_)
+            | GovernanceAction::SetCircuitBreaker(_)
+            | GovernanceAction::SetAgentNft(_)
+            | GovernanceAction::SetAdminKey(_) => true,
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(ContractError::InvalidAmount)
+        }
+    }
+
+    /// Store `proposal`, or apply and close it if it now has enough approvals.
+    fn record_governance_approval(
+        env: &Env,
+        signer: &Address,
+        proposal: GovernanceProposal,
+    ) -> bool {
+        let threshold: u32 = env
+            .storage()
+            .instance()
+            .get(&MULTISIG_THRESHOLD)
+            .unwrap_or(u32::MAX);
+        let signers: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&MULTISIG_SIGNERS)
+            .unwrap_or(Vec::new(env));
+        let mut proposals: Map<u64, GovernanceProposal> = env
+            .storage()
+            .instance()
+            .get(&GOV_PROPOSALS)
+            .unwrap_or(Map::new(env));
+
+        // Approvals from signers removed by a `SetSigners` proposal no longer
+        // count.
+        let approvals = proposal
+            .approvals
+            .iter()
+            .filter(|approver| signers.contains(approver))
+            .count() as u32;
+        let executed = approvals >= threshold;
+        if executed {
+            Self::apply_governance_action(env, &proposal.action);
+            proposals.remove(proposal.id);
+            events::governance(env, events::EXECUTED, signer, &proposal);
+        } else {
+            proposals.set(proposal.id, proposal);
+        }
+        env.storage().instance().set(&GOV_PROPOSALS, &proposals);
+        executed
+    }
+
+    /// The storage writes behind every admin setter, shared by the direct
+    /// (pre-multisig) path and executed governance proposals.
+    fn apply_governance_action(env: &Env, action: &GovernanceAction) {
+        let storage = env.storage().instance();
+        match action {
+            GovernanceAction::SetFees(fee_bips, treasury_address) => {
+                storage.set(&FEE_BIPS, fee_bips);
+                storage.set(&TREASURY_ADDR, treasury_address);
+            }
+            GovernanceAction::SetMaxStake(limit) => storage.set(&MAX_STAKE, limit),
+            GovernanceAction::ConfigureTimeout(duration) => {
+                storage.set(&TIMEOUT_DURATION, duration)
+            }
+            GovernanceAction::ConfigureDisputeSystem(arbitrator, dispute_fee) => {
+                storage.set(&ARBITRATOR, arbitrator);
+                storage.set(&DISPUTE_FEE, dispute_fee);
+            }
+            GovernanceAction::ConfigureOracle(oracle) => storage.set(&ORACLE_CONTRACT, oracle),
+            GovernanceAction::UpgradeAdmin(admin) => storage.set(&CONTRACT_ADMIN, admin),
+            GovernanceAction::ConfigureTournamentTimelock(duration) => {
+                storage.set(&TOURNAMENT_TIMELOCK, duration)
+            }
+            GovernanceAction::SetProposalLifetime(lifetime) => storage.set(&GOV_LIFETIME, lifetime),
+            // The new code takes over from the next invocation; run `migrate`
+            // afterwards if it bumps SCHEMA_VERSION.
+            GovernanceAction::UpgradeWasm(wasm_hash) => env
+                .deployer()
+                .update_current_contract_wasm(wasm_hash.clone()),
+            GovernanceAction::UpgradeContract(contract, wasm_hash) => {
+                UpgradeableClient::new(env, contract).upgrade(wasm_hash)
+            }
+            GovernanceAction::ConfigureSlashing(slash_bps, reporter) => {
+                storage.set(&SLASH_RATE, slash_bps);
+                storage.set(&SLASH_REPORTER, reporter);
+            }
+            GovernanceAction::SetSigners(signers, threshold) => {
+                storage.set(&MULTISIG_SIGNERS, signers);
+                storage.set(&MULTISIG_THRESHOLD, threshold);
+            }
+            GovernanceAction::AllowToken(token) => {
+                let mut allowed: Map<Address, bool> =
+                    storage.get(&ALLOWED_TOKENS).unwrap_or(Map::new(env));
+                allowed.set(token.clone(), true);
+                storage.set(&ALLOWED_TOKENS, &allowed);
+            }
+            GovernanceAction::DisallowToken(token) => {
+                let mut allowed: Map<Address, bool> =
+                    storage.get(&ALLOWED_TOKENS).unwrap_or(Map::new(env));
+                allowed.remove(token.clone());
+                storage.set(&ALLOWED_TOKENS, &allowed);
+            }
+            GovernanceAction::SetCircuitBreaker(breaker) => storage.set(&CIRCUIT_BREAKER, breaker),
+            GovernanceAction::SetAgentNft(nft_contract) => storage.set(&AGENT_NFT, nft_contract),
+            GovernanceAction::ConfigurePredictionPools(close_after_moves) => {
+                storage.set(&PRED_CLOSE_MOVES, close_after_moves)
+            }
+            GovernanceAction::SetAdminKey(key) => {
+                storage.set(&ADMIN_KEY, &Bytes::from(key.clone()))
+            }
+        }
+    }
+
+    // ── Upgrades ──────────────────────────────────────────────────────────────
+    //
+    // Contract code is only replaced through an `UpgradeWasm` governance
+    // proposal, so multisig must be configured first. Escrowed funds and games
+    // stay in place; `migrate` rewrites storage when a release changes its
+    // layout. `UpgradeContract` drives the same flow for ai_nft and
+    // game_registry once their upgrade authority is set to this contract.
+
+    /// Storage layout version currently persisted (v1 for deployments that
+    /// predate versioning and hold games).
+    pub fn schema_version(env: Env) -> u32 {
+        let storage = env.storage().instance();
+        storage.get(&SCHEMA_VER).unwrap_or(if storage.has(&GAMES) {
+            1
+        } else {
+            SCHEMA_VERSION
+        })
+    }
+
+    /// Bring persisted state up to `SCHEMA_VERSION`. Idempotent, so anyone
+    /// may call it after an upgrade. Returns the version now persisted; a
+    /// large migration takes several calls, so repeat until it returns
+    /// `SCHEMA_VERSION`.
+    pub fn migrate(env: Env) -> Result<u32, ContractError> {
+        let from = Self::schema_version(env.clone());
+        if from > SCHEMA_VERSION {
+            return Err(ContractError::SchemaTooNew);
+        }
+
+        if from < 2 && !Self::migrate_legacy_games(&env) {
+            env.storage().instance().set(&SCHEMA_VER, &from);
+            return Ok(from);
+        }
+
+        env.storage().instance().set(&SCHEMA_VER, &SCHEMA_VERSION);
+        Ok(SCHEMA_VERSION)
+    }
+
+    /// v1 → v2: move up to MIGRATE_BATCH games from the instance map into
+    /// their own entries and the paged indexes. True once none are left.
+    fn migrate_legacy_games(env: &Env) -> bool {
+        let storage = env.storage().instance();
+        let Some(mut legacy) = storage.get::<_, Map<u64, Game>>(&GAMES) else {
+            storage.remove(&OPEN_GAMES);
+            return true;
+        };
+
+        let ids = legacy.keys();
+        for id in ids.slice(0..ids.len().min(MIGRATE_BATCH)).iter() {
+            // Games touched since the upgrade already have their own entry,
+            // which is the current state.
+            let game = match env.storage().persistent().get::<_, Game>(&(GAMES, id)) {
+                Some(game) => game,
+                None => {
+                    let game = legacy.get(id).unwrap();
+                    Self::store_game(env, &game);
+                    game
+                }
+            };
+            let unfinished = matches!(game.state, GameState::Created | GameState::InProgress);
+            let mut players = Vec::from_array(env, [game.player1.clone()]);
+            if let Some(ref player2) = game.player2 {
+                players.push_back(player2.clone());
+            }
+            for player in players.iter() {
+                Self::history_add(env, &player, id);
+                if unfinished {
+                    Self::active_add(env, &player, id);
+                }
+                storage.remove(&(PLAYER_GAMES, player.clone()));
+                storage.remove(&(PLAYER_ACTIVE, player));
+            }
+            if game.state == GameState::Created && game.player2.is_none() && game.opponent.is_none()
+            {
+                Self::open_add(env, id);
+            }
+            legacy.remove(id);
+        }
+
+        if legacy.is_empty() {
+            storage.remove(&GAMES);
+            storage.remove(&OPEN_GAMES);
+            true
+        } else {
+            storage.set(&GAMES, &legacy);
+            false
+        }
+    }
+
+    // ── Circuit breaker ───────────────────────────────────────────────────────
+    //
+    // When a circuit-breaker contract is configured, fund-moving entrypoints
+    // ask it whether their category is paused before doing anything. Refunds
+    // (cancel_game, claim_draw, drawn dispute resolutions, reject_dispute) are
+    // never blocked, so players can always recover their stakes.
+
+    /// Point the contract at an `emergency_circuit_breaker` deployment.
+    pub fn set_circuit_breaker(
+        env: Env,
+        admin: Address,
+        breaker: Address,
+    ) -> Result<(), ContractError> {
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&CONTRACT_ADMIN)
+            .expect("Not initialized");
+        current_admin.require_auth();
+        if admin != current_admin {
+            return Err(ContractError::Unauthorized);
+        }
+        if Self::governance_enabled(&env) {
+            return Err(ContractError::GovernanceRequired);
+        }
+
+        Self::apply_governance_action(&env, &GovernanceAction::SetCircuitBreaker(breaker));
+        Ok(())
+    }
+
+    /// The configured circuit-breaker contract, if any.
+    pub fn get_circuit_breaker(env: Env) -> Option<Address> {
+        env.storage().instance().get(&CIRCUIT_BREAKER)
+    }
+
+    fn require_not_paused(env: &Env, category: PauseCategory) -> Result<(), ContractError> {
+        let breaker: Option<Address> = env.storage().instance().get(&CIRCUIT_BREAKER);
+        if let Some(breaker) = breaker
+            && CircuitBreakerClient::new(env, &breaker).is_category_paused(&category)
+        {
+            return Err(ContractError::Paused);
+        }
+        Ok(())
+    }
+
+    // ── Disconnect slashing ───────────────────────────────────────────────────
+    //
+    // The backend watches game connections. When a player drops and does not
+    // reconnect within its grace period (30 s), the configured reporter calls
+    // `slash_disconnect`. The game ends in the opponent's favour. The opponent
+    // gets their own stake back plus the slashed part of the absent player's
+    // stake, less the protocol fee, which goes to the treasury. The absent
+    // player gets the rest of their stake back. The rate defaults to 25 % and,
+    // like the reporter, is changed through governance once multisig is on.
+
+    /// Set the slash rate (basis points of the absent player's stake) and the
+    /// address allowed to report disconnects.
+    pub fn configure_slashing(
+        env: Env,
+        admin: Address,
+        slash_bps: u32,
+        reporter: Address,
+    ) -> Result<(), ContractError> {
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&CONTRACT_ADMIN)
+            .expect("Not initialized");
+        current_admin.require_auth();
+        if admin != current_admin {
+            return Err(ContractError::Unauthorized);
+        }
+        if Self::governance_enabled(&env) {
+            return Err(ContractError::GovernanceRequired);
+        }
+
+        let action = GovernanceAction::ConfigureSlashing(slash_bps, reporter);
+        Self::validate_governance_action(&action)?;
+        Self::apply_governance_action(&env, &action);
+        Ok(())
+    }
+
+    /// Current slash rate in basis points.
+    pub fn get_slash_rate(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&SLASH_RATE)
+            .unwrap_or(DEFAULT_SLASH_BPS)
+    }
+
+    /// End a game abandoned by `absent` and slash their stake. Reporter only.
+    /// Returns the slashed amount.
+    pub fn slash_disconnect(
+        env: Env,
+        game_id: u64,
+        absent: Address,
+    ) -> Result<i128, ContractError> {
+        Self::require_not_paused(&env, PauseCategory::Payouts)?;
+
+        let reporter: Address = env
+            .storage()
+            .instance()
+            .get(&SLASH_REPORTER)
+            .ok_or(ContractError::SlashingNotConfigured)?;
+        reporter.require_auth();
+
+        let mut game = Self::load_game(&env, game_id)?;
+
+        if game.state != GameState::InProgress {
+            return Err(ContractError::GameNotInProgress);
+        }
+        let player2 = game.player2.clone().ok_or(ContractError::GameFull)?;
+        let opponent = if absent == game.player1 {
+            player2
+        } else if absent == player2 {
+            game.player1.clone()
+        } else {
+            return Err(ContractError::NotPlayer);
+        };
+
+        let slashed =
+            game.wager_amount * Self::get_slash_rate(env.clone()) as i128 / MAX_SLASH_BPS as i128;
+        let (to_opponent, fee, treasury_addr_opt) = Self::protocol_fee(&env, slashed);
+        let opponent_payout = game.wager_amount + to_opponent;
+        let absent_refund = game.wager_amount - slashed;
+
+        let mut escrow = Self::load_escrow(&env, &game.token);
+        for player in [&absent, &opponent] {
+            let balance = escrow.get(player.clone()).unwrap_or(0);
+            escrow.set(player.clone(), balance - game.wager_amount);
+        }
+        Self::store_escrow(&env, &game.token, &escrow);
+
+        let token_client = TokenClient::new(&env, &game.token);
+        let contract_address = env.current_contract_address();
+        token_client.transfer(&contract_address, &opponent, &opponent_payout);
+        if absent_refund > 0 {
+            token_client.transfer(&contract_address, &absent, &absent_refund);
+        }
+        if fee > 0
+            && let Some(ref treasury_addr) = treasury_addr_opt
+        {
+            token_client.transfer(&contract_address, treasury_addr, &fee);
+            Self::record_treasury_fee(&env, &game.token, fee);
+        }
+
+        game.winner = Some(opponent.clone());
+        game.state = GameState::Forfeited;
+
+        events::payout(&env, events::WINNER, &game, &opponent, opponent_payout, fee);
+        events::payout(&env, events::REFUND, &game, &absent, absent_refund, 0);
+        events::game(&env, events::ABANDONED, &game);
+        Self::finish_game(&env, &game);
+        Self::store_game(&env, &game);
+
+        Ok(slashed)
+    }
+
+    // ── AI agent NFTs ─────────────────────────────────────────────────────────
+    //
+    // A player can field an ai_nft agent they own or rent in one of their
+    // games. When the game settles, the result is added to the NFT's on-chain
+    // record through `ai_nft::record_result`, which accepts calls from this
+    // contract once it is set as the result reporter. Cancelled games are not
+    // recorded.
+
+    /// Point the contract at the `ai_nft` deployment agents come from.
+    pub fn set_agent_nft(
+        env: Env,
+        admin: Address,
+        nft_contract: Address,
+    ) -> Result<(), ContractError> {
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&CONTRACT_ADMIN)
+            .expect("Not initialized");
+        current_admin.require_auth();
+        if admin != current_admin {
+            return Err(ContractError::Unauthorized);
+        }
+        if Self::governance_enabled(&env) {
+            return Err(ContractError::GovernanceRequired);
+        }
+
+        Self::apply_governance_action(&env, &GovernanceAction::SetAgentNft(nft_contract));
+        Ok(())
+    }
+
+    /// Field agent `nft_id` for `player` in a game. Allowed until the first
+    /// move, so a result always belongs to the agent that played it.
+    pub fn assign_agent(
+        env: Env,
+        game_id: u64,
+        player: Address,
+        nft_id: u64,
+    ) -> Result<(), ContractError> {
+        player.require_auth();
+
+        let nft_contract: Address = env
+            .storage()
+            .instance()
+            .get(&AGENT_NFT)
+            .ok_or(ContractError::AgentsNotConfigured)?;
+
+        let game = Self::load_game(&env, game_id)?;
+        if game.player1 != player && game.player2.as_ref() != Some(&player) {
+            return Err(ContractError::NotPlayer);
+        }
+        if !matches!(game.state, GameState::Created | GameState::InProgress)
+            || !game.moves.is_empty()
+        {
+            return Err(ContractError::GameNotInProgress);
+        }
+
+        // The current user is the renter while the NFT is rented out.
+        let user = AgentNftClient::new(&env, &nft_contract).try_user_of(&nft_id);
+        if !matches!(user, Ok(Ok(ref user)) if *user == player) {
+            return Err(ContractError::AgentNotOwned);
+        }
+
+        let mut all_agents: Map<u64, Map<Address, u64>> = env
+            .storage()
+            .instance()
+            .get(&GAME_AGENTS)
+            .unwrap_or(Map::new(&env));
+        let mut agents = all_agents.get(game_id).unwrap_or(Map::new(&env));
+        agents.set(player, nft_id);
+        all_agents.set(game_id, agents);
+        env.storage().instance().set(&GAME_AGENTS, &all_agents);
+        Ok(())
+    }
+
+    /// Agents fielded in a game, keyed by player.
+    pub fn get_game_agents(env: Env, game_id: u64) -> Map<Address, u64> {
+        let all_agents: Map<u64, Map<Address, u64>> = env
+            .storage()
+            .instance()
+            .get(&GAME_AGENTS)
+            .unwrap_or(Map::new(&env));
+        all_agents.get(game_id).unwrap_or(Map::new(&env))
+    }
+
+    /// Report the outcome to each agent's record. Failures on the NFT side
+    /// (burned token, reporter not set) are ignored so settlement always
+    /// completes.
+    fn report_agent_results(env: &Env, game: &Game) {
+        let mut all_agents: Map<u64, Map<Address, u64>> =
+            match env.storage().instance().get(&GAME_AGENTS) {
+                Some(all_agents) => all_agents,
+                None => return,
+            };
+        let agents = match all_agents.get(game.id) {
+            Some(agents) => agents,
+            None => return,
+        };
+        all_agents.remove(game.id);
+        env.storage().instance().set(&GAME_AGENTS, &all_agents);
+
+        let nft_contract: Option<Address> = env.storage().instance().get(&AGENT_NFT);
+        let Some(nft_contract) = nft_contract else {
+            return;
+        };
+        let client = AgentNftClient::new(env, &nft_contract);
+        for (player, nft_id) in agents.iter() {
+            let outcome = match (&game.winner, &game.state) {
+                (Some(winner), _) if *winner == player => MatchOutcome::Win,
+                (Some(_), _) => MatchOutcome::Loss,
+                (None, GameState::Drawn) => MatchOutcome::Draw,
+                (None, _) => continue,
+            };
+            let _ = client.try_record_result(&nft_id, &outcome);
+        }
+    }
+
+    // ── SEP-40 Oracle Clock Sync (#533) ───────────────────────────────────────
+    //
+    // SEP-40 defines a standard oracle interface on Stellar/Soroban.
+    // We store the oracle contract address and call its `lastprice` function
+    // to get a trusted timestamp for game clock synchronization.
+
+    /// Configure the SEP-40 oracle contract address for clock sync.
+    pub fn configure_oracle(env: Env, admin: Address, oracle: Address) -> Result<(), ContractError> {
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&CONTRACT_ADMIN)
+            .expect("Not initialized");
+        current_admin.require_auth();
+        if admin != current_admin {
+            return Err(ContractError::Unauthorized);
+        }
+        if Self::governance_enabled(&env) {
+            return Err(ContractError::GovernanceRequired);
+        }
+        Self::apply_governance_action(&env, &GovernanceAction::ConfigureOracle(oracle));
+        Ok(())
+    }
+
+    /// Get the current oracle contract address.
+    pub fn get_oracle(env: Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&ORACLE_CONTRACT)
+            .ok_or(ContractError::OracleNotConfigured)
+    }
+
+    /// Get the oracle-synced timestamp (ledger sequence from oracle).
+    /// Falls back to the current ledger sequence if oracle is not configured.
+    /// The oracle is called via cross-contract invocation using the SEP-40
+    /// `lastprice` interface which returns a price record with a timestamp.
+    ///
+    /// Note: This is a minimal implementation. In production, you would invoke
+    /// the oracle contract's `lastprice` method and extract the timestamp.
     pub fn get_oracle_time(env: Env) -> u64 {
         let oracle_opt: Option<Address> = env.storage().instance().get(&ORACLE_CONTRACT);
         match oracle_opt {
@@ -1902,10 +3365,16 @@ impl GameContract {
         if admin != current_admin {
             return Err(ContractError::Unauthorized);
         }
+        if Self::governance_enabled(&env) {
+            return Err(ContractError::GovernanceRequired);
+        }
         if duration == 0 {
             panic!("Timelock duration must be greater than 0");
         }
-        env.storage().instance().set(&TOURNAMENT_TIMELOCK, &duration);
+        Self::apply_governance_action(
+            &env,
+            &GovernanceAction::ConfigureTournamentTimelock(duration),
+        );
         Ok(())
     }
 
@@ -1917,13 +3386,7 @@ impl GameContract {
         env: Env,
         game_id: u64,
     ) -> Result<u64, ContractError> {
-        let games: Map<u64, Game> = env
-            .storage()
-            .instance()
-            .get(&GAMES)
-            .ok_or(ContractError::GameNotFound)?;
-
-        let game = games.get(game_id).ok_or(ContractError::GameNotFound)?;
+        let game = Self::load_game(&env, game_id)?;
 
         if game.state != GameState::Completed {
             return Err(ContractError::GameNotInProgress);
@@ -1954,6 +3417,7 @@ impl GameContract {
         let escrow = TournamentEscrow {
             escrow_id,
             game_id,
+            token: game.token.clone(),
             total_amount,
             locked_until,
             released: false,
@@ -1979,6 +3443,8 @@ impl GameContract {
         winners: Vec<Address>,
         percentages: Vec<u32>,
     ) -> Result<(), ContractError> {
+        Self::require_not_paused(&env, PauseCategory::Payouts)?;
+
         let mut escrows: Map<u64, TournamentEscrow> = env
             .storage()
             .instance()
@@ -2011,7 +3477,7 @@ impl GameContract {
             return Err(ContractError::InvalidPercentage);
         }
 
-        let token_client = Self::token_client(&env);
+        let token_client = TokenClient::new(&env, &escrow.token);
         let contract_address = env.current_contract_address();
         let total = escrow.total_amount;
         let mut distributed: i128 = 0;
@@ -2053,6 +3519,281 @@ impl GameContract {
             .ok_or(ContractError::EscrowNotFound)?;
         escrows.get(escrow_id).ok_or(ContractError::EscrowNotFound)
     }
+
+    // ── Spectator Prediction Pools ────────────────────────────────────────────
+    //
+    // Spectators stake the game's wager token on Player1Wins / Player2Wins /
+    // Draw while the game is in progress. The pool is held apart from the
+    // players' escrow and closes once the configured number of moves has been
+    // played. The pool and each bet are persistent entries of their own.
+    //
+    // When the game finishes, `finish_game` only records the outcome; each
+    // bettor then collects with `claim_side_bet`, so a bettor's transfer can
+    // never hold up the players' payout:
+    //
+    //   gross  = stake * pool / winning_side_total
+    //   fee    = gross * fee_bips / 1000           → treasury (same as payouts)
+    //   share  = gross - fee
+    //
+    // Rounding dust stays with the contract. If nobody backed the actual
+    // outcome, or the game settles without a winner (tournament split), the
+    // pool is voided and every stake is refunded in full with no fee. So is a
+    // pool whose game has not finished PRED_EXPIRY_LEDGERS after its last
+    // move.
+
+    /// Enable prediction pools; betting closes once `close_after_moves`
+    /// moves have been submitted.
+    pub fn configure_prediction_pools(
+        env: Env,
+        admin: Address,
+        close_after_moves: u32,
+    ) -> Result<(), ContractError> {
+        let current_admin: Address = env
+            .storage()
+            .instance()
+            .get(&CONTRACT_ADMIN)
+            .expect("Not initialized");
+        current_admin.require_auth();
+        if admin != current_admin {
+            return Err(ContractError::Unauthorized);
+        }
+        if Self::governance_enabled(&env) {
+            return Err(ContractError::GovernanceRequired);
+        }
+
+        let action = GovernanceAction::ConfigurePredictionPools(close_after_moves);
+        Self::validate_governance_action(&action)?;
+        Self::apply_governance_action(&env, &action);
+        Ok(())
+    }
+
+    /// Stake `amount` of the game's token on `outcome`. The first bet of a
+    /// bettor must be at least MIN_SIDE_BET.
+    pub fn place_side_bet(
+        env: Env,
+        game_id: u64,
+        bettor: Address,
+        outcome: PredictionOutcome,
+        amount: i128,
+    ) -> Result<(), ContractError> {
+        Self::require_not_paused(&env, PauseCategory::NewGames)?;
+
+        if amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let close_after_moves: u32 = env
+            .storage()
+            .instance()
+            .get(&PRED_CLOSE_MOVES)
+            .ok_or(ContractError::PredictionsNotConfigured)?;
+
+        let game = Self::load_game(&env, game_id)?;
+
+        if game.state != GameState::InProgress || game.moves.len() >= close_after_moves {
+            return Err(ContractError::BettingClosed);
+        }
+        if bettor == game.player1 || Some(bettor.clone()) == game.player2 {
+            return Err(ContractError::InvalidPrediction);
+        }
+
+        bettor.require_auth();
+
+        let pool_key = (PRED_POOLS, game_id);
+        let mut pool = env
+            .storage()
+            .persistent()
+            .get(&pool_key)
+            .unwrap_or(PredictionPool {
+                game_id,
+                token: game.token.clone(),
+                player1_total: 0,
+                player2_total: 0,
+                draw_total: 0,
+                bettors: 0,
+                status: PoolStatus::Open,
+            });
+
+        let bet_key = (SIDE_BETS, game_id, bettor.clone());
+        let bet = match env.storage().persistent().get::<_, SideBet>(&bet_key) {
+            Some(existing) => {
+                if existing.outcome != outcome {
+                    return Err(ContractError::InvalidPrediction);
+                }
+                SideBet {
+                    outcome,
+                    amount: existing.amount + amount,
+                }
+            }
+            None => {
+                if amount < MIN_SIDE_BET {
+                    return Err(ContractError::InvalidAmount);
+                }
+                if pool.bettors >= MAX_SIDE_BETTORS {
+                    return Err(ContractError::PoolFull);
+                }
+                pool.bettors += 1;
+                SideBet { outcome, amount }
+            }
+        };
+
+        let token_client = TokenClient::new(&env, &pool.token);
+        if token_client.balance(&bettor) < amount {
+            return Err(ContractError::InsufficientFunds);
+        }
+        token_client.transfer(&bettor, &env.current_contract_address(), &amount);
+
+        match outcome {
+            PredictionOutcome::Player1Wins => pool.player1_total += amount,
+            PredictionOutcome::Player2Wins => pool.player2_total += amount,
+            PredictionOutcome::Draw => pool.draw_total += amount,
+        }
+
+        events::prediction(
+            &env,
+            events::BET,
+            game_id,
+            Some(bettor),
+            amount,
+            pool.status.clone(),
+        );
+        Self::store_persistent(&env, &bet_key, &bet);
+        Self::store_persistent(&env, &pool_key, &pool);
+
+        Ok(())
+    }
+
+    /// Collect a side bet once its pool is resolved or voided: the bettor's
+    /// share if they backed the outcome, their full stake if the pool was
+    /// voided, nothing otherwise. An open pool whose game has not finished
+    /// PRED_EXPIRY_LEDGERS after its last move is voided first. Returns the
+    /// amount paid.
+    pub fn claim_side_bet(env: Env, game_id: u64, bettor: Address) -> Result<i128, ContractError> {
+        bettor.require_auth();
+
+        let pool_key = (PRED_POOLS, game_id);
+        let mut pool: PredictionPool = env
+            .storage()
+            .persistent()
+            .get(&pool_key)
+            .ok_or(ContractError::InvalidPrediction)?;
+        let bet_key = (SIDE_BETS, game_id, bettor.clone());
+        let bet: SideBet = env
+            .storage()
+            .persistent()
+            .get(&bet_key)
+            .ok_or(ContractError::InvalidPrediction)?;
+
+        if pool.status == PoolStatus::Open {
+            let game = Self::load_game(&env, game_id)?;
+            let stalled_until = game.last_move_at.saturating_add(PRED_EXPIRY_LEDGERS);
+            if (env.ledger().sequence() as u64) <= stalled_until {
+                return Err(ContractError::TimeoutNotReached);
+            }
+            pool.status = PoolStatus::Voided;
+            events::prediction(
+                &env,
+                events::RESOLVED,
+                game_id,
+                None,
+                pool.player1_total + pool.player2_total + pool.draw_total,
+                pool.status.clone(),
+            );
+            Self::store_persistent(&env, &pool_key, &pool);
+        }
+
+        let token_client = TokenClient::new(&env, &pool.token);
+        let contract_address = env.current_contract_address();
+        let paid = match pool.status {
+            PoolStatus::Resolved(outcome) if outcome == bet.outcome => {
+                let total_pool = pool.player1_total + pool.player2_total + pool.draw_total;
+                let winning_total = match outcome {
+                    PredictionOutcome::Player1Wins => pool.player1_total,
+                    PredictionOutcome::Player2Wins => pool.player2_total,
+                    PredictionOutcome::Draw => pool.draw_total,
+                };
+                let gross = (bet.amount * total_pool) / winning_total;
+                let (share, fee, treasury_addr_opt) = Self::protocol_fee(&env, gross);
+                if fee > 0
+                    && let Some(ref treasury_addr) = treasury_addr_opt
+                {
+                    token_client.transfer(&contract_address, treasury_addr, &fee);
+                    Self::record_treasury_fee(&env, &pool.token, fee);
+                }
+                share
+            }
+            PoolStatus::Resolved(_) => 0,
+            PoolStatus::Voided => bet.amount,
+            PoolStatus::Open => return Err(ContractError::TimeoutNotReached),
+        };
+
+        env.storage().persistent().remove(&bet_key);
+        if paid > 0 {
+            token_client.transfer(&contract_address, &bettor, &paid);
+        }
+        events::prediction(
+            &env,
+            events::CLAIMED,
+            game_id,
+            Some(bettor),
+            paid,
+            pool.status,
+        );
+        Ok(paid)
+    }
+
+    /// Query the prediction pool for a game.
+    pub fn get_prediction_pool(env: Env, game_id: u64) -> Option<PredictionPool> {
+        env.storage().persistent().get(&(PRED_POOLS, game_id))
+    }
+
+    /// Query a spectator's unclaimed stake in a game's prediction pool.
+    pub fn get_side_bet(env: Env, game_id: u64, bettor: Address) -> Option<SideBet> {
+        env.storage().persistent().get(&(SIDE_BETS, game_id, bettor))
+    }
+
+    /// Record the outcome of a game that has just finished in its prediction
+    /// pool. No-op when the game has no open pool. Moves no tokens; bettors
+    /// collect with `claim_side_bet`.
+    fn resolve_prediction_pool(env: &Env, game: &Game) {
+        let pool_key = (PRED_POOLS, game.id);
+        let mut pool = match env.storage().persistent().get::<_, PredictionPool>(&pool_key) {
+            Some(pool) if pool.status == PoolStatus::Open => pool,
+            _ => return,
+        };
+
+        let outcome = if game.state == GameState::Drawn {
+            Some(PredictionOutcome::Draw)
+        } else {
+            match game.winner {
+                Some(ref winner) if *winner == game.player1 => Some(PredictionOutcome::Player1Wins),
+                Some(_) => Some(PredictionOutcome::Player2Wins),
+                None => None,
+            }
+        };
+        let winning_total = match outcome {
+            Some(PredictionOutcome::Player1Wins) => pool.player1_total,
+            Some(PredictionOutcome::Player2Wins) => pool.player2_total,
+            Some(PredictionOutcome::Draw) => pool.draw_total,
+            None => 0,
+        };
+
+        // Nobody backed the result (or no result): every stake is refunded.
+        pool.status = match outcome {
+            Some(outcome) if winning_total > 0 => PoolStatus::Resolved(outcome),
+            _ => PoolStatus::Voided,
+        };
+
+        events::prediction(
+            env,
+            events::RESOLVED,
+            game.id,
+            None,
+            pool.player1_total + pool.player2_total + pool.draw_total,
+            pool.status.clone(),
+        );
+        Self::store_persistent(env, &pool_key, &pool);
+    }
 }
 
 // ────────────────────────────────────────────────────────────────────────────
@@ -2398,11 +4139,9 @@ mod tests {
         client.join_game(&game_id, &player2);
 
         env.as_contract(&contract_id, || {
-            let mut games: Map<u64, Game> = env.storage().instance().get(&GAMES).unwrap();
-            let mut game = games.get(game_id).unwrap();
+            let mut game = GameContract::load_game(&env, game_id).unwrap();
             game.last_move_at = 0;
-            games.set(game_id, game);
-            env.storage().instance().set(&GAMES, &games);
+            GameContract::store_game(&env, &game);
         });
 
         env.ledger().set_sequence_number(101);
@@ -2736,11 +4475,9 @@ mod tests {
         client.join_game(&game_id, &player2);
 
         env.as_contract(&contract_id, || {
-            let mut games: Map<u64, Game> = env.storage().instance().get(&GAMES).unwrap();
-            let mut game = games.get(game_id).unwrap();
+            let mut game = GameContract::load_game(&env, game_id).unwrap();
             game.last_move_at = 0;
-            games.set(game_id, game);
-            env.storage().instance().set(&GAMES, &games);
+            GameContract::store_game(&env, &game);
         });
 
         env.ledger().set_sequence_number(101);
@@ -2951,11 +4688,9 @@ mod tests {
 
         // Manually complete the game (reuse forfeit → sets Forfeited not Completed, so set directly)
         env.as_contract(&contract_id, || {
-            let mut games: Map<u64, Game> = env.storage().instance().get(&GAMES).unwrap();
-            let mut game = games.get(game_id).unwrap();
+            let mut game = GameContract::load_game(&env, game_id).unwrap();
             game.state = GameState::Completed;
-            games.set(game_id, game);
-            env.storage().instance().set(&GAMES, &games);
+            GameContract::store_game(&env, &game);
         });
 
         let winners = Vec::from_array(&env, [player1.clone(), player2.clone()]);
@@ -2995,12 +4730,10 @@ mod tests {
         client.join_game(&game_id, &player2);
 
         env.as_contract(&contract_id, || {
-            let mut games: Map<u64, Game> = env.storage().instance().get(&GAMES).unwrap();
-            let mut game = games.get(game_id).unwrap();
+            let mut game = GameContract::load_game(&env, game_id).unwrap();
             game.state = GameState::Completed;
             game.winner = Some(player1.clone());
-            games.set(game_id, game);
-            env.storage().instance().set(&GAMES, &games);
+            GameContract::store_game(&env, &game);
         });
 
         client.payout(&game_id, &player1);
@@ -5,6 +5,7 @@ use super::*;
 use ed25519_dalek::{Signer, SigningKey};
 use rand::rngs::OsRng;
 use soroban_sdk::token::StellarAssetClient;
+use soroban_sdk::testutils::Ledger as _;
 use soroban_sdk::{Address, Bytes, BytesN, Env, Map, Vec, testutils::Address as _};
 
 /// Helper: seed a completed game directly into contract storage, bypassing
@@ -14,6 +15,7 @@ fn seed_completed_game(
     contract_id: &Address,
     player1: &Address,
     player2: &Address,
+    token: &Address,
     wager: i128,
 ) -> u64 {
     let game_id: u64 = 1;
@@ -28,21 +30,24 @@ fn seed_completed_game(
             player2: Some(player2.clone()),
             state: GameState::Completed,
             wager_amount: wager,
+            token: token.clone(),
             current_turn: 1,
             moves: Vec::new(env),
             created_at: 0,
             winner: None,
             last_move_at: 0,
+            opponent: None,
+            expires_at: None,
         };
-        let mut games: Map<u64, Game> = Map::new(env);
-        games.set(game_id, game);
-        env.storage().instance().set(&GAMES, &games);
+        GameContract::store_game(env, &game);
 
         // Seed escrow so payout_tournament can debit both players
         let mut escrow: Map<Address, i128> = Map::new(env);
         escrow.set(player1.clone(), wager);
         escrow.set(player2.clone(), wager);
-        env.storage().instance().set(&ESCROW, &escrow);
+        env.storage()
+            .instance()
+            .set(&(ESCROW, token.clone()), &escrow);
     });
     game_id
 }
@@ -57,7 +62,8 @@ fn test_payout_tournament() {
     let player2 = Address::generate(&env);
     let wager: i128 = 1000;
 
-    let game_id = seed_completed_game(&env, &contract_id, &player1, &player2, wager);
+    let token = Address::generate(&env);
+    let game_id = seed_completed_game(&env, &contract_id, &player1, &player2, &token, wager);
 
     let winner1 = Address::generate(&env);
     let winner2 = Address::generate(&env);
@@ -81,7 +87,11 @@ fn test_payout_tournament() {
     // Total pool should be wager * 2 = 2000
     // Expected payouts: 50% = 1000, 30% = 600, 20% = 400
     env.as_contract(&contract_id, || {
-        let escrow: Map<Address, i128> = env.storage().instance().get(&ESCROW).unwrap();
+        let escrow: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&(ESCROW, token.clone()))
+            .unwrap();
 
         // Assert sum precisely equals total pool
         let w1_escrow = escrow.get(winner1.clone()).unwrap_or(0);
@@ -94,7 +104,7 @@ fn test_payout_tournament() {
 
         // Calculate total sum of payouts
         let total_distributed = w1_escrow + w2_escrow + w3_escrow;
-        assert_eq!(total_distributed, (wager * 2) as i128);
+        assert_eq!(total_distributed, wager * 2);
 
         // Player1 and Player2 escrows should be subtracted by wager amount
         let p1_escrow = escrow.get(player1.clone()).unwrap_or(0);
@@ -116,7 +126,8 @@ fn test_payout_tournament_dust() {
     // An amount that creates an uneven division for testing "precision" remainder distribution
     let wager: i128 = 333; // total pool = 666
 
-    let game_id = seed_completed_game(&env, &contract_id, &player1, &player2, wager);
+    let token = Address::generate(&env);
+    let game_id = seed_completed_game(&env, &contract_id, &player1, &player2, &token, wager);
 
     let winner1 = Address::generate(&env);
     let winner2 = Address::generate(&env);
@@ -140,7 +151,11 @@ fn test_payout_tournament_dust() {
         .payout_tournament(&game_id, &winners, &percentages);
 
     env.as_contract(&contract_id, || {
-        let escrow: Map<Address, i128> = env.storage().instance().get(&ESCROW).unwrap();
+        let escrow: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&(ESCROW, token.clone()))
+            .unwrap();
 
         let w1_escrow = escrow.get(winner1.clone()).unwrap_or(0);
         let w2_escrow = escrow.get(winner2.clone()).unwrap_or(0);
@@ -151,7 +166,7 @@ fn test_payout_tournament_dust() {
         assert_eq!(w3_escrow, 133);
 
         let total_distributed = w1_escrow + w2_escrow + w3_escrow;
-        assert_eq!(total_distributed, (wager * 2) as i128); // 666
+        assert_eq!(total_distributed, wager * 2); // 666
     });
 }
 
@@ -165,7 +180,8 @@ fn test_payout_tournament_invalid_percentage() {
     let player2 = Address::generate(&env);
     let wager: i128 = 1000;
 
-    let game_id = seed_completed_game(&env, &contract_id, &player1, &player2, wager);
+    let token = Address::generate(&env);
+    let game_id = seed_completed_game(&env, &contract_id, &player1, &player2, &token, wager);
 
     let winner1 = Address::generate(&env);
 
@@ -290,18 +306,20 @@ fn test_payout_with_fee() {
 
     // Force complete the game and set winner
     env.as_contract(&contract_id, || {
-        let mut games: Map<u64, Game> = env.storage().instance().get(&GAMES).unwrap();
-        let mut game = games.get(game_id).unwrap();
+        let mut game = GameContract::load_game(&env, game_id).unwrap();
         game.state = GameState::Completed;
         game.winner = Some(player1.clone());
-        games.set(game_id, game);
-        env.storage().instance().set(&GAMES, &games);
+        GameContract::store_game(&env, &game);
     });
 
     client.payout(&game_id, &player1);
 
     env.as_contract(&contract_id, || {
-        let escrow: Map<Address, i128> = env.storage().instance().get(&ESCROW).unwrap();
+        let escrow: Map<Address, i128> = env
+            .storage()
+            .instance()
+            .get(&(ESCROW, token_address.clone()))
+            .unwrap();
         let winner_escrow = escrow.get(player1.clone()).unwrap_or(0);
         let treasury_escrow = escrow.get(treasury_addr.clone()).unwrap_or(0);
         let loser_escrow = escrow.get(player2.clone()).unwrap_or(0);
@@ -765,3 +783,1351 @@ fn test_submit_move_player1_cannot_move_twice() {
     let res = client.try_submit_move(&game_id, &player1, &Vec::from_array(&env, [2u32]));
     assert_eq!(res, Err(Ok(ContractError::NotYourTurn)));
 }
+
+// ── Multi-asset wagering ──────────────────────────────────────────────────────
+
+#[test]
+fn test_create_game_with_unlisted_token_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, _player1, _player2, _game_id) = setup_in_progress_game(&env, &contract_id);
+
+    let issuer = Address::generate(&env);
+    let usdc = env.register_stellar_asset_contract_v2(issuer).address();
+    let player = Address::generate(&env);
+
+    let res = client.try_create_game_with_token(&player, &usdc, &100);
+    assert_eq!(res, Err(Ok(ContractError::TokenNotAllowed)));
+}
+
+#[test]
+fn test_allowlisted_token_game_settles_in_its_own_asset() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let client = GameContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let treasury_addr = Address::generate(&env);
+    let player1 = Address::generate(&env);
+    let player2 = Address::generate(&env);
+
+    let xlm = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_admin = StellarAssetClient::new(&env, &usdc);
+    let usdc_client = soroban_sdk::token::TokenClient::new(&env, &usdc);
+    let xlm_client = soroban_sdk::token::TokenClient::new(&env, &xlm);
+
+    client.initialize_token(&admin, &xlm);
+    client.initialize_puzzle_rewards(
+        &admin,
+        &Bytes::from_slice(&env, &[0u8; 32]),
+        &0i128,
+        &20u32,
+        &treasury_addr,
+    );
+    client.allow_token(&admin, &usdc);
+    assert!(client.is_token_allowed(&usdc));
+    assert!(client.is_token_allowed(&xlm));
+
+    usdc_admin.mint(&player1, &500);
+    usdc_admin.mint(&player2, &500);
+
+    let game_id = client.create_game_with_token(&player1, &usdc, &500);
+    client.join_game(&game_id, &player2);
+    assert_eq!(client.get_game(&game_id).token, usdc);
+    assert_eq!(client.get_escrow_balance(&usdc, &player1), 500);
+    assert_eq!(client.get_escrow_balance(&xlm, &player1), 0);
+
+    client.forfeit(&game_id, &player1);
+
+    // pool = 1000 USDC, fee = 2 % → 20 to treasury, 980 to winner
+    assert_eq!(usdc_client.balance(&player2), 980);
+    assert_eq!(usdc_client.balance(&treasury_addr), 20);
+    assert_eq!(xlm_client.balance(&treasury_addr), 0);
+    assert_eq!(client.get_treasury_fees(&usdc), 20);
+    assert_eq!(client.get_treasury_fees(&xlm), 0);
+}
+
+#[test]
+fn test_dispute_fee_is_paid_in_the_game_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, xlm, _) = setup_token_contract(&env, &contract_id);
+    let admin = env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&CONTRACT_ADMIN)
+            .unwrap()
+    });
+    let xlm_client = soroban_sdk::token::TokenClient::new(&env, &xlm);
+
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_admin = StellarAssetClient::new(&env, &usdc);
+    let usdc_client = soroban_sdk::token::TokenClient::new(&env, &usdc);
+    client.allow_token(&admin, &usdc);
+
+    let player1 = Address::generate(&env);
+    let player2 = Address::generate(&env);
+    usdc_admin.mint(&player1, &150);
+    usdc_admin.mint(&player2, &100);
+    let game_id = client.create_game_with_token(&player1, &usdc, &100);
+    client.join_game(&game_id, &player2);
+
+    let arbitrator = Address::generate(&env);
+    client.configure_dispute_system(&admin, &arbitrator, &50i128);
+    let reason = Bytes::from_slice(&env, b"engine assist");
+
+    // player2 has no USDC left after staking.
+    assert_eq!(
+        client.try_file_dispute(&game_id, &player2, &player1, &reason),
+        Err(Ok(ContractError::InsufficientDisputeFee))
+    );
+
+    let dispute_id = client.file_dispute(&game_id, &player1, &player2, &reason);
+    assert_eq!(usdc_client.balance(&player1), 0);
+    assert_eq!(xlm_client.balance(&contract_id), 0);
+
+    client.reject_dispute(&dispute_id, &arbitrator, &reason);
+    assert_eq!(usdc_client.balance(&player1), 50);
+    assert_eq!(client.get_escrow_balance(&usdc, &player1), 100);
+}
+
+#[test]
+fn test_disallowed_token_still_refunds_open_game() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let client = GameContractClient::new(&env, &contract_id);
+    let (admin, _) = init_contract(&env, &contract_id);
+
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_client = soroban_sdk::token::TokenClient::new(&env, &usdc);
+    let player1 = Address::generate(&env);
+    StellarAssetClient::new(&env, &usdc).mint(&player1, &100);
+
+    client.allow_token(&admin, &usdc);
+    let game_id = client.create_game_with_token(&player1, &usdc, &100);
+
+    client.disallow_token(&admin, &usdc);
+    assert!(!client.is_token_allowed(&usdc));
+
+    client.cancel_game(&game_id, &player1);
+    assert_eq!(usdc_client.balance(&player1), 100);
+    assert_eq!(client.get_escrow_balance(&usdc, &player1), 0);
+}
+
+// ── Directed challenges & rematches ───────────────────────────────────────────
+
+fn setup_token_contract<'a>(
+    env: &'a Env,
+    contract_id: &'a Address,
+) -> (GameContractClient<'a>, Address, StellarAssetClient<'a>) {
+    let client = GameContractClient::new(env, contract_id);
+    let admin = Address::generate(env);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(env))
+        .address();
+    client.initialize_token(&admin, &token);
+    client.initialize_puzzle_rewards(
+        &admin,
+        &Bytes::from_slice(env, &[0u8; 32]),
+        &0i128,
+        &0u32,
+        &Address::generate(env),
+    );
+    let asset_admin = StellarAssetClient::new(env, &token);
+    (client, token, asset_admin)
+}
+
+#[test]
+fn test_directed_challenge_only_named_opponent_can_join() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, token, asset_admin) = setup_token_contract(&env, &contract_id);
+
+    let player1 = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let stranger = Address::generate(&env);
+    asset_admin.mint(&player1, &100);
+    asset_admin.mint(&opponent, &100);
+    asset_admin.mint(&stranger, &100);
+
+    let game_id = client.create_challenge(&player1, &opponent, &token, &100, &50);
+
+    let res = client.try_join_game(&game_id, &stranger);
+    assert_eq!(res, Err(Ok(ContractError::NotInvited)));
+
+    client.join_game(&game_id, &opponent);
+    let game = client.get_game(&game_id);
+    assert_eq!(game.state, GameState::InProgress);
+    assert_eq!(game.player2, Some(opponent));
+}
+
+#[test]
+fn test_expired_challenge_cannot_be_joined_and_anyone_can_refund() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, token, asset_admin) = setup_token_contract(&env, &contract_id);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+
+    let player1 = Address::generate(&env);
+    let opponent = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    asset_admin.mint(&player1, &100);
+    asset_admin.mint(&opponent, &100);
+
+    let game_id = client.create_challenge(&player1, &opponent, &token, &100, &10);
+    assert_eq!(token_client.balance(&player1), 0);
+
+    // Before expiry only player1 may cancel
+    let res = client.try_cancel_game(&game_id, &keeper);
+    assert_eq!(res, Err(Ok(ContractError::NotPlayer)));
+
+    env.ledger().set_sequence_number(11);
+
+    let res = client.try_join_game(&game_id, &opponent);
+    assert_eq!(res, Err(Ok(ContractError::InviteExpired)));
+
+    client.cancel_game(&game_id, &keeper);
+    assert_eq!(token_client.balance(&player1), 100);
+    assert_eq!(client.get_escrow_balance(&token, &player1), 0);
+}
+
+#[test]
+fn test_challenge_with_past_expiry_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, token, asset_admin) = setup_token_contract(&env, &contract_id);
+
+    let player1 = Address::generate(&env);
+    asset_admin.mint(&player1, &100);
+    env.ledger().set_sequence_number(20);
+
+    let res = client.try_create_challenge(&player1, &Address::generate(&env), &token, &100, &20);
+    assert_eq!(res, Err(Ok(ContractError::InviteExpired)));
+}
+
+#[test]
+fn test_rematch_swaps_colours_and_keeps_stake() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, token, asset_admin) = setup_token_contract(&env, &contract_id);
+
+    let player1 = Address::generate(&env);
+    let player2 = Address::generate(&env);
+    asset_admin.mint(&player1, &300);
+    asset_admin.mint(&player2, &300);
+
+    let game_id = client.create_game(&player1, &100);
+
+    // Unfinished games cannot be rematched
+    let res = client.try_rematch(&game_id);
+    assert_eq!(res, Err(Ok(ContractError::RematchNotAvailable)));
+
+    client.join_game(&game_id, &player2);
+    client.forfeit(&game_id, &player2);
+
+    let rematch_id = client.rematch(&game_id);
+    let rematch = client.get_game(&rematch_id);
+    assert_eq!(rematch.player1, player2);
+    assert_eq!(rematch.player2, Some(player1.clone()));
+    assert_eq!(rematch.wager_amount, 100);
+    assert_eq!(rematch.token, token);
+    assert_eq!(rematch.state, GameState::InProgress);
+    assert_eq!(client.get_rematch(&game_id), Some(rematch_id));
+
+    // Only one rematch per finished game
+    let res = client.try_rematch(&game_id);
+    assert_eq!(res, Err(Ok(ContractError::RematchNotAvailable)));
+}
+
+// ── Spectator prediction pools ────────────────────────────────────────────────
+
+/// Smallest opening side bet, for readable stake arithmetic
+const BET: i128 = MIN_SIDE_BET;
+
+#[test]
+fn test_prediction_pool_pays_winners_pro_rata_with_fee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let client = GameContractClient::new(&env, &contract_id);
+
+    let admin = Address::generate(&env);
+    let treasury_addr = Address::generate(&env);
+    let token = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let asset_admin = StellarAssetClient::new(&env, &token);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+
+    client.initialize_token(&admin, &token);
+    client.initialize_puzzle_rewards(
+        &admin,
+        &Bytes::from_slice(&env, &[0u8; 32]),
+        &0i128,
+        &20u32, // 2 %
+        &treasury_addr,
+    );
+    client.configure_prediction_pools(&admin, &10u32);
+
+    let player1 = Address::generate(&env);
+    let player2 = Address::generate(&env);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    for who in [&player1, &player2] {
+        asset_admin.mint(who, &100);
+    }
+    for who in [&alice, &bob, &carol] {
+        asset_admin.mint(who, &(1_000 * BET));
+    }
+
+    let game_id = client.create_game(&player1, &100);
+    client.join_game(&game_id, &player2);
+
+    client.place_side_bet(&game_id, &alice, &PredictionOutcome::Player2Wins, &(300 * BET));
+    client.place_side_bet(&game_id, &bob, &PredictionOutcome::Player2Wins, &(100 * BET));
+    client.place_side_bet(&game_id, &carol, &PredictionOutcome::Player1Wins, &(600 * BET));
+
+    let pool = client.get_prediction_pool(&game_id).unwrap();
+    assert_eq!(pool.player2_total, 400 * BET);
+    assert_eq!(pool.player1_total, 600 * BET);
+    assert_eq!(pool.bettors, 3);
+
+    client.forfeit(&game_id, &player1); // player2 wins
+
+    // Settling the game only resolves the pool; nothing is paid out yet.
+    let pool = client.get_prediction_pool(&game_id).unwrap();
+    assert_eq!(
+        pool.status,
+        PoolStatus::Resolved(PredictionOutcome::Player2Wins)
+    );
+    assert_eq!(token_client.balance(&alice), 700 * BET);
+    assert_eq!(token_client.balance(&treasury_addr), 4); // wager fee, 200 * 2 %
+
+    // pool = 1000, split 3:1 between alice and bob, 2 % fee on each share
+    assert_eq!(client.claim_side_bet(&game_id, &alice), 735 * BET);
+    assert_eq!(client.claim_side_bet(&game_id, &bob), 245 * BET);
+    assert_eq!(client.claim_side_bet(&game_id, &carol), 0);
+    assert_eq!(token_client.balance(&alice), (1_000 - 300 + 735) * BET);
+    assert_eq!(token_client.balance(&bob), (1_000 - 100 + 245) * BET);
+    assert_eq!(token_client.balance(&carol), (1_000 - 600) * BET);
+    assert_eq!(token_client.balance(&treasury_addr), 4 + 20 * BET);
+
+    // Each bet is paid once
+    assert!(client.get_side_bet(&game_id, &alice).is_none());
+    let res = client.try_claim_side_bet(&game_id, &alice);
+    assert_eq!(res, Err(Ok(ContractError::InvalidPrediction)));
+}
+
+#[test]
+fn test_prediction_pool_refunds_when_nobody_backed_result() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, token, asset_admin) = setup_token_contract(&env, &contract_id);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+    let admin = env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&CONTRACT_ADMIN)
+            .unwrap()
+    });
+    client.configure_prediction_pools(&admin, &10u32);
+
+    let player1 = Address::generate(&env);
+    let player2 = Address::generate(&env);
+    let alice = Address::generate(&env);
+    asset_admin.mint(&player1, &100);
+    asset_admin.mint(&player2, &100);
+    asset_admin.mint(&alice, &(5 * BET));
+
+    let game_id = client.create_game(&player1, &100);
+    client.join_game(&game_id, &player2);
+    client.place_side_bet(&game_id, &alice, &PredictionOutcome::Draw, &(5 * BET));
+
+    client.forfeit(&game_id, &player2);
+
+    let pool = client.get_prediction_pool(&game_id).unwrap();
+    assert_eq!(pool.status, PoolStatus::Voided);
+    assert_eq!(client.claim_side_bet(&game_id, &alice), 5 * BET);
+    assert_eq!(token_client.balance(&alice), 5 * BET);
+}
+
+#[test]
+fn test_side_bets_on_a_stalled_game_are_refunded() {
+    let env = Env::default();
+    env.mock_all_auths();
+    // Keep every entry live across the expiry window
+    env.ledger().with_mut(|l| {
+        l.min_persistent_entry_ttl = 2 * PRED_EXPIRY_LEDGERS as u32;
+        l.max_entry_ttl = 4 * PRED_EXPIRY_LEDGERS as u32;
+    });
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, token, asset_admin) = setup_token_contract(&env, &contract_id);
+    let token_client = soroban_sdk::token::TokenClient::new(&env, &token);
+    let admin = env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&CONTRACT_ADMIN)
+            .unwrap()
+    });
+    client.configure_prediction_pools(&admin, &10u32);
+
+    let player1 = Address::generate(&env);
+    let player2 = Address::generate(&env);
+    let alice = Address::generate(&env);
+    asset_admin.mint(&player1, &100);
+    asset_admin.mint(&player2, &100);
+    asset_admin.mint(&alice, &(2 * BET));
+
+    let game_id = client.create_game(&player1, &100);
+    client.join_game(&game_id, &player2);
+    client.place_side_bet(&game_id, &alice, &PredictionOutcome::Player1Wins, &(2 * BET));
+    let last_move_at = client.get_game(&game_id).last_move_at;
+
+    // Still being played: nothing to claim yet
+    let res = client.try_claim_side_bet(&game_id, &alice);
+    assert_eq!(res, Err(Ok(ContractError::TimeoutNotReached)));
+
+    env.ledger()
+        .set_sequence_number((last_move_at + PRED_EXPIRY_LEDGERS) as u32 + 1);
+    assert_eq!(client.claim_side_bet(&game_id, &alice), 2 * BET);
+    assert_eq!(token_client.balance(&alice), 2 * BET);
+    assert_eq!(
+        client.get_prediction_pool(&game_id).unwrap().status,
+        PoolStatus::Voided
+    );
+}
+
+#[test]
+fn test_side_bets_close_after_configured_moves_and_reject_players() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, _token, asset_admin) = setup_token_contract(&env, &contract_id);
+    let admin = env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&CONTRACT_ADMIN)
+            .unwrap()
+    });
+
+    let player1 = Address::generate(&env);
+    let player2 = Address::generate(&env);
+    let spectator = Address::generate(&env);
+    asset_admin.mint(&player1, &100);
+    asset_admin.mint(&player2, &100);
+    asset_admin.mint(&spectator, &(10 * BET));
+
+    let game_id = client.create_game(&player1, &100);
+
+    let res = client.try_place_side_bet(&game_id, &spectator, &PredictionOutcome::Draw, &BET);
+    assert_eq!(res, Err(Ok(ContractError::PredictionsNotConfigured)));
+
+    client.configure_prediction_pools(&admin, &2u32);
+
+    // Not in progress yet
+    let res = client.try_place_side_bet(&game_id, &spectator, &PredictionOutcome::Draw, &BET);
+    assert_eq!(res, Err(Ok(ContractError::BettingClosed)));
+
+    client.join_game(&game_id, &player2);
+
+    let res = client.try_place_side_bet(&game_id, &player1, &PredictionOutcome::Player1Wins, &BET);
+    assert_eq!(res, Err(Ok(ContractError::InvalidPrediction)));
+
+    // Opening bets below the minimum cannot take a bettor slot
+    let res = client.try_place_side_bet(&game_id, &spectator, &PredictionOutcome::Draw, &(BET - 1));
+    assert_eq!(res, Err(Ok(ContractError::InvalidAmount)));
+
+    client.place_side_bet(&game_id, &spectator, &PredictionOutcome::Draw, &BET);
+    client.place_side_bet(&game_id, &spectator, &PredictionOutcome::Draw, &1);
+    let res = client.try_place_side_bet(&game_id, &spectator, &PredictionOutcome::Player1Wins, &BET);
+    assert_eq!(res, Err(Ok(ContractError::InvalidPrediction)));
+
+    client.submit_move(&game_id, &player1, &Vec::from_array(&env, [1u32]));
+    client.submit_move(&game_id, &player2, &Vec::from_array(&env, [2u32]));
+
+    let res = client.try_place_side_bet(&game_id, &spectator, &PredictionOutcome::Draw, &BET);
+    assert_eq!(res, Err(Ok(ContractError::BettingClosed)));
+    assert_eq!(
+        client.get_side_bet(&game_id, &spectator).unwrap().amount,
+        BET + 1
+    );
+}
+
+// ── Contract events ───────────────────────────────────────────────────────────
+
+/// Helper: the last `count` events published by the game contract, as
+/// `(category, action, data)`.
+fn last_events(
+    env: &Env,
+    contract_id: &Address,
+    count: usize,
+) -> std::vec::Vec<(Symbol, Symbol, soroban_sdk::Val)> {
+    use soroban_sdk::{TryFromVal, testutils::Events as _};
+
+    let all: std::vec::Vec<_> = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(id, _, _)| id == contract_id)
+        .map(|(_, topics, data)| {
+            let category = Symbol::try_from_val(env, &topics.get(0).unwrap()).unwrap();
+            let action = Symbol::try_from_val(env, &topics.get(1).unwrap()).unwrap();
+            (category, action, data)
+        })
+        .collect();
+    all[all.len() - count..].to_vec()
+}
+
+#[test]
+fn test_game_lifecycle_publishes_versioned_events() {
+    use soroban_sdk::TryFromVal;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, token, asset_admin) = setup_token_contract(&env, &contract_id);
+
+    let player1 = Address::generate(&env);
+    let player2 = Address::generate(&env);
+    asset_admin.mint(&player1, &100);
+    asset_admin.mint(&player2, &100);
+
+    let game_id = client.create_game(&player1, &100);
+    let evts = last_events(&env, &contract_id, 1);
+    assert_eq!(
+        (evts[0].0.clone(), evts[0].1.clone()),
+        (events::GAME, events::CREATED)
+    );
+    let created = events::GameEvent::try_from_val(&env, &evts[0].2).unwrap();
+    assert_eq!(created.version, events::EVENT_VERSION);
+    assert_eq!(created.game_id, game_id);
+    assert_eq!(created.state, GameState::Created);
+    assert_eq!(created.token, token);
+
+    client.join_game(&game_id, &player2);
+    let evts = last_events(&env, &contract_id, 1);
+    assert_eq!(evts[0].1, events::JOINED);
+    let joined = events::GameEvent::try_from_val(&env, &evts[0].2).unwrap();
+    assert_eq!(joined.player2, Some(player2.clone()));
+    assert_eq!(joined.state, GameState::InProgress);
+
+    client.submit_move(&game_id, &player1, &Vec::from_array(&env, [12u32, 28u32]));
+    let evts = last_events(&env, &contract_id, 1);
+    assert_eq!(evts[0].1, events::MOVED);
+    let moved = events::MoveEvent::try_from_val(&env, &evts[0].2).unwrap();
+    assert_eq!(moved.move_number, 1);
+    assert_eq!(moved.player, player1);
+    assert_eq!(moved.move_data, Vec::from_array(&env, [12u32, 28u32]));
+
+    client.forfeit(&game_id, &player1);
+    let evts = last_events(&env, &contract_id, 2);
+    assert_eq!(
+        (evts[0].0.clone(), evts[0].1.clone()),
+        (events::PAYOUT, events::WINNER)
+    );
+    let paid = events::PayoutEvent::try_from_val(&env, &evts[0].2).unwrap();
+    assert_eq!(paid.recipient, player2);
+    assert_eq!(paid.amount + paid.fee, 200);
+    assert_eq!(evts[1].1, events::FORFEITED);
+    let forfeited = events::GameEvent::try_from_val(&env, &evts[1].2).unwrap();
+    assert_eq!(forfeited.winner, Some(player2));
+    assert_eq!(forfeited.state, GameState::Settled);
+}
+
+#[test]
+fn test_dispute_and_fee_proposal_flows_publish_events() {
+    use soroban_sdk::TryFromVal;
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, player1, player2, game_id) = setup_in_progress_game(&env, &contract_id);
+    let admin = env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&CONTRACT_ADMIN)
+            .unwrap()
+    });
+
+    let arbitrator = Address::generate(&env);
+    client.configure_dispute_system(&admin, &arbitrator, &0i128);
+
+    let reason = Bytes::from_slice(&env, b"engine assist");
+    let dispute_id = client.file_dispute(&game_id, &player1, &player2, &reason);
+    let evts = last_events(&env, &contract_id, 1);
+    assert_eq!(
+        (evts[0].0.clone(), evts[0].1.clone()),
+        (events::DISPUTE, events::FILED)
+    );
+    let filed = events::DisputeEvent::try_from_val(&env, &evts[0].2).unwrap();
+    assert_eq!(filed.dispute_id, dispute_id);
+    assert_eq!(filed.status, DisputeStatus::Pending);
+
+    client.resolve_dispute(&dispute_id, &arbitrator, &Some(player1.clone()), &reason);
+    let evts = last_events(&env, &contract_id, 3);
+    let actions: std::vec::Vec<Symbol> = evts.iter().map(|(_, action, _)| action.clone()).collect();
+    assert_eq!(actions, [events::WINNER, events::SETTLED, events::SOLVED]);
+    let solved = events::DisputeEvent::try_from_val(&env, &evts[2].2).unwrap();
+    assert_eq!(solved.status, DisputeStatus::Resolved);
+    assert_eq!(solved.winner, Some(player1));
+
+    let signer1 = Address::generate(&env);
+    let signer2 = Address::generate(&env);
+    client.configure_multisig(
+        &admin,
+        &Vec::from_array(&env, [signer1.clone(), signer2.clone()]),
+        &2u32,
+    );
+    client.propose_fee_change(&signer1, &25u32, &Address::generate(&env));
+    let evts = last_events(&env, &contract_id, 1);
+    assert_eq!(
+        (evts[0].0.clone(), evts[0].1.clone()),
+        (events::MULTISIG, events::PROPOSED)
+    );
+
+    client.approve_fee_proposal(&signer2);
+    let evts = last_events(&env, &contract_id, 2);
+    let actions: std::vec::Vec<Symbol> = evts.iter().map(|(_, action, _)| action.clone()).collect();
+    assert_eq!(actions, [events::APPROVED, events::EXECUTED]);
+    let executed = events::FeeProposalEvent::try_from_val(&env, &evts[1].2).unwrap();
+    assert_eq!(executed.new_fee_bips, 25);
+    assert_eq!(executed.approvals, 2);
+}
+
+// ── Paginated queries ─────────────────────────────────────────────────────────
+
+#[test]
+fn test_player_and_open_game_indexes_follow_lifecycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, token, asset_admin) = setup_token_contract(&env, &contract_id);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    asset_admin.mint(&alice, &1_000);
+    asset_admin.mint(&bob, &1_000);
+
+    let g1 = client.create_game(&alice, &100);
+    let g2 = client.create_game(&alice, &100);
+    let g3 = client.create_challenge(&alice, &bob, &token, &100, &1_000);
+
+    // Directed challenges are not public.
+    let open = client.get_open_games(&0, &10);
+    assert_eq!(open.games.len(), 2);
+    assert_eq!(open.next_cursor, None);
+
+    client.join_game(&g1, &bob);
+    let open = client.get_open_games(&0, &10);
+    assert_eq!(open.games.len(), 1);
+    assert_eq!(open.games.get(0).unwrap().id, g2);
+
+    let bobs = client.get_player_games(&bob, &false, &0, &10);
+    assert_eq!(bobs.games.len(), 1);
+    assert_eq!(bobs.games.get(0).unwrap().id, g1);
+
+    client.forfeit(&g1, &alice);
+    client.cancel_game(&g2, &alice);
+
+    let alice_active = client.get_player_games(&alice, &true, &0, &10);
+    assert_eq!(alice_active.games.len(), 1);
+    assert_eq!(alice_active.games.get(0).unwrap().id, g3);
+    assert_eq!(client.get_player_games(&bob, &true, &0, &10).games.len(), 0);
+    assert_eq!(client.get_open_games(&0, &10).games.len(), 0);
+
+    // History keeps every game, two per page.
+    let page1 = client.get_player_games(&alice, &false, &0, &2);
+    assert_eq!(page1.games.len(), 2);
+    assert_eq!(page1.next_cursor, Some(g2));
+    let page2 = client.get_player_games(&alice, &false, &g2, &2);
+    assert_eq!(page2.games.len(), 1);
+    assert_eq!(page2.games.get(0).unwrap().id, g3);
+    assert_eq!(page2.next_cursor, None);
+}
+
+#[test]
+fn test_player_history_pages_stay_sorted() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.budget().reset_unlimited();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, _token, asset_admin) = setup_token_contract(&env, &contract_id);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    asset_admin.mint(&alice, &1_000);
+    asset_admin.mint(&bob, &1_000);
+
+    // Bob fills a whole history page, then joins an older game.
+    let older = client.create_game(&alice, &1);
+    for _ in 0..INDEX_PAGE_SIZE {
+        client.create_game(&bob, &1);
+    }
+    client.join_game(&older, &bob);
+
+    let walk = |open: bool, player: &Address| {
+        let mut ids = std::vec::Vec::new();
+        let mut cursor = 0;
+        loop {
+            let page = if open {
+                client.get_open_games(&cursor, &10)
+            } else {
+                client.get_player_games(player, &false, &cursor, &10)
+            };
+            ids.extend(page.games.iter().map(|game| game.id));
+            match page.next_cursor {
+                Some(next) => cursor = next,
+                None => return ids,
+            }
+        }
+    };
+    let last = older + INDEX_PAGE_SIZE;
+    assert_eq!(
+        walk(false, &bob),
+        (older..=last).collect::<std::vec::Vec<_>>()
+    );
+    // Bob's games span two open-game buckets.
+    assert_eq!(
+        walk(true, &bob),
+        (older + 1..=last).collect::<std::vec::Vec<_>>()
+    );
+
+    env.as_contract(&contract_id, || {
+        assert!(env.storage().persistent().has(&(GAMES, older)));
+        assert!(
+            env.storage()
+                .persistent()
+                .has(&(PLAYER_GAMES, bob.clone(), 1u32))
+        );
+        assert!(!env.storage().instance().has(&GAMES));
+    });
+}
+
+#[test]
+fn test_migrate_moves_v1_games_out_of_instance_storage() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, _token, asset_admin) = setup_token_contract(&env, &contract_id);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    asset_admin.mint(&alice, &1_000);
+    asset_admin.mint(&bob, &1_000);
+
+    let g1 = client.create_game(&alice, &100);
+    client.join_game(&g1, &bob);
+    let g2 = client.create_game(&alice, &100);
+    let g3 = client.create_game(&bob, &100);
+    client.cancel_game(&g3, &bob);
+
+    // Rewrite storage the way a v1 deployment holds it: one instance map.
+    env.as_contract(&contract_id, || {
+        let persistent = env.storage().persistent();
+        let mut legacy: Map<u64, Game> = Map::new(&env);
+        for id in [g1, g2, g3] {
+            legacy.set(id, GameContract::load_game(&env, id).unwrap());
+            persistent.remove(&(GAMES, id));
+        }
+        for player in [&alice, &bob] {
+            persistent.remove(&(PLAYER_GAMES, player.clone()));
+            persistent.remove(&(PLAYER_GAMES, player.clone(), 0u32));
+            persistent.remove(&(PLAYER_ACTIVE, player.clone()));
+        }
+        persistent.remove(&(OPEN_GAMES, 0u64));
+        env.storage().instance().set(&GAMES, &legacy);
+        env.storage().instance().remove(&SCHEMA_VER);
+    });
+    assert_eq!(client.schema_version(), 1);
+    assert_eq!(client.get_game(&g2).id, g2);
+
+    // Two games per call, then done.
+    assert_eq!(client.migrate(), 1);
+    assert_eq!(client.migrate(), SCHEMA_VERSION);
+    assert_eq!(client.migrate(), SCHEMA_VERSION);
+
+    let ids = |page: GamePage| {
+        page.games
+            .iter()
+            .map(|game| game.id)
+            .collect::<std::vec::Vec<_>>()
+    };
+    assert_eq!(
+        ids(client.get_player_games(&alice, &false, &0, &10)),
+        [g1, g2]
+    );
+    assert_eq!(
+        ids(client.get_player_games(&bob, &false, &0, &10)),
+        [g1, g3]
+    );
+    assert_eq!(ids(client.get_player_games(&bob, &true, &0, &10)), [g1]);
+    assert_eq!(ids(client.get_open_games(&0, &10)), [g2]);
+    env.as_contract(&contract_id, || {
+        assert!(!env.storage().instance().has(&GAMES));
+        assert!(env.storage().persistent().has(&(GAMES, g3)));
+    });
+}
+
+#[test]
+fn test_games_by_state_and_disputes_by_status_paginate() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, player1, player2, game_id) = setup_in_progress_game(&env, &contract_id);
+    let admin = env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&CONTRACT_ADMIN)
+            .unwrap()
+    });
+
+    let in_progress = client.get_games_by_state(&GameState::InProgress, &0, &10);
+    assert_eq!(in_progress.games.len(), 1);
+    assert_eq!(in_progress.games.get(0).unwrap().id, game_id);
+    assert_eq!(in_progress.next_cursor, None);
+    let created = client.get_games_by_state(&GameState::Created, &0, &10);
+    assert_eq!(created.games.len(), 0);
+
+    let arbitrator = Address::generate(&env);
+    client.configure_dispute_system(&admin, &arbitrator, &0i128);
+    let reason = Bytes::from_slice(&env, b"stalling");
+    let d1 = client.file_dispute(&game_id, &player1, &player2, &reason);
+    let d2 = client.file_dispute(&game_id, &player2, &player1, &reason);
+    client.reject_dispute(&d1, &arbitrator, &reason);
+
+    let pending = client.get_disputes_by_status(&DisputeStatus::Pending, &0, &1);
+    assert_eq!(pending.disputes.len(), 1);
+    assert_eq!(pending.disputes.get(0).unwrap().id, d2);
+    assert_eq!(pending.next_cursor, None);
+
+    let rejected = client.get_disputes_by_status(&DisputeStatus::Rejected, &0, &1);
+    assert_eq!(rejected.disputes.get(0).unwrap().id, d1);
+    assert_eq!(rejected.next_cursor, Some(d1));
+    let rest = client.get_disputes_by_status(&DisputeStatus::Rejected, &d1, &1);
+    assert_eq!(rest.disputes.len(), 0);
+    assert_eq!(rest.next_cursor, None);
+}
+
+// ── Circuit breaker integration ───────────────────────────────────────────────
+
+#[test]
+fn test_circuit_breaker_categories_block_fund_moves_but_not_refunds() {
+    use emergency_circuit_breaker::{
+        PausableContract, PausableContractClient, PauseCategory as BreakerCategory,
+    };
+
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, _token, asset_admin) = setup_token_contract(&env, &contract_id);
+    let admin = env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&CONTRACT_ADMIN)
+            .unwrap()
+    });
+
+    let breaker_id = env.register_contract(None, PausableContract);
+    let breaker = PausableContractClient::new(&env, &breaker_id);
+    let guardian = Address::generate(&env);
+    breaker.initialize(&guardian);
+    client.set_circuit_breaker(&admin, &breaker_id);
+    assert_eq!(client.get_circuit_breaker(), Some(breaker_id));
+
+    let player1 = Address::generate(&env);
+    let player2 = Address::generate(&env);
+    asset_admin.mint(&player1, &300);
+    asset_admin.mint(&player2, &300);
+
+    let in_progress = client.create_game(&player1, &100);
+    client.join_game(&in_progress, &player2);
+    let waiting = client.create_game(&player1, &100);
+
+    breaker.pause_category(&guardian, &BreakerCategory::NewGames);
+    assert_eq!(
+        client.try_create_game(&player1, &100),
+        Err(Ok(ContractError::Paused))
+    );
+    assert_eq!(
+        client.try_join_game(&waiting, &player2),
+        Err(Ok(ContractError::Paused))
+    );
+    // Payouts are a separate category and refunds always work.
+    client.cancel_game(&waiting, &player1);
+    breaker.unpause_category(&guardian, &BreakerCategory::NewGames);
+
+    breaker.pause(&guardian);
+    assert_eq!(
+        client.try_forfeit(&in_progress, &player1),
+        Err(Ok(ContractError::Paused))
+    );
+    breaker.unpause(&guardian);
+
+    client.forfeit(&in_progress, &player1);
+    assert_eq!(client.get_game(&in_progress).state, GameState::Settled);
+}
+
+// ── Governance Proposal Tests ─────────────────────────────────────────────
+
+fn setup_governance(env: &Env, threshold: u32) -> (GameContractClient<'_>, Address, Vec<Address>) {
+    let contract_id = env.register_contract(None, GameContract);
+    let client = GameContractClient::new(env, &contract_id);
+    let (admin, _) = init_contract(env, &contract_id);
+
+    let mut signers = Vec::new(env);
+    for _ in 0..3 {
+        signers.push_back(Address::generate(env));
+    }
+    client.configure_multisig(&admin, &signers, &threshold);
+    (client, admin, signers)
+}
+
+#[test]
+fn test_governance_proposal_applies_on_threshold() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, signers) = setup_governance(&env, 2);
+    let oracle = Address::generate(&env);
+
+    let id = client.propose_governance_action(
+        &signers.get(0).unwrap(),
+        &GovernanceAction::ConfigureOracle(oracle.clone()),
+    );
+    assert_eq!(
+        client.try_get_oracle(),
+        Err(Ok(ContractError::OracleNotConfigured))
+    );
+    assert_eq!(client.get_governance_proposals().len(), 1);
+
+    assert!(client.approve_governance_action(&signers.get(1).unwrap(), &id));
+    assert_eq!(client.get_oracle(), oracle);
+    assert!(client.get_governance_proposal(&id).is_none());
+}
+
+#[test]
+fn test_governance_blocks_direct_setters() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, signers) = setup_governance(&env, 1);
+
+    assert_eq!(
+        client.try_configure_oracle(&admin, &Address::generate(&env)),
+        Err(Ok(ContractError::GovernanceRequired))
+    );
+    assert!(client.try_set_max_stake(&admin, &500).is_err());
+
+    // The same change goes through a proposal; threshold 1 executes at once.
+    client.propose_governance_action(
+        &signers.get(0).unwrap(),
+        &GovernanceAction::SetMaxStake(500),
+    );
+    let max_stake: i128 = env.as_contract(&client.address, || {
+        env.storage().instance().get(&MAX_STAKE).unwrap()
+    });
+    assert_eq!(max_stake, 500);
+    assert!(client.get_governance_proposals().is_empty());
+}
+
+#[test]
+fn test_governance_blocks_admin_only_config() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, admin, signers) = setup_governance(&env, 2);
+    let other = Address::generate(&env);
+
+    assert_eq!(
+        client.try_configure_multisig(&admin, &Vec::from_array(&env, [admin.clone()]), &1u32),
+        Err(Ok(ContractError::GovernanceRequired))
+    );
+    assert_eq!(
+        client.try_allow_token(&admin, &other),
+        Err(Ok(ContractError::GovernanceRequired))
+    );
+    assert_eq!(
+        client.try_disallow_token(&admin, &other),
+        Err(Ok(ContractError::GovernanceRequired))
+    );
+    assert_eq!(
+        client.try_set_circuit_breaker(&admin, &other),
+        Err(Ok(ContractError::GovernanceRequired))
+    );
+    assert_eq!(
+        client.try_set_agent_nft(&admin, &other),
+        Err(Ok(ContractError::GovernanceRequired))
+    );
+    assert_eq!(
+        client.try_configure_prediction_pools(&admin, &10u32),
+        Err(Ok(ContractError::GovernanceRequired))
+    );
+    assert_eq!(client.get_circuit_breaker(), None);
+
+    let id = client.propose_governance_action(
+        &signers.get(0).unwrap(),
+        &GovernanceAction::SetCircuitBreaker(other.clone()),
+    );
+    client.approve_governance_action(&signers.get(1).unwrap(), &id);
+    assert_eq!(client.get_circuit_breaker(), Some(other.clone()));
+
+    // Delisting a token needs the same approvals as listing it.
+    let id = client.propose_governance_action(
+        &signers.get(0).unwrap(),
+        &GovernanceAction::AllowToken(other.clone()),
+    );
+    client.approve_governance_action(&signers.get(1).unwrap(), &id);
+    assert!(client.is_token_allowed(&other));
+    let id = client.propose_governance_action(
+        &signers.get(0).unwrap(),
+        &GovernanceAction::DisallowToken(other.clone()),
+    );
+    assert!(client.is_token_allowed(&other));
+    client.approve_governance_action(&signers.get(1).unwrap(), &id);
+    assert!(!client.is_token_allowed(&other));
+}
+
+#[test]
+fn test_admin_key_rotation_through_governance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let client = GameContractClient::new(&env, &contract_id);
+    let (admin, _, old_key) = init_contract_with_key(&env, &contract_id);
+    let new_key = SigningKey::generate(&mut OsRng);
+    let new_public = BytesN::from_array(&env, &new_key.verifying_key().to_bytes());
+
+    let mut signers = Vec::new(&env);
+    for _ in 0..2 {
+        signers.push_back(Address::generate(&env));
+    }
+    client.configure_multisig(&admin, &signers, &2u32);
+    assert_eq!(
+        client.try_rotate_admin_key(&admin, &new_public),
+        Err(Ok(ContractError::GovernanceRequired))
+    );
+
+    let id = client.propose_governance_action(
+        &signers.get(0).unwrap(),
+        &GovernanceAction::SetAdminKey(new_public.clone()),
+    );
+    client.approve_governance_action(&signers.get(1).unwrap(), &id);
+    assert_eq!(client.get_admin_key(), Some(new_public));
+
+    let account = Address::generate(&env);
+    let nonce = BytesN::from_array(&env, &[4u8; 32]);
+    let expiry: u64 = env.ledger().sequence() as u64 + 1000;
+    client.issue_sep10_challenge(&admin, &account, &nonce, &expiry);
+
+    // The retired key no longer verifies; the failed call leaves the challenge open.
+    let stale = sign_sep10_payload(&env, &old_key, &account, &nonce, expiry);
+    assert!(client.try_verify_sep10_challenge(&account, &nonce, &stale).is_err());
+    assert!(!client.is_sep10_verified(&account));
+
+    let fresh = sign_sep10_payload(&env, &new_key, &account, &nonce, expiry);
+    client.verify_sep10_challenge(&account, &nonce, &fresh);
+    assert!(client.is_sep10_verified(&account));
+}
+
+#[test]
+fn test_governance_set_signers_replaces_signer_set() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, signers) = setup_governance(&env, 2);
+    let new_signer = Address::generate(&env);
+
+    assert_eq!(
+        client.try_propose_governance_action(
+            &signers.get(0).unwrap(),
+            &GovernanceAction::SetSigners(Vec::from_array(&env, [new_signer.clone()]), 2),
+        ),
+        Err(Ok(ContractError::InvalidThreshold))
+    );
+
+    // Approved by a signer that is about to be removed.
+    let pending = client.propose_governance_action(
+        &signers.get(0).unwrap(),
+        &GovernanceAction::SetMaxStake(500),
+    );
+
+    let id = client.propose_governance_action(
+        &signers.get(0).unwrap(),
+        &GovernanceAction::SetSigners(
+            Vec::from_array(&env, [signers.get(1).unwrap(), new_signer.clone()]),
+            2,
+        ),
+    );
+    assert!(client.approve_governance_action(&signers.get(1).unwrap(), &id));
+    assert_eq!(
+        client.try_propose_governance_action(
+            &signers.get(0).unwrap(),
+            &GovernanceAction::SetMaxStake(500),
+        ),
+        Err(Ok(ContractError::NotASigner))
+    );
+
+    // The removed signer's approval no longer counts towards the threshold.
+    assert!(!client.approve_governance_action(&signers.get(1).unwrap(), &pending));
+    assert!(client.approve_governance_action(&new_signer, &pending));
+}
+
+#[test]
+fn test_governance_proposal_expires() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, signers) = setup_governance(&env, 2);
+    env.as_contract(&client.address, || {
+        env.storage().instance().set(&GOV_LIFETIME, &10u64);
+    });
+
+    let id = client.propose_governance_action(
+        &signers.get(0).unwrap(),
+        &GovernanceAction::ConfigureTimeout(100),
+    );
+    let proposal = client.get_governance_proposal(&id).unwrap();
+    env.ledger()
+        .with_mut(|l| l.sequence_number = proposal.expires_at as u32 + 1);
+
+    assert_eq!(
+        client.try_approve_governance_action(&signers.get(1).unwrap(), &id),
+        Err(Ok(ContractError::ProposalExpired))
+    );
+    client.cancel_governance_action(&signers.get(2).unwrap(), &id);
+    assert!(client.get_governance_proposal(&id).is_none());
+}
+
+#[test]
+fn test_governance_rejects_invalid_and_duplicate_approvals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, signers) = setup_governance(&env, 3);
+    let proposer = signers.get(0).unwrap();
+
+    assert_eq!(
+        client.try_propose_governance_action(&proposer, &GovernanceAction::SetMaxStake(0)),
+        Err(Ok(ContractError::InvalidAmount))
+    );
+    assert_eq!(
+        client.try_propose_governance_action(
+            &Address::generate(&env),
+            &GovernanceAction::ConfigureTimeout(10)
+        ),
+        Err(Ok(ContractError::NotASigner))
+    );
+
+    let id = client.propose_governance_action(
+        &proposer,
+        &GovernanceAction::SetFees(100, Address::generate(&env)),
+    );
+    assert_eq!(
+        client.try_approve_governance_action(&proposer, &id),
+        Err(Ok(ContractError::AlreadyApproved))
+    );
+    assert!(!client.approve_governance_action(&signers.get(1).unwrap(), &id));
+    assert_eq!(
+        client.get_governance_proposal(&id).unwrap().approvals.len(),
+        2
+    );
+}
+
+// ── Upgrade Tests ─────────────────────────────────────────────────────────
+
+/// Stand-in for ai_nft / game_registry: records the hash it was asked to
+/// upgrade to.
+#[contract]
+struct UpgradeTarget;
+
+#[contractimpl]
+impl UpgradeTarget {
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("wasm"), &new_wasm_hash);
+    }
+}
+
+#[test]
+fn test_upgrade_contract_goes_through_governance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, _, signers) = setup_governance(&env, 2);
+    let target = env.register_contract(None, UpgradeTarget);
+    let wasm_hash = BytesN::from_array(&env, &[5u8; 32]);
+
+    let id = client.propose_governance_action(
+        &signers.get(0).unwrap(),
+        &GovernanceAction::UpgradeContract(target.clone(), wasm_hash.clone()),
+    );
+    let upgraded = || -> Option<BytesN<32>> {
+        env.as_contract(&target, || {
+            env.storage().instance().get(&symbol_short!("wasm"))
+        })
+    };
+    assert_eq!(upgraded(), None);
+
+    client.approve_governance_action(&signers.get(1).unwrap(), &id);
+    assert_eq!(upgraded(), Some(wasm_hash));
+}
+
+#[test]
+fn test_migrate_tracks_schema_version() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let client = GameContractClient::new(&env, &contract_id);
+    init_contract(&env, &contract_id);
+
+    assert_eq!(client.schema_version(), SCHEMA_VERSION);
+    assert_eq!(client.migrate(), SCHEMA_VERSION);
+
+    env.as_contract(&contract_id, || {
+        env.storage()
+            .instance()
+            .set(&SCHEMA_VER, &(SCHEMA_VERSION + 1));
+    });
+    assert_eq!(client.try_migrate(), Err(Ok(ContractError::SchemaTooNew)));
+}
+
+// ── AI Agent Tests ────────────────────────────────────────────────────────
+
+/// Stand-in for ai_nft: fixed users, and results appended in report order.
+#[contract]
+struct AgentNftStub;
+
+#[contractimpl]
+impl AgentNftStub {
+    pub fn set_user(env: Env, nft_id: u64, user: Address) {
+        env.storage().instance().set(&nft_id, &user);
+    }
+
+    pub fn user_of(env: Env, nft_id: u64) -> Address {
+        env.storage().instance().get(&nft_id).expect("Unknown NFT")
+    }
+
+    pub fn record_result(env: Env, nft_id: u64, outcome: MatchOutcome) {
+        let mut results: Vec<(u64, MatchOutcome)> = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("results"))
+            .unwrap_or(Vec::new(&env));
+        results.push_back((nft_id, outcome));
+        env.storage()
+            .instance()
+            .set(&symbol_short!("results"), &results);
+    }
+
+    pub fn results(env: Env) -> Vec<(u64, MatchOutcome)> {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("results"))
+            .unwrap_or(Vec::new(&env))
+    }
+}
+
+#[test]
+fn test_agent_results_reported_on_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, player1, player2, game_id) = setup_in_progress_game(&env, &contract_id);
+    let admin: Address = env.as_contract(&contract_id, || {
+        env.storage().instance().get(&CONTRACT_ADMIN).unwrap()
+    });
+
+    let nft_id = env.register_contract(None, AgentNftStub);
+    let nft = AgentNftStubClient::new(&env, &nft_id);
+    nft.set_user(&1, &player1);
+    nft.set_user(&2, &player2);
+
+    assert_eq!(
+        client.try_assign_agent(&game_id, &player1, &1),
+        Err(Ok(ContractError::AgentsNotConfigured))
+    );
+    client.set_agent_nft(&admin, &nft_id);
+
+    // Only the NFT's current user can field it, and only as a participant.
+    assert_eq!(
+        client.try_assign_agent(&game_id, &player1, &2),
+        Err(Ok(ContractError::AgentNotOwned))
+    );
+    assert_eq!(
+        client.try_assign_agent(&game_id, &player1, &99),
+        Err(Ok(ContractError::AgentNotOwned))
+    );
+    assert_eq!(
+        client.try_assign_agent(&game_id, &Address::generate(&env), &1),
+        Err(Ok(ContractError::NotPlayer))
+    );
+
+    client.assign_agent(&game_id, &player1, &1);
+    client.assign_agent(&game_id, &player2, &2);
+    assert_eq!(client.get_game_agents(&game_id).len(), 2);
+
+    client.forfeit(&game_id, &player2);
+
+    let results = nft.results();
+    assert_eq!(results.len(), 2);
+    assert!(results.contains((1, MatchOutcome::Win)));
+    assert!(results.contains((2, MatchOutcome::Loss)));
+    assert!(client.get_game_agents(&game_id).is_empty());
+}
+
+#[test]
+fn test_slash_disconnect_pays_opponent_and_refunds_remainder() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let contract_id = env.register_contract(None, GameContract);
+    let (client, player1, player2, game_id) = setup_in_progress_game(&env, &contract_id);
+    let admin: Address = env.as_contract(&contract_id, || {
+        env.storage().instance().get(&CONTRACT_ADMIN).unwrap()
+    });
+    let reporter = Address::generate(&env);
+
+    assert_eq!(
+        client.try_slash_disconnect(&game_id, &player2),
+        Err(Ok(ContractError::SlashingNotConfigured))
+    );
+    assert_eq!(
+        client.try_configure_slashing(&admin, &10_001, &reporter),
+        Err(Ok(ContractError::InvalidAmount))
+    );
+    assert_eq!(client.get_slash_rate(), 2_500);
+    client.configure_slashing(&admin, &2_500, &reporter);
+
+    assert_eq!(
+        client.try_slash_disconnect(&game_id, &Address::generate(&env)),
+        Err(Ok(ContractError::NotPlayer))
+    );
+
+    // 25 % of player2's 100 stake goes to player1 on top of their own stake.
+    assert_eq!(client.slash_disconnect(&game_id, &player2), 25);
+    let game = client.get_game(&game_id);
+    assert_eq!(game.state, GameState::Forfeited);
+    assert_eq!(game.winner, Some(player1.clone()));
+
+    let token = TokenClient::new(&env, &game.token);
+    assert_eq!(token.balance(&player1), 125);
+    assert_eq!(token.balance(&player2), 75);
+    assert_eq!(token.balance(&contract_id), 0);
+
+    assert_eq!(
+        client.try_slash_disconnect(&game_id, &player2),
+        Err(Ok(ContractError::GameNotInProgress))
+    );
+}
@@ -1,5 +1,8 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, contracterror, Address, Env, String, Symbol, token};
+use soroban_sdk::{contract, contractclient, contractimpl, contracttype, contracterror, Address, Bytes, BytesN, Env, String, Symbol, Vec, token};
+
+/// Storage layout version this code reads and writes; see `migrate`.
+pub const SCHEMA_VERSION: u32 = 1;
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -14,6 +17,16 @@ pub enum RegistryError {
     InsufficientEntryFee = 7,
     TournamentNotFound = 8,
     TournamentAlreadyExists = 9,
+    Paused = 10,
+    UpgradeAuthorityNotSet = 11,
+    SchemaTooNew = 12,
+    InvalidPayouts = 13,
+    InvalidTournamentStatus = 14,
+    NotRegistered = 15,
+    InvalidRanking = 16,
+    EmptyBatch = 17,
+    EpochNotFound = 18,
+    InvalidProof = 19,
 }
 
 #[contracttype]
@@ -35,6 +48,52 @@ pub struct Tournament {
     pub participants: u32,
 }
 
+/// Basis points denominator for `payout_bips`.
+pub const BIPS_DENOMINATOR: i128 = 10_000;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TournamentStatus {
+    Open,
+    InProgress,
+    Finalized,
+    Cancelled,
+}
+
+/// Lifecycle and prize pool of a tournament, kept next to `Tournament` so
+/// records written before the lifecycle existed still decode.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TournamentState {
+    pub status: TournamentStatus,
+    pub min_participants: u32,
+    pub payout_bips: Vec<u32>, // share of the pool per rank, summing to 10_000
+    pub prize_pool: i128,      // entry fees held by this contract
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Placement {
+    pub rank: u32,
+    pub player: Address,
+    pub prize: i128,
+}
+
+/// Longest inclusion proof accepted; enough for 2^32 games in one epoch.
+pub const MAX_PROOF_DEPTH: u32 = 32;
+
+/// Merkle root of a batch of finished games. Leaves are
+/// `sha256(0x00 || proof_hash)` where `proof_hash` is the game's proof-of-game
+/// hash, and inner nodes are `sha256(0x01 || min(a, b) || max(a, b))`. An odd
+/// node at the end of a level is carried up unchanged.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchAnchor {
+    pub root: BytesN<32>,
+    pub leaf_count: u32,
+    pub timestamp: u64,
+}
+
 #[contracttype]
 pub enum DataKey {
     Admin,
@@ -42,6 +101,31 @@ pub enum DataKey {
     Game(String),
     Tournament(String),
     Registration(String, Address),
+    CircuitBreaker,
+    UpgradeAuthority,
+    SchemaVersion,
+    TournamentState(String),
+    EntryPaid(String, Address),
+    TournamentResults(String),
+    EpochCount,
+    Epoch(u64),
+    ProvenGame(BytesN<32>),
+    GameProof(String),
+}
+
+/// Mirrors `emergency_circuit_breaker::PauseCategory`; only the variant
+/// names cross the contract boundary.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PauseCategory {
+    NewGames,
+    Payouts,
+    PuzzleRewards,
+}
+
+#[contractclient(name = "CircuitBreakerClient")]
+pub trait CircuitBreaker {
+    fn is_category_paused(env: Env, category: PauseCategory) -> bool;
 }
 
 #[contract]
@@ -49,21 +133,27 @@ pub struct GameRegistry;
 
 #[contractimpl]
 impl GameRegistry {
-    /// Initialize the contract with an admin and an authorized server address.
-    pub fn initialize(env: Env, admin: Address, server: Address) -> Result<(), RegistryError> {
+    /// Initialize the contract with an admin, an authorized server address
+    /// and the address allowed to upgrade it (normally the game contract).
+    pub fn initialize(env: Env, admin: Address, server: Address, upgrade_authority: Address) -> Result<(), RegistryError> {
         if env.storage().persistent().has(&DataKey::Admin) {
             return Err(RegistryError::AlreadyInitialized);
         }
         env.storage().persistent().set(&DataKey::Admin, &admin);
         env.storage().persistent().set(&DataKey::Server, &server);
+        env.storage().persistent().set(&DataKey::UpgradeAuthority, &upgrade_authority);
+        env.storage().persistent().set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
 
         // Extend TTL for Admin and Server keys to prevent expiration
         env.storage().persistent().extend_ttl(&DataKey::Admin, 100_000, 500_000);
         env.storage().persistent().extend_ttl(&DataKey::Server, 100_000, 500_000);
+        env.storage().persistent().extend_ttl(&DataKey::UpgradeAuthority, 100_000, 500_000);
+        env.storage().persistent().extend_ttl(&DataKey::SchemaVersion, 100_000, 500_000);
         Ok(())
     }
 
-    /// Records a game result. Only the authorized server can call this.
+    /// Records a game result and its proof hash (`chess::proof` in the
+    /// backend). Only the authorized server can call this.
     pub fn record_game(
         env: Env,
         game_id: String,
@@ -71,6 +161,7 @@ impl GameRegistry {
         white: Address,
         black: Address,
         timestamp: u64,
+        proof_hash: BytesN<32>,
     ) -> Result<(), RegistryError> {
         let server: Address = env.storage().persistent().get(&DataKey::Server).ok_or(RegistryError::NotInitialized)?;
         server.require_auth();
@@ -92,14 +183,29 @@ impl GameRegistry {
         // Extend TTL for the game record to ensure it stays active.
         env.storage().persistent().extend_ttl(&key, 100_000, 500_000);
 
+        // Stored apart from `GameResult` so records from before proofs still decode.
+        let proof_key = DataKey::GameProof(game_id.clone());
+        env.storage().persistent().set(&proof_key, &proof_hash);
+        env.storage().persistent().extend_ttl(&proof_key, 100_000, 500_000);
+
         // Emit GameFinalized event
         env.events().publish(
             (Symbol::new(&env, "GameFinalized"), game_id),
-            (winner, timestamp),
+            (winner, timestamp, proof_hash),
         );
         Ok(())
     }
 
+    /// Returns the proof hash anchored with a game, if it was recorded with one.
+    pub fn get_game_proof(env: Env, game_id: String) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&DataKey::GameProof(game_id))
+    }
+
+    /// Checks a proof hash recomputed off chain against the one anchored for a game.
+    pub fn verify_game(env: Env, game_id: String, proof_hash: BytesN<32>) -> bool {
+        Self::get_game_proof(env, game_id) == Some(proof_hash)
+    }
+
     /// Retrieves a recorded game result.
     pub fn get_game(env: Env, game_id: String) -> Result<GameResult, RegistryError> {
         env.storage()
@@ -108,6 +214,78 @@ impl GameRegistry {
             .ok_or(RegistryError::GameNotFound)
     }
 
+    /// Anchors the Merkle root of a batch of game proof hashes as the next
+    /// epoch. Only the authorized server can call this. Returns the epoch,
+    /// numbered from 1. Individual games are not stored; `prove_game` checks
+    /// one against the root on demand.
+    pub fn anchor_batch(env: Env, root: BytesN<32>, leaf_count: u32) -> Result<u64, RegistryError> {
+        let server: Address = env.storage().persistent().get(&DataKey::Server).ok_or(RegistryError::NotInitialized)?;
+        server.require_auth();
+
+        if leaf_count == 0 {
+            return Err(RegistryError::EmptyBatch);
+        }
+
+        let epoch = Self::epoch_count(env.clone()) + 1;
+        let key = DataKey::Epoch(epoch);
+        let timestamp = env.ledger().timestamp();
+        env.storage().persistent().set(&key, &BatchAnchor { root: root.clone(), leaf_count, timestamp });
+        env.storage().persistent().extend_ttl(&key, 100_000, 500_000);
+        env.storage().persistent().set(&DataKey::EpochCount, &epoch);
+        env.storage().persistent().extend_ttl(&DataKey::EpochCount, 100_000, 500_000);
+
+        env.events().publish(
+            (Symbol::new(&env, "BatchAnchored"), epoch),
+            (root, leaf_count),
+        );
+        Ok(epoch)
+    }
+
+    /// Number of anchored epochs.
+    pub fn epoch_count(env: Env) -> u64 {
+        env.storage().persistent().get(&DataKey::EpochCount).unwrap_or(0)
+    }
+
+    /// Retrieves the anchor of an epoch.
+    pub fn get_batch(env: Env, epoch: u64) -> Result<BatchAnchor, RegistryError> {
+        env.storage().persistent().get(&DataKey::Epoch(epoch)).ok_or(RegistryError::EpochNotFound)
+    }
+
+    /// Checks that `proof_hash` is a leaf of the epoch's batch. `proof` lists
+    /// the sibling hashes from the leaf up to the root.
+    pub fn verify_inclusion(env: Env, epoch: u64, proof_hash: BytesN<32>, proof: Vec<BytesN<32>>) -> Result<bool, RegistryError> {
+        let batch = Self::get_batch(env.clone(), epoch)?;
+        if proof.len() > MAX_PROOF_DEPTH {
+            return Ok(false);
+        }
+
+        let mut node = Self::hash_tagged(&env, 0, &proof_hash, None);
+        for sibling in proof.iter() {
+            node = Self::hash_tagged(&env, 1, &node, Some(&sibling));
+        }
+        Ok(node == batch.root)
+    }
+
+    /// Verifies a game's inclusion proof and records the game as proven in
+    /// that epoch. Anyone can call this.
+    pub fn prove_game(env: Env, epoch: u64, proof_hash: BytesN<32>, proof: Vec<BytesN<32>>) -> Result<(), RegistryError> {
+        if !Self::verify_inclusion(env.clone(), epoch, proof_hash.clone(), proof)? {
+            return Err(RegistryError::InvalidProof);
+        }
+
+        let key = DataKey::ProvenGame(proof_hash.clone());
+        env.storage().persistent().set(&key, &epoch);
+        env.storage().persistent().extend_ttl(&key, 100_000, 500_000);
+
+        env.events().publish((Symbol::new(&env, "GameProven"), epoch), proof_hash);
+        Ok(())
+    }
+
+    /// Returns the epoch a game was proven in, if `prove_game` succeeded for it.
+    pub fn get_proven_epoch(env: Env, proof_hash: BytesN<32>) -> Option<u64> {
+        env.storage().persistent().get(&DataKey::ProvenGame(proof_hash))
+    }
+
     /// Updates the authorized server address. Only the admin can call this.
     pub fn set_server(env: Env, new_server: Address) -> Result<(), RegistryError> {
         let admin: Address = env.storage().persistent().get(&DataKey::Admin).ok_or(RegistryError::NotInitialized)?;
@@ -126,13 +304,76 @@ impl GameRegistry {
         Ok(())
     }
 
-    /// Creates a new tournament. Only admin can call this.
+    /// Sets the emergency circuit breaker consulted before entry fees are
+    /// collected (`NewGames`) and prizes paid out (`Payouts`). Only the admin
+    /// can call this.
+    pub fn set_circuit_breaker(env: Env, breaker: Address) -> Result<(), RegistryError> {
+        let admin: Address = env.storage().persistent().get(&DataKey::Admin).ok_or(RegistryError::NotInitialized)?;
+        admin.require_auth();
+        env.storage().persistent().set(&DataKey::CircuitBreaker, &breaker);
+        env.storage().persistent().extend_ttl(&DataKey::CircuitBreaker, 100_000, 500_000);
+        Ok(())
+    }
+
+    /// Hands the upgrade authority over to another address. Only the current
+    /// authority can do this; the admin has no say once `initialize` has set it.
+    pub fn set_upgrade_authority(env: Env, authority: Address) -> Result<(), RegistryError> {
+        let current: Address = env.storage().persistent().get(&DataKey::UpgradeAuthority).ok_or(RegistryError::UpgradeAuthorityNotSet)?;
+        current.require_auth();
+        env.storage().persistent().set(&DataKey::UpgradeAuthority, &authority);
+        env.storage().persistent().extend_ttl(&DataKey::UpgradeAuthority, 100_000, 500_000);
+        Ok(())
+    }
+
+    /// Returns the upgrade authority, if one has been set.
+    pub fn get_upgrade_authority(env: Env) -> Option<Address> {
+        env.storage().persistent().get(&DataKey::UpgradeAuthority)
+    }
+
+    /// Replaces the contract code with already-uploaded WASM. Records and
+    /// tournaments are kept; call `migrate` afterwards if the new code bumps
+    /// `SCHEMA_VERSION`.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), RegistryError> {
+        let authority: Address = env.storage().persistent().get(&DataKey::UpgradeAuthority).ok_or(RegistryError::UpgradeAuthorityNotSet)?;
+        authority.require_auth();
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    /// Storage layout version currently persisted (v1 for deployments that
+    /// predate versioning).
+    pub fn schema_version(env: Env) -> u32 {
+        env.storage().persistent().get(&DataKey::SchemaVersion).unwrap_or(1)
+    }
+
+    /// Brings persisted state up to `SCHEMA_VERSION`. Idempotent, so anyone
+    /// may call it after an upgrade.
+    pub fn migrate(env: Env) -> Result<u32, RegistryError> {
+        let from = Self::schema_version(env.clone());
+        if from > SCHEMA_VERSION {
+            return Err(RegistryError::SchemaTooNew);
+        }
+
+        // Per-version steps go here (`if from < 2 { ... }`) when the layout
+        // changes; v1 is the only layout so far.
+
+        env.storage().persistent().set(&DataKey::SchemaVersion, &SCHEMA_VERSION);
+        env.storage().persistent().extend_ttl(&DataKey::SchemaVersion, 100_000, 500_000);
+        Ok(SCHEMA_VERSION)
+    }
+
+    /// Creates a new tournament. Only admin can call this. `payout_bips` is
+    /// the share of the prize pool for each finishing rank (first place
+    /// first) and must add up to 10_000; `start_tournament` cancels the
+    /// tournament if fewer than `min_participants` registered.
     pub fn create_tournament(
         env: Env, 
         id: String, 
         capacity: u32, 
         entry_fee: i128, 
-        token_address: Address
+        token_address: Address,
+        min_participants: u32,
+        payout_bips: Vec<u32>,
     ) -> Result<(), RegistryError> {
         let admin: Address = env.storage().persistent().get(&DataKey::Admin).ok_or(RegistryError::NotInitialized)?;
         admin.require_auth();
@@ -142,8 +383,13 @@ impl GameRegistry {
             return Err(RegistryError::TournamentAlreadyExists);
         }
 
+        let total_bips: i128 = payout_bips.iter().map(|bips| bips as i128).sum();
+        if payout_bips.is_empty() || payout_bips.len() > capacity || total_bips != BIPS_DENOMINATOR || min_participants > capacity {
+            return Err(RegistryError::InvalidPayouts);
+        }
+
         let tournament = Tournament {
-            id,
+            id: id.clone(),
             capacity,
             entry_fee,
             token_address,
@@ -152,10 +398,12 @@ impl GameRegistry {
 
         env.storage().persistent().set(&t_key, &tournament);
         env.storage().persistent().extend_ttl(&t_key, 100_000, 500_000);
+        Self::save_state(&env, &id, &TournamentState { status: TournamentStatus::Open, min_participants, payout_bips, prize_pool: 0 });
         Ok(())
     }
 
-    /// Registers a player for a tournament.
+    /// Registers a player for a tournament. The entry fee is held by this
+    /// contract until the tournament is finalized or refunded.
     pub fn register_tournament(
         env: Env, 
         player: Address, 
@@ -163,8 +411,15 @@ impl GameRegistry {
     ) -> Result<(), RegistryError> {
         player.require_auth();
 
+        // Registration collects an entry fee, so it counts as starting new games.
+        Self::require_not_paused(&env, PauseCategory::NewGames)?;
+
         let t_key = DataKey::Tournament(tournament_id.clone());
         let mut tournament: Tournament = env.storage().persistent().get(&t_key).ok_or(RegistryError::TournamentNotFound)?;
+        let mut state = Self::get_tournament_state(env.clone(), tournament_id.clone())?;
+        if state.status != TournamentStatus::Open {
+            return Err(RegistryError::InvalidTournamentStatus);
+        }
 
         if tournament.participants >= tournament.capacity {
             return Err(RegistryError::TournamentFull);
@@ -181,8 +436,13 @@ impl GameRegistry {
                 return Err(RegistryError::InsufficientEntryFee);
             }
 
-            let admin: Address = env.storage().persistent().get(&DataKey::Admin).ok_or(RegistryError::NotInitialized)?;
-            token_client.transfer(&player, &admin, &tournament.entry_fee);
+            token_client.transfer(&player, &env.current_contract_address(), &tournament.entry_fee);
+
+            let paid_key = DataKey::EntryPaid(tournament_id.clone(), player.clone());
+            env.storage().persistent().set(&paid_key, &tournament.entry_fee);
+            env.storage().persistent().extend_ttl(&paid_key, 100_000, 500_000);
+            state.prize_pool += tournament.entry_fee;
+            Self::save_state(&env, &tournament_id, &state);
         }
 
         tournament.participants += 1;
@@ -194,6 +454,215 @@ impl GameRegistry {
 
         Ok(())
     }
+
+    /// Leaves a tournament that has not started yet and refunds the entry fee.
+    pub fn withdraw_registration(env: Env, player: Address, tournament_id: String) -> Result<(), RegistryError> {
+        player.require_auth();
+
+        let state = Self::get_tournament_state(env.clone(), tournament_id.clone())?;
+        if state.status != TournamentStatus::Open {
+            return Err(RegistryError::InvalidTournamentStatus);
+        }
+
+        Self::refund_registration(&env, &player, &tournament_id)?;
+
+        let t_key = DataKey::Tournament(tournament_id.clone());
+        let mut tournament: Tournament = env.storage().persistent().get(&t_key).ok_or(RegistryError::TournamentNotFound)?;
+        tournament.participants -= 1;
+        env.storage().persistent().set(&t_key, &tournament);
+        env.storage().persistent().extend_ttl(&t_key, 100_000, 500_000);
+        Ok(())
+    }
+
+    /// Closes registration and starts the tournament. Only admin can call
+    /// this. If fewer than `min_participants` registered the tournament is
+    /// cancelled instead and registrants can `claim_refund`. Returns the new
+    /// status.
+    pub fn start_tournament(env: Env, tournament_id: String) -> Result<TournamentStatus, RegistryError> {
+        let admin: Address = env.storage().persistent().get(&DataKey::Admin).ok_or(RegistryError::NotInitialized)?;
+        admin.require_auth();
+
+        let tournament: Tournament = env.storage().persistent().get(&DataKey::Tournament(tournament_id.clone())).ok_or(RegistryError::TournamentNotFound)?;
+        let mut state = Self::get_tournament_state(env.clone(), tournament_id.clone())?;
+        if state.status != TournamentStatus::Open {
+            return Err(RegistryError::InvalidTournamentStatus);
+        }
+
+        state.status = if tournament.participants < state.min_participants { TournamentStatus::Cancelled } else { TournamentStatus::InProgress };
+        Self::save_state(&env, &tournament_id, &state);
+
+        let event = if state.status == TournamentStatus::Cancelled { "TournamentCancelled" } else { "TournamentStarted" };
+        env.events().publish((Symbol::new(&env, event), tournament_id), tournament.participants);
+        Ok(state.status)
+    }
+
+    /// Cancels a tournament that has not been finalized. Only admin can call
+    /// this. Registrants then get their entry fee back with `claim_refund`.
+    pub fn cancel_tournament(env: Env, tournament_id: String) -> Result<(), RegistryError> {
+        let admin: Address = env.storage().persistent().get(&DataKey::Admin).ok_or(RegistryError::NotInitialized)?;
+        admin.require_auth();
+
+        let mut state = Self::get_tournament_state(env.clone(), tournament_id.clone())?;
+        if !matches!(state.status, TournamentStatus::Open | TournamentStatus::InProgress) {
+            return Err(RegistryError::InvalidTournamentStatus);
+        }
+
+        state.status = TournamentStatus::Cancelled;
+        Self::save_state(&env, &tournament_id, &state);
+
+        env.events().publish((Symbol::new(&env, "TournamentCancelled"), tournament_id), state.prize_pool);
+        Ok(())
+    }
+
+    /// Returns a registrant's entry fee from a cancelled tournament.
+    pub fn claim_refund(env: Env, player: Address, tournament_id: String) -> Result<(), RegistryError> {
+        player.require_auth();
+
+        let state = Self::get_tournament_state(env.clone(), tournament_id.clone())?;
+        if state.status != TournamentStatus::Cancelled {
+            return Err(RegistryError::InvalidTournamentStatus);
+        }
+
+        Self::refund_registration(&env, &player, &tournament_id)
+    }
+
+    /// Pays out the prize pool and records the final standings. Only the
+    /// authorized server can call this. `ranking` lists registered players
+    /// from first place down; rank `i` receives `payout_bips[i]` of the pool.
+    /// Shares for ranks nobody filled, and rounding dust, go to first place.
+    pub fn finalize_tournament(env: Env, tournament_id: String, ranking: Vec<Address>) -> Result<Vec<Placement>, RegistryError> {
+        let server: Address = env.storage().persistent().get(&DataKey::Server).ok_or(RegistryError::NotInitialized)?;
+        server.require_auth();
+        Self::require_not_paused(&env, PauseCategory::Payouts)?;
+
+        let tournament: Tournament = env.storage().persistent().get(&DataKey::Tournament(tournament_id.clone())).ok_or(RegistryError::TournamentNotFound)?;
+        let mut state = Self::get_tournament_state(env.clone(), tournament_id.clone())?;
+        if state.status != TournamentStatus::InProgress {
+            return Err(RegistryError::InvalidTournamentStatus);
+        }
+
+        if ranking.is_empty() || ranking.len() > state.payout_bips.len() {
+            return Err(RegistryError::InvalidRanking);
+        }
+        for (i, player) in ranking.iter().enumerate() {
+            if !env.storage().persistent().has(&DataKey::Registration(tournament_id.clone(), player.clone())) || ranking.first_index_of(&player) != Some(i as u32) {
+                return Err(RegistryError::InvalidRanking);
+            }
+        }
+
+        let mut placements = Vec::new(&env);
+        let mut paid = 0i128;
+        for (i, player) in ranking.iter().enumerate() {
+            let prize = state.prize_pool * state.payout_bips.get_unchecked(i as u32) as i128 / BIPS_DENOMINATOR;
+            paid += prize;
+            placements.push_back(Placement { rank: i as u32 + 1, player, prize });
+        }
+        let mut first = placements.get_unchecked(0);
+        first.prize += state.prize_pool - paid;
+        placements.set(0, first);
+
+        let token_client = token::Client::new(&env, &tournament.token_address);
+        for placement in placements.iter() {
+            if placement.prize > 0 {
+                token_client.transfer(&env.current_contract_address(), &placement.player, &placement.prize);
+            }
+        }
+
+        state.status = TournamentStatus::Finalized;
+        state.prize_pool = 0;
+        Self::save_state(&env, &tournament_id, &state);
+
+        let results_key = DataKey::TournamentResults(tournament_id.clone());
+        env.storage().persistent().set(&results_key, &placements);
+        env.storage().persistent().extend_ttl(&results_key, 100_000, 500_000);
+
+        env.events().publish((Symbol::new(&env, "TournamentFinalized"), tournament_id), placements.clone());
+        Ok(placements)
+    }
+
+    /// Retrieves a tournament.
+    pub fn get_tournament(env: Env, tournament_id: String) -> Result<Tournament, RegistryError> {
+        env.storage().persistent().get(&DataKey::Tournament(tournament_id)).ok_or(RegistryError::TournamentNotFound)
+    }
+
+    /// Retrieves a tournament's status and prize pool. Tournaments created
+    /// before the lifecycle existed report as open with an empty pool; their
+    /// entry fees were paid to the admin.
+    pub fn get_tournament_state(env: Env, tournament_id: String) -> Result<TournamentState, RegistryError> {
+        if let Some(state) = env.storage().persistent().get(&DataKey::TournamentState(tournament_id.clone())) {
+            return Ok(state);
+        }
+        if !env.storage().persistent().has(&DataKey::Tournament(tournament_id)) {
+            return Err(RegistryError::TournamentNotFound);
+        }
+        let mut payout_bips = Vec::new(&env);
+        payout_bips.push_back(BIPS_DENOMINATOR as u32);
+        Ok(TournamentState { status: TournamentStatus::Open, min_participants: 0, payout_bips, prize_pool: 0 })
+    }
+
+    /// Retrieves the final standings and prizes of a finalized tournament.
+    pub fn get_tournament_results(env: Env, tournament_id: String) -> Option<Vec<Placement>> {
+        env.storage().persistent().get(&DataKey::TournamentResults(tournament_id))
+    }
+}
+
+impl GameRegistry {
+    /// `sha256(tag || a)` for leaves, `sha256(tag || min(a, b) || max(a, b))`
+    /// for inner nodes.
+    fn hash_tagged(env: &Env, tag: u8, a: &BytesN<32>, b: Option<&BytesN<32>>) -> BytesN<32> {
+        let mut data = Bytes::from_array(env, &[tag]);
+        match b {
+            Some(b) if b < a => {
+                data.append(&Bytes::from(b.clone()));
+                data.append(&Bytes::from(a.clone()));
+            }
+            Some(b) => {
+                data.append(&Bytes::from(a.clone()));
+                data.append(&Bytes::from(b.clone()));
+            }
+            None => data.append(&Bytes::from(a.clone())),
+        }
+        env.crypto().sha256(&data).to_bytes()
+    }
+
+    /// Fails with `Paused` if a circuit breaker is set and has `category` paused.
+    fn require_not_paused(env: &Env, category: PauseCategory) -> Result<(), RegistryError> {
+        if let Some(breaker) = env.storage().persistent().get::<_, Address>(&DataKey::CircuitBreaker) {
+            if CircuitBreakerClient::new(env, &breaker).is_category_paused(&category) {
+                return Err(RegistryError::Paused);
+            }
+        }
+        Ok(())
+    }
+
+    fn save_state(env: &Env, tournament_id: &String, state: &TournamentState) {
+        let key = DataKey::TournamentState(tournament_id.clone());
+        env.storage().persistent().set(&key, state);
+        env.storage().persistent().extend_ttl(&key, 100_000, 500_000);
+    }
+
+    /// Drops `player`'s registration and pays back what they paid in.
+    fn refund_registration(env: &Env, player: &Address, tournament_id: &String) -> Result<(), RegistryError> {
+        let reg_key = DataKey::Registration(tournament_id.clone(), player.clone());
+        if !env.storage().persistent().has(&reg_key) {
+            return Err(RegistryError::NotRegistered);
+        }
+        env.storage().persistent().remove(&reg_key);
+
+        let paid_key = DataKey::EntryPaid(tournament_id.clone(), player.clone());
+        let paid: i128 = env.storage().persistent().get(&paid_key).unwrap_or(0);
+        if paid > 0 {
+            env.storage().persistent().remove(&paid_key);
+
+            let mut state = Self::get_tournament_state(env.clone(), tournament_id.clone())?;
+            state.prize_pool -= paid;
+            Self::save_state(env, tournament_id, &state);
+
+            let tournament: Tournament = env.storage().persistent().get(&DataKey::Tournament(tournament_id.clone())).ok_or(RegistryError::TournamentNotFound)?;
+            token::Client::new(env, &tournament.token_address).transfer(&env.current_contract_address(), player, &paid);
+        }
+        Ok(())
+    }
 }
 
 mod test;
@@ -2,7 +2,8 @@
 
 use super::*;
 use soroban_sdk::testutils::Address as _;
-use soroban_sdk::{Address, Env, String};
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::{contract, contractimpl, vec, Address, BytesN, Env, String};
 
 #[test]
 fn test_game_registry_success() {
@@ -14,17 +15,18 @@ fn test_game_registry_success() {
     let player1 = Address::generate(&env);
     let player2 = Address::generate(&env);
 
-    let contract_id = env.register(GameRegistry, ());
+    let contract_id = env.register_contract(None, GameRegistry);
     let client = GameRegistryClient::new(&env, &contract_id);
 
     // Initialize
-    client.initialize(&admin, &server);
+    client.initialize(&admin, &server, &admin);
 
     let game_id = String::from_str(&env, "game-123");
     let timestamp = 1737500000u64;
     
     // Record game by server
-    client.record_game(&game_id, &player1, &player1, &player2, &timestamp);
+    let proof_hash = BytesN::from_array(&env, &[7; 32]);
+    client.record_game(&game_id, &player1, &player1, &player2, &timestamp, &proof_hash);
 
     // Verify game retrieval
     let recorded_game = client.get_game(&game_id);
@@ -32,6 +34,12 @@ fn test_game_registry_success() {
     assert_eq!(recorded_game.white, player1);
     assert_eq!(recorded_game.black, player2);
     assert_eq!(recorded_game.timestamp, timestamp);
+
+    // Verify the anchored proof
+    assert_eq!(client.get_game_proof(&game_id), Some(proof_hash.clone()));
+    assert!(client.verify_game(&game_id, &proof_hash));
+    assert!(!client.verify_game(&game_id, &BytesN::from_array(&env, &[8; 32])));
+    assert!(!client.verify_game(&String::from_str(&env, "unknown"), &proof_hash));
 }
 
 #[test]
@@ -44,14 +52,14 @@ fn test_unauthorized_record() {
     let server = Address::generate(&env);
     let player = Address::generate(&env);
 
-    let contract_id = env.register(GameRegistry, ());
+    let contract_id = env.register_contract(None, GameRegistry);
     let client = GameRegistryClient::new(&env, &contract_id);
 
-    client.initialize(&admin, &server);
+    client.initialize(&admin, &server, &admin);
 
     let game_id = String::from_str(&env, "fail");
     // This should panic because 'server' has not authorized the call.
-    client.record_game(&game_id, &player, &player, &player, &1);
+    client.record_game(&game_id, &player, &player, &player, &1, &BytesN::from_array(&env, &[0; 32]));
 }
 
 #[test]
@@ -64,10 +72,10 @@ fn test_update_server_and_admin() {
     let new_server = Address::generate(&env);
     let new_admin = Address::generate(&env);
 
-    let contract_id = env.register(GameRegistry, ());
+    let contract_id = env.register_contract(None, GameRegistry);
     let client = GameRegistryClient::new(&env, &contract_id);
 
-    client.initialize(&admin, &server);
+    client.initialize(&admin, &server, &admin);
 
     // Change server
     client.set_server(&new_server);
@@ -77,7 +85,7 @@ fn test_update_server_and_admin() {
 
     // Verify we can still record with new server
     let game_id = String::from_str(&env, "game-456");
-    client.record_game(&game_id, &new_admin, &new_admin, &new_admin, &456);
+    client.record_game(&game_id, &new_admin, &new_admin, &new_admin, &456, &BytesN::from_array(&env, &[0; 32]));
 }
 
 #[test]
@@ -88,11 +96,11 @@ fn test_double_initialize() {
     let admin = Address::generate(&env);
     let server = Address::generate(&env);
 
-    let contract_id = env.register(GameRegistry, ());
+    let contract_id = env.register_contract(None, GameRegistry);
     let client = GameRegistryClient::new(&env, &contract_id);
 
-    client.initialize(&admin, &server);
-    client.initialize(&admin, &server); // Should panic with AlreadyInitialized error
+    client.initialize(&admin, &server, &admin);
+    client.initialize(&admin, &server, &admin); // Should panic with AlreadyInitialized error
 }
 
 #[test]
@@ -107,15 +115,15 @@ fn test_tournament_full() {
     let player2 = Address::generate(&env);
     let player3 = Address::generate(&env);
 
-    let contract_id = env.register(GameRegistry, ());
+    let contract_id = env.register_contract(None, GameRegistry);
     let client = GameRegistryClient::new(&env, &contract_id);
 
-    client.initialize(&admin, &server);
+    client.initialize(&admin, &server, &admin);
 
     let token_address = Address::generate(&env);
     let tournament_id = String::from_str(&env, "tourney-1");
 
-    client.create_tournament(&tournament_id, &2, &0, &token_address);
+    client.create_tournament(&tournament_id, &2, &0, &token_address, &0, &vec![&env, 10_000]);
     client.register_tournament(&player1, &tournament_id);
     client.register_tournament(&player2, &tournament_id);
 
@@ -133,15 +141,15 @@ fn test_already_registered() {
     let server = Address::generate(&env);
     let player1 = Address::generate(&env);
 
-    let contract_id = env.register(GameRegistry, ());
+    let contract_id = env.register_contract(None, GameRegistry);
     let client = GameRegistryClient::new(&env, &contract_id);
 
-    client.initialize(&admin, &server);
+    client.initialize(&admin, &server, &admin);
 
     let token_address = Address::generate(&env);
     let tournament_id = String::from_str(&env, "tourney-2");
 
-    client.create_tournament(&tournament_id, &2, &0, &token_address);
+    client.create_tournament(&tournament_id, &2, &0, &token_address, &0, &vec![&env, 10_000]);
     client.register_tournament(&player1, &tournament_id);
     
     // This should panic with AlreadyRegistered (6)
@@ -158,10 +166,10 @@ fn test_insufficient_entry_fee() {
     let server = Address::generate(&env);
     let player1 = Address::generate(&env);
 
-    let contract_id = env.register(GameRegistry, ());
+    let contract_id = env.register_contract(None, GameRegistry);
     let client = GameRegistryClient::new(&env, &contract_id);
 
-    client.initialize(&admin, &server);
+    client.initialize(&admin, &server, &admin);
 
     // Register a mock token contract
     // For testing insufficient balance, we can just use a non-existent token contract 
@@ -180,8 +188,263 @@ fn test_insufficient_entry_fee() {
     let tournament_id = String::from_str(&env, "tourney-3");
 
     // Entry fee is 100
-    client.create_tournament(&tournament_id, &2, &100, &token_address);
+    client.create_tournament(&tournament_id, &2, &100, &token_address, &0, &vec![&env, 10_000]);
     
     // Player 1 has 0 balance, so this should panic with InsufficientEntryFee (7)
     client.register_tournament(&player1, &tournament_id);
 }
+
+/// Stand-in for `emergency_circuit_breaker` with every category paused.
+#[contract]
+struct PausedBreaker;
+
+#[contractimpl]
+impl PausedBreaker {
+    pub fn is_category_paused(_env: Env, _category: PauseCategory) -> bool {
+        true
+    }
+}
+
+#[test]
+#[should_panic(expected = "HostError: Error(Contract, #10)")]
+fn test_register_tournament_blocked_by_circuit_breaker() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let server = Address::generate(&env);
+    let player1 = Address::generate(&env);
+
+    let contract_id = env.register_contract(None, GameRegistry);
+    let client = GameRegistryClient::new(&env, &contract_id);
+
+    client.initialize(&admin, &server, &admin);
+
+    let breaker_id = env.register_contract(None, PausedBreaker);
+    client.set_circuit_breaker(&breaker_id);
+
+    let token_address = Address::generate(&env);
+    let tournament_id = String::from_str(&env, "tourney-4");
+    client.create_tournament(&tournament_id, &2, &0, &token_address, &0, &vec![&env, 10_000]);
+
+    // This should panic with Paused (10)
+    client.register_tournament(&player1, &tournament_id);
+}
+
+#[test]
+fn test_upgrade_authority_and_migrate() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let server = Address::generate(&env);
+    let authority = Address::generate(&env);
+
+    let contract_id = env.register_contract(None, GameRegistry);
+    let client = GameRegistryClient::new(&env, &contract_id);
+
+    client.initialize(&admin, &server, &authority);
+    assert_eq!(client.get_upgrade_authority(), Some(authority.clone()));
+
+    // Handing it over is signed by the current authority, not the admin.
+    client.set_upgrade_authority(&server);
+    assert_eq!(env.auths()[0].0, authority);
+    assert_eq!(client.get_upgrade_authority(), Some(server));
+
+    assert_eq!(client.schema_version(), SCHEMA_VERSION);
+    assert_eq!(client.migrate(), SCHEMA_VERSION);
+}
+
+/// Sets up a registry and a tournament with a 100-token entry fee, a
+/// 60/30/10 prize split and a minimum of 2 players. Returns the client, the
+/// token, the tournament id and four funded players.
+fn setup_paid_tournament(env: &Env) -> (GameRegistryClient<'_>, TokenClient<'_>, String, [Address; 4]) {
+    let admin = Address::generate(env);
+    let server = Address::generate(env);
+
+    let contract_id = env.register_contract(None, GameRegistry);
+    let client = GameRegistryClient::new(env, &contract_id);
+    client.initialize(&admin, &server, &admin);
+
+    let token_address = env.register_stellar_asset_contract_v2(Address::generate(env)).address();
+    let players = [Address::generate(env), Address::generate(env), Address::generate(env), Address::generate(env)];
+    for player in players.iter() {
+        StellarAssetClient::new(env, &token_address).mint(player, &100);
+    }
+
+    let tournament_id = String::from_str(env, "tourney-paid");
+    client.create_tournament(&tournament_id, &4, &100, &token_address, &2, &vec![env, 6_000, 3_000, 1_000]);
+
+    (client, TokenClient::new(env, &token_address), tournament_id, players)
+}
+
+#[test]
+fn test_create_tournament_rejects_bad_payouts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, _, _) = setup_paid_tournament(&env);
+
+    let id = String::from_str(&env, "bad");
+    assert_eq!(client.try_create_tournament(&id, &4, &100, &token.address, &2, &vec![&env, 6_000, 3_000]), Err(Ok(RegistryError::InvalidPayouts)));
+    assert_eq!(client.try_create_tournament(&id, &2, &100, &token.address, &2, &vec![&env, 5_000, 3_000, 2_000]), Err(Ok(RegistryError::InvalidPayouts)));
+    assert_eq!(client.try_create_tournament(&id, &2, &100, &token.address, &3, &vec![&env, 10_000]), Err(Ok(RegistryError::InvalidPayouts)));
+}
+
+#[test]
+fn test_tournament_lifecycle_pays_prizes_by_rank() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, tournament_id, [p1, p2, p3, outsider]) = setup_paid_tournament(&env);
+
+    for player in [&p1, &p2, &p3] {
+        client.register_tournament(player, &tournament_id);
+    }
+    assert_eq!(token.balance(&client.address), 300);
+    assert_eq!(client.get_tournament_state(&tournament_id).prize_pool, 300);
+
+    assert_eq!(client.start_tournament(&tournament_id), TournamentStatus::InProgress);
+    assert_eq!(client.try_register_tournament(&outsider, &tournament_id), Err(Ok(RegistryError::InvalidTournamentStatus)));
+    assert_eq!(client.try_withdraw_registration(&p1, &tournament_id), Err(Ok(RegistryError::InvalidTournamentStatus)));
+
+    // Rankings may only name registrants, once each.
+    assert_eq!(client.try_finalize_tournament(&tournament_id, &vec![&env, outsider.clone()]), Err(Ok(RegistryError::InvalidRanking)));
+    assert_eq!(client.try_finalize_tournament(&tournament_id, &vec![&env, p2.clone(), p2.clone()]), Err(Ok(RegistryError::InvalidRanking)));
+
+    let placements = client.finalize_tournament(&tournament_id, &vec![&env, p2.clone(), p1.clone(), p3.clone()]);
+    assert_eq!(placements.get_unchecked(0), Placement { rank: 1, player: p2.clone(), prize: 180 });
+    assert_eq!(token.balance(&p2), 180);
+    assert_eq!(token.balance(&p1), 90);
+    assert_eq!(token.balance(&p3), 30);
+    assert_eq!(token.balance(&client.address), 0);
+
+    assert_eq!(client.get_tournament_results(&tournament_id), Some(placements));
+    assert_eq!(client.get_tournament_state(&tournament_id).status, TournamentStatus::Finalized);
+    assert_eq!(client.try_cancel_tournament(&tournament_id), Err(Ok(RegistryError::InvalidTournamentStatus)));
+}
+
+// Own module: each `#[contractimpl]` exports its functions at module level.
+mod payouts_breaker {
+    use crate::PauseCategory;
+    use soroban_sdk::{contract, contractimpl, Env};
+
+    #[contract]
+    pub struct PayoutsPausedBreaker;
+
+    #[contractimpl]
+    impl PayoutsPausedBreaker {
+        pub fn is_category_paused(_env: Env, category: PauseCategory) -> bool {
+            category == PauseCategory::Payouts
+        }
+    }
+}
+
+#[test]
+fn test_finalize_tournament_blocked_by_paused_payouts() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, tournament_id, [p1, p2, _, _]) = setup_paid_tournament(&env);
+
+    client.set_circuit_breaker(&env.register_contract(None, payouts_breaker::PayoutsPausedBreaker));
+    client.register_tournament(&p1, &tournament_id);
+    client.register_tournament(&p2, &tournament_id);
+    client.start_tournament(&tournament_id);
+
+    assert_eq!(client.try_finalize_tournament(&tournament_id, &vec![&env, p1.clone(), p2.clone()]), Err(Ok(RegistryError::Paused)));
+    assert_eq!(token.balance(&client.address), 200);
+    assert_eq!(client.get_tournament_state(&tournament_id).status, TournamentStatus::InProgress);
+}
+
+#[test]
+fn test_unfilled_ranks_go_to_first_place() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, tournament_id, [p1, p2, _, _]) = setup_paid_tournament(&env);
+
+    client.register_tournament(&p1, &tournament_id);
+    client.register_tournament(&p2, &tournament_id);
+    client.start_tournament(&tournament_id);
+    client.finalize_tournament(&tournament_id, &vec![&env, p1.clone(), p2.clone()]);
+
+    // 60 % + the unfilled 10 % third place.
+    assert_eq!(token.balance(&p1), 140);
+    assert_eq!(token.balance(&p2), 60);
+}
+
+#[test]
+fn test_withdraw_and_refunds_when_cancelled_or_under_filled() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let (client, token, tournament_id, [p1, p2, _, _]) = setup_paid_tournament(&env);
+
+    client.register_tournament(&p1, &tournament_id);
+    client.register_tournament(&p2, &tournament_id);
+    client.withdraw_registration(&p2, &tournament_id);
+    assert_eq!(token.balance(&p2), 100);
+    assert_eq!(client.get_tournament(&tournament_id).participants, 1);
+    assert_eq!(client.try_withdraw_registration(&p2, &tournament_id), Err(Ok(RegistryError::NotRegistered)));
+
+    // One player against a minimum of two: starting cancels instead.
+    assert_eq!(client.try_claim_refund(&p1, &tournament_id), Err(Ok(RegistryError::InvalidTournamentStatus)));
+    assert_eq!(client.start_tournament(&tournament_id), TournamentStatus::Cancelled);
+    client.claim_refund(&p1, &tournament_id);
+    assert_eq!(token.balance(&p1), 100);
+    assert_eq!(token.balance(&client.address), 0);
+    assert_eq!(client.try_claim_refund(&p1, &tournament_id), Err(Ok(RegistryError::NotRegistered)));
+
+    // An admin cancel after the start refunds the same way.
+    let id = String::from_str(&env, "tourney-cancel");
+    client.create_tournament(&id, &4, &100, &token.address, &2, &vec![&env, 10_000]);
+    client.register_tournament(&p1, &id);
+    client.register_tournament(&p2, &id);
+    client.start_tournament(&id);
+    client.cancel_tournament(&id);
+    client.claim_refund(&p1, &id);
+    client.claim_refund(&p2, &id);
+    assert_eq!(token.balance(&p1), 100);
+    assert_eq!(token.balance(&p2), 100);
+}
+
+#[test]
+fn test_anchor_batch_and_prove_game() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let server = Address::generate(&env);
+    let contract_id = env.register_contract(None, GameRegistry);
+    let client = GameRegistryClient::new(&env, &contract_id);
+    client.initialize(&admin, &server, &admin);
+
+    // Three games with proof hashes [1; 32], [2; 32], [3; 32]. The root is the
+    // one st_core's `merkle::MerkleBatch` computes for the same batch.
+    let games = [1u8, 2, 3].map(|i| BytesN::from_array(&env, &[i; 32]));
+    let leaves = games.clone().map(|game| GameRegistry::hash_tagged(&env, 0, &game, None));
+    let root = BytesN::from_array(&env, &hex32("f49772894c14ce4b584bb71fab253ff9106d8fb60739c32b8731ae557a745c45"));
+
+    assert_eq!(client.try_anchor_batch(&root, &0), Err(Ok(RegistryError::EmptyBatch)));
+    assert_eq!(client.anchor_batch(&root, &3), 1);
+    assert_eq!(client.epoch_count(), 1);
+    assert_eq!(client.get_batch(&1).leaf_count, 3);
+
+    // Game 1 pairs with game 2, then with game 3 carried up; game 3 only needs
+    // the (1, 2) node.
+    let proof_1 = vec![&env, leaves[1].clone(), leaves[2].clone()];
+    let proof_3 = vec![&env, GameRegistry::hash_tagged(&env, 1, &leaves[0], Some(&leaves[1]))];
+    assert!(client.verify_inclusion(&1, &games[0], &proof_1));
+    assert!(client.verify_inclusion(&1, &games[2], &proof_3));
+    assert!(!client.verify_inclusion(&1, &games[1], &proof_1));
+    assert_eq!(client.try_verify_inclusion(&2, &games[0], &proof_1), Err(Ok(RegistryError::EpochNotFound)));
+
+    assert_eq!(client.try_prove_game(&1, &games[1], &proof_1), Err(Ok(RegistryError::InvalidProof)));
+    assert_eq!(client.get_proven_epoch(&games[0]), None);
+    client.prove_game(&1, &games[0], &proof_1);
+    assert_eq!(client.get_proven_epoch(&games[0]), Some(1));
+}
+
+fn hex32(hex: &str) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+    }
+    out
+}
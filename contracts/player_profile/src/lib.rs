@@ -0,0 +1,298 @@
+#![no_std]
+
+// ────────────────────────────────────────────────────────────────────────────
+// Player profiles
+// ────────────────────────────────────────────────────────────────────────────
+//
+// On-chain copy of the backend's player profiles (username, Elo, games
+// played), written in batches by the backend's migration job
+// (`db::profile_migration`). Profiles live in persistent storage under a
+// 16-byte key: the first 16 bytes of `sha256("profile:" + player_id)`, as in
+// `pers-store/playerProfileMigration.ts`. Player ids therefore never appear
+// on chain.
+
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, symbol_short, Address, BytesN, Env,
+    String, Vec,
+};
+
+/// Most profiles accepted by one `put_profiles` call
+pub const MAX_BATCH: u32 = 50;
+
+const TTL_THRESHOLD: u32 = 100_000;
+const TTL_EXTEND_TO: u32 = 500_000;
+
+#[contracttype]
+pub enum DataKey {
+    Admin,
+    Writer,
+    UpgradeAuthority,
+    ProfileCount,
+    Profile(BytesN<16>),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PlayerProfile {
+    pub username: String,
+    pub elo: u32,
+    pub games_played: u32,
+    pub updated_at: u64, // ledger timestamp of the last write
+}
+
+/// One profile in a `put_profiles` batch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProfileEntry {
+    pub key: BytesN<16>,
+    pub username: String,
+    pub elo: u32,
+    pub games_played: u32,
+}
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub enum ProfileError {
+    AlreadyInitialized = 1,
+    NotInitialized = 2,
+    BatchTooLarge = 3,
+    EmptyUsername = 4,
+    UpgradeAuthorityNotSet = 5,
+}
+
+#[contract]
+pub struct PlayerProfileContract;
+
+#[contractimpl]
+impl PlayerProfileContract {
+    /// Set the admin, the address allowed to write profiles and the address
+    /// allowed to call `upgrade`
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        writer: Address,
+        upgrade_authority: Address,
+    ) -> Result<(), ProfileError> {
+        if env.storage().instance().has(&DataKey::Admin) {
+            return Err(ProfileError::AlreadyInitialized);
+        }
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Writer, &writer);
+        env.storage()
+            .instance()
+            .set(&DataKey::UpgradeAuthority, &upgrade_authority);
+        Ok(())
+    }
+
+    /// Replace the writer. Admin only.
+    pub fn set_writer(env: Env, writer: Address) -> Result<(), ProfileError> {
+        Self::admin(&env)?.require_auth();
+        env.storage().instance().set(&DataKey::Writer, &writer);
+        Ok(())
+    }
+
+    /// Create or overwrite a batch of profiles. Writer only. Returns how
+    /// many of them were new.
+    pub fn put_profiles(env: Env, entries: Vec<ProfileEntry>) -> Result<u32, ProfileError> {
+        let writer: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Writer)
+            .ok_or(ProfileError::NotInitialized)?;
+        writer.require_auth();
+
+        if entries.len() > MAX_BATCH {
+            return Err(ProfileError::BatchTooLarge);
+        }
+        if entries.iter().any(|entry| entry.username.is_empty()) {
+            return Err(ProfileError::EmptyUsername);
+        }
+
+        let updated_at = env.ledger().timestamp();
+        let mut created = 0;
+        for entry in entries.iter() {
+            let key = DataKey::Profile(entry.key.clone());
+            if !env.storage().persistent().has(&key) {
+                created += 1;
+            }
+            let profile = PlayerProfile {
+                username: entry.username,
+                elo: entry.elo,
+                games_played: entry.games_played,
+                updated_at,
+            };
+            env.storage().persistent().set(&key, &profile);
+            env.storage()
+                .persistent()
+                .extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+            env.events()
+                .publish((symbol_short!("profile"), entry.key), profile.elo);
+        }
+
+        if created > 0 {
+            let count = Self::profile_count(env.clone()) + created;
+            env.storage().instance().set(&DataKey::ProfileCount, &count);
+        }
+        Ok(created)
+    }
+
+    /// Get one profile by storage key
+    pub fn get_profile(env: Env, key: BytesN<16>) -> Option<PlayerProfile> {
+        env.storage().persistent().get(&DataKey::Profile(key))
+    }
+
+    /// Get profiles by storage key, in order; `None` where there is none
+    pub fn get_profiles(env: Env, keys: Vec<BytesN<16>>) -> Vec<Option<PlayerProfile>> {
+        let mut profiles = Vec::new(&env);
+        for key in keys.iter() {
+            profiles.push_back(Self::get_profile(env.clone(), key));
+        }
+        profiles
+    }
+
+    /// Number of distinct profiles stored
+    pub fn profile_count(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::ProfileCount)
+            .unwrap_or(0)
+    }
+
+    /// Move the upgrade authority. Only the current authority can do this;
+    /// the admin has no say once `initialize` has set it.
+    pub fn set_upgrade_authority(env: Env, authority: Address) -> Result<(), ProfileError> {
+        let current: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::UpgradeAuthority)
+            .ok_or(ProfileError::UpgradeAuthorityNotSet)?;
+        current.require_auth();
+        env.storage()
+            .instance()
+            .set(&DataKey::UpgradeAuthority, &authority);
+        Ok(())
+    }
+
+    /// Replace the contract code with already-uploaded WASM
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) -> Result<(), ProfileError> {
+        let authority: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::UpgradeAuthority)
+            .ok_or(ProfileError::UpgradeAuthorityNotSet)?;
+        authority.require_auth();
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+}
+
+impl PlayerProfileContract {
+    fn admin(env: &Env) -> Result<Address, ProfileError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(ProfileError::NotInitialized)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    fn entry(env: &Env, key: u8, username: &str, elo: u32) -> ProfileEntry {
+        ProfileEntry {
+            key: BytesN::from_array(env, &[key; 16]),
+            username: String::from_str(env, username),
+            elo,
+            games_played: 3,
+        }
+    }
+
+    #[test]
+    fn test_put_and_get_profiles() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, PlayerProfileContract);
+        let client = PlayerProfileContractClient::new(&env, &contract_id);
+        client.initialize(
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+        );
+
+        let batch = Vec::from_array(
+            &env,
+            [entry(&env, 1, "alice", 1500), entry(&env, 2, "bob", 1320)],
+        );
+        assert_eq!(client.put_profiles(&batch), 2);
+
+        // Overwriting an existing profile does not count it again.
+        let update = Vec::from_array(&env, [entry(&env, 1, "alice", 1532)]);
+        assert_eq!(client.put_profiles(&update), 0);
+        assert_eq!(client.profile_count(), 2);
+
+        let keys = Vec::from_array(
+            &env,
+            [
+                BytesN::from_array(&env, &[1; 16]),
+                BytesN::from_array(&env, &[9; 16]),
+            ],
+        );
+        let profiles = client.get_profiles(&keys);
+        assert_eq!(profiles.len(), 2);
+        let alice = profiles.get(0).unwrap().unwrap();
+        assert_eq!(alice.username, String::from_str(&env, "alice"));
+        assert_eq!(alice.elo, 1532);
+        assert_eq!(alice.games_played, 3);
+        assert_eq!(profiles.get(1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_rejects_oversized_and_invalid_batches() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, PlayerProfileContract);
+        let client = PlayerProfileContractClient::new(&env, &contract_id);
+
+        let batch = Vec::from_array(&env, [entry(&env, 1, "alice", 1500)]);
+        assert_eq!(
+            client.try_put_profiles(&batch),
+            Err(Ok(ProfileError::NotInitialized))
+        );
+
+        client.initialize(
+            &Address::generate(&env),
+            &Address::generate(&env),
+            &Address::generate(&env),
+        );
+        let mut oversized = Vec::new(&env);
+        for i in 0..=MAX_BATCH {
+            oversized.push_back(entry(&env, i as u8, "player", 1200));
+        }
+        assert_eq!(
+            client.try_put_profiles(&oversized),
+            Err(Ok(ProfileError::BatchTooLarge))
+        );
+        let unnamed = Vec::from_array(&env, [entry(&env, 1, "", 1500)]);
+        assert_eq!(
+            client.try_put_profiles(&unnamed),
+            Err(Ok(ProfileError::EmptyUsername))
+        );
+        assert_eq!(client.profile_count(), 0);
+    }
+
+    #[test]
+    fn test_only_upgrade_authority_can_hand_it_over() {
+        let env = Env::default();
+        env.mock_all_auths();
+        let contract_id = env.register_contract(None, PlayerProfileContract);
+        let client = PlayerProfileContractClient::new(&env, &contract_id);
+        let admin = Address::generate(&env);
+        let authority = Address::generate(&env);
+        client.initialize(&admin, &Address::generate(&env), &authority);
+
+        client.set_upgrade_authority(&admin);
+        assert_eq!(env.auths()[0].0, authority);
+    }
+}